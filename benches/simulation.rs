@@ -0,0 +1,113 @@
+//! Benchmarks of the hot loops of the simulation, so slowdowns are caught before a release: the field a particle
+//! radiates, filling a field from the applied wave, reading a field between its divisions, and whole updates, each
+//! across numbers of divisions of the grid and, for updates, of particles.
+//! Run with `cargo bench`, comparing against a saved baseline with `cargo bench -- --baseline <name>`.
+
+use std::hint::black_box;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+
+use refraction::app::simulation::Simulation;
+use refraction::app::simulation::config::SimulationConfig;
+use refraction::app::simulation::field::{Field, refined_grid};
+use refraction::app::simulation::scene::Scene;
+use refraction::app::simulation::variables::{DIVISIONS, Float, WORLD_SIZE};
+use refraction::app::simulation::waveform::Waveform;
+
+// numbers of evenly spaced divisions of the world, around the default DIVISIONS
+const DIVISION_COUNTS: [usize; 3] = [DIVISIONS / 4, DIVISIONS, 4 * DIVISIONS];
+// numbers of particles in the row updated
+const PARTICLE_COUNTS: [usize; 3] = [1, 4, 16];
+// updates run before measuring, so the particles have a history to look the retarded time up in
+const WARM_UP_STEPS: usize = 300;
+
+// a row of particles a Gaussian packet has reached, run for WARM_UP_STEPS
+fn warmed_up(particle_count: usize, divisions: usize) -> Simulation {
+    let config = SimulationConfig::default()
+        .divisions(divisions)
+        .scene(Scene {
+            particle_count,
+            particle_spacing: 1.0,
+            ..Scene::blank()
+        });
+    let mut simulation = Simulation::from_config(&config).unwrap();
+    for _ in 0..WARM_UP_STEPS {
+        simulation.update();
+    }
+    simulation
+}
+
+fn update_induced_field(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_induced_field");
+    for divisions in DIVISION_COUNTS {
+        let simulation = warmed_up(1, divisions);
+        let mut particle = simulation.particles()[0].clone();
+        let t = Float::from(simulation.time());
+        group.bench_function(BenchmarkId::from_parameter(divisions), |b| {
+            b.iter(|| particle.update_induced_field(black_box(t)))
+        });
+    }
+    group.finish();
+}
+
+fn set_from_function(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Field::set_from_function");
+    let wave = Waveform::GaussianPacket.properties().function;
+    for divisions in DIVISION_COUNTS {
+        let mut field = Field::from_points(&refined_grid(WORLD_SIZE, divisions, &[0.0]));
+        group.bench_function(BenchmarkId::from_parameter(divisions), |b| {
+            b.iter(|| field.set_from_function(&wave, black_box(1.0)))
+        });
+    }
+    group.finish();
+}
+
+fn value_at(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Field::value_at");
+    let wave = Waveform::GaussianPacket.properties().function;
+    // points between the divisions, as many as the default grid has
+    let points: Vec<f32> = (0..DIVISIONS)
+        .map(|i| WORLD_SIZE.min + WORLD_SIZE.span() * (i as f32 + 0.5) / DIVISIONS as f32)
+        .collect();
+    for divisions in DIVISION_COUNTS {
+        let mut field = Field::from_points(&refined_grid(WORLD_SIZE, divisions, &[0.0]));
+        field.set_from_function(&wave, 1.0);
+        group.bench_function(BenchmarkId::from_parameter(divisions), |b| {
+            b.iter(|| {
+                points
+                    .iter()
+                    .map(|x| field.value_at(black_box(*x)))
+                    .sum::<f32>()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Simulation::update");
+    for particle_count in PARTICLE_COUNTS {
+        for divisions in DIVISION_COUNTS {
+            let simulation = warmed_up(particle_count, divisions);
+            let id = BenchmarkId::new(format!("{particle_count} particles"), divisions);
+            // each update starts from the same state, as the histories grow with every one
+            group.bench_function(id, |b| {
+                b.iter_batched(
+                    || simulation.clone(),
+                    |mut simulation| simulation.update(),
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    update_induced_field,
+    set_from_function,
+    value_at,
+    update
+);
+criterion_main!(benches);
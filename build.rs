@@ -0,0 +1,36 @@
+//! Records the commit the app is built from, for the manifests of runs it exports, marked as modified if the
+//! sources differ from it.
+
+use std::path::Path;
+use std::process::Command;
+
+// output of a git command, if git and the repository are there
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+fn main() {
+    let build = match git(&["rev-parse", "HEAD"]) {
+        Some(hash) => {
+            let modified = git(&["status", "--porcelain", "--untracked-files=no"])
+                .is_some_and(|changes| !changes.is_empty());
+            if modified {
+                format!("{hash}-modified")
+            } else {
+                hash
+            }
+        }
+        None => "unknown".to_owned(),
+    };
+    println!("cargo:rustc-env=REFRACTION_BUILD={build}");
+    // run again on a commit or checkout, or a change to the sources
+    for path in [".git/HEAD", ".git/index", ".git/refs", "src", "Cargo.toml"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+}
@@ -1,25 +1,156 @@
 //! Contains all application code, including application state and drawing logic
 
+mod background;
+mod bookmarks;
 mod canvas;
-mod simulation;
+#[cfg(feature = "serde-state")]
+pub mod checkpoint;
+mod compare;
+#[cfg(any(feature = "gamepad", feature = "media-keys"))]
+mod controllers;
+mod convergence;
+mod demo;
+#[cfg(feature = "scripting")]
+mod diagnostics;
+mod energy;
+mod ensemble;
+mod explain;
+mod export;
+mod extinction;
+mod fit;
+mod i18n;
+mod inspector;
+mod layout;
+mod link;
+mod looping;
+mod manifest;
+mod multiples;
+mod noise;
+mod palette;
+mod panels;
+mod perf;
+mod phasor;
+mod plot;
+mod pointer;
+mod presentation;
+mod pulse;
+mod quiz;
+mod reference;
+mod scattering;
+mod session;
+mod settings;
+mod signal;
+pub mod simulation;
+mod snapshot;
+mod spectrum;
+mod start;
+mod status;
+#[cfg(feature = "stream")]
+mod stream;
+mod surface;
+pub mod sweep;
+mod tour;
+mod tween;
 
-use canvas::Canvas;
+use bookmarks::BookmarkPanel;
+use canvas::{Canvas, VISIBLE_Y_SPAN, YScale};
+use convergence::ConvergencePanel;
+use demo::{Action, DEMO_EXTENSION, Demo, DemoRunner, Trace, draw_banner};
+use inspector::Inspector;
+use layout::{LayoutMenu, Measurement, Section};
+use link::{Link, View};
+use looping::LoopWindow;
+use simulation::arrangement::Arrangement;
+use simulation::attenuation::ExponentialFit;
+use simulation::command::SimulationCommand;
+use simulation::config::SimulationConfig;
+use simulation::observer::StepObserver;
 use simulation::particle::ChargedParticleType;
-use simulation::variables::{ELECTRON_DAMPING, ELECTRON_MASS, PARTICLE_SPACING, SPRING_CONSTANT};
+use simulation::preset::Preset;
+use simulation::ramp::{DEFAULT_RAMP_STEPS, ParameterChanges};
+use simulation::replay::{Player, REPLAY_EXTENSION, Recorder, Replay};
+use simulation::scene::{SCENE_EXTENSION, Scene};
+use simulation::variables::{
+    BEAT_AMPLITUDE, BEAT_SEPARATION, ELECTRON_DAMPING, ELECTRON_MASS, PARTICLE_SPACING,
+    SPRING_CONSTANT, TEMPERATURE, WAVE_FREQUENCY,
+};
 use simulation::{Simulation, waveform::*};
 
-use egui::{Color32, Rangef, Rect, Response, Sense, Style, pos2};
-use std::time::SystemTime;
+#[cfg(feature = "serde-state")]
+use checkpoint::{CHECKPOINT_EXTENSION, Checkpointer};
+use compare::RunComparison;
+#[cfg(feature = "scripting")]
+use diagnostics::DiagnosticsPanel;
+use egui::{Align2, Color32, Pos2, Rangef, Rect, Sense, Style, Vec2, pos2, vec2};
+use energy::EnergyTracker;
+use ensemble::EnsemblePanel;
+use export::FrameExport;
+use extinction::ExtinctionTracker;
+use fit::FitPanel;
+use i18n::{Language, tr};
+use manifest::{MANIFEST_EXTENSION, Manifest};
+use noise::NoiseTracker;
+use palette::{LineDash, Overlay, Palette, TRACE_WIDTH, colours};
+use perf::{FPS_COLOUR, FrameStats, UPS_COLOUR};
+use phasor::PhasorTracker;
+use pointer::LaserPointer;
+use presentation::{PlaybackCommand, SPEED_STEP};
+use pulse::PulseTracker;
+use quiz::{Quiz, QuizAction};
+use reference::{ReferenceKind, ReferenceTrace, References};
+use scattering::ScatteringPanel;
+use session::{Autosave, SESSION_EXTENSION, Session};
+use settings::{FEATHERING, Settings, UI_SCALE};
+use signal::AnalyticSignal;
+use snapshot::Snapshot;
+use spectrum::SpectrumTracker;
+use start::StartChoice;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+#[cfg(feature = "stream")]
+use stream::Stream;
 use strum::IntoEnumIterator;
+use surface::FieldSurface;
+use tour::{Tour, TourAction, TourTarget};
+use tween::Tween;
 
-use crate::app::simulation::ChargedParticle;
+use crate::app::simulation::{ChargedParticle, ParticleId};
 
-const MONITOR_REFRESH_RATE: u32 = 60;
-const SIMULATION_FPS: u32 = if MONITOR_REFRESH_RATE < 60 {
-    MONITOR_REFRESH_RATE
-} else {
-    60
-};
+// name of the application, which also names the directory its files are saved in
+pub const APP_NAME: &str = "Refraction";
+
+// extension of sampled waveform files, with columns of time and field strength
+const WAVE_EXTENSION: &str = "csv";
+
+// number of simulation updates per second of simulated time, unless matched to the display's refresh rate
+const SIMULATION_FPS: u32 = 60;
+// range of refresh rates the simulation's updates are matched to, beyond which SIMULATION_FPS is used.
+// slower displays would make the time step too coarse, and faster ones the simulation too costly
+const MIN_MATCHED_RATE: f32 = 50.0;
+const MAX_MATCHED_RATE: f32 = 240.0;
+// longest real time a single frame may advance the simulation by. After a stall (e.g. the window being dragged)
+// the simulation skips ahead by at most this much rather than running a large burst of updates to catch up.
+const MAX_FRAME_TIME: f32 = 0.25;
+// updates between redraws in timelapse, to start with
+const TIMELAPSE_EVERY: u32 = 100;
+// real time spent each frame catching up with a jump, leaving the rest of the frame for drawing
+const JUMP_FRAME_BUDGET: Duration = Duration::from_millis(30);
+const MARKER_COLOUR: Color32 = Color32::from_rgb(230, 200, 80);
+const HOVER_COLOUR: Color32 = Color32::from_gray(170);
+const SELECTION_COLOUR: Color32 = Color32::from_rgb(255, 220, 90);
+const RESONANCE_COLOUR: Color32 = Color32::from_rgb(255, 120, 200);
+// fraction the plane wave's frequency may be off the particles' resonance and still be described as on it
+const RESONANCE_TOLERANCE: f32 = 0.03;
+// radius particles are drawn with, in world units
+const PARTICLE_RADIUS: f32 = 0.25;
+// width of the x axis shown when focusing on a particle
+const FOCUS_SPAN: f32 = 4.0;
+// smallest width or height in points of a box zoom selection, below which that axis isn't zoomed
+const MIN_BOX_ZOOM_SIZE: f32 = 8.0;
+// range of the speed slider
+const MIN_SPEED: f32 = 0.1;
+const MAX_SPEED: f32 = 10.0;
+const RECORDING_COLOUR: Color32 = Color32::from_rgb(230, 60, 60);
 
 fn zoom_to(range: &Rangef, zoom: f32, centre: f32) -> Rangef {
     Rangef {
@@ -28,235 +159,2021 @@ fn zoom_to(range: &Rangef, zoom: f32, centre: f32) -> Rangef {
     }
 }
 
+// colours of the palette in use, at an opacity
+fn palette_colour((r, g, b): (u8, u8, u8), a: f32) -> Color32 {
+    Color32::from_rgba_unmultiplied(r, g, b, (a * 255.0) as u8)
+}
 fn particle_colour(a: f32, particle: &ChargedParticle) -> Color32 {
-    let (r, g, b) = particle.particle_type().colour();
-    Color32::from_rgba_unmultiplied(r, g, b, (a * a * 255.0) as u8)
+    palette_colour(colours().particle(*particle.particle_type()), a * a)
 }
 fn particle_field_colour(a: f32) -> Color32 {
-    Color32::from_rgba_unmultiplied(20, 100, 255, (a * a * 255.0) as u8)
+    palette_colour(colours().particle_field, a * a)
 }
 fn applied_field_colour(a: f32, waveform: &Waveform) -> Color32 {
-    let (r, g, b) = waveform.properties().colour;
-    Color32::from_rgba_unmultiplied(r, g, b, (a * a * 255.0) as u8)
+    let colour = colours().applied.unwrap_or(waveform.properties().colour);
+    palette_colour(colour, a * a)
 }
 fn resultant_field_colour(a: f32) -> Color32 {
-    Color32::from_rgba_unmultiplied(180, 20, 180, (a * a * 255.0) as u8)
+    palette_colour(colours().resultant, a * a)
+}
+// colour for a phase in radians, going once around the colour wheel each cycle
+fn phase_colour(a: f32, phase: f32) -> Color32 {
+    let hue = phase.rem_euclid(std::f32::consts::TAU) / std::f32::consts::TAU;
+    egui::ecolor::Hsva::new(hue, 0.8, 1.0, a * a).into()
+}
+fn envelope_colour(a: f32) -> Color32 {
+    palette_colour(colours().envelope, a * a * 160.0 / 255.0)
+}
+fn snapshot_colour(a: f32) -> Color32 {
+    palette_colour(colours().snapshot, a * a * 200.0 / 255.0)
+}
+fn reference_colour(a: f32) -> Color32 {
+    palette_colour(colours().reference, a * a)
+}
+fn scattered_field_colour(a: f32) -> Color32 {
+    palette_colour(colours().scattered, a * a)
+}
+fn intensity_colour(a: f32) -> Color32 {
+    palette_colour(colours().intensity, a * a)
+}
+
+// draws one of the fields in the width and dashes chosen for it, which by default are its own line style when traces
+// are told apart by style as well as colour, over the area under it if traces are filled
+fn draw_trace(
+    lane: &Canvas,
+    trace: Trace,
+    settings: &Settings,
+    x_points: &[f32],
+    y_points: &[f32],
+    colour: &Color32,
+) {
+    if settings.filled_traces {
+        lane.draw_filled(x_points, y_points, *colour);
+    }
+    let styles = &settings.trace_styles;
+    lane.draw_points_styled(
+        x_points,
+        y_points,
+        colour,
+        styles.get(trace).width,
+        styles.pattern(trace, settings.line_styles).as_ref(),
+    );
+}
+
+// a tick across a horizontal slider at a value, which is left out if outside the slider's range
+fn draw_slider_marker(
+    ui: &egui::Ui,
+    slider: &egui::Response,
+    range: std::ops::RangeInclusive<f32>,
+    value: f32,
+    colour: Color32,
+) {
+    if !range.contains(&value) {
+        return;
+    }
+    // the slider's rail takes up the start of its response, inset by the radius of its handle as egui lays it out
+    let thickness = ui
+        .text_style_height(&egui::TextStyle::Body)
+        .max(ui.spacing().interact_size.y);
+    let rail = Rangef::new(
+        slider.rect.left(),
+        slider.rect.left() + ui.spacing().slider_width,
+    )
+    .shrink(thickness / 2.5);
+    let fraction = (value - range.start()) / (range.end() - range.start());
+    let x = rail.min + fraction * rail.span();
+    ui.painter().vline(
+        x,
+        Rangef::new(slider.rect.top() + 2.0, slider.rect.bottom() - 2.0),
+        egui::Stroke::new(2.0, colour),
+    );
+}
+
+// number of points the exponential fitted to the intensity is drawn with
+const ATTENUATION_FIT_POINTS: usize = 50;
+
+// the exponential fitted to the intensity over the particles, labelled with its attenuation coefficient
+fn draw_attenuation(lane: &Canvas, fit: &ExponentialFit, theory: Option<f32>) {
+    let x_points: Vec<f32> = (0..=ATTENUATION_FIT_POINTS)
+        .map(|i| fit.start + (fit.end - fit.start) * i as f32 / ATTENUATION_FIT_POINTS as f32)
+        .collect();
+    let y_points: Vec<f32> = x_points.iter().map(|x| fit.value_at(*x)).collect();
+    lane.draw_overlay(&x_points, &y_points, &envelope_colour(1.0), Overlay::Theory);
+    let mut text = format!("α = {:.3}", fit.coefficient);
+    if let Some(theory) = theory {
+        text += &format!(" ({}: {theory:.3})", tr("dilute theory"));
+    }
+    lane.draw_label(
+        &pos2(fit.start, fit.value_at(fit.start)),
+        vec2(0.0, -12.0),
+        egui::Align2::CENTER_BOTTOM,
+        text,
+        intensity_colour(1.0),
+    );
+}
+
+// shown over the canvas while files are dragged over the window
+fn draw_drop_indicator(painter: &egui::Painter, rect: Rect) {
+    let colour = Color32::from_rgb(90, 170, 255);
+    painter.rect_filled(rect, 0.0, Color32::from_black_alpha(150));
+    painter.rect_stroke(
+        rect.shrink(12.0),
+        8.0,
+        egui::Stroke::new(3.0, colour),
+        egui::StrokeKind::Inside,
+    );
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        tr("Drop a session, scene, waveform (CSV) or replay file to open it"),
+        egui::FontId::proportional(24.0),
+        colour,
+    );
 }
 
 pub struct RefractionApp {
     simulation: Simulation,
     paused: bool,
     speed: f32,
-    requested_frames: f32,
+    accumulated_time: f32,    // real time not yet simulated, scaled by speed
+    timelapse: bool,          // runs as fast as possible instead of in real time
+    timelapse_every: u32,     // updates between redraws in timelapse
+    jump_target: Option<f32>, // time being jumped to, caught up with over as many frames as it takes
+    last_frame_time: Instant,
     frame: u32,
-    zoom: f32,
-    world_centre: f32,
     zoom_centre: Option<f32>,
     dragging: Option<f32>,
+    box_zoom: Option<Pos2>, // screen position where a box zoom selection started
+    context_menu_pos: Option<Pos2>, // position in view coordinates where the canvas was last right-clicked
+    box_select: Option<Pos2>,       // screen position where a box selection of particles started
+    selected_particles: Vec<ParticleId>, // particles shown in the inspector
+    copied_particles: Option<Arrangement>, // particles copied to be pasted elsewhere
+    recorder: Option<Recorder>,     // records the run to a replay file
+    player: Option<Player>,         // replays a recorded run
+    looping: LoopWindow,            // interval replayed over and over
+    bookmarks: BookmarkPanel,
+    snapshot: Option<Snapshot>, // resultant field frozen for comparison
+    references: References,     // data loaded from files to compare against
+    tween: Tween, // state before the last update, for drawing between updates in slow motion
+    demo: Option<DemoRunner>, // scripted demo being run
+    frame_export: FrameExport,
+    #[cfg(feature = "stream")]
+    stream: Stream,
+    #[cfg(feature = "scripting")]
+    diagnostics: DiagnosticsPanel,
+    #[cfg(feature = "serde-state")]
+    checkpointer: Option<Checkpointer>, // saves checkpoints of the simulation as it runs, if they are being saved
+    #[cfg(feature = "serde-state")]
+    checkpoint_minutes: f32, // minutes of running between checkpoints
+    #[cfg(any(feature = "gamepad", feature = "media-keys"))]
+    controllers: controllers::Controllers, // gamepads and media keys
     frame_skip: u32,
-    last_n_frames_start: SystemTime,
-    last_n_frames_time_micros: f32,
+    frame_stats: FrameStats,
+    energy: EnergyTracker,
+    comparison: RunComparison, // runs loaded from files, for the Compare runs panel
+    surface: FieldSurface,
+    phasors: PhasorTracker,
+    noise: NoiseTracker,
+    spectrum: SpectrumTracker,
+    extinction: ExtinctionTracker,
+    pulse: PulseTracker,
+    convergence: ConvergencePanel,
+    scattering: ScatteringPanel,
+    fit: FitPanel,
+    ensemble: EnsemblePanel,
+    quiz: Quiz,
+    inspector: Inspector,
+    analytic_signal: AnalyticSignal,
+
+    settings: Settings,
+    autosave: Autosave,
+    recovered: Option<Session>, // session from a previous launch that ended unexpectedly, offered for restoring
+    start_screen_open: bool,
+    error: Option<String>, // shown to the user until dismissed
 
-    applied_field_opacity: f32,
-    resultant_field_opacity: f32,
-    particle_field_opacity: f32,
+    tour: Tour,
+    presenting: bool, // fullscreen presentation mode, with only the canvas shown
+    laser: LaserPointer,
+    layout_menu: LayoutMenu,
+    suspended: bool, // stepping and redrawing stopped while the app is in the background
+    applied_ui_scale: f32, // zoom factor last given to egui, to notice it being changed with Ctrl and +/-
 }
 
 impl RefractionApp {
     /// Called once before the first frame.
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let simulation = Simulation::new(Waveform::GaussianPacket);
-        let world_centre = simulation.size().center();
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let settings = Settings::load(cc.storage);
+        cc.egui_ctx.set_visuals(settings.visuals());
+        settings.apply_feathering(&cc.egui_ctx);
+        cc.egui_ctx.set_zoom_factor(settings.ui_scale);
+        i18n::set_language(settings.language.unwrap_or_else(Language::from_environment));
+        palette::set_palette(settings.palette);
+
+        let recovered = session::find_recovery();
+        let start_screen_open = settings.show_start_screen && recovered.is_none();
+
+        let mut simulation = Simulation::from_config(&SimulationConfig::default())
+            .expect("the default configuration is valid");
+        if let Some(preset) = settings.last_preset {
+            simulation.apply_preset(preset);
+        }
         Self {
             simulation,
             paused: true,
 
             speed: 1.0,
-            requested_frames: 1.0,
-            frame: 1,
+            accumulated_time: 0.0,
+            timelapse: false,
+            timelapse_every: TIMELAPSE_EVERY,
+            jump_target: None,
+            last_frame_time: Instant::now(),
+            frame: 0,
             frame_skip: SIMULATION_FPS / 5,
-            last_n_frames_start: SystemTime::now(),
-            last_n_frames_time_micros: 1e6,
+            frame_stats: FrameStats::new(),
+            energy: EnergyTracker::default(),
+            comparison: RunComparison::default(),
+            surface: FieldSurface::new(cc.gl.as_deref()),
+            phasors: PhasorTracker::default(),
+            noise: NoiseTracker::default(),
+            spectrum: SpectrumTracker::default(),
+            extinction: ExtinctionTracker::default(),
+            pulse: PulseTracker::default(),
+            convergence: ConvergencePanel::default(),
+            scattering: ScatteringPanel::default(),
+            fit: FitPanel::default(),
+            ensemble: EnsemblePanel::default(),
+            quiz: Quiz::default(),
+            inspector: Inspector::default(),
+            analytic_signal: AnalyticSignal::default(),
+
+            zoom_centre: None,
+            dragging: None,
+            box_zoom: None,
+            context_menu_pos: None,
+            box_select: None,
+            selected_particles: Vec::new(),
+            copied_particles: None,
+            recorder: None,
+            player: None,
+            looping: LoopWindow::default(),
+            bookmarks: BookmarkPanel::default(),
+            snapshot: None,
+            references: References::default(),
+            tween: Tween::default(),
+            demo: None,
+            frame_export: FrameExport::default(),
+            #[cfg(feature = "stream")]
+            stream: Stream::default(),
+            #[cfg(feature = "scripting")]
+            diagnostics: DiagnosticsPanel::default(),
+            #[cfg(feature = "serde-state")]
+            checkpointer: None,
+            #[cfg(feature = "serde-state")]
+            checkpoint_minutes: checkpoint::DEFAULT_INTERVAL,
+            #[cfg(any(feature = "gamepad", feature = "media-keys"))]
+            controllers: controllers::Controllers::default(),
+
+            settings,
+            autosave: Autosave::default(),
+            recovered,
+            start_screen_open,
+            error: None,
+
+            tour: Tour::default(),
+            presenting: false,
+            laser: LaserPointer::default(),
+            layout_menu: LayoutMenu::default(),
+            suspended: false,
+            applied_ui_scale: cc.egui_ctx.zoom_factor(),
+        }
+    }
+
+    // pauses and rewinds the simulation to the beginning
+    fn restart(&mut self) {
+        self.stop_recording();
+        self.frame_export.stop();
+        self.looping.clear();
+        self.jump_target = None;
+        self.paused = true;
+        self.frame = 0;
+        self.accumulated_time = 0.0;
+        self.simulation.set_time_step(self.time_step());
+        self.simulation.reset();
+        self.reset_trackers();
+    }
+
+    // draws a section of the settings bar at the top of the window
+    fn draw_settings_section(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        section: Section,
+        present: &mut bool,
+    ) {
+        match section {
+            Section::Waveform => {
+                // dropdown to select applied wave type
+                let waveform = self.simulation.waveform();
+                let waveform_rect = ui
+                    .scope(|ui| {
+                        ui.label(tr("Waveform:"));
+                        let mut selected = waveform;
+                        // the colour the applied field is drawn in, so it can be told apart from the other traces
+                        ui.label(
+                            egui::RichText::new("◼").color(applied_field_colour(0.7, &waveform)),
+                        );
+                        egui::ComboBox::from_id_salt("Wave")
+                            .selected_text(match self.simulation.sampled_wave() {
+                                Some(wave) if waveform == Waveform::Sampled => {
+                                    format!("{} ({})", tr("Sampled"), wave.name)
+                                }
+                                _ => tr(waveform.properties().name).to_owned(),
+                            })
+                            .show_ui(ui, |ui| {
+                                // a sampled wave can only be chosen once one has been loaded
+                                let sampled = self.simulation.sampled_wave().is_some();
+                                for form in Waveform::iter()
+                                    .filter(|form| sampled || *form != Waveform::Sampled)
+                                {
+                                    ui.horizontal(|ui| {
+                                        ui.label(
+                                            egui::RichText::new("◼")
+                                                .color(applied_field_colour(0.7, &form)),
+                                        );
+                                        ui.selectable_value(
+                                            &mut selected,
+                                            form,
+                                            tr(form.properties().name),
+                                        );
+                                    });
+                                }
+                            });
+                        if selected != waveform {
+                            self.simulation.send(SimulationCommand::Waveform(selected));
+                        }
+                    })
+                    .response
+                    .rect;
+                self.tour.register(TourTarget::Waveform, waveform_rect);
+
+                // frequency of the plane wave, changed while running so the particles can be tuned through resonance.
+                // a beat's first wave is at this frequency too
+                let beat = waveform == Waveform::Beat;
+                if beat || waveform == Waveform::PlaneWave {
+                    ui.label("ω")
+                        .on_hover_text(tr("Angular frequency of the plane wave"));
+                    let mut frequency = self.simulation.frequency();
+                    let slider = ui.add(egui::Slider::new(
+                        &mut frequency,
+                        WAVE_FREQUENCY.min..=WAVE_FREQUENCY.max,
+                    ));
+                    // the particles' resonance, marked on the slider so it can be driven on, below or above
+                    let resonance = self.simulation.natural_frequency();
+                    draw_slider_marker(
+                        ui,
+                        &slider,
+                        WAVE_FREQUENCY.min..=WAVE_FREQUENCY.max,
+                        resonance,
+                        RESONANCE_COLOUR,
+                    );
+                    if ui.button("↺").on_hover_text(tr("Reset")).clicked() {
+                        frequency = WAVE_FREQUENCY.initial;
+                    }
+                    if frequency != self.simulation.frequency() {
+                        self.simulation
+                            .send(SimulationCommand::Frequency(frequency));
+                    }
+                    let relation = match frequency / resonance {
+                        ratio if (ratio - 1.0).abs() < RESONANCE_TOLERANCE => tr("at resonance"),
+                        ratio if ratio < 1.0 => tr("below resonance"),
+                        _ => tr("above resonance"),
+                    };
+                    ui.label(
+                        egui::RichText::new(format!("ω₀ = {resonance:.2}, {relation}"))
+                            .color(RESONANCE_COLOUR),
+                    )
+                    .on_hover_text(tr(
+                        "Resonant frequency of the particles, √(k/m), marked on the frequency slider",
+                    ));
+                }
+                // the second wave of a beat, and the strength of each
+                if beat {
+                    let mut beat = self.simulation.beat();
+                    ui.label("Δω").on_hover_text(tr(
+                        "Angular frequency of the beat's second wave above the first",
+                    ));
+                    ui.add(egui::Slider::new(
+                        &mut beat.separation,
+                        BEAT_SEPARATION.min..=BEAT_SEPARATION.max,
+                    ));
+                    for (label, amplitude) in ["A₁", "A₂"].into_iter().zip(&mut beat.amplitudes)
+                    {
+                        ui.label(label)
+                            .on_hover_text(tr("Strength of each wave of the beat"));
+                        ui.add(egui::Slider::new(
+                            amplitude,
+                            BEAT_AMPLITUDE.min..=BEAT_AMPLITUDE.max,
+                        ));
+                    }
+                    if ui.button("↺").on_hover_text(tr("Reset")).clicked() {
+                        beat = Beat::default();
+                    }
+                    if beat != self.simulation.beat() {
+                        self.simulation.send(SimulationCommand::Beat(beat));
+                    }
+                    ui.label(format!("T = {:.1} s", beat.period()))
+                        .on_hover_text(tr("Time between the swells of the beat, 2π/Δω"));
+                }
+            }
+            Section::Particles => {
+                // number of particles, allow only the amount that can appear onscreen at once
+                let max_p = self.simulation.max_particles();
+                let (mut count, mut spacing) = (
+                    self.simulation.particle_count(),
+                    self.simulation.particle_spacing(),
+                );
+                ui.label(tr("Particles:"));
+                ui.add(egui::DragValue::new(&mut count).range(0..=max_p));
+
+                // distance between each particle
+                ui.label(tr("Spacing:"));
+                ui.add(egui::Slider::new(
+                    &mut spacing,
+                    PARTICLE_SPACING.min..=PARTICLE_SPACING.max,
+                ));
+                if ui
+                    .button("↺")
+                    .on_hover_text(tr("Reset particles"))
+                    .clicked()
+                {
+                    count = 1;
+                    spacing = PARTICLE_SPACING.initial;
+                }
+                if count != self.simulation.particle_count() {
+                    self.simulation
+                        .send(SimulationCommand::ParticleCount(count));
+                }
+                if spacing != self.simulation.particle_spacing() {
+                    self.simulation
+                        .send(SimulationCommand::ParticleSpacing(spacing));
+                }
+            }
+            Section::ParticleType => {
+                // particle type selection
+                ui.label(tr("Particle Type:"));
+                let current_type = self.simulation.particle_type();
+                let mut selected_type = current_type;
+                egui::ComboBox::from_id_salt("ParticleType")
+                    .selected_text(tr(current_type.name()))
+                    .show_ui(ui, |ui| {
+                        for form in ChargedParticleType::iter() {
+                            ui.selectable_value(
+                                &mut selected_type,
+                                form,
+                                tr(form.properties().name),
+                            );
+                        }
+                    });
+                if selected_type != current_type {
+                    self.simulation
+                        .send(SimulationCommand::ParticleType(selected_type));
+                }
+            }
+            Section::ParticleProperties => {
+                // particle properties
+                let properties_rect = ui
+                    .scope(|ui| {
+                        let particle_type = self.simulation.particle_type();
+                        let (mut mass, mut spring_constant, mut damping, mut temperature) = (
+                            self.simulation.particle_mass(),
+                            self.simulation.spring_constant(),
+                            self.simulation.damping(),
+                            self.simulation.temperature(),
+                        );
+                        ui.label("M").on_hover_text(tr("Particle mass"));
+                        // presets may go beyond the ranges of the sliders, so values are only kept to them when edited
+                        ui.add(
+                            egui::Slider::new(
+                                &mut mass,
+                                ELECTRON_MASS.min..=ELECTRON_MASS.max,
+                            )
+                            .clamping(egui::SliderClamping::Edits),
+                        );
+                        if ui.button("↺").on_hover_text(tr("Reset")).clicked() {
+                            mass = particle_type.mass();
+                        }
+
+                        ui.separator();
+
+                        ui.label("k").on_hover_text(tr("Particle spring constant"));
+                        ui.add(
+                            egui::Slider::new(
+                                &mut spring_constant,
+                                SPRING_CONSTANT.min..=SPRING_CONSTANT.max,
+                            )
+                            .clamping(egui::SliderClamping::Edits),
+                        );
+                        if ui.button("↺").on_hover_text(tr("Reset")).clicked() {
+                            spring_constant = particle_type.default_spring_constant();
+                        }
+
+                        ui.separator();
+
+                        ui.label(tr("Damping"))
+                            .on_hover_text(tr("Particle motion damping factor"));
+                        ui.add(
+                            egui::Slider::new(
+                                &mut damping,
+                                ELECTRON_DAMPING.min..=ELECTRON_DAMPING.max,
+                            )
+                            .clamping(egui::SliderClamping::Edits),
+                        );
+                        if ui.button("↺").on_hover_text(tr("Reset")).clicked() {
+                            damping = particle_type.default_damping();
+                        }
+
+                        ui.separator();
+
+                        ui.label("T").on_hover_text(tr(
+                            "Temperature of the heat bath giving the particles thermal motion, \
+                            through their damping",
+                        ));
+                        ui.add(
+                            egui::Slider::new(&mut temperature, TEMPERATURE.min..=TEMPERATURE.max)
+                                .clamping(egui::SliderClamping::Edits),
+                        );
+                        if ui.button("↺").on_hover_text(tr("Reset")).clicked() {
+                            temperature = TEMPERATURE.initial;
+                        }
+                        for (value, current, command) in [
+                            (mass, self.simulation.particle_mass(), SimulationCommand::Mass(mass)),
+                            (
+                                spring_constant,
+                                self.simulation.spring_constant(),
+                                SimulationCommand::SpringConstant(spring_constant),
+                            ),
+                            (damping, self.simulation.damping(), SimulationCommand::Damping(damping)),
+                            (
+                                temperature,
+                                self.simulation.temperature(),
+                                SimulationCommand::Temperature(temperature),
+                            ),
+                        ] {
+                            if value != current {
+                                self.simulation.send(command);
+                            }
+                        }
+
+                        ui.separator();
+
+                        // how changes to the properties above reach particles that are already moving
+                        ui.label(tr("Changes:")).on_hover_text(tr(
+                            "When changes to mass, k and damping reach the particles while the simulation runs",
+                        ));
+                        let mut changes = self.simulation.parameter_changes();
+                        let steps = match changes {
+                            ParameterChanges::Ramped { steps } => steps,
+                            _ => DEFAULT_RAMP_STEPS,
+                        };
+                        egui::ComboBox::from_id_salt("ParameterChanges")
+                            .selected_text(tr(changes.name()))
+                            .show_ui(ui, |ui| {
+                                for option in [
+                                    ParameterChanges::Immediate,
+                                    ParameterChanges::Ramped { steps },
+                                    ParameterChanges::OnRestart,
+                                ] {
+                                    ui.selectable_value(&mut changes, option, tr(option.name()));
+                                }
+                            });
+                        if let ParameterChanges::Ramped { steps } = &mut changes {
+                            ui.add(
+                                egui::DragValue::new(steps)
+                                    .range(1..=10 * DEFAULT_RAMP_STEPS)
+                                    .suffix(tr(" steps")),
+                            )
+                            .on_hover_text(tr("Number of steps changes are ramped in over"));
+                        }
+                        if changes != self.simulation.parameter_changes() {
+                            self.simulation.send(SimulationCommand::ParameterChanges(changes));
+                        }
+                        if self.simulation.pending_changes() {
+                            ui.label(egui::RichText::new(tr("⏳ pending")).color(Color32::YELLOW))
+                                .on_hover_text(tr(
+                                    "The particles haven't reached the properties set yet",
+                                ));
+                        }
+                    })
+                    .response
+                    .rect;
+                self.tour
+                    .register(TourTarget::ParticleProperties, properties_rect);
+            }
+            Section::Opacities => {
+                ui.label(tr("Field opacities:"));
+                ui.label(
+                    egui::RichText::new("◼")
+                        .color(applied_field_colour(0.7, &self.simulation.waveform())),
+                )
+                .on_hover_text(tr("Initial electric field"));
+                ui.add(egui::Slider::new(
+                    &mut self.settings.applied_field_opacity,
+                    0.0..=1.0,
+                ));
+                ui.label(egui::RichText::new("◼").color(resultant_field_colour(0.7)))
+                    .on_hover_text(tr("Resultant electric field"));
+                ui.add(egui::Slider::new(
+                    &mut self.settings.resultant_field_opacity,
+                    0.0..=1.0,
+                ));
+                ui.checkbox(&mut self.settings.colour_by_phase, tr("Phase"))
+                    .on_hover_text(tr(
+                        "Colour the resultant field by its instantaneous phase. \
+                        The colours cycle faster where the wavelength is shorter.",
+                    ));
+                ui.checkbox(&mut self.settings.stacked_traces, tr("Stacked"))
+                    .on_hover_text(tr(
+                        "Draw the applied, induced and resultant fields in separate lanes, each with its own zero line",
+                    ));
+                ui.checkbox(&mut self.settings.filled_traces, tr("Filled"))
+                    .on_hover_text(tr(
+                        "Fill the area between each field and its zero line, lighter where the field is positive \
+                        and darker where it is negative, which reads better than thin lines when projected or printed small",
+                    ));
+                ui.checkbox(&mut self.settings.show_steady_state, tr("Theory"))
+                    .on_hover_text(tr(
+                        "For a plane wave, draw the resultant field the simulation settles to, calculated analytically, as a dashed line. \
+                        It assumes the particles move little compared to their distance from each point, so is most accurate far from them, \
+                        and can't be calculated for particles driven at resonance with no damping.",
+                    ));
+                ui.checkbox(&mut self.settings.show_attenuation, tr("Attenuation"))
+                    .on_hover_text(tr(
+                        "Fit an exponential to the intensity between the first and last particles, \
+                        and show the attenuation coefficient: the fraction of intensity absorbed per unit distance",
+                    ));
+                ui.checkbox(&mut self.settings.show_envelope, tr("Envelope"))
+                    .on_hover_text(tr(
+                        "Outline the resultant field with its envelope, which shows how pulses are delayed and spread out by the medium",
+                    ));
+                if ui
+                    .button(tr("Snapshot"))
+                    .on_hover_text(tr(
+                        "Freeze the resultant field as a ghost trace. \
+                        After changing something and running again, the simulation pauses at the same time to compare the two.",
+                    ))
+                    .clicked()
+                {
+                    self.snapshot = Some(Snapshot::take(&self.simulation));
+                }
+                if let Some(time) = self.snapshot.as_ref().map(Snapshot::time) {
+                    ui.label(
+                        egui::RichText::new(format!("t = {time:.2} s")).color(snapshot_colour(1.0)),
+                    );
+                    if ui
+                        .button("✖")
+                        .on_hover_text(tr("Remove the snapshot"))
+                        .clicked()
+                    {
+                        self.snapshot = None;
+                    }
+                }
+                ui.menu_button(tr("Reference"), |ui| {
+                    for kind in [ReferenceKind::Field, ReferenceKind::Dispersion] {
+                        if ui.button(tr(kind.name())).clicked() {
+                            self.load_reference(kind);
+                            ui.close_menu();
+                        }
+                    }
+                })
+                .response
+                .on_hover_text(tr(
+                    "Draw two columns of numbers from a CSV file as a reference trace: a field against x on the canvas, \
+                    or a refractive index against ω on the dispersion plot of the Phasors panel",
+                ));
+                for kind in [ReferenceKind::Field, ReferenceKind::Dispersion] {
+                    let reference = self.references.get_mut(kind);
+                    if let Some(name) = reference.as_ref().map(|r| r.name.clone()) {
+                        ui.label(egui::RichText::new(name).color(reference_colour(1.0)))
+                            .on_hover_text(tr(kind.name()));
+                        if ui
+                            .button("✖")
+                            .on_hover_text(tr("Remove the reference"))
+                            .clicked()
+                        {
+                            *reference = None;
+                        }
+                    }
+                }
+                ui.label(egui::RichText::new("◼").color(particle_field_colour(0.7)))
+                    .on_hover_text(tr("Induced electric field of particles"));
+                ui.add(egui::Slider::new(
+                    &mut self.settings.particle_field_opacity,
+                    0.0..=1.0,
+                ));
+                ui.label(egui::RichText::new("◼").color(scattered_field_colour(0.7)))
+                    .on_hover_text(tr(
+                        "Scattered field: the resultant minus the applied field, radiated by all the particles together",
+                    ));
+                ui.add(egui::Slider::new(
+                    &mut self.settings.scattered_field_opacity,
+                    0.0..=1.0,
+                ));
+                ui.label(egui::RichText::new("◼").color(intensity_colour(0.7)))
+                    .on_hover_text(tr(
+                        "Intensity: the resultant field squared, averaged over the last few seconds",
+                    ));
+                ui.add(egui::Slider::new(
+                    &mut self.settings.intensity_opacity,
+                    0.0..=1.0,
+                ));
+            }
+            Section::Options => {
+                ui.checkbox(
+                    &mut self.settings.pause_in_background,
+                    tr("Pause in background"),
+                )
+                .on_hover_text(tr(
+                    "Stop the simulation while the window is minimised or not focused, to save power",
+                ));
+                ui.checkbox(
+                    &mut self.settings.match_refresh_rate,
+                    tr("Match refresh rate"),
+                )
+                .on_hover_text(tr(
+                    "Update the simulation once per frame of the display, for smooth motion on high refresh rate displays, \
+                    rather than 60 times a second. Takes effect when the simulation restarts",
+                ));
+                ui.checkbox(
+                    &mut self.settings.smooth_slow_motion,
+                    tr("Smooth slow motion"),
+                )
+                .on_hover_text(tr(
+                    "Below normal speed, draw the particles and fields between simulation updates, \
+                    rather than holding them still until the next update",
+                ));
+                ui.checkbox(&mut self.settings.large_controls, tr("Large controls"))
+                    .on_hover_text(tr(
+                        "Bigger buttons and sliders for touch screens. Switched on the first time the screen is touched",
+                    ));
+                ui.label(tr("UI scale")).on_hover_text(tr(
+                    "Enlarge the text, controls and traces of the whole interface. Ctrl and + or - change it too",
+                ));
+                ui.add(
+                    egui::Slider::new(&mut self.settings.ui_scale, UI_SCALE)
+                        .step_by(0.05)
+                        .suffix("×"),
+                );
+                if ui
+                    .button(tr("📽 Projector"))
+                    .on_hover_text(tr(
+                        "Enlarge everything to be readable from the back of a lecture hall, with large controls",
+                    ))
+                    .clicked()
+                {
+                    self.settings.use_projector_profile();
+                }
+            }
+            Section::Explain => {
+                ui.checkbox(&mut self.settings.explain, tr("Explain"))
+                    .on_hover_text(tr(
+                        "Show the equations being solved, with their current values",
+                    ));
+            }
+            Section::Tour => {
+                if ui
+                    .add_enabled(!self.tour.is_running(), egui::Button::new(tr("Tour")))
+                    .on_hover_text(tr("Guided tour of the controls and the physics"))
+                    .clicked()
+                {
+                    let action = self.tour.start();
+                    self.apply_tour_action(action);
+                }
+            }
+            Section::Appearance => {
+                let mut language = i18n::language();
+                egui::ComboBox::from_id_salt("Language")
+                    .selected_text(language.name())
+                    .show_ui(ui, |ui| {
+                        for option in Language::iter() {
+                            ui.selectable_value(&mut language, option, option.name());
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr("Language"));
+                if language != i18n::language() {
+                    i18n::set_language(language);
+                    self.settings.language = Some(language);
+                }
+
+                if ui
+                    .button(if self.settings.dark_theme {
+                        "☀"
+                    } else {
+                        "🌙"
+                    })
+                    .on_hover_text(tr("Switch between dark and light theme"))
+                    .clicked()
+                {
+                    self.settings.dark_theme = !self.settings.dark_theme;
+                    ctx.set_visuals(self.settings.visuals());
+                }
+
+                let mut selected = self.settings.palette;
+                egui::ComboBox::from_id_salt("Palette")
+                    .selected_text("🎨")
+                    .show_ui(ui, |ui| {
+                        for option in Palette::iter() {
+                            ui.selectable_value(&mut selected, option, tr(option.name()));
+                        }
+                        ui.separator();
+                        ui.checkbox(&mut self.settings.line_styles, tr("Line styles"))
+                            .on_hover_text(tr(
+                                "Draw the initial, induced and scattered fields and the intensity in dashed and dotted lines, \
+                                so they can be told apart without colour",
+                            ));
+                    })
+                    .response
+                    .on_hover_text(tr("Colour palette"));
+                if selected != self.settings.palette {
+                    self.settings.palette = selected;
+                    palette::set_palette(selected);
+                }
+
+                egui::ComboBox::from_id_salt("Lines")
+                    .selected_text("〰")
+                    .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+                    .show_ui(ui, |ui| {
+                        egui::Grid::new("TraceStyles")
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for trace in Trace::iter() {
+                                    let style = self.settings.trace_styles.get_mut(trace);
+                                    ui.label(tr(trace.name()));
+                                    ui.add(
+                                        egui::Slider::new(&mut style.width, TRACE_WIDTH)
+                                            .suffix("×"),
+                                    )
+                                    .on_hover_text(tr("Width, relative to the usual width"));
+                                    egui::ComboBox::from_id_salt(("Dash", trace))
+                                        .selected_text(tr(style.dash.name()))
+                                        .show_ui(ui, |ui| {
+                                            for dash in LineDash::iter() {
+                                                ui.selectable_value(
+                                                    &mut style.dash,
+                                                    dash,
+                                                    tr(dash.name()),
+                                                );
+                                            }
+                                        });
+                                    ui.end_row();
+                                }
+                            });
+                        ui.separator();
+                        let feathering = ui
+                            .add(
+                                egui::Slider::new(&mut self.settings.feathering, FEATHERING)
+                                    .text(tr("Anti-aliasing"))
+                                    .suffix(" px"),
+                            )
+                            .on_hover_text(tr(
+                                "Width of the softened edges of lines, 0 for hard edges. \
+                                Exported images are anti-aliased unless it is 0",
+                            ));
+                        if feathering.changed() {
+                            self.settings.apply_feathering(ctx);
+                        }
+                        if ui.button(tr("Reset")).clicked() {
+                            self.settings.trace_styles = Default::default();
+                            self.settings.feathering = Settings::default().feathering;
+                            self.settings.apply_feathering(ctx);
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr("Line widths, dashes and anti-aliasing"));
+
+                if ui
+                    .button("⛶")
+                    .on_hover_text(tr("Presentation mode (F11)"))
+                    .clicked()
+                {
+                    *present = true;
+                }
+                ui.toggle_value(&mut self.laser.active, "✏")
+                    .on_hover_text(tr(
+                        "Laser pointer (L): press or drag on the canvas to point things out with a halo or a stroke \
+                        that fades away, instead of moving the view",
+                    ));
+            }
+        }
+    }
+
+    // time between simulation updates, one frame of the display if matching its refresh rate, so that each frame
+    // has the same number of updates rather than judder from some having more than others
+    fn time_step(&self) -> f32 {
+        let rate = self
+            .frame_stats
+            .measured_refresh_rate()
+            .filter(|rate| {
+                self.settings.match_refresh_rate
+                    && (MIN_MATCHED_RATE..=MAX_MATCHED_RATE).contains(rate)
+            })
+            .unwrap_or(SIMULATION_FPS as f32);
+        1.0 / rate
+    }
+
+    // clears the analysis gathered from the run so far, such as plots of history, to start again from the
+    // simulation's current state
+    fn reset_trackers(&mut self) {
+        self.energy.clear();
+        self.surface.clear();
+        self.phasors.clear();
+        self.noise.clear();
+        self.spectrum.clear();
+        self.extinction.clear();
+        self.pulse.clear();
+        #[cfg(feature = "scripting")]
+        self.diagnostics.clear();
+    }
+
+    // performs one simulation update, returning true if the simulation has ended
+    fn memory_use(&self) -> status::MemoryUse {
+        status::MemoryUse {
+            history: self.simulation.history_memory(),
+            recording: self.recorder.as_ref().map_or(0, Recorder::memory),
+            surface: self.surface.memory(),
+        }
+    }
+
+    // whether updates are drawn in between, below normal speed
+    fn slow_motion(&self) -> bool {
+        self.settings.smooth_slow_motion && !self.timelapse && self.speed < 1.0
+    }
+
+    fn advance(&mut self) -> bool {
+        if self
+            .player
+            .as_mut()
+            .is_some_and(|player| !player.apply(&mut self.simulation))
+        {
+            // the end of the recorded run
+            self.paused = true;
+            return false;
+        }
+        // updates made to catch up with a jump aren't seen, so aren't recorded or exported
+        let jumping = self.jump_target.is_some();
+        if !jumping
+            && self
+                .recorder
+                .as_mut()
+                .is_some_and(|recorder| !recorder.record(&self.simulation))
+        {
+            self.stop_recording();
+        }
+        match !jumping && self.slow_motion() {
+            true => self.tween.record(&self.simulation),
+            false => self.tween.clear(),
+        }
+        // analysis is only gathered for the panels showing it, as some of it, such as the pulse's envelope, is
+        // costly every update. a closed panel's analysis is cleared, so it starts afresh rather than with a gap
+        let panels = &self.settings;
+        if !panels.phasor_panel.open {
+            self.phasors.clear();
+        }
+        if !panels.noise_panel.open {
+            self.noise.clear();
+        }
+        if !panels.spectrum_panel.open {
+            self.spectrum.clear();
+        }
+        if !panels.extinction_panel.open {
+            self.extinction.clear();
+        }
+        if !panels.pulse_panel.open {
+            self.pulse.clear();
+        }
+        let mut phasors = |simulation: &Simulation| self.phasors.record(simulation, panels.probe_x);
+        let mut noise = |simulation: &Simulation| self.noise.record(simulation, panels.probe_x);
+        let mut spectrum =
+            |simulation: &Simulation| self.spectrum.record(simulation, panels.probe_x);
+        let mut observers: Vec<&mut dyn StepObserver> = vec![&mut self.energy, &mut self.surface];
+        if panels.phasor_panel.open {
+            observers.push(&mut phasors);
+        }
+        if panels.noise_panel.open {
+            observers.push(&mut noise);
+        }
+        if panels.spectrum_panel.open {
+            observers.push(&mut spectrum);
+        }
+        if panels.extinction_panel.open {
+            observers.push(&mut self.extinction);
+        }
+        if panels.pulse_panel.open {
+            observers.push(&mut self.pulse);
+        }
+        // a script runs whenever one is loaded, so its channels are complete when exported
+        #[cfg(feature = "scripting")]
+        observers.extend(self.diagnostics.observer());
+        let before = self.simulation.time();
+        let finished = self.simulation.update_observed(&mut observers);
+        let exported = match jumping {
+            true => Ok(()),
+            false => self.frame_export.record(&self.simulation, &self.settings),
+        };
+        if let Err(e) = exported {
+            self.error = Some(format!("{}\n{e}", tr("Could not export image")));
+            self.frame_export.stop();
+        }
+        if self
+            .snapshot
+            .as_ref()
+            .is_some_and(|snapshot| snapshot.reached(before, self.simulation.time()))
+        {
+            // stop at the moment the snapshot was taken, so the fields are compared at the same time
+            self.paused = true;
+        }
+        if self.quiz.reached(before, self.simulation.time()) {
+            // stop for the quiz's question, and again to show its answer
+            self.paused = true;
+        }
+        if self.simulation.divergence().is_some() {
+            // stop where the simulation blew up, so the values that diverged can be looked at
+            self.paused = true;
+        }
+        if self.simulation.limiter_paused() {
+            // stop as the motion leaves the bounds, rather than letting it grow off the canvas
+            self.paused = true;
+        }
+        if let Some(start) = self.looping.rewind(&self.simulation) {
+            // back to the start of the loop, with the plots of history starting again from there
+            self.simulation = start;
+            self.reset_trackers();
+        }
+        finished
+    }
+
+    // jumps to a time, starting from a copy of the simulation kept by a bookmark or from the current state,
+    // whichever is latest without being after the time, otherwise running again from the start.
+    // the simulation is run forward to the time over the following frames, by catch_up
+    fn jump_to(&mut self, time: f32) {
+        self.looping.clear();
+        let current = self.simulation.time();
+        let checkpoint = self
+            .bookmarks
+            .checkpoint(&self.simulation, time)
+            .filter(|checkpoint| current > time || checkpoint.time() > current)
+            .cloned();
+        match checkpoint {
+            Some(checkpoint) => {
+                self.simulation = checkpoint;
+                self.reset_trackers();
+            }
+            None if current > time => self.restart(),
+            None => {}
+        }
+        self.paused = true;
+        self.jump_target = Some(time);
+    }
+
+    // runs the simulation towards the time being jumped to for up to JUMP_FRAME_BUDGET, so a long jump doesn't
+    // freeze the window. returns the number of updates made
+    fn catch_up(&mut self) -> u32 {
+        let Some(time) = self.jump_target else {
+            return 0;
+        };
+        let deadline = Instant::now() + JUMP_FRAME_BUDGET;
+        let mut updates = 0;
+        let reached = loop {
+            if self.simulation.time() >= time - self.simulation.time_step() / 2.0 {
+                break true;
+            }
+            if self.advance() || self.simulation.divergence().is_some() {
+                break true;
+            }
+            updates += 1;
+            if Instant::now() >= deadline {
+                break false;
+            }
+        };
+        if reached {
+            self.jump_target = None;
+        }
+        self.frame = self.simulation.steps() as u32;
+        updates
+    }
+
+    // advances the paused simulation by frame_skip updates, returning the number made
+    fn step(&mut self) -> u32 {
+        for _ in 0..self.frame_skip {
+            self.advance();
+            if self.simulation.divergence().is_some() {
+                break;
+            }
+        }
+        self.frame_skip
+    }
+
+    // restarts the simulation and records the run from the beginning
+    fn start_recording(&mut self) {
+        self.player = None;
+        self.restart();
+        self.recorder = Some(Recorder::start(&self.simulation));
+        self.paused = false;
+    }
+
+    // stops recording, asking where to save the run if anything was recorded
+    fn stop_recording(&mut self) {
+        let Some(recorder) = self.recorder.take() else {
+            return;
+        };
+        if recorder.steps() == 0 {
+            return;
+        }
+        let replay = recorder.finish();
+        self.save_file_dialog(tr("Replay"), REPLAY_EXTENSION, |_, path| replay.save(path));
+    }
+
+    // opens a recorded run, to be played through from the beginning
+    fn play_replay(&mut self, replay: Replay) -> Result<(), String> {
+        let player = Player::new(replay);
+        self.simulation.apply_scene(player.scene())?;
+        self.settings.last_preset = None;
+        self.restart();
+        self.player = Some(player);
+        Ok(())
+    }
+
+    // shows the whole simulation again
+    fn reset_view(&mut self) {
+        self.settings.zoom = 1.0;
+        self.settings.world_centre = self.simulation.size().center();
+        self.settings.y_zoom = 1.0;
+        self.settings.y_centre = 0.0;
+        self.zoom_centre = None;
+        self.dragging = None;
+        self.box_zoom = None;
+        self.box_select = None;
+    }
+
+    // menu shown when right-clicking the canvas, at a position in view coordinates
+    fn canvas_context_menu(&mut self, ui: &mut egui::Ui, pos: Pos2) {
+        if ui.button(tr("Add particle here")).clicked() {
+            self.simulation.add_particle(pos.x);
+            ui.close_menu();
+        }
+        if ui.button(tr("Place probe here")).clicked() {
+            self.settings.probe_x = pos.x;
+            self.settings.phasor_panel.open = true;
+            ui.close_menu();
+        }
+        if ui.button(tr("Add marker")).clicked() {
+            self.settings.markers.push(pos.x);
+            ui.close_menu();
+        }
+        if !self.settings.markers.is_empty() && ui.button(tr("Remove markers")).clicked() {
+            self.settings.markers.clear();
+            ui.close_menu();
+        }
+        ui.separator();
+        if ui
+            .add_enabled(
+                !self.selected_particles.is_empty(),
+                egui::Button::new(tr("Copy selected particles")),
+            )
+            .on_hover_text(tr("Ctrl+C with the pointer over the canvas"))
+            .clicked()
+        {
+            self.copy_particles(ui.ctx());
+            ui.close_menu();
+        }
+        let copied = self.copied_particles.as_ref().map_or(0, Arrangement::len);
+        if copied > 0
+            && ui
+                .button(format!("{} ({copied})", tr("Paste particles here")))
+                .on_hover_text(tr("Ctrl+V with the pointer over the canvas"))
+                .clicked()
+        {
+            self.paste_particles(pos.x);
+            ui.close_menu();
+        }
+        if ui
+            .button(tr("Copy coordinates"))
+            .on_hover_text(tr(
+                "Copy the x coordinate and the position on the vertical axis",
+            ))
+            .clicked()
+        {
+            ui.ctx().copy_text(format!("{:.3}, {:.3}", pos.x, pos.y));
+            ui.close_menu();
+        }
+        if ui.button(tr("Reset view")).clicked() {
+            self.reset_view();
+            ui.close_menu();
+        }
+    }
+
+    // the positions of the copied particles also go to the clipboard, replacing anything there such as a link,
+    // so that pasting is taken to be pasting the particles
+    fn copy_particles(&mut self, ctx: &egui::Context) {
+        if let Some(copied) = self.simulation.copy_arrangement(&self.selected_particles) {
+            self.copied_particles = Some(copied);
+            let positions: Vec<String> = self
+                .simulation
+                .particles()
+                .iter()
+                .filter(|p| self.selected_particles.contains(&p.id()))
+                .map(|p| format!("{:.3}", p.position().x))
+                .collect();
+            ctx.copy_text(positions.join(", "));
+        }
+    }
+
+    // copies a link to the current scene and view to the clipboard
+    fn copy_link(&mut self, ctx: &egui::Context) {
+        let link = Link {
+            scene: self.simulation.scene(),
+            view: View::of(&self.settings),
+        };
+        match link.encode() {
+            Ok(text) => ctx.copy_text(text),
+            Err(e) => self.error = Some(format!("{}\n{e}", tr("Could not copy the link"))),
+        }
+    }
+
+    fn open_link(&mut self, link: Link) {
+        match self.apply_scene(&link.scene) {
+            Ok(()) => link.view.apply(&mut self.settings),
+            Err(e) => self.error = Some(format!("{}\n{e}", tr("Could not open the link"))),
+        }
+    }
+
+    // pastes the copied particles with the leftmost at x, and selects them so they can be pasted again further on
+    fn paste_particles(&mut self, x: f32) {
+        if let Some(copied) = &self.copied_particles {
+            self.selected_particles = self.simulation.paste_arrangement(copied, x);
+        }
+    }
+
+    // centres the view on a point on the x axis, zoomed in to show FOCUS_SPAN around it
+    fn focus_on(&mut self, x: f32) {
+        let size = self.simulation.size();
+        self.settings.zoom = (size.span() / FOCUS_SPAN).max(1.0);
+        let half_span = size.span() / (2.0 * self.settings.zoom);
+        self.settings.world_centre = x.clamp(size.min + half_span, size.max - half_span);
+    }
+
+    // zooms to exactly the box between two corners, given in view coordinates. the vertical axis is only
+    // zoomed if zoom_y is true
+    fn zoom_to_box(&mut self, corner: Pos2, opposite_corner: Pos2, zoom_y: bool) {
+        let selection = Rect::from_two_pos(corner, opposite_corner);
+        let size = self.simulation.size();
+        self.settings.zoom = (size.span() / selection.width()).max(1.0);
+        let half_span = size.span() / (2.0 * self.settings.zoom);
+        self.settings.world_centre = selection
+            .center()
+            .x
+            .clamp(size.min + half_span, size.max - half_span);
+        if zoom_y {
+            self.settings.y_zoom = (VISIBLE_Y_SPAN / selection.height()).max(1.0);
+            let limit = (VISIBLE_Y_SPAN - VISIBLE_Y_SPAN / self.settings.y_zoom) / 2.0;
+            self.settings.y_centre = selection.center().y.clamp(-limit, limit);
+        }
+    }
+
+    fn load_preset(&mut self, preset: Preset) {
+        self.player = None;
+        self.simulation.apply_preset(preset);
+        self.settings.last_preset = Some(preset);
+        if let Some(probe) = preset.properties().probe {
+            self.settings.probe_x = probe;
+            self.settings.phasor_panel.open = true;
+        }
+        if preset.properties().delay {
+            self.settings.pulse_panel.open = true;
+        }
+        if preset.properties().scattering {
+            self.settings.scattered_field_opacity = self.settings.scattered_field_opacity.max(0.8);
+            self.settings.scattering_panel.open = true;
+        }
+        if preset.properties().attenuation {
+            self.settings.intensity_opacity = self.settings.intensity_opacity.max(0.8);
+            self.settings.show_attenuation = true;
+        }
+        self.restart();
+    }
+
+    // takes one of the actions of a scripted demo
+    fn apply_demo_action(&mut self, action: Action) {
+        match action {
+            Action::LoadPreset(preset) => self.load_preset(preset),
+            Action::Play => self.paused = false,
+            Action::Pause => self.paused = true,
+            Action::Restart => self.restart(),
+            Action::Speed(speed) => self.speed = speed.clamp(MIN_SPEED, MAX_SPEED),
+            Action::Opacity(trace, opacity) => {
+                let setting = match trace {
+                    Trace::Applied => &mut self.settings.applied_field_opacity,
+                    Trace::Resultant => &mut self.settings.resultant_field_opacity,
+                    Trace::Induced => &mut self.settings.particle_field_opacity,
+                    Trace::Intensity => &mut self.settings.intensity_opacity,
+                    Trace::Scattered => &mut self.settings.scattered_field_opacity,
+                };
+                *setting = opacity.clamp(0.0, 1.0);
+            }
+            Action::ZoomTo(min, max) => self.zoom_to_box(pos2(min, 0.0), pos2(max, 0.0), false),
+            Action::ResetView => self.reset_view(),
+            // annotations and waits are handled by the runner
+            Action::Annotate(_) | Action::WaitUntil(_) | Action::WaitSeconds(_) => {}
+        }
+    }
+
+    fn set_presenting(&mut self, ctx: &egui::Context, presenting: bool) {
+        self.presenting = presenting;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(presenting));
+    }
+
+    // asks whether to restore a session that ended unexpectedly
+    fn show_recovery_prompt(&mut self, ctx: &egui::Context) {
+        let Some(recovered) = &self.recovered else {
+            return;
+        };
+        let mut restore = None;
+        egui::Modal::new(egui::Id::new("recovery")).show(ctx, |ui| {
+            ui.heading(tr("Restore previous session?"));
+            ui.label(tr(
+                "Refraction did not close normally last time. Your previous setup and settings can be restored.",
+            ));
+            ui.horizontal(|ui| {
+                if ui.button(tr("Restore")).clicked() {
+                    restore = Some(true);
+                }
+                if ui.button(tr("Discard")).clicked() {
+                    restore = Some(false);
+                }
+            });
+        });
+        match restore {
+            Some(true) => {
+                let recovered = recovered.clone();
+                if let Err(e) = self.apply_session(ctx, recovered) {
+                    self.error = Some(format!("{}\n{e}", tr("Could not restore the session")));
+                }
+                self.recovered = None;
+            }
+            Some(false) => self.recovered = None,
+            None => (),
+        }
+    }
+
+    // replaces the simulation setup and settings with those of a session, leaving both as they were if its
+    // scene is rejected. the list of recent files and the saved layouts belong to this installation, so are kept
+    // rather than replaced
+    fn apply_session(&mut self, ctx: &egui::Context, session: Session) -> Result<(), String> {
+        self.simulation.apply_scene(&session.scene)?;
+        let recent_files = std::mem::take(&mut self.settings.recent_files);
+        let saved_layouts = std::mem::take(&mut self.settings.saved_layouts);
+        self.settings = session.settings;
+        self.settings.recent_files = recent_files;
+        self.settings.saved_layouts = saved_layouts;
+        ctx.set_visuals(self.settings.visuals());
+        self.settings.apply_feathering(ctx);
+        i18n::set_language(
+            self.settings
+                .language
+                .unwrap_or_else(Language::from_environment),
+        );
+        palette::set_palette(self.settings.palette);
+        self.player = None;
+        self.restart();
+        Ok(())
+    }
+
+    fn current_session(&self) -> Session {
+        Session {
+            scene: self.simulation.scene(),
+            settings: self.settings.clone(),
+        }
+    }
+
+    // carries on from a simulation made elsewhere, such as one restored from a checkpoint or set up from a
+    // manifest, paused as it is
+    fn start_from(&mut self, simulation: Simulation) {
+        self.stop_recording();
+        self.frame_export.stop();
+        self.looping.clear();
+        self.jump_target = None;
+        self.player = None;
+        self.settings.last_preset = None;
+        self.paused = true;
+        self.accumulated_time = 0.0;
+        self.frame = simulation.steps() as u32;
+        self.simulation = simulation;
+        self.reset_trackers();
+    }
+
+    // menu items for saving checkpoints of the running simulation, and resuming from them
+    #[cfg(feature = "serde-state")]
+    fn checkpoint_menu(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        if ui.button(tr("Save checkpoint…")).clicked() {
+            ui.close_menu();
+            self.save_file_dialog(tr("Checkpoint"), CHECKPOINT_EXTENSION, |app, path| {
+                checkpoint::save(&app.simulation, path)
+            });
+        }
+        ui.horizontal(|ui| {
+            let mut periodic = self.checkpointer.is_some();
+            if ui
+                .checkbox(&mut periodic, tr("Save checkpoints every"))
+                .on_hover_text(tr(
+                    "Save the whole state of the simulation to a file as it runs, to resume a long run from \
+                    after a crash or in a later session",
+                ))
+                .changed()
+            {
+                self.checkpointer = match periodic {
+                    true => rfd::FileDialog::new()
+                        .add_filter(tr("Checkpoint"), &[CHECKPOINT_EXTENSION])
+                        .set_file_name(format!("{}.{CHECKPOINT_EXTENSION}", tr("Untitled")))
+                        .save_file()
+                        .map(|path| Checkpointer::new(path, self.checkpoint_minutes)),
+                    false => None,
+                };
+            }
+            let minutes = ui.add(
+                egui::DragValue::new(&mut self.checkpoint_minutes)
+                    .range(0.5..=1440.0)
+                    .suffix(" min"),
+            );
+            if minutes.changed() {
+                if let Some(checkpointer) = &mut self.checkpointer {
+                    checkpointer.set_interval(self.checkpoint_minutes);
+                }
+            }
+        });
+        if let Some(checkpointer) = &self.checkpointer {
+            ui.label(format!(
+                "{} {}",
+                tr("Saving to"),
+                session::display_name(&checkpointer.path)
+            ))
+            .on_hover_text(checkpointer.path.display().to_string());
+        }
+        if ui.button(tr("Resume from checkpoint…")).clicked() {
+            ui.close_menu();
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter(tr("Checkpoint"), &[CHECKPOINT_EXTENSION])
+                .pick_file()
+            {
+                self.open_file(ctx, path);
+            }
+        }
+    }
+
+    // sets a run up again from the start, from its manifest, noting any way this build differs from the one that
+    // exported it
+    fn apply_manifest(&mut self, manifest: Manifest) -> Result<(), String> {
+        let simulation = Simulation::from_config(&manifest.config())?;
+        self.settings.memory_cap = manifest.memory_cap;
+        self.start_from(simulation);
+        let differences = manifest.differences();
+        if !differences.is_empty() {
+            self.error = Some(format!(
+                "{}\n{}",
+                tr(
+                    "This app differs from the one that exported the manifest, so may not reproduce the run exactly:"
+                ),
+                differences.join("\n")
+            ));
+        }
+        Ok(())
+    }
+
+    fn apply_scene(&mut self, scene: &Scene) -> Result<(), String> {
+        self.simulation.apply_scene(scene)?;
+        self.player = None;
+        self.settings.last_preset = None;
+        self.restart();
+        Ok(())
+    }
+
+    // opens a session, scene or sampled waveform file, depending on its extension
+    fn open_file(&mut self, ctx: &egui::Context, path: PathBuf) {
+        let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        let result = match extension.as_deref() {
+            Some(SESSION_EXTENSION) => {
+                Session::open(&path).and_then(|session| self.apply_session(ctx, session))
+            }
+            Some(SCENE_EXTENSION) => Scene::open(&path).and_then(|scene| self.apply_scene(&scene)),
+            Some(MANIFEST_EXTENSION) => {
+                Manifest::open(&path).and_then(|manifest| self.apply_manifest(manifest))
+            }
+            #[cfg(feature = "serde-state")]
+            Some(CHECKPOINT_EXTENSION) => {
+                checkpoint::open(&path).map(|simulation| self.start_from(simulation))
+            }
+            Some(DEMO_EXTENSION) => Demo::open(&path).map(|demo| {
+                self.demo = Some(DemoRunner::new(demo));
+            }),
+            Some(REPLAY_EXTENSION) => {
+                Replay::open(&path).and_then(|replay| self.play_replay(replay))
+            }
+            Some(WAVE_EXTENSION) => std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| SampledWave::from_csv(session::display_name(&path), &text))
+                .map(|wave| {
+                    self.player = None;
+                    self.simulation.set_sampled_wave(wave);
+                    self.restart();
+                }),
+            _ => Err(tr("Unrecognised type of file").to_owned()),
+        };
+        match result {
+            Ok(()) => self.settings.add_recent_file(path),
+            Err(e) => {
+                self.error = Some(format!("{} {}:\n{e}", tr("Could not open"), path.display()));
+            }
+        }
+    }
+
+    // asks for a file of reference data to draw, replacing any reference of the same kind
+    fn load_reference(&mut self, kind: ReferenceKind) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr("Comma-separated values"), &["csv", "txt"])
+            .pick_file()
+        else {
+            return;
+        };
+        match ReferenceTrace::open(&path) {
+            Ok(reference) => *self.references.get_mut(kind) = Some(reference),
+            Err(e) => {
+                self.error = Some(format!("{} {}:\n{e}", tr("Could not open"), path.display()));
+            }
+        }
+    }
+
+    fn open_file_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter(
+                tr("Sessions, scenes, waveforms and replays"),
+                &[
+                    SESSION_EXTENSION,
+                    SCENE_EXTENSION,
+                    WAVE_EXTENSION,
+                    REPLAY_EXTENSION,
+                    DEMO_EXTENSION,
+                    MANIFEST_EXTENSION,
+                ],
+            )
+            .pick_file()
+        {
+            self.open_file(ctx, path);
+        }
+    }
+
+    // asks where to save a file, then saves it there
+    fn save_file_dialog(
+        &mut self,
+        file_type: &str,
+        extension: &str,
+        save: impl FnOnce(&Self, &Path) -> Result<(), String>,
+    ) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(file_type, &[extension])
+            .set_file_name(format!("{}.{extension}", tr("Untitled")))
+            .save_file()
+        else {
+            return;
+        };
+        match save(self, &path) {
+            Ok(()) => self.settings.add_recent_file(path),
+            Err(e) => {
+                self.error = Some(format!("{} {}:\n{e}", tr("Could not save"), path.display()));
+            }
+        }
+    }
+
+    fn start(&mut self, ctx: &egui::Context, choice: StartChoice) {
+        match choice {
+            StartChoice::Preset(preset) => self.load_preset(preset),
+            StartChoice::Blank => {
+                if let Err(e) = self.apply_scene(&Scene::blank()) {
+                    self.error = Some(e);
+                }
+            }
+            StartChoice::File(path) => self.open_file(ctx, path),
+        }
+    }
+
+    fn show_error(&mut self, ctx: &egui::Context) {
+        let Some(error) = &self.error else {
+            return;
+        };
+        let mut dismissed = false;
+        let modal = egui::Modal::new(egui::Id::new("error")).show(ctx, |ui| {
+            ui.label(error);
+            if ui.button(tr("OK")).clicked() {
+                dismissed = true;
+            }
+        });
+        if dismissed || modal.should_close() {
+            self.error = None;
+        }
+    }
+
+    fn apply_tour_action(&mut self, action: TourAction) {
+        if let Some(preset) = action.preset {
+            self.load_preset(preset);
+        }
+        self.paused = !action.play;
+    }
+}
+
+impl eframe::App for RefractionApp {
+    /// Called by the framework to save state before shutdown.
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.settings.save(storage);
+    }
+
+    /// Called once the application has closed normally.
+    fn on_exit(&mut self, gl: Option<&eframe::glow::Context>) {
+        session::remove_recovery();
+        if let Some(gl) = gl {
+            self.surface.destroy(gl);
+        }
+    }
+
+    /// Called each time the UI needs repainting
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // number of simulation updates performed this frame
+        let mut updates = 0;
+
+        // the app is in the background if it is minimised, or none of its windows have focus
+        let background = ctx.input(|i| {
+            i.viewport().minimized == Some(true)
+                || i.raw.viewports.values().all(|v| v.focused == Some(false))
+        });
+        let suspended = background && self.settings.pause_in_background;
+
+        // touch screens need larger controls, so they are switched on the first time the screen is touched
+        if !self.settings.large_controls && ctx.input(|i| i.any_touches()) {
+            self.settings.large_controls = true;
+        }
+        let spacing = self.settings.spacing();
+        if ctx.style().spacing.interact_size != spacing.interact_size {
+            ctx.all_styles_mut(|style| style.spacing = spacing.clone());
+        }
+        // the scale of the whole interface follows Ctrl and +/-, which egui handles itself, and the UI scale setting,
+        // which is only applied once it is let go of so the slider doesn't move while it is dragged
+        if ctx.zoom_factor() != self.applied_ui_scale {
+            self.settings.ui_scale = ctx.zoom_factor();
+        } else if self.settings.ui_scale != self.applied_ui_scale
+            && !ctx.input(|i| i.pointer.any_down())
+        {
+            ctx.set_zoom_factor(self.settings.ui_scale);
+        }
+        self.applied_ui_scale = ctx.zoom_factor();
+
+        // real time that has passed since the last frame. time spent suspended is not counted,
+        // so the simulation carries on from where it was rather than skipping ahead
+        let now = Instant::now();
+        let frame_time = match self.suspended {
+            true => 0.0,
+            false => now
+                .duration_since(self.last_frame_time)
+                .as_secs_f32()
+                .min(MAX_FRAME_TIME),
+        };
+        self.last_frame_time = now;
+        self.suspended = suspended;
+
+        // keyboard shortcuts for presentation mode
+        let (toggle_presenting, escape, space) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::F11),
+                i.key_pressed(egui::Key::Escape),
+                i.key_pressed(egui::Key::Space),
+            )
+        });
+        if toggle_presenting || (self.presenting && escape) {
+            self.set_presenting(ctx, !self.presenting);
+        } else if self.presenting && space {
+            self.paused = !self.paused;
+        }
+
+        // playback from a presentation clicker or the keyboard, unless typing into a text field
+        let typing = ctx.wants_keyboard_input();
+        let command = match typing {
+            true => None,
+            false => ctx.input(presentation::playback_command),
+        };
+        // L switches the laser pointer on and off, as for the ✏ button
+        if !typing && ctx.input(|i| i.key_pressed(egui::Key::L) && i.modifiers.is_none()) {
+            self.laser.active = !self.laser.active;
+        }
+        // or from a gamepad or media keys
+        #[cfg(any(feature = "gamepad", feature = "media-keys"))]
+        let command = command.or(self.controllers.poll(ctx, self.presenting, !typing));
+        match command {
+            Some(PlaybackCommand::PlayPause) => self.paused = !self.paused,
+            // stepping while playing pauses first, so the next press steps from there
+            Some(PlaybackCommand::Step) if !self.paused => self.paused = true,
+            Some(PlaybackCommand::Step) => updates += self.step(),
+            Some(PlaybackCommand::Restart) => self.restart(),
+            Some(PlaybackCommand::Faster) => {
+                self.speed = (self.speed * SPEED_STEP).min(MAX_SPEED);
+            }
+            Some(PlaybackCommand::Slower) => {
+                self.speed = (self.speed / SPEED_STEP).max(MIN_SPEED);
+            }
+            None => {}
+        }
+
+        // a scripted demo takes whatever actions are due
+        if let Some(demo) = &mut self.demo {
+            let actions = demo.poll(self.simulation.time());
+            for action in actions {
+                self.apply_demo_action(action);
+            }
+        }
 
-            world_centre,
-            zoom: 1.0,
-            zoom_centre: None,
-            dragging: None,
+        // open files dropped onto the window
+        let (dropped_files, files_hovered) = ctx.input(|i| {
+            let dropped: Vec<PathBuf> = i
+                .raw
+                .dropped_files
+                .iter()
+                .filter_map(|file| file.path.clone())
+                .collect();
+            (dropped, !i.raw.hovered_files.is_empty())
+        });
+        for path in dropped_files {
+            self.open_file(ctx, path);
+        }
 
-            applied_field_opacity: 0.8,
-            resultant_field_opacity: 0.7,
-            particle_field_opacity: 0.2,
+        // the time step changes once the refresh rate has been measured, or matching it is switched, straight away
+        // if the simulation hasn't started, otherwise from when it is next restarted. a replay keeps its own
+        let time_step = self.time_step();
+        if time_step != self.simulation.time_step()
+            && self.simulation.steps() == 0
+            && self.player.is_none()
+        {
+            self.simulation.set_time_step(time_step);
+            self.simulation.reset();
         }
-    }
-}
 
-impl eframe::App for RefractionApp {
-    /// Called by the framework to save state before shutdown.
+        // a jump is caught up with before the simulation carries on as normal
+        if self.jump_target.is_some() {
+            updates += self.catch_up();
+            ctx.request_repaint();
+        }
 
-    /// Called each time the UI needs repainting
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        if self.frame % SIMULATION_FPS == SIMULATION_FPS - 1 {
-            self.last_n_frames_time_micros =
-                self.last_n_frames_start.elapsed().unwrap().as_micros() as f32;
-            self.last_n_frames_start = SystemTime::now();
-        }
-
-        // advance simulation when not paused and with (hardcoded) vsync in order to not run sim faster than app can draw
-        if !self.paused && (self.frame % MONITOR_REFRESH_RATE / SIMULATION_FPS == 0) {
-            // speed works by allowing a fractional number of requested frames per update.
-            // this means that when using a speed different to 1, each redraw may have a varying number of simulation updates.
-            self.requested_frames += self.speed;
-            while (self.frame as f32) < self.requested_frames {
-                // update sim until frame number satisfies requests
-                if self.simulation.update() {
+        // advance simulation when not paused, by fixed time steps that add up to the real time elapsed.
+        // speed scales the elapsed time, so each redraw may have a varying number of simulation updates
+        // depending on speed, frame rate and dropped frames. the remainder is carried over to the next frame.
+        // in timelapse, a fixed number of updates are made instead, however long they take, so a long run
+        // isn't held back by redrawing after every few updates
+        if !self.paused && !suspended && self.jump_target.is_none() {
+            let mut due = 0;
+            match self.timelapse {
+                true => {
+                    self.accumulated_time = 0.0;
+                    due = self.timelapse_every;
+                }
+                false => self.accumulated_time += frame_time * self.speed,
+            }
+            let time_step = self.simulation.time_step();
+            while due > 0 || self.accumulated_time >= time_step {
+                match due {
+                    0 => self.accumulated_time -= time_step,
+                    _ => due -= 1,
+                }
+                if self.advance() {
                     // sim complete, reset
-                    self.paused = true;
-                    self.simulation.reset();
-                    self.frame = 0;
-                    self.requested_frames = 0.0;
+                    self.restart();
+                    break;
                 }
                 self.frame += 1;
+                updates += 1;
+                if self.paused {
+                    break;
+                }
             }
         }
-
-        // recorded for checking if any change to these this redraw -
-        // only want to update sim when these values change as it's an expensive thing to do
-        let particle_count = self.simulation.particle_count;
-        let particle_spacing = self.simulation.particle_spacing;
+        // in slow motion, the canvas is drawn as far towards the next update as the time carried over to it
+        self.tween
+            .set_fraction(match !self.paused && self.slow_motion() {
+                true => self.accumulated_time / self.simulation.time_step(),
+                false => 1.0,
+            });
 
         // draws simulation settings at the top of the window
+        let mut present = false;
         let settings = egui::TopBottomPanel::top("settings");
-        let settings_drawn: Response = settings
-            .show(ctx, |ui| {
-                ui.horizontal(|ui| {
-                    // dropdown to select applied wave type
-                    ui.label("Waveform:");
-                    egui::ComboBox::from_id_salt("Wave")
-                        .selected_text(format!("{:?}", &self.simulation.waveform))
-                        .show_ui(ui, |ui| {
-                            for form in Waveform::iter() {
-                                ui.selectable_value(
-                                    &mut self.simulation.waveform,
-                                    form,
-                                    format!("{}", form.properties().name),
-                                );
+        settings.show_animated(ctx, !self.presenting, |ui| {
+            ui.horizontal(|ui| {
+                // opening and saving of session and scene files
+                ui.menu_button(tr("File"), |ui| {
+                    if ui.button(tr("Start screen…")).clicked() {
+                        self.start_screen_open = true;
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("Open…")).clicked() {
+                        ui.close_menu();
+                        self.open_file_dialog(ctx);
+                    }
+                    ui.add_enabled_ui(!self.settings.recent_files.is_empty(), |ui| {
+                        ui.menu_button(tr("Open recent"), |ui| {
+                            let mut chosen = None;
+                            for path in &self.settings.recent_files {
+                                if ui
+                                    .add_enabled(
+                                        path.exists(),
+                                        egui::Button::new(session::display_name(path)),
+                                    )
+                                    .on_hover_text(path.display().to_string())
+                                    .clicked()
+                                {
+                                    chosen = Some(path.clone());
+                                }
+                            }
+                            ui.separator();
+                            if ui.button(tr("Clear recent files")).clicked() {
+                                self.settings.recent_files.clear();
+                                ui.close_menu();
+                            }
+                            if let Some(path) = chosen {
+                                ui.close_menu();
+                                self.open_file(ctx, path);
                             }
                         });
-
+                    });
                     ui.separator();
-
-                    // number of particles, allow only the amount that can appear onscreen at once
-                    let max_p = self.simulation.max_particles();
-                    ui.label("Particles:");
-                    ui.add(
-                        egui::DragValue::new(&mut self.simulation.particle_count).range(1..=max_p),
-                    );
-
-                    // distance between each particle
-                    ui.label("Spacing:");
-                    ui.add(egui::Slider::new(
-                        &mut self.simulation.particle_spacing,
-                        PARTICLE_SPACING.min..=PARTICLE_SPACING.max,
-                    ));
-                    if ui.button("↺").on_hover_text("Reset particles").clicked() {
-                        self.simulation.particle_count = 1;
-                        self.simulation.particle_spacing = PARTICLE_SPACING.initial;
+                    if ui.button(tr("Save session…")).clicked() {
+                        ui.close_menu();
+                        self.save_file_dialog(tr("Session"), SESSION_EXTENSION, |app, path| {
+                            app.current_session().save(path)
+                        });
                     }
-
-                    ui.separator();
-
-                    // particle type selection
-                    ui.label("Particle Type:");
-                    let current_type = self.simulation.particle_type;
-                    let mut selected_type = current_type;
-                    egui::ComboBox::from_id_salt("ParticleType")
-                        .selected_text(current_type.name())
-                        .show_ui(ui, |ui| {
-                            for form in ChargedParticleType::iter() {
-                                ui.selectable_value(
-                                    &mut selected_type,
-                                    form,
-                                    format!("{}", form.properties().name),
-                                );
-                            }
+                    if ui.button(tr("Save scene…")).clicked() {
+                        ui.close_menu();
+                        self.save_file_dialog(tr("Scene"), SCENE_EXTENSION, |app, path| {
+                            app.simulation.scene().save(path)
                         });
-                    if selected_type != current_type {
-                        self.simulation.set_particle_type(selected_type);
                     }
+                    if ui
+                        .button(tr("Export manifest…"))
+                        .on_hover_text(tr(
+                            "Save everything the run is set up from, with the version of the app, so it can \
+                            be cited and run again",
+                        ))
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.save_file_dialog(tr("Manifest"), MANIFEST_EXTENSION, |app, path| {
+                            Manifest::new(&app.simulation, app.settings.memory_cap).save(path)
+                        });
+                    }
+                    if ui.button(tr("Load manifest…")).clicked() {
+                        ui.close_menu();
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter(tr("Manifest"), &[MANIFEST_EXTENSION])
+                            .pick_file()
+                        {
+                            self.open_file(ctx, path);
+                        }
+                    }
+                    #[cfg(feature = "serde-state")]
+                    {
+                        ui.separator();
+                        self.checkpoint_menu(ctx, ui);
+                        ui.separator();
+                    }
+                    if ui
+                        .button(tr("Copy link"))
+                        .on_hover_text(tr("Copy a link to the scene and view, to share them. \
+                            Pasting a link onto the canvas opens it"))
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.copy_link(ctx);
+                    }
+                });
 
-                    ui.separator();
-
-                    // particle properties
-                    ui.label("M").on_hover_text("Particle mass");
-                    ui.add(egui::Slider::new(
-                        &mut self.simulation.particle_mass,
-                        ELECTRON_MASS.min..=ELECTRON_MASS.max,
-                    ));
-                    if ui.button("↺").on_hover_text("Reset").clicked() {
-                        self.simulation.particle_mass = self.simulation.particle_type.mass();
+                // menu of built-in starting configurations
+                let mut chosen_preset = None;
+                ui.menu_button(tr("Presets"), |ui| {
+                    for preset in Preset::iter() {
+                        let properties = preset.properties();
+                        if ui
+                            .button(tr(properties.name))
+                            .on_hover_text(tr(properties.description))
+                            .clicked()
+                        {
+                            chosen_preset = Some(preset);
+                            ui.close_menu();
+                        }
                     }
+                });
+                if let Some(preset) = chosen_preset {
+                    self.load_preset(preset);
+                }
 
-                    ui.separator();
+                // analysis panels, which can be shown in the main window or a window of their own
+                ui.menu_button(tr("Panels"), |ui| {
+                    ui.checkbox(&mut self.settings.energy_panel.open, tr("Energy"));
+                    ui.checkbox(
+                        &mut self.settings.surface_panel.open,
+                        tr("3D field history"),
+                    );
+                    ui.checkbox(&mut self.settings.phasor_panel.open, tr("Phasors"));
+                    ui.checkbox(&mut self.settings.noise_panel.open, tr("Noise statistics"));
+                    ui.checkbox(&mut self.settings.spectrum_panel.open, tr("Spectrum"));
+                    ui.checkbox(
+                        &mut self.settings.extinction_panel.open,
+                        tr("Extinction theorem"),
+                    );
+                    ui.checkbox(&mut self.settings.pulse_panel.open, tr("Pulse delay"));
+                    ui.checkbox(
+                        &mut self.settings.multiples_panel.open,
+                        tr("Particle fields"),
+                    );
+                    ui.checkbox(&mut self.settings.convergence_panel.open, tr("Convergence"));
+                    ui.checkbox(&mut self.settings.scattering_panel.open, tr("Scattering"));
+                    ui.checkbox(&mut self.settings.fit_panel.open, tr("Fit to a medium"));
+                    ui.checkbox(&mut self.settings.ensemble_panel.open, tr("Ensemble"));
+                    ui.checkbox(&mut self.settings.compare_panel.open, tr("Compare runs"));
+                    ui.checkbox(&mut self.settings.quiz_panel.open, tr("Prediction quiz"));
+                    ui.checkbox(&mut self.settings.inspector_panel.open, tr("Inspector"));
+                    ui.checkbox(&mut self.settings.bookmarks_panel.open, tr("Bookmarks"));
+                    ui.checkbox(&mut self.settings.export_panel.open, tr("Image export"));
+                    #[cfg(feature = "stream")]
+                    ui.checkbox(&mut self.settings.stream_panel.open, tr("Live stream"));
+                    #[cfg(feature = "scripting")]
+                    ui.checkbox(&mut self.settings.diagnostics_panel.open, tr("Diagnostics"));
+                });
 
-                    ui.label("k").on_hover_text("Particle spring constant");
-                    ui.add(egui::Slider::new(
-                        &mut self.simulation.spring_constant,
-                        SPRING_CONSTANT.min..=SPRING_CONSTANT.max,
-                    ));
-                    if ui.button("↺").on_hover_text("Reset").clicked() {
-                        self.simulation.spring_constant =
-                            self.simulation.particle_type.default_spring_constant();
-                    }
+                // which controls are shown, and layouts of them saved for different uses
+                ui.menu_button(tr("Layout"), |ui| {
+                    self.layout_menu.draw(
+                        ui,
+                        &mut self.settings.layout,
+                        &mut self.settings.saved_layouts,
+                    );
+                });
 
+                for section in self.settings.layout.shown(0) {
                     ui.separator();
+                    self.draw_settings_section(ctx, ui, section, &mut present);
+                }
+            });
 
-                    ui.label("Damping")
-                        .on_hover_text("Particle motion damping factor");
-                    ui.add(egui::Slider::new(
-                        &mut self.simulation.damping,
-                        ELECTRON_DAMPING.min..=ELECTRON_DAMPING.max,
-                    ));
-                    if ui.button("↺").on_hover_text("Reset").clicked() {
-                        self.simulation.damping = self.simulation.particle_type.default_damping();
+            let opacities_drawn = ui.horizontal(|ui| {
+                for (i, section) in self.settings.layout.shown(1).into_iter().enumerate() {
+                    if i > 0 {
+                        ui.separator();
                     }
-                });
+                    self.draw_settings_section(ctx, ui, section, &mut present);
+                }
+            });
+            self.tour
+                .register(TourTarget::FieldOpacities, opacities_drawn.response.rect);
+        });
 
-                ui.horizontal(|ui| {
-                    ui.label("Field opacities:");
-                    ui.label(
-                        egui::RichText::new("◼")
-                            .color(applied_field_colour(0.7, &self.simulation.waveform)),
-                    )
-                    .on_hover_text("Initial electric field");
-                    ui.add(egui::Slider::new(
-                        &mut self.applied_field_opacity,
-                        0.0..=1.0,
-                    ));
-                    ui.label(egui::RichText::new("◼").color(resultant_field_colour(0.7)))
-                        .on_hover_text("Resultant electric field");
-                    ui.add(egui::Slider::new(
-                        &mut self.resultant_field_opacity,
-                        0.0..=1.0,
-                    ));
-                    ui.label(egui::RichText::new("◼").color(particle_field_colour(0.7)))
-                        .on_hover_text("Induced electric field of particles");
-                    ui.add(egui::Slider::new(
-                        &mut self.particle_field_opacity,
-                        0.0..=1.0,
-                    ));
-                });
-            })
-            .response;
+        if present {
+            self.set_presenting(ctx, true);
+        }
+
+        // keeps what grows as the simulation runs within the memory cap, as far as the histories can be cut back
+        let mut memory = self.memory_use();
+        let cap = self.settings.memory_cap << 20;
+        let over_memory_cap = memory.total() > cap
+            && !self
+                .simulation
+                .limit_history(cap.saturating_sub(memory.recording + memory.surface));
+        memory.history = self.simulation.history_memory();
+
+        // draws the status bar at the very bottom of the window
+        let warnings = status::check(&self.simulation, over_memory_cap);
+        egui::TopBottomPanel::bottom("status").show_animated(ctx, !self.presenting, |ui| {
+            status::draw_status_bar(
+                ui,
+                &mut self.simulation,
+                &warnings,
+                self.frame_stats.refresh_rate(),
+                &memory,
+                &mut self.settings.memory_cap,
+            );
+        });
 
         // draws simulation controls at the bottom of the window
         let controls = egui::TopBottomPanel::bottom("controls");
-        let controls_drawn: Response = controls
-            .show(ctx, |ui| {
+        controls.show_animated(ctx, !self.presenting, |ui| {
                 ui.horizontal(|ui| {
+                    let playback_start = ui.cursor().min;
 
                     if ui
                         .add( egui::Button::new(if self.paused {"▶"} else {"⏸"}))
-                        .on_hover_text(if self.paused {"Play simulation"} else {"Pause simulation"})
+                        .on_hover_text(tr(if self.paused {"Play simulation"} else {"Pause simulation"}))
                         .clicked()
                     {
                         self.paused = !self.paused;
@@ -264,73 +2181,161 @@ impl eframe::App for RefractionApp {
                     // button for stepping the simulation by a configurable number of updates
                     if ui
                         .add_enabled(self.paused, egui::Button::new("⏭"))
-                        .on_hover_text("Advance simulation by a number of frames")
+                        .on_hover_text(tr("Advance simulation by a number of frames"))
                         .clicked()
+                        && self.paused
                     {
-                        if self.paused {
-                            for _ in 0..self.frame_skip {
-                                self.simulation.update();
-                            }
-                        }
+                        updates += self.step();
                     }
                     ui.add(egui::DragValue::new(&mut self.frame_skip))
-                        .on_hover_text("Number of updates to advance per step");
+                        .on_hover_text(tr("Number of updates to advance per step"));
 
-                    ui.label(format!("{0:.2}s @ {1}", self.simulation.time(), self.frame)).on_hover_text("[Elapsed time]s @ [number of frames]");
+                    if self.settings.layout.shows(Measurement::Time) {
+                        ui.label(format!("{0:.2}s @ {1}", self.simulation.time(), self.frame)).on_hover_text(tr("[Elapsed time]s @ [number of frames]"));
+                    }
 
                     if ui
                         .add_enabled(self.simulation.time() > 0.0, egui::Button::new("⟲"))
-                        .on_hover_text("Restart simulation")
+                        .on_hover_text(tr("Restart simulation"))
+                        .clicked()
+                    {
+                        self.restart();
+                    }
+
+                    // recording the run to a replay file, or replaying one
+                    if self.recorder.is_some() {
+                        if ui
+                            .button(egui::RichText::new("⏹").color(RECORDING_COLOUR))
+                            .on_hover_text(tr("Stop recording and save the replay"))
+                            .clicked()
+                        {
+                            self.stop_recording();
+                        }
+                    } else if ui
+                        .add_enabled(self.player.is_none(), egui::Button::new("⏺"))
+                        .on_hover_text(tr("Record the run from the start to a replay file, to watch again later"))
+                        .clicked()
+                    {
+                        self.start_recording();
+                    }
+                    if let Some(steps) = self.player.as_ref().map(|player| player.replay().steps()) {
+                        ui.label(format!("{} {}/{steps}", tr("Replay"), self.simulation.steps()));
+                        if ui
+                            .button("✖")
+                            .on_hover_text(tr("Stop replaying, keeping the simulation as it is"))
+                            .clicked()
+                        {
+                            self.player = None;
+                        }
+                    }
+                    if let Some(demo) = &self.demo {
+                        let status = match demo.is_finished() {
+                            true => tr("finished").to_string(),
+                            false => format!("{:.0} %", 100.0 * demo.progress()),
+                        };
+                        ui.label(format!("🎬 {status}"));
+                        if ui.button("✖").on_hover_text(tr("Stop the demo")).clicked() {
+                            self.demo = None;
+                        }
+                    }
+
+                    // marking the ends of a loop, to replay the interval between them over and over
+                    ui.separator();
+                    if ui
+                        .button("A")
+                        .on_hover_text(tr("Mark the start of a loop at the current time"))
+                        .clicked()
+                    {
+                        self.looping.set_start(&self.simulation);
+                    }
+                    let time = self.simulation.time();
+                    if ui
+                        .add_enabled(
+                            self.looping.start().is_some_and(|start| start < time),
+                            egui::Button::new("B"),
+                        )
+                        .on_hover_text(tr("Mark the end of the loop at the current time. \
+                            Each time the simulation gets there, it jumps back to the start of the loop"))
                         .clicked()
                     {
-                        self.paused = true;
-                        self.frame = 0;
-                        self.requested_frames = 0.0;
-                        self.simulation.reset();
+                        self.looping.set_end(time);
                     }
+                    if let Some(start) = self.looping.start() {
+                        let end = self.looping.end().map_or(String::new(), |end| format!("{end:.2}"));
+                        ui.label(format!("🔁 {start:.2}–{end} s"));
+                        if ui.button("✖").on_hover_text(tr("Stop looping")).clicked() {
+                            self.looping.clear();
+                        }
+                    }
+                    let playback_rect = Rect::from_min_max(playback_start, ui.min_rect().max);
+                    self.tour.register(TourTarget::Playback, playback_rect);
 
                     ui.separator();
 
                     // ratio of simulation UPS to screen FPS
-                    ui.label("Speed");
-                    ui.add(egui::Slider::new(&mut self.speed, 0.1..=10.0));
-                    if ui.button("↺").on_hover_text("Reset").clicked() {
+                    ui.label(tr("Speed"));
+                    ui.add_enabled(!self.timelapse, egui::Slider::new(&mut self.speed, MIN_SPEED..=MAX_SPEED));
+                    if ui.button("↺").on_hover_text(tr("Reset")).clicked() {
                         self.speed = 1.0;
                     }
+                    ui.toggle_value(&mut self.timelapse, "⏩")
+                        .on_hover_text(tr("Timelapse: run as fast as possible rather than in real time, \
+                            only redrawing after a number of updates"));
+                    if self.timelapse {
+                        ui.add(egui::DragValue::new(&mut self.timelapse_every).range(1..=100_000))
+                            .on_hover_text(tr("Updates between redraws"));
+                    }
 
                     ui.separator();
 
-                    ui.label("Zoom").on_hover_text("You can also zoom using the mouse wheel, and move around by dragging with the mouse.");
-                    ui.add(egui::Slider::new(&mut self.zoom, 1.0..=10.0));
-                    if ui.button("↺").on_hover_text("Reset view").clicked() {
-                        self.zoom = 1.0;
-                        self.world_centre = self.simulation.size().center();
-                        self.zoom_centre = None;
-                        self.dragging = None;
+                    ui.label(tr("Zoom")).on_hover_text(tr("You can also zoom using the mouse wheel, and move around by dragging with the mouse. Hold Ctrl (Cmd on a Mac) while dragging to zoom into a box."));
+                    ui.add(egui::Slider::new(&mut self.settings.zoom, 1.0..=10.0));
+                    if ui.button("↺").on_hover_text(tr("Reset view")).clicked() {
+                        self.reset_view();
                     }
 
-                    ui.separator();
+                    ui.label(tr("Y scale")).on_hover_text(tr("Logarithmic scales show weak transmitted and reflected fields alongside strong ones"));
+                    egui::ComboBox::from_id_salt("YScale")
+                        .selected_text(tr(self.settings.y_scale.name()))
+                        .show_ui(ui, |ui| {
+                            for scale in YScale::iter() {
+                                ui.selectable_value(&mut self.settings.y_scale, scale, tr(scale.name()));
+                            }
+                        });
 
-                    ui.label(format!("{0:.0} FPS", 6e7 / self.last_n_frames_time_micros));
+                    // measurements chosen in the layout
+                    let layout = &self.settings.layout;
+                    if [Measurement::Energy, Measurement::Rates, Measurement::RatePlot].iter().any(|m| layout.shows(*m)) {
+                        ui.separator();
+                    }
+                    if layout.shows(Measurement::Energy) {
+                        let (kinetic, potential) = self.simulation.oscillator_energy();
+                        ui.label(format!("E = {:.3}", kinetic + potential))
+                            .on_hover_text(tr("Energy of the particles' oscillations, kinetic and potential"));
+                    }
+                    if layout.shows(Measurement::Rates) {
+                        let (fps, ups) = self.frame_stats.averages();
+                        ui.label(egui::RichText::new(format!("{fps:.0} FPS")).color(FPS_COLOUR));
+                        ui.label(egui::RichText::new(format!("{ups:.0} UPS")).color(UPS_COLOUR));
+                    }
+                    if layout.shows(Measurement::RatePlot) {
+                        self.frame_stats.plot(ui);
+                    }
                 });
-            })
-            .response;
+            });
 
-        // adds/removes/modifies particles only if required
-        let particle_count_changed = self.simulation.particle_count != particle_count;
-        let particle_spacing_changed = self.simulation.particle_spacing != particle_spacing;
-        if particle_count_changed || particle_spacing_changed {
-            self.simulation.update_particles(particle_spacing_changed);
-        }
+        // changes made with the controls are applied before the canvas is drawn, even while paused
+        self.simulation.apply_commands();
 
         // the space on the screen in points between the settings/control bars
-        let canvas_extent = Rect::from_two_pos(
-            pos2(ctx.screen_rect().left(), settings_drawn.rect.bottom()),
-            pos2(ctx.screen_rect().right(), controls_drawn.rect.top()),
-        );
+        let canvas_extent = ctx.available_rect();
 
         // dimensions on the x axis of the amount of the simulation that is visible on-screen
-        let mut visible_world = zoom_to(self.simulation.size(), self.zoom, self.world_centre);
+        let mut visible_world = zoom_to(
+            self.simulation.size(),
+            self.settings.zoom,
+            self.settings.world_centre,
+        );
 
         // position of mouse pointer
         let pointer_pos = ctx.pointer_latest_pos().unwrap_or(pos2(0.0, 0.0)); // in screen space (measured in points)
@@ -352,8 +2357,8 @@ impl eframe::App for RefractionApp {
                 // the dimensions of the visible part of the simulation after this frame's zoom is applied
                 let future_visible_world = zoom_to(
                     self.simulation.size(),
-                    (self.zoom + scroll_delta / 100.0).max(1.0),
-                    self.world_centre,
+                    (self.settings.zoom + scroll_delta / 100.0).max(1.0),
+                    self.settings.world_centre,
                 );
 
                 // If this is the first frame of a zoom action, remember the centre we are zooming on.
@@ -364,18 +2369,38 @@ impl eframe::App for RefractionApp {
                 }
 
                 // if zoom centre is near the edges, clamp centre so no no part of the canvas is outside the simulation's bounds
-                self.world_centre = self
+                self.settings.world_centre = self
                     .zoom_centre
                     .unwrap()
                     .min(self.simulation.size().max - future_visible_world.span() / 2.0)
                     .max(self.simulation.size().min + future_visible_world.span() / 2.0);
 
                 // change zoom level based on scroll amount
-                self.zoom = (self.zoom + scroll_delta / 100.0).max(1.0);
+                self.settings.zoom = (self.settings.zoom + scroll_delta / 100.0).max(1.0);
             }
 
             // apply new zoom
-            visible_world = zoom_to(self.simulation.size(), self.zoom, self.world_centre);
+            visible_world = zoom_to(
+                self.simulation.size(),
+                self.settings.zoom,
+                self.settings.world_centre,
+            );
+        }
+
+        // pinching with two fingers on a touch screen zooms, keeping the point between the fingers under them as they move
+        let pinch = ctx.multi_touch();
+        if let Some(touch) = pinch.filter(|touch| canvas_extent.contains(touch.center_pos)) {
+            let size = *self.simulation.size();
+            let fraction = (touch.center_pos.x - canvas_extent.left()) / canvas_extent.width();
+            let previous_x = touch.center_pos.x - touch.translation_delta.x;
+            let pinched = visible_world.min
+                + (previous_x - canvas_extent.left()) * visible_world.span()
+                    / canvas_extent.width();
+            self.settings.zoom = (self.settings.zoom * touch.zoom_delta).max(1.0);
+            let span = size.span() / self.settings.zoom;
+            self.settings.world_centre = (pinched - (fraction - 0.5) * span)
+                .clamp(size.min + span / 2.0, size.max - span / 2.0);
+            visible_world = zoom_to(&size, self.settings.zoom, self.settings.world_centre);
         }
 
         // draws the simulation in the main panel of the window
@@ -384,20 +2409,167 @@ impl eframe::App for RefractionApp {
             .frame(egui::Frame::canvas(&style))
             .show(ctx, |ui| {
                 // this class draws objects in screen space based on coordinates given in simulation (world) space
-                let canvas = Canvas::new(ui, canvas_extent, visible_world);
+                let line_scale = match self.presenting {
+                    true => presentation::PRESENTATION_SCALE,
+                    false => 1.0,
+                };
+                let visible_y_axis = zoom_to(
+                    &Rangef::new(-VISIBLE_Y_SPAN / 2.0, VISIBLE_Y_SPAN / 2.0),
+                    self.settings.y_zoom,
+                    self.settings.y_centre,
+                );
+                let canvas = Canvas::new(
+                    ui,
+                    canvas_extent,
+                    visible_world,
+                    visible_y_axis,
+                    line_scale,
+                    self.settings.y_scale,
+                    self.simulation.applied_amplitude(),
+                );
+                // stacked traces draw the applied, induced and resultant fields in separate lanes
+                let lane = |index| match self.settings.stacked_traces {
+                    true => canvas.lane(index, 3),
+                    false => canvas.clone(),
+                };
+                let (applied_lane, particle_lane, resultant_lane) = (lane(0), lane(1), lane(2));
 
-                // detects user dragging canvas with the mouse and shifts visible world accordingly
-                if ui
-                    .interact(canvas_extent, egui::Id::new("canvas-drag"), Sense::drag())
-                    .dragged()
-                {
+                let drag = ui.interact(
+                    canvas_extent,
+                    egui::Id::new("canvas-drag"),
+                    Sense::click_and_drag(),
+                );
+                // particle under the pointer, if any
+                let hovered_particle = self
+                    .simulation
+                    .particles()
+                    .iter()
+                    .find(|p| {
+                        drag.hovered()
+                            && particle_lane.circle_contains(
+                                p.position(),
+                                PARTICLE_RADIUS,
+                                pointer_pos,
+                            )
+                    })
+                    .map(|p| (p.id(), *p.position()));
+                if hovered_particle.is_some() {
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                }
+                // clicking a particle selects it for the inspector, and clicking anywhere else deselects it.
+                // with shift held, the particle is added to or removed from the selection instead
+                if drag.clicked() {
+                    match hovered_particle {
+                        Some((id, _)) if ui.input(|i| i.modifiers.shift) => {
+                            match self.selected_particles.iter().position(|s| *s == id) {
+                                Some(i) => {
+                                    self.selected_particles.remove(i);
+                                }
+                                None => self.selected_particles.push(id),
+                            }
+                        }
+                        Some((id, _)) => self.selected_particles = vec![id],
+                        None => self.selected_particles.clear(),
+                    }
+                    if !self.selected_particles.is_empty() {
+                        self.settings.inspector_panel.open = true;
+                    }
+                }
+                // double-clicking a particle zooms onto it, and double-clicking anywhere else resets the view
+                if drag.double_clicked() {
+                    match hovered_particle {
+                        Some((_, position)) => self.focus_on(position.x),
+                        None => self.reset_view(),
+                    }
+                }
+                // copying and pasting particles from the keyboard, pasting at the pointer.
+                // a link to a setup pasted onto the canvas opens it instead
+                if drag.hovered() {
+                    let (copy, pasted) = ui.input(|i| {
+                        (
+                            i.events.iter().any(|e| matches!(e, egui::Event::Copy)),
+                            i.events.iter().find_map(|e| match e {
+                                egui::Event::Paste(text) => Some(text.clone()),
+                                _ => None,
+                            }),
+                        )
+                    });
+                    if copy {
+                        self.copy_particles(ui.ctx());
+                    }
+                    match pasted.as_deref().map(Link::decode) {
+                        Some(Some(Ok(link))) => self.open_link(link),
+                        Some(Some(Err(e))) => {
+                            self.error = Some(format!("{}\n{e}", tr("Could not open the link")));
+                        }
+                        Some(None) => self.paste_particles(canvas.screen_to_view(pointer_pos).x),
+                        None => {}
+                    }
+                }
+                if drag.secondary_clicked() {
+                    self.context_menu_pos = Some(canvas.screen_to_view(pointer_pos));
+                }
+                if let Some(pos) = self.context_menu_pos {
+                    drag.context_menu(|ui| self.canvas_context_menu(ui, pos));
+                }
+                // with the laser pointer on, pressing and dragging draws on the canvas instead of moving the view
+                if self.laser.active {
+                    self.laser
+                        .trace(pointer_pos, drag.is_pointer_button_down_on());
+                    if drag.hovered() {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::Crosshair);
+                    }
+                }
+                // dragging with ctrl (cmd on a Mac) held selects a box to zoom into, instead of moving the view
+                else if drag.drag_started() && ui.input(|i| i.modifiers.command) {
+                    self.box_zoom = Some(pointer_pos);
+                }
+                // dragging with shift held selects the particles in a box
+                else if drag.drag_started() && ui.input(|i| i.modifiers.shift) {
+                    self.box_select = Some(pointer_pos);
+                }
+                if let Some(start) = self.box_select {
+                    if !drag.dragged() {
+                        self.box_select = None;
+                        let selection = Rect::from_two_pos(start, pointer_pos);
+                        for p in self.simulation.particles() {
+                            let screen_pos = particle_lane.world_to_screen_pos(p.position());
+                            if selection.contains(screen_pos)
+                                && !self.selected_particles.contains(&p.id())
+                            {
+                                self.selected_particles.push(p.id());
+                            }
+                        }
+                        if !self.selected_particles.is_empty() {
+                            self.settings.inspector_panel.open = true;
+                        }
+                    }
+                } else if let Some(start) = self.box_zoom {
+                    if !drag.dragged() {
+                        self.box_zoom = None;
+                        // ignore boxes too small to have been meant, zooming only in x if the box is flat
+                        let size = (pointer_pos - start).abs();
+                        if size.x >= MIN_BOX_ZOOM_SIZE {
+                            let (corner, opposite_corner) = (
+                                canvas.screen_to_view(start),
+                                canvas.screen_to_view(pointer_pos),
+                            );
+                            self.zoom_to_box(corner, opposite_corner, size.y >= MIN_BOX_ZOOM_SIZE);
+                        }
+                    }
+                }
+                // detects user dragging canvas with the mouse or a finger and shifts visible world accordingly.
+                // two fingers are handled by the pinch zoom instead
+                else if drag.dragged() && pinch.is_none() && !self.laser.active {
                     // get shift in pointer based on remembered mouse position last frame
                     let diff = self.dragging.unwrap_or(pointer_pos.x) - pointer_pos.x;
                     // Change world centre, changing from screen space diff to world space diff
                     // This will apply on the next frame, due to necessary 1 frame delay to calculate initial mouse movement from start of dragging
-                    self.world_centre += diff * visible_world.span() / canvas_extent.width();
+                    self.settings.world_centre +=
+                        diff * visible_world.span() / canvas_extent.width();
                     // clamp centre so no part of the canvas is outside simulation bounds
-                    self.world_centre = self
+                    self.settings.world_centre = self
+                        .settings
                         .world_centre
                         .min(self.simulation.size().max - visible_world.span() / 2.0)
                         .max(self.simulation.size().min + visible_world.span() / 2.0);
@@ -408,39 +2580,440 @@ impl eframe::App for RefractionApp {
                     self.dragging = None;
                 }
 
+                self.tour.register(TourTarget::Canvas, canvas_extent);
+                if let Some(particle) = self.simulation.particles().first() {
+                    let screen_pos = particle_lane.world_to_screen_pos(particle.position());
+                    self.tour.register(
+                        TourTarget::Particle,
+                        Rect::from_center_size(screen_pos, Vec2::splat(48.0)),
+                    );
+                }
+
                 // draw lines on the canvas
-                canvas.draw_grid_lines();
-                canvas.draw_axes();
+                if self.settings.stacked_traces {
+                    for lane in [&applied_lane, &particle_lane, &resultant_lane] {
+                        lane.draw_grid_lines();
+                        lane.draw_axes();
+                    }
+                } else {
+                    canvas.draw_grid_lines();
+                    canvas.draw_axes();
+                }
 
-                // draw particles and fields
-                for particle in self.simulation.particles() {
-                    canvas.draw_filled_circle(
-                        particle.position(),
-                        0.25,
+                // draw particles and fields, between the last two updates in slow motion
+                let drawn = self.tween.drawn(&self.simulation);
+                for (particle, (position, field)) in
+                    self.simulation.particles().iter().zip(&drawn.particles)
+                {
+                    particle_lane.draw_filled_circle(
+                        position,
+                        PARTICLE_RADIUS,
                         particle_colour(1.0, particle),
                     );
-                    canvas.draw_points(
+                    draw_trace(
+                        &particle_lane,
+                        Trace::Induced,
+                        &self.settings,
+                        self.simulation.x_intervals(),
+                        field,
+                        &particle_field_colour(self.settings.particle_field_opacity),
+                    );
+                }
+
+                draw_trace(
+                    &applied_lane,
+                    Trace::Applied,
+                    &self.settings,
+                    self.simulation.x_intervals(),
+                    &drawn.applied,
+                    &applied_field_colour(
+                        self.settings.applied_field_opacity,
+                        &self.simulation.waveform(),
+                    ),
+                );
+
+                let opacity = self.settings.resultant_field_opacity;
+                // the analytic signal needs evenly spaced samples, which the grid near particles isn't
+                let even_field = (self.settings.colour_by_phase || self.settings.show_envelope)
+                    .then(|| self.simulation.even_resultant_field());
+                let analytic = even_field
+                    .as_ref()
+                    .map(|field| self.analytic_signal.compute(field));
+                if let (Some(field), Some(analytic)) = (
+                    &even_field,
+                    analytic.filter(|_| self.settings.colour_by_phase),
+                ) {
+                    // the instantaneous phase along x cycles faster where the wavelength is shorter
+                    let colours: Vec<Color32> = analytic
+                        .iter()
+                        .map(|z| phase_colour(opacity, z.arg()))
+                        .collect();
+                    resultant_lane.draw_points_coloured(
+                        self.simulation.even_intervals(),
+                        field,
+                        &colours,
+                    );
+                } else {
+                    draw_trace(
+                        &resultant_lane,
+                        Trace::Resultant,
+                        &self.settings,
+                        self.simulation.x_intervals(),
+                        &drawn.resultant,
+                        &resultant_field_colour(opacity),
+                    );
+                }
+                let steady_state = self
+                    .settings
+                    .show_steady_state
+                    .then(|| self.simulation.steady_state_field())
+                    .flatten();
+                if let Some(steady_state) = steady_state {
+                    resultant_lane.draw_overlay(
                         self.simulation.x_intervals(),
-                        particle.field(),
-                        &particle_field_colour(self.particle_field_opacity),
+                        &steady_state,
+                        &envelope_colour(opacity),
+                        Overlay::Theory,
+                    );
+                }
+                if let Some(snapshot) = &self.snapshot {
+                    resultant_lane.draw_overlay(
+                        snapshot.x_points(),
+                        snapshot.field(),
+                        &snapshot_colour(opacity.max(0.5)),
+                        Overlay::Snapshot,
+                    );
+                }
+                if let Some(reference) = &self.references.field {
+                    let (x, field) = reference.columns();
+                    resultant_lane.draw_overlay(
+                        &x,
+                        &field,
+                        &reference_colour(opacity.max(0.5)),
+                        Overlay::Reference,
                     );
                 }
+                if let Some(ensemble) = self.ensemble.shown() {
+                    let band = |sign: f32| -> Vec<f32> {
+                        ensemble
+                            .mean
+                            .iter()
+                            .zip(&ensemble.deviation)
+                            .map(|(mean, deviation)| mean + sign * deviation)
+                            .collect()
+                    };
+                    resultant_lane.draw_band(
+                        &ensemble.x_points,
+                        &band(-1.0),
+                        &band(1.0),
+                        resultant_field_colour(0.5 * opacity),
+                    );
+                    resultant_lane.draw_overlay(
+                        &ensemble.x_points,
+                        &ensemble.mean,
+                        &resultant_field_colour(opacity),
+                        Overlay::Ensemble,
+                    );
+                }
+                if let Some(analytic) = analytic.filter(|_| self.settings.show_envelope) {
+                    // the magnitude of the analytic signal bounds the oscillation above and below
+                    let upper: Vec<f32> = analytic.iter().map(|z| z.norm()).collect();
+                    let lower: Vec<f32> = upper.iter().map(|e| -e).collect();
+                    for envelope in [upper, lower] {
+                        resultant_lane.draw_overlay(
+                            self.simulation.even_intervals(),
+                            &envelope,
+                            &envelope_colour(opacity),
+                            Overlay::Envelope,
+                        );
+                    }
+                }
 
-                canvas.draw_points(
+                draw_trace(
+                    &particle_lane,
+                    Trace::Scattered,
+                    &self.settings,
                     self.simulation.x_intervals(),
-                    self.simulation.applied_field(),
-                    &applied_field_colour(self.applied_field_opacity, &self.simulation.waveform),
+                    &drawn.scattered,
+                    &scattered_field_colour(self.settings.scattered_field_opacity),
                 );
 
-                canvas.draw_points(
+                draw_trace(
+                    &resultant_lane,
+                    Trace::Intensity,
+                    &self.settings,
                     self.simulation.x_intervals(),
-                    self.simulation.resultant_field(),
-                    &resultant_field_colour(self.resultant_field_opacity),
+                    self.simulation.intensity(),
+                    &intensity_colour(self.settings.intensity_opacity),
                 );
+                let attenuation = self
+                    .settings
+                    .show_attenuation
+                    .then(|| self.simulation.attenuation_fit())
+                    .flatten();
+                if let Some(fit) = attenuation {
+                    draw_attenuation(&resultant_lane, &fit, self.simulation.dilute_attenuation());
+                }
+
+                if self.settings.explain {
+                    let pointer_x = canvas_extent
+                        .contains(pointer_pos)
+                        .then_some(pointer_world_pos);
+                    explain::draw_explanations(&particle_lane, &self.simulation, pointer_x);
+                }
+
+                if let Some(demo) = &self.demo {
+                    demo.draw_annotation(ui.painter(), canvas_extent);
+                }
+                // captions from the scene sit at the bottom, clear of a demo's annotations
+                if let Some(caption) = self.simulation.caption() {
+                    draw_banner(ui.painter(), canvas_extent, caption, Align2::CENTER_BOTTOM);
+                }
+
+                // the laser pointer goes over everything else
+                self.laser.draw(&ui.painter().with_clip_rect(canvas_extent));
+                if self.laser.is_visible() {
+                    ui.ctx().request_repaint();
+                }
+
+                if self.presenting {
+                    presentation::draw_hud(
+                        ui.painter(),
+                        canvas_extent,
+                        self.simulation.time(),
+                        self.paused,
+                    );
+                }
+
+                if self.settings.phasor_panel.open
+                    || self.settings.noise_panel.open
+                    || self.settings.spectrum_panel.open
+                {
+                    canvas.draw_marker(self.settings.probe_x, tr("Probe"), Color32::LIGHT_GRAY);
+                }
+
+                if let Some((_, position)) = hovered_particle {
+                    particle_lane.draw_ring(&position, PARTICLE_RADIUS * 1.3, 1.5, HOVER_COLOUR);
+                }
+                for (_, particle) in self
+                    .selected_particles
+                    .iter()
+                    .filter_map(|id| self.simulation.particle(*id))
+                {
+                    particle_lane.draw_ring(
+                        particle.position(),
+                        PARTICLE_RADIUS * 1.5,
+                        2.5,
+                        SELECTION_COLOUR,
+                    );
+                }
+
+                for (i, x) in self.settings.markers.iter().enumerate() {
+                    canvas.draw_marker(*x, &format!("{} {}", tr("Marker"), i + 1), MARKER_COLOUR);
+                }
+
+                if let Some(start) = self.box_zoom.or(self.box_select) {
+                    canvas.draw_selection(
+                        canvas.screen_to_view(start),
+                        canvas.screen_to_view(pointer_pos),
+                    );
+                }
+
+                if files_hovered {
+                    draw_drop_indicator(ui.painter(), canvas_extent);
+                }
             })
             .response;
 
-        // immediately redraw so simulation is constantly updated as fast as monitor refresh
-        ctx.request_repaint();
+        // analysis panels
+        let energy = &mut self.energy;
+        panels::show(ctx, &mut self.settings.energy_panel, "Energy", |ui| {
+            energy.draw(ui)
+        });
+        let surface = &mut self.surface;
+        panels::show(
+            ctx,
+            &mut self.settings.surface_panel,
+            "3D field history",
+            |ui| surface.draw(ui),
+        );
+        let (phasors, simulation, references) =
+            (&mut self.phasors, &self.simulation, &self.references);
+        let probe_x = &mut self.settings.probe_x;
+        panels::show(ctx, &mut self.settings.phasor_panel, "Phasors", |ui| {
+            phasors.draw(ui, simulation, probe_x, references.dispersion.as_ref())
+        });
+        let noise = &mut self.noise;
+        panels::show(
+            ctx,
+            &mut self.settings.noise_panel,
+            "Noise statistics",
+            |ui| noise.draw(ui, simulation, probe_x),
+        );
+        let spectrum = &mut self.spectrum;
+        panels::show(ctx, &mut self.settings.spectrum_panel, "Spectrum", |ui| {
+            spectrum.draw(ui, simulation, probe_x)
+        });
+        let extinction = &self.extinction;
+        panels::show(
+            ctx,
+            &mut self.settings.extinction_panel,
+            "Extinction theorem",
+            |ui| extinction.draw(ui, simulation),
+        );
+        let pulse = &self.pulse;
+        panels::show(ctx, &mut self.settings.pulse_panel, "Pulse delay", |ui| {
+            pulse.draw(ui, simulation)
+        });
+        panels::show(
+            ctx,
+            &mut self.settings.multiples_panel,
+            "Particle fields",
+            |ui| multiples::draw_particle_fields(ui, simulation, visible_world),
+        );
+        let (inspector, simulation) = (&mut self.inspector, &mut self.simulation);
+        let selected_particles = &mut self.selected_particles;
+        panels::show(ctx, &mut self.settings.inspector_panel, "Inspector", |ui| {
+            inspector.draw(ui, simulation, selected_particles)
+        });
+        let simulation = &self.simulation;
+        let convergence = &mut self.convergence;
+        panels::show(
+            ctx,
+            &mut self.settings.convergence_panel,
+            "Convergence",
+            |ui| convergence.draw(ui, simulation),
+        );
+        let scattering = &mut self.scattering;
+        panels::show(
+            ctx,
+            &mut self.settings.scattering_panel,
+            "Scattering",
+            |ui| scattering.draw(ui, simulation),
+        );
+        let ensemble = &mut self.ensemble;
+        panels::show(ctx, &mut self.settings.ensemble_panel, "Ensemble", |ui| {
+            ensemble.draw(ui, simulation)
+        });
+        let comparison = &mut self.comparison;
+        panels::show(
+            ctx,
+            &mut self.settings.compare_panel,
+            "Compare runs",
+            |ui| comparison.draw(ui),
+        );
+        let (bookmarks, bookmark_list) = (&mut self.bookmarks, &mut self.settings.bookmarks);
+        let mut jump = None;
+        panels::show(ctx, &mut self.settings.bookmarks_panel, "Bookmarks", |ui| {
+            jump = bookmarks.draw(ui, bookmark_list, simulation);
+        });
+        if let Some(time) = jump {
+            self.jump_to(time);
+        }
+        let (quiz, simulation) = (&mut self.quiz, &self.simulation);
+        let mut quiz_action = None;
+        panels::show(
+            ctx,
+            &mut self.settings.quiz_panel,
+            "Prediction quiz",
+            |ui| {
+                quiz_action = quiz.draw(ui, simulation);
+            },
+        );
+        match quiz_action {
+            Some(QuizAction::Start) => {
+                self.restart();
+                self.quiz.start(&self.simulation);
+            }
+            Some(QuizAction::Play) => self.paused = false,
+            None => {}
+        }
+        let (fit, simulation) = (&mut self.fit, &self.simulation);
+        let mut fitted = None;
+        panels::show(ctx, &mut self.settings.fit_panel, "Fit to a medium", |ui| {
+            fitted = fit.draw(ui, simulation);
+        });
+        if let Some((fitted, frequency)) = fitted {
+            for command in [
+                SimulationCommand::SpringConstant(fitted.spring_constant),
+                SimulationCommand::Damping(fitted.damping),
+                SimulationCommand::Frequency(frequency),
+            ] {
+                self.simulation.send(command);
+            }
+        }
+        let frame_export = &mut self.frame_export;
+        let mut save_image = false;
+        panels::show(ctx, &mut self.settings.export_panel, "Image export", |ui| {
+            save_image = frame_export.draw(ui);
+        });
+        if save_image {
+            let saved = self
+                .frame_export
+                .save_image(&self.simulation, &self.settings);
+            if let Err(e) = saved {
+                self.error = Some(format!("{}\n{e}", tr("Could not export image")));
+            }
+        }
+        #[cfg(feature = "stream")]
+        {
+            let stream = &mut self.stream;
+            panels::show(ctx, &mut self.settings.stream_panel, "Live stream", |ui| {
+                stream.draw(ui)
+            });
+            stream.publish(&self.simulation, &self.settings);
+        }
+        #[cfg(feature = "scripting")]
+        {
+            let (diagnostics, simulation) = (&mut self.diagnostics, &self.simulation);
+            let mut compared = None;
+            panels::show(
+                ctx,
+                &mut self.settings.diagnostics_panel,
+                "Diagnostics",
+                |ui| compared = diagnostics.draw(ui, simulation),
+            );
+            if let Some(run) = compared {
+                self.comparison.add(run);
+                self.settings.compare_panel.open = true;
+            }
+        }
+
+        // the tour is drawn last so that it appears over everything else
+        if let Some(action) = self.tour.show(ctx) {
+            self.apply_tour_action(action);
+        }
+
+        self.show_recovery_prompt(ctx);
+        if let Some(choice) = start::show(ctx, &mut self.start_screen_open, &mut self.settings) {
+            self.start(ctx, choice);
+        }
+        self.show_error(ctx);
+
+        // the recovery file isn't overwritten until the user has decided whether to restore it
+        if self.recovered.is_none() {
+            self.autosave.update(|| Session {
+                scene: self.simulation.scene(),
+                settings: self.settings.clone(),
+            });
+        }
+
+        #[cfg(feature = "serde-state")]
+        if updates > 0 {
+            if let Some(checkpointer) = &mut self.checkpointer {
+                if let Err(e) = checkpointer.update(&self.simulation) {
+                    self.error = Some(format!("{}\n{e}", tr("Could not save a checkpoint")));
+                    self.checkpointer = None;
+                }
+            }
+        }
+
+        self.frame_stats.end_frame(updates);
+
+        // immediately redraw so simulation is constantly updated as fast as monitor refresh.
+        // while suspended, only redraw in response to input such as the window regaining focus
+        if !suspended {
+            ctx.request_repaint();
+        }
     }
 }
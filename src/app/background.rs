@@ -0,0 +1,46 @@
+//! Work run on another thread while the UI carries on, such as rerunning the scene for analysis,
+//! with a count of how much of it has finished for a progress bar
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::JoinHandle;
+
+use egui::{ProgressBar, Ui};
+
+pub struct BackgroundRun<T> {
+    thread: JoinHandle<T>,
+    finished: Arc<AtomicUsize>, // number of parts of the work finished so far
+}
+
+impl<T: Send + 'static> BackgroundRun<T> {
+    // starts work on another thread. it is given a function to call with the number of its parts finished
+    pub fn start(work: impl FnOnce(&dyn Fn(usize)) -> T + Send + 'static) -> Self {
+        let finished = Arc::new(AtomicUsize::new(0));
+        let progress = finished.clone();
+        let thread = std::thread::spawn(move || {
+            work(&|parts: usize| progress.store(parts, Ordering::Relaxed))
+        });
+        BackgroundRun { thread, finished }
+    }
+
+    // takes the result out of a run once it has finished, leaving it in place while it is still running.
+    // name is used to report a run that failed
+    pub fn poll(run: &mut Option<Self>, name: &str) -> Option<T> {
+        let run = run.take_if(|run| run.thread.is_finished())?;
+        match run.thread.join() {
+            Ok(result) => Some(result),
+            Err(_) => {
+                log::error!("{name} failed");
+                None
+            }
+        }
+    }
+
+    // progress bar out of the number of parts the work has
+    pub fn show_progress(&self, ui: &mut Ui, parts: usize) {
+        let finished = self.finished.load(Ordering::Relaxed);
+        ui.add(ProgressBar::new(finished as f32 / parts as f32).show_percentage());
+        // keep redrawing to show progress, even while the simulation is paused
+        ui.ctx().request_repaint();
+    }
+}
@@ -0,0 +1,88 @@
+//! Bookmarks at interesting times in the simulation, listed in a panel and saved with the session.
+//! Choosing a bookmark jumps the simulation there. A copy of the simulation is kept when a bookmark is added,
+//! so it can be jumped back to directly; otherwise the run is repeated from the start, which gives the same
+//! result as the simulation is deterministic.
+
+use egui::{Grid, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n::tr;
+use crate::app::simulation::Simulation;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub time: f32,
+}
+
+#[derive(Default)]
+pub struct BookmarkPanel {
+    name: String,                 // name typed for the next bookmark
+    checkpoints: Vec<Simulation>, // copies of the simulation at the times bookmarks were added
+}
+
+impl BookmarkPanel {
+    // lists the bookmarks, returning the time of one chosen to jump to
+    pub fn draw(
+        &mut self,
+        ui: &mut Ui,
+        bookmarks: &mut Vec<Bookmark>,
+        simulation: &Simulation,
+    ) -> Option<f32> {
+        ui.horizontal(|ui| {
+            if self.name.is_empty() {
+                self.name = format!("{} {}", tr("Bookmark"), bookmarks.len() + 1);
+            }
+            ui.text_edit_singleline(&mut self.name);
+            if ui
+                .button(format!("{} t = {:.2} s", tr("Add at"), simulation.time()))
+                .clicked()
+            {
+                bookmarks.push(Bookmark {
+                    name: std::mem::take(&mut self.name),
+                    time: simulation.time(),
+                });
+                bookmarks.sort_by(|a, b| a.time.total_cmp(&b.time));
+                self.checkpoints.push(simulation.clone());
+            }
+        });
+        if bookmarks.is_empty() {
+            ui.label(tr("No bookmarks yet"));
+            return None;
+        }
+
+        let mut jump = None;
+        let mut removed = None;
+        Grid::new("Bookmarks").striped(true).show(ui, |ui| {
+            for (i, bookmark) in bookmarks.iter().enumerate() {
+                if ui
+                    .link(&bookmark.name)
+                    .on_hover_text(tr("Jump to this time"))
+                    .clicked()
+                {
+                    jump = Some(bookmark.time);
+                }
+                ui.monospace(format!("{:.2} s", bookmark.time));
+                if ui.button("🗑").on_hover_text(tr("Remove")).clicked() {
+                    removed = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = removed {
+            let removed = bookmarks.remove(i);
+            self.checkpoints
+                .retain(|checkpoint| checkpoint.time() != removed.time);
+        }
+        jump
+    }
+
+    // the latest copy of the simulation from no later than a time, if one was kept with the same setup
+    pub fn checkpoint(&self, simulation: &Simulation, time: f32) -> Option<&Simulation> {
+        let scene = simulation.scene();
+        self.checkpoints
+            .iter()
+            .filter(|checkpoint| checkpoint.time() <= time && checkpoint.scene() == scene)
+            .max_by(|a, b| a.time().total_cmp(&b.time()))
+    }
+}
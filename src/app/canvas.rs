@@ -1,109 +1,424 @@
 //! Helper struct for drawing objects in world space onto the screen.
+//! What is drawn is collected through the frame and handed to the painter at once, rather than with a call to the
+//! painter for every particle and trace, with the particles' circles built into a single mesh.
 
-use egui::{Color32, Pos2, Rangef, Rect, Stroke, Ui, Vec2, epaint::CircleShape, pos2, vec2};
+use std::cell::RefCell;
+use std::f32::consts::TAU;
+use std::rc::Rc;
+
+use egui::{
+    Align2, Color32, FontId, Mesh, Painter, Pos2, Rangef, Rect, Shape, Stroke, Ui, Vec2,
+    epaint::CircleShape, layers::ShapeIdx, pos2, vec2,
+};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::app::palette::{LinePattern, Overlay, colours};
 
 // Don't draw points with absolute y value less than this
 const SUPPRESS_ZERO_POINTS_THRESHOLD: f32 = 0.005;
+// height of the visible part of the world
+pub const VISIBLE_Y_SPAN: f32 = 4.0;
+// field strength below which the symmetric log scale is linear
+const SYMLOG_THRESHOLD: f32 = 0.01;
+// range of decibels shown below the reference amplitude, and above it
+const DECIBEL_RANGE: (f32, f32) = (60.0, 20.0);
 
-pub struct Canvas<'a> {
-    ui: &'a Ui,
+// how field strengths are mapped onto the vertical axis
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, Serialize, Deserialize)]
+pub enum YScale {
+    #[default]
+    Linear,
+    Symlog,   // logarithmic away from zero and linear close to it, keeping the sign
+    Decibels, // magnitude in decibels relative to the applied wave's amplitude, keeping the sign
+}
+
+impl YScale {
+    pub fn name(&self) -> &'static str {
+        match self {
+            YScale::Linear => "Linear",
+            YScale::Symlog => "Symmetric log",
+            YScale::Decibels => "Decibels",
+        }
+    }
+
+    // position on the axis of a field strength, such that the visible span is unchanged
+    pub fn apply(&self, y: f32, reference: f32) -> f32 {
+        let edge = VISIBLE_Y_SPAN / 2.0;
+        match self {
+            YScale::Linear => y,
+            YScale::Symlog => {
+                let log = |y: f32| (1.0 + y.abs() / SYMLOG_THRESHOLD).log10();
+                y.signum() * edge * log(y) / log(edge)
+            }
+            YScale::Decibels => {
+                let (below, above) = DECIBEL_RANGE;
+                let decibels = 20.0 * (y.abs() / reference).log10();
+                y.signum() * edge * ((decibels + below) / (below + above)).max(0.0)
+            }
+        }
+    }
+
+    // field strengths worth marking on the axis, with their labels
+    fn ticks(&self, reference: f32) -> Vec<(f32, String)> {
+        match self {
+            YScale::Linear => Vec::new(),
+            YScale::Symlog => [0.01, 0.1, 1.0]
+                .into_iter()
+                .map(|y| (y, format!("{y}")))
+                .collect(),
+            YScale::Decibels => (-2..=1)
+                .map(|i| {
+                    let decibels = 20.0 * i as f32;
+                    (
+                        reference * 10f32.powf(decibels / 20.0),
+                        format!("{decibels:+} dB"),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+// opacity of the area filled under a curve, relative to the curve's, and how far its two tones are mixed from the
+// curve's colour towards white above the zero line and black below it
+const FILL_OPACITY: f32 = 0.35;
+const FILL_TONE: f32 = 0.3;
+
+// colours of the area under a curve of a colour, where it is positive and where it is negative
+pub fn fill_tones(colour: Color32) -> (Color32, Color32) {
+    let [r, g, b, a] = colour.to_srgba_unmultiplied();
+    let tone = |towards: f32| {
+        let mix = |c: u8| (c as f32 + (towards - c as f32) * FILL_TONE) as u8;
+        Color32::from_rgba_unmultiplied(mix(r), mix(g), mix(b), (a as f32 * FILL_OPACITY) as u8)
+    };
+    (tone(255.0), tone(0.0))
+}
+
+// width in points over which the edge of a filled circle fades out, to smooth it as the painter's own shapes are
+const FEATHERING: f32 = 1.0;
+
+// shapes drawn on a canvas and its lanes. they take the place in the painter's list that the canvas was created at,
+// in the order they were drawn other than the circles, underneath them, once the last lane using them is dropped
+struct Batch {
+    painter: Painter,
+    place: ShapeIdx,
+    circles: Mesh, // filled circles
+    shapes: Vec<Shape>,
+}
+
+impl Batch {
+    // adds a filled circle to the mesh as a fan of triangles, with a ring fading out around its edge
+    fn add_circle(&mut self, centre: Pos2, radius: f32, colour: Color32) {
+        let segments = (radius * 2.0).clamp(12.0, 64.0) as u32;
+        let mesh = &mut self.circles;
+        let first = mesh.vertices.len() as u32;
+        mesh.colored_vertex(centre, colour);
+        let (inner, outer) = (
+            (radius - FEATHERING / 2.0).max(0.0),
+            radius + FEATHERING / 2.0,
+        );
+        for i in 0..segments {
+            let direction = Vec2::angled(TAU * i as f32 / segments as f32);
+            mesh.colored_vertex(centre + inner * direction, colour);
+            mesh.colored_vertex(centre + outer * direction, Color32::TRANSPARENT);
+        }
+        for i in 0..segments {
+            let (inner, outer) = (first + 1 + 2 * i, first + 2 + 2 * i);
+            let (next_inner, next_outer) = (
+                first + 1 + 2 * ((i + 1) % segments),
+                first + 2 + 2 * ((i + 1) % segments),
+            );
+            mesh.add_triangle(first, inner, next_inner);
+            mesh.add_triangle(inner, outer, next_outer);
+            mesh.add_triangle(inner, next_outer, next_inner);
+        }
+    }
+}
+
+impl Drop for Batch {
+    fn drop(&mut self) {
+        let mut shapes = vec![Shape::mesh(std::mem::take(&mut self.circles))];
+        shapes.append(&mut self.shapes);
+        self.painter.set(self.place, Shape::Vec(shapes));
+    }
+}
+
+// mapping of world space onto an area of the screen, or of an image drawn offscreen, through the visible range
+#[derive(Clone, Copy)]
+pub struct CanvasTransform {
     screen_extent: Rect, // screen area to be drawn to
     range: Rect,         // area of simulation to draw from
     scale: Vec2,         // ratios between screen and world space for each axis
+    y_axis: YScale,      // mapping of field strengths onto the vertical axis
+    reference: f32,      // field strength that 0 dB refers to
+    lane_offset: f32,    // world y of the zero line, when drawing in a lane
+    lane_scale: f32,     // fraction of the height taken by the lane
 }
 
-impl<'a> Canvas<'a> {
-    pub fn new(ui: &'a Ui, screen_extent: Rect, visible_x_axis: Rangef) -> Self {
-        // calculate world space
-        //let y_span = visible_x_axis.span() / screen_extent.aspect_ratio();
-        let y_span = 4.0;
-        let range = Rect::from_x_y_ranges(visible_x_axis, Rangef::new(-y_span / 2.0, y_span / 2.0));
+impl CanvasTransform {
+    pub fn new(
+        screen_extent: Rect,
+        visible_x_axis: Rangef,
+        visible_y_axis: Rangef,
+        y_axis: YScale,
+        reference: f32,
+    ) -> Self {
+        // calculate world space. y is flipped, as the screen origin is the top left
+        let range = Rect::from_x_y_ranges(
+            visible_x_axis,
+            Rangef::new(-visible_y_axis.max, -visible_y_axis.min),
+        );
 
         let x_scale = screen_extent.width() / range.width();
         let y_scale = screen_extent.height() / range.height();
 
-        Canvas {
-            ui,
+        CanvasTransform {
             screen_extent,
             range,
             scale: vec2(x_scale, y_scale),
+            y_axis,
+            reference,
+            lane_offset: 0.0,
+            lane_scale: 1.0,
         }
     }
 
-    fn world_to_screen_pos(&self, pos: &Pos2) -> Pos2 {
+    // transform into one of a number of horizontal lanes stacked from the top,
+    // each with its own zero line and the full range of field strengths squeezed to fit
+    pub fn lane(&self, index: usize, count: usize) -> Self {
+        let height = VISIBLE_Y_SPAN / count as f32;
+        CanvasTransform {
+            lane_offset: VISIBLE_Y_SPAN / 2.0 - (index as f32 + 0.5) * height,
+            lane_scale: 1.0 / count as f32,
+            ..*self
+        }
+    }
+
+    // position of a field strength on the vertical axis, in world space
+    fn y_position(&self, y: f32) -> f32 {
+        self.lane_offset + self.lane_scale * self.y_axis.apply(y, self.reference)
+    }
+
+    pub fn world_to_screen_pos(&self, pos: &Pos2) -> Pos2 {
         // convert vector from simulation coords to screen pixel location
-        self.screen_extent.min + self.scale * (pos2(pos.x, -pos.y) - self.range.min)
+        let y = self.y_position(pos.y);
+        self.screen_extent.min + self.scale * (pos2(pos.x, -y) - self.range.min)
     }
 
-    fn world_to_screen_x(&self, x: f32) -> f32 {
+    pub fn world_to_screen_x(&self, x: f32) -> f32 {
         // convert simulation x coord to screen pixel location
         self.screen_extent.min.x + self.scale.x * (x - self.range.min.x)
     }
 
-    fn world_to_screen_y(&self, y: f32) -> f32 {
+    pub fn world_to_screen_y(&self, y: f32) -> f32 {
         // convert simulation y coord to screen pixel location.
         // note: -y because the screen origin is the top left.
+        let y = self.y_position(y);
         self.screen_extent.min.y + self.scale.y * (-y - self.range.min.y)
     }
 
-    fn world_to_screen_scale(&self) -> f32 {
-        // when drawing objects with fixed aspect ratio, use x scale for sizing
-        self.scale.x
+    // position in the visible range of a point on the screen. y is the position on the vertical axis,
+    // before any lane or nonlinear scale is applied
+    pub fn screen_to_view(&self, screen_pos: Pos2) -> Pos2 {
+        let pos = self.range.min + (screen_pos - self.screen_extent.min) / self.scale;
+        pos2(pos.x, -pos.y)
     }
 
-    // draws a circle
-    pub fn draw_filled_circle(&self, pos: &Pos2, radius: f32, colour: Color32) {
-        let screen_pos = self.world_to_screen_pos(pos);
-        let screen_radius = radius * self.world_to_screen_scale();
-        self.ui
-            .painter()
-            .add(CircleShape::filled(screen_pos, screen_radius, colour));
+    // screen position of a point in the visible range, the inverse of screen_to_view
+    fn view_to_screen(&self, pos: Pos2) -> Pos2 {
+        self.screen_extent.min + self.scale * (pos2(pos.x, -pos.y) - self.range.min)
     }
 
-    // draw fine background lines
-    pub fn draw_grid_lines(&self) {
-        // draw a horizontal line every 0.5 world units
-        let mut y = (2.0 * self.range.min.y).round() / 2.0;
-        while y < self.range.max.y {
-            self.ui.painter().hline(
-                self.screen_extent.x_range(),
-                self.world_to_screen_y(y),
-                Stroke::new(1.0, Color32::from_rgb(15, 15, 15)),
-            );
-            y += 0.5;
+    pub fn world_to_screen_scale(&self) -> f32 {
+        // when drawing objects with fixed aspect ratio, use x scale for sizing
+        self.scale.x
+    }
+
+    // field strengths marked by horizontal lines, with their labels. a linear scale has a line every 0.5 world units,
+    // and nonlinear scales are marked at labelled values above and below zero
+    pub fn horizontal_gridlines(&self) -> Vec<(f32, Option<String>)> {
+        let mut lines = Vec::new();
+        if self.y_axis == YScale::Linear {
+            let top = -self.range.min.y;
+            let mut y = (-2.0 * self.range.max.y).round() / 2.0;
+            while y < top {
+                lines.push((y, None));
+                y += 0.5;
+            }
+        } else {
+            for (y, label) in self.y_axis.ticks(self.reference) {
+                lines.extend([y, -y].map(|y| (y, Some(label.clone()))));
+            }
         }
+        lines
+    }
 
+    // world x of the vertical lines in the background
+    pub fn vertical_gridlines(&self) -> Vec<f32> {
         // try to fit close to this many vertical lines on the screen
         const MAX_GRIDLINES: f32 = 20.0;
         let step = (self.range.x_range().span() / MAX_GRIDLINES).round();
         // starting coordinate
         let mut x = step * (self.range.min.x / step).round();
+        let mut lines = Vec::new();
         while x < self.range.max.x {
-            self.ui.painter().vline(
-                self.world_to_screen_x(x),
-                self.screen_extent.y_range(),
-                Stroke::new(1.0, Color32::from_rgb(15, 15, 15)),
-            );
+            lines.push(x);
             x += step;
         }
+        lines
+    }
+}
+
+#[derive(Clone)]
+pub struct Canvas<'a> {
+    ui: &'a Ui,
+    batch: Rc<RefCell<Batch>>, // shared by the lanes of a canvas
+    transform: CanvasTransform,
+    line_scale: f32, // factor applied to line thickness and text size
+}
+
+impl<'a> Canvas<'a> {
+    pub fn new(
+        ui: &'a Ui,
+        screen_extent: Rect,
+        visible_x_axis: Rangef,
+        visible_y_axis: Rangef,
+        line_scale: f32,
+        y_axis: YScale,
+        reference: f32,
+    ) -> Self {
+        let painter = ui.painter().clone();
+        let batch = Batch {
+            place: painter.add(Shape::Noop),
+            painter,
+            circles: Mesh::default(),
+            shapes: Vec::new(),
+        };
+
+        Canvas {
+            ui,
+            batch: Rc::new(RefCell::new(batch)),
+            transform: CanvasTransform::new(
+                screen_extent,
+                visible_x_axis,
+                visible_y_axis,
+                y_axis,
+                reference,
+            ),
+            line_scale,
+        }
+    }
+
+    // canvas drawing into one of a number of horizontal lanes stacked from the top
+    pub fn lane(&self, index: usize, count: usize) -> Self {
+        Canvas {
+            transform: self.transform.lane(index, count),
+            ..self.clone()
+        }
+    }
+
+    pub fn world_to_screen_pos(&self, pos: &Pos2) -> Pos2 {
+        self.transform.world_to_screen_pos(pos)
+    }
+
+    pub fn screen_to_view(&self, screen_pos: Pos2) -> Pos2 {
+        self.transform.screen_to_view(screen_pos)
+    }
+
+    fn add(&self, shape: impl Into<Shape>) {
+        self.batch.borrow_mut().shapes.push(shape.into());
+    }
+
+    // draws a circle
+    pub fn draw_filled_circle(&self, pos: &Pos2, radius: f32, colour: Color32) {
+        let screen_pos = self.world_to_screen_pos(pos);
+        let screen_radius = radius * self.transform.world_to_screen_scale();
+        self.batch
+            .borrow_mut()
+            .add_circle(screen_pos, screen_radius, colour);
+    }
+
+    // draws the outline of a circle, with a line width in points
+    pub fn draw_ring(&self, pos: &Pos2, radius: f32, width: f32, colour: Color32) {
+        let screen_pos = self.world_to_screen_pos(pos);
+        let screen_radius = radius * self.transform.world_to_screen_scale();
+        self.add(CircleShape::stroke(
+            screen_pos,
+            screen_radius,
+            Stroke::new(width * self.line_scale, colour),
+        ));
+    }
+
+    // true if a point on the screen is inside a circle drawn with draw_filled_circle
+    pub fn circle_contains(&self, pos: &Pos2, radius: f32, screen_pos: Pos2) -> bool {
+        let screen_radius = radius * self.transform.world_to_screen_scale();
+        self.world_to_screen_pos(pos).distance(screen_pos) <= screen_radius
+    }
+
+    // draw fine background lines
+    pub fn draw_grid_lines(&self) {
+        let transform = &self.transform;
+        for (y, label) in transform.horizontal_gridlines() {
+            let screen_y = transform.world_to_screen_y(y);
+            let colour = match label {
+                Some(_) => colours().ticks,
+                None => colours().grid,
+            };
+            self.add(Shape::hline(
+                transform.screen_extent.x_range(),
+                screen_y,
+                Stroke::new(1.0, colour),
+            ));
+            if let Some(label) = label {
+                self.add(self.ui.fonts(|fonts| {
+                    Shape::text(
+                        fonts,
+                        pos2(transform.screen_extent.left() + 4.0, screen_y),
+                        Align2::LEFT_BOTTOM,
+                        &label,
+                        FontId::proportional(11.0 * self.line_scale),
+                        Color32::from_gray(90),
+                    )
+                }));
+            }
+        }
+        for x in transform.vertical_gridlines() {
+            self.add(Shape::vline(
+                transform.world_to_screen_x(x),
+                transform.screen_extent.y_range(),
+                Stroke::new(1.0, colours().grid),
+            ));
+        }
     }
 
     // draw thicker lines at x=0 and y=0
     pub fn draw_axes(&self) {
-        self.ui.painter().vline(
-            self.world_to_screen_x(0.0),
-            self.screen_extent.y_range(),
-            Stroke::new(2.0, Color32::from_rgb(20, 20, 20)),
-        );
-        self.ui.painter().hline(
-            self.screen_extent.x_range(),
-            self.world_to_screen_y(0.0),
-            Stroke::new(2.0, Color32::from_rgb(20, 20, 20)),
-        );
+        self.add(Shape::vline(
+            self.transform.world_to_screen_x(0.0),
+            self.transform.screen_extent.y_range(),
+            Stroke::new(2.0, colours().axes),
+        ));
+        self.add(Shape::hline(
+            self.transform.screen_extent.x_range(),
+            self.transform.world_to_screen_y(0.0),
+            Stroke::new(2.0, colours().axes),
+        ));
     }
 
-    // draw a set of points as a continuous line
-    pub fn draw_points(&self, x_points: &[f32], y_points: &[f32], colour: &Color32) {
+    // draw a set of points as a line a number of times the usual width, continuous or in a pattern of dashes
+    pub fn draw_points_styled(
+        &self,
+        x_points: &[f32],
+        y_points: &[f32],
+        colour: &Color32,
+        width: f32,
+        pattern: Option<&LinePattern>,
+    ) {
+        if let Some(pattern) = pattern {
+            self.draw_points_patterned(x_points, y_points, colour, width, pattern);
+            return;
+        }
         // number of elements must match
         if (x_points.len() < 2) || (x_points.len() != y_points.len()) {
             log::error!("Slices passed to draw_points have invalid sizes");
@@ -115,10 +430,257 @@ impl<'a> Canvas<'a> {
             if y.abs() < SUPPRESS_ZERO_POINTS_THRESHOLD {
                 continue;
             }
-            screen_points.push(pos2(self.world_to_screen_x(*x), self.world_to_screen_y(*y)));
+            screen_points.push(pos2(
+                self.transform.world_to_screen_x(*x),
+                self.transform.world_to_screen_y(*y),
+            ));
+        }
+        self.add(Shape::line(
+            screen_points,
+            Stroke::new(2.5 * width * self.line_scale, *colour),
+        ));
+    }
+
+    // draw a set of points as a line in a pattern of dashes, a number of times the width of a continuous one, with
+    // the dashes lengthened in proportion
+    pub fn draw_points_patterned(
+        &self,
+        x_points: &[f32],
+        y_points: &[f32],
+        colour: &Color32,
+        width: f32,
+        pattern: &LinePattern,
+    ) {
+        if (x_points.len() < 2) || (x_points.len() != y_points.len()) {
+            log::error!("Slices passed to draw_points_patterned have invalid sizes");
+            return;
+        }
+        let screen_points: Vec<Pos2> = x_points
+            .iter()
+            .zip(y_points)
+            .map(|(x, y)| {
+                pos2(
+                    self.transform.world_to_screen_x(*x),
+                    self.transform.world_to_screen_y(*y),
+                )
+            })
+            .collect();
+        let scale = |lengths: &[f32]| -> Vec<f32> {
+            lengths
+                .iter()
+                .map(|length| length * width * self.line_scale)
+                .collect()
+        };
+        self.add(Shape::dashed_line_with_offset(
+            &screen_points,
+            Stroke::new(2.5 * width * self.line_scale, *colour),
+            &scale(pattern.dashes),
+            &scale(pattern.gaps),
+            0.0,
+        ));
+    }
+
+    // draw a curve over the traces, thinner than them and in the overlay's own pattern of dashes
+    pub fn draw_overlay(
+        &self,
+        x_points: &[f32],
+        y_points: &[f32],
+        colour: &Color32,
+        overlay: Overlay,
+    ) {
+        if (x_points.len() < 2) || (x_points.len() != y_points.len()) {
+            log::error!("Slices passed to draw_overlay have invalid sizes");
+            return;
+        }
+        let screen_points: Vec<Pos2> = x_points
+            .iter()
+            .zip(y_points)
+            .map(|(x, y)| {
+                pos2(
+                    self.transform.world_to_screen_x(*x),
+                    self.transform.world_to_screen_y(*y),
+                )
+            })
+            .collect();
+        let pattern = overlay.pattern();
+        let scale = |lengths: &[f32]| -> Vec<f32> {
+            lengths
+                .iter()
+                .map(|length| length * self.line_scale)
+                .collect()
+        };
+        self.add(Shape::dashed_line_with_offset(
+            &screen_points,
+            Stroke::new(1.5 * self.line_scale, *colour),
+            &scale(pattern.dashes),
+            &scale(pattern.gaps),
+            0.0,
+        ));
+    }
+
+    // fill the area between two curves over the same points, e.g. a band of uncertainty about a mean
+    pub fn draw_band(&self, x_points: &[f32], lower: &[f32], upper: &[f32], colour: Color32) {
+        if (x_points.len() < 2) || (x_points.len() != lower.len()) || (lower.len() != upper.len()) {
+            log::error!("Slices passed to draw_band have invalid sizes");
+            return;
+        }
+        let mut mesh = Mesh::default();
+        for ((x, low), high) in x_points.iter().zip(lower).zip(upper) {
+            let screen_x = self.transform.world_to_screen_x(*x);
+            mesh.colored_vertex(
+                pos2(screen_x, self.transform.world_to_screen_y(*low)),
+                colour,
+            );
+            mesh.colored_vertex(
+                pos2(screen_x, self.transform.world_to_screen_y(*high)),
+                colour,
+            );
+        }
+        for i in 0..x_points.len() as u32 - 1 {
+            mesh.add_triangle(2 * i, 2 * i + 1, 2 * i + 2);
+            mesh.add_triangle(2 * i + 1, 2 * i + 3, 2 * i + 2);
+        }
+        self.add(Shape::mesh(mesh));
+    }
+
+    // draw a dashed vertical line across the canvas at x, labelled at the top
+    pub fn draw_marker(&self, x: f32, label: &str, colour: Color32) {
+        let screen_x = self.transform.world_to_screen_x(x);
+        let top = pos2(screen_x, self.transform.screen_extent.top());
+        let bottom = pos2(screen_x, self.transform.screen_extent.bottom());
+        self.add(Shape::dashed_line(
+            &[top, bottom],
+            Stroke::new(1.5 * self.line_scale, colour),
+            6.0,
+            4.0,
+        ));
+        self.add(self.ui.fonts(|fonts| {
+            Shape::text(
+                fonts,
+                top + vec2(4.0, 4.0),
+                Align2::LEFT_TOP,
+                label,
+                FontId::proportional(13.0 * self.line_scale),
+                colour,
+            )
+        }));
+    }
+
+    // fill the area between a curve and its zero line, lighter where it is positive and darker where it is negative,
+    // splitting the area where the curve crosses zero
+    pub fn draw_filled(&self, x_points: &[f32], y_points: &[f32], colour: Color32) {
+        if (x_points.len() < 2) || (x_points.len() != y_points.len()) {
+            log::error!("Slices passed to draw_filled have invalid sizes");
+            return;
+        }
+        let (positive, negative) = fill_tones(colour);
+        let zero = self.transform.world_to_screen_y(0.0);
+        let point = |x: f32, y: f32| {
+            pos2(
+                self.transform.world_to_screen_x(x),
+                self.transform.world_to_screen_y(y),
+            )
+        };
+        let mut mesh = Mesh::default();
+        // adds a polygon of the area, which is convex, as a fan of triangles
+        let mut add = |corners: &[Pos2], y: f32| {
+            let colour = if y >= 0.0 { positive } else { negative };
+            let first = mesh.vertices.len() as u32;
+            for corner in corners {
+                mesh.colored_vertex(*corner, colour);
+            }
+            for i in 1..corners.len() as u32 - 1 {
+                mesh.add_triangle(first, first + i, first + i + 1);
+            }
+        };
+        for (x, y) in x_points.windows(2).zip(y_points.windows(2)) {
+            let (start, end) = (point(x[0], y[0]), point(x[1], y[1]));
+            if (y[0] >= 0.0) == (y[1] >= 0.0) {
+                add(&[pos2(start.x, zero), start, end, pos2(end.x, zero)], y[0]);
+            } else {
+                let crossing = x[0] + (x[1] - x[0]) * y[0] / (y[0] - y[1]);
+                let crossing = pos2(self.transform.world_to_screen_x(crossing), zero);
+                add(&[pos2(start.x, zero), start, crossing], y[0]);
+                add(&[crossing, end, pos2(end.x, zero)], y[1]);
+            }
+        }
+        self.add(Shape::mesh(mesh));
+    }
+
+    // draw a set of points as a continuous line, with a colour for each point
+    pub fn draw_points_coloured(&self, x_points: &[f32], y_points: &[f32], colours: &[Color32]) {
+        if (x_points.len() < 2)
+            || (x_points.len() != y_points.len())
+            || (x_points.len() != colours.len())
+        {
+            log::error!("Slices passed to draw_points_coloured have invalid sizes");
+            return;
+        }
+        // filter out small values for visual clarity, as in draw_points
+        let points: Vec<(Pos2, Color32)> = x_points
+            .iter()
+            .zip(y_points)
+            .zip(colours)
+            .filter(|((_, y), _)| y.abs() >= SUPPRESS_ZERO_POINTS_THRESHOLD)
+            .map(|((x, y), colour)| {
+                let pos = pos2(
+                    self.transform.world_to_screen_x(*x),
+                    self.transform.world_to_screen_y(*y),
+                );
+                (pos, *colour)
+            })
+            .collect();
+        for pair in points.windows(2) {
+            let [(start, colour), (end, _)] = pair else {
+                continue;
+            };
+            self.add(Shape::line_segment(
+                [*start, *end],
+                Stroke::new(2.5 * self.line_scale, *colour),
+            ));
         }
-        self.ui
-            .painter()
-            .line(screen_points, Stroke::new(2.5, *colour));
+    }
+
+    // draw a translucent box between opposite corners, given in view coordinates as from screen_to_view
+    pub fn draw_selection(&self, corner: Pos2, opposite_corner: Pos2) {
+        let rect = Rect::from_two_pos(
+            self.transform.view_to_screen(corner),
+            self.transform.view_to_screen(opposite_corner),
+        );
+        self.add(Shape::rect_filled(
+            rect,
+            0.0,
+            Color32::from_rgba_unmultiplied(120, 170, 255, 30),
+        ));
+        self.add(Shape::rect_stroke(
+            rect,
+            0.0,
+            Stroke::new(1.0, Color32::from_rgb(120, 170, 255)),
+            egui::StrokeKind::Inside,
+        ));
+    }
+
+    // draw text on a dark background, anchored to a point in world space and offset in screen space
+    pub fn draw_label(
+        &self,
+        pos: &Pos2,
+        offset: Vec2,
+        anchor: Align2,
+        text: String,
+        colour: Color32,
+    ) {
+        let galley = self.ui.painter().layout_no_wrap(
+            text,
+            FontId::monospace(13.0 * self.line_scale),
+            colour,
+        );
+        let screen_pos = self.world_to_screen_pos(pos) + offset;
+        let rect = anchor.anchor_size(screen_pos, galley.size());
+        self.add(Shape::rect_filled(
+            rect.expand(4.0),
+            3.0,
+            Color32::from_rgba_unmultiplied(0, 0, 0, 200),
+        ));
+        self.add(Shape::galley(rect.min, galley, colour));
     }
 }
@@ -0,0 +1,206 @@
+//! Checkpoints of the whole state of a running simulation, so long runs survive a crash and can be carried on in a
+//! later session. Built with the `serde-state` feature. The app can save a checkpoint periodically while it runs,
+//! and resume from one, or runs can be made without opening the window:
+//!
+//! ```text
+//! refraction run <scene> --until <time> [--checkpoint <file>] [--every <minutes>] [--divisions <count>]
+//! refraction resume <checkpoint> --until <time> [--every <minutes>]
+//! ```
+//!
+//! runs a scene, or carries on from a checkpoint, to a time, saving a checkpoint every so many minutes of running
+//! and once more at the end. A resumed run goes on saving to the checkpoint it started from.
+//! A checkpoint is only read by the version of the app that saved it, as the state saved changes between versions.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::simulation::Simulation;
+use crate::app::simulation::config::SimulationConfig;
+use crate::app::simulation::scene::Scene;
+
+pub const CHECKPOINT_EXTENSION: &str = "checkpoint";
+// minutes of running between checkpoints unless others are given
+pub const DEFAULT_INTERVAL: f32 = 10.0;
+// file a run's checkpoints are saved to unless another is given
+const DEFAULT_FILE: &str = "run.checkpoint";
+
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    version: String, // of the app that saved it
+    simulation: Simulation,
+}
+
+pub fn save(simulation: &Simulation, path: &Path) -> Result<(), String> {
+    let checkpoint = Checkpoint {
+        version: env!("CARGO_PKG_VERSION").to_owned(),
+        simulation: simulation.clone(),
+    };
+    let text = ron::to_string(&checkpoint).map_err(|e| e.to_string())?;
+    // written to a temporary file first, so a crash part way through writing leaves the previous checkpoint whole
+    let temporary = path.with_extension("checkpoint.tmp");
+    std::fs::write(&temporary, text).map_err(|e| e.to_string())?;
+    std::fs::rename(&temporary, path).map_err(|e| e.to_string())
+}
+
+pub fn open(path: &Path) -> Result<Simulation, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    // the version is read on its own first, so a checkpoint from another version is reported as such rather than as
+    // whatever part of its state no longer reads
+    #[derive(Deserialize)]
+    struct Version {
+        version: String,
+    }
+    let Version { version } = ron::from_str(&text).map_err(|e| e.to_string())?;
+    if version != env!("CARGO_PKG_VERSION") {
+        return Err(format!(
+            "the checkpoint was saved by version {version}, and can only be resumed by that version"
+        ));
+    }
+    let checkpoint: Checkpoint = ron::from_str(&text).map_err(|e| e.to_string())?;
+    Ok(checkpoint.simulation)
+}
+
+// saves checkpoints of a running simulation to a file, each time an interval of running has passed
+pub struct Checkpointer {
+    pub path: PathBuf,
+    interval: Duration,
+    last_save: Instant,
+}
+
+impl Checkpointer {
+    pub fn new(path: PathBuf, minutes: f32) -> Self {
+        let mut checkpointer = Checkpointer {
+            path,
+            interval: Duration::ZERO,
+            last_save: Instant::now(),
+        };
+        checkpointer.set_interval(minutes);
+        checkpointer
+    }
+
+    pub fn set_interval(&mut self, minutes: f32) {
+        self.interval = Duration::from_secs_f32(minutes.max(0.0) * 60.0);
+    }
+
+    // saves a checkpoint if the interval has passed, returning whether it did
+    pub fn update(&mut self, simulation: &Simulation) -> Result<bool, String> {
+        if self.last_save.elapsed() < self.interval {
+            return Ok(false);
+        }
+        self.save(simulation).map(|()| true)
+    }
+
+    pub fn save(&mut self, simulation: &Simulation) -> Result<(), String> {
+        self.last_save = Instant::now();
+        save(simulation, &self.path).map_err(|e| format!("{}: {e}", self.path.display()))
+    }
+}
+
+// runs a scene or carries on from a checkpoint, from the arguments after "run" or "resume"
+pub fn run(args: &[String], resume: bool) -> Result<(), String> {
+    let usage = || {
+        match resume {
+        true => "usage: refraction resume <checkpoint> --until <time> [--every <minutes>]".to_owned(),
+        false => "usage: refraction run <scene> --until <time> [--checkpoint <file>] [--every <minutes>] \
+            [--divisions <count>]"
+            .to_owned(),
+    }
+    };
+    let [file, options @ ..] = args else {
+        return Err(usage());
+    };
+    let number = |text: &String| {
+        text.parse::<f32>()
+            .map_err(|_| format!("{text} isn't a number"))
+    };
+    let (mut until, mut minutes, mut divisions) = (None, DEFAULT_INTERVAL, None);
+    let mut path = PathBuf::from(if resume { file } else { DEFAULT_FILE });
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let value = options.next().ok_or_else(usage)?;
+        match option.as_str() {
+            "--until" => until = Some(number(value)?),
+            "--every" => minutes = number(value)?,
+            "--checkpoint" if !resume => path = PathBuf::from(value),
+            "--divisions" if !resume => {
+                divisions = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("{value} isn't a number of divisions"))?,
+                );
+            }
+            _ => return Err(usage()),
+        }
+    }
+    let until = until.ok_or_else(usage)?;
+
+    let mut simulation = match resume {
+        true => open(file.as_ref())?,
+        false => {
+            let mut config = SimulationConfig::default().scene(Scene::open(file.as_ref())?);
+            if let Some(divisions) = divisions {
+                config = config.divisions(divisions);
+            }
+            Simulation::from_config(&config)?
+        }
+    };
+    let mut checkpointer = Checkpointer::new(path, minutes);
+    println!("t = {:.2} s, running to {until} s", simulation.time());
+    while simulation.time() < until {
+        if simulation.update() {
+            break;
+        }
+        if checkpointer.update(&simulation)? {
+            println!(
+                "t = {:.2} s, saved to {}",
+                simulation.time(),
+                checkpointer.path.display()
+            );
+        }
+    }
+    checkpointer.save(&simulation)?;
+    println!(
+        "t = {:.2} s, finished and saved to {}",
+        simulation.time(),
+        checkpointer.path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::waveform::Waveform;
+
+    // a run resumed from a checkpoint carries on as if it had never stopped, thermal kicks and all
+    #[test]
+    fn resumed_run_matches_uninterrupted_run() {
+        let scene = Scene {
+            waveform: Waveform::PlaneWave,
+            particle_count: 2,
+            damping: 0.5,
+            temperature: 0.01,
+            ..Scene::blank()
+        };
+        let mut simulation =
+            Simulation::from_config(&SimulationConfig::default().scene(scene)).unwrap();
+        for _ in 0..200 {
+            simulation.update();
+        }
+        let path =
+            std::env::temp_dir().join(format!("refraction-{}.checkpoint", std::process::id()));
+        super::save(&simulation, &path).unwrap();
+        let mut resumed = super::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        for _ in 0..200 {
+            simulation.update();
+            resumed.update();
+        }
+        assert_eq!(simulation.time(), resumed.time());
+        assert_eq!(simulation.resultant_field(), resumed.resultant_field());
+    }
+}
@@ -0,0 +1,273 @@
+//! Comparison of runs exported to CSV files, such as sweeps giving the refractive index, transmittance, reflectance
+//! and absorptance against a parameter, or the energy exported from the Energy panel, overlaid on one plot with a
+//! line for each run. Loading runs that differ in one property of the medium shows the effect of changing it.
+//! Files have a header naming their columns, the first of which the others are plotted against. The rows of a sweep
+//! name the parameter swept in the first column, and are plotted against its values in the second, as several
+//! sweeps of different parameters may have been appended to one file. The rows of values recorded by a diagnostic
+//! script name the channel each value is in, and are plotted against time with a line for each channel.
+
+use std::path::Path;
+
+use egui::{Color32, ComboBox, Pos2, Ui, pos2};
+
+use crate::app::i18n::tr;
+use crate::app::plot::{SERIES_COLOURS, Series, line_plot};
+use crate::app::session::display_name;
+
+const PLOT_HEIGHT: f32 = 220.0;
+// name of the first column of a sweep, which holds the parameter swept
+const SWEEP_PARAMETER: &str = "parameter";
+// name of the first column of values recorded by a diagnostic script, which holds the channel
+const CHANNEL: &str = "channel";
+
+// one quantity of a run, against another
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve {
+    pub x: String, // names of the quantities, from the file's header
+    pub y: String,
+    pub points: Vec<Pos2>, // in increasing order of x
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunData {
+    pub name: String,
+    pub curves: Vec<Curve>,
+}
+
+impl RunData {
+    // reads the columns of a file with a header, as described above
+    pub fn from_csv(name: String, text: &str) -> Result<Self, String> {
+        let mut lines = (1..)
+            .zip(text.lines())
+            .filter(|(_, line)| !line.trim().is_empty());
+        let (_, header) = lines.next().ok_or("the file is empty")?;
+        let header: Vec<&str> = header.split(',').map(str::trim).collect();
+        // a sweep's first column names its x quantity, otherwise the first column is the x quantity itself
+        let sweep = header[0] == SWEEP_PARAMETER;
+        let channels = header[0] == CHANNEL;
+        let first = if sweep || channels { 2 } else { 1 };
+        if header.len() <= first {
+            return Err(
+                "there must be a column to plot, besides those it is plotted against".to_owned(),
+            );
+        }
+        let mut curves: Vec<Curve> = Vec::new();
+        for (number, line) in lines {
+            let columns: Vec<&str> = match channels {
+                // the channel's name is quoted and may contain commas, so the values are split from the end
+                true => {
+                    let mut columns: Vec<&str> = line.rsplitn(3, ',').map(str::trim).collect();
+                    columns.reverse();
+                    columns
+                }
+                false => line.split(',').map(str::trim).collect(),
+            };
+            if columns.len() != header.len() {
+                return Err(format!("line {number}: expected {} columns", header.len()));
+            }
+            let number_in = |column: &str| {
+                column
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| format!("line {number}: {column} isn't a finite number"))
+            };
+            let (x_name, x) = match sweep {
+                true => (columns[0], number_in(columns[1])?),
+                false if channels => (header[1], number_in(columns[1])?),
+                false => (header[0], number_in(columns[0])?),
+            };
+            let y_names: Vec<String> = match channels {
+                true => {
+                    let name = columns[0];
+                    let name = name.strip_prefix('"').unwrap_or(name);
+                    let name = name.strip_suffix('"').unwrap_or(name);
+                    vec![name.replace("\"\"", "\"")]
+                }
+                false => header[first..]
+                    .iter()
+                    .map(|name| (*name).to_owned())
+                    .collect(),
+            };
+            for (y_name, column) in y_names.iter().zip(&columns[first..]) {
+                let point = pos2(x, number_in(column)?);
+                match curves.iter_mut().find(|c| c.x == x_name && c.y == *y_name) {
+                    Some(curve) => curve.points.push(point),
+                    None => curves.push(Curve {
+                        x: x_name.to_owned(),
+                        y: y_name.clone(),
+                        points: vec![point],
+                    }),
+                }
+            }
+        }
+        if curves.is_empty() {
+            return Err("there are no rows to plot".to_owned());
+        }
+        for curve in &mut curves {
+            curve.points.sort_by(|a, b| a.x.total_cmp(&b.x));
+        }
+        Ok(RunData { name, curves })
+    }
+
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        RunData::from_csv(display_name(path), &text)
+    }
+
+    fn curve(&self, x: &str, y: &str) -> Option<&Curve> {
+        self.curves.iter().find(|c| c.x == x && c.y == y)
+    }
+}
+
+#[derive(Default)]
+pub struct RunComparison {
+    runs: Vec<(RunData, Color32)>,
+    quantity: Option<(String, String)>, // names of the x and y quantities plotted
+    error: Option<String>,              // why the latest file couldn't be loaded
+}
+
+impl RunComparison {
+    // every quantity of the runs loaded that can be plotted, each once, in the order they were found
+    fn quantities(&self) -> Vec<(String, String)> {
+        let mut quantities: Vec<(String, String)> = Vec::new();
+        for curve in self.runs.iter().flat_map(|(run, _)| &run.curves) {
+            let quantity = (curve.x.clone(), curve.y.clone());
+            if !quantities.contains(&quantity) {
+                quantities.push(quantity);
+            }
+        }
+        quantities
+    }
+
+    pub fn add(&mut self, run: RunData) {
+        // colours stay with their runs, taking the first not in use
+        let colour = SERIES_COLOURS
+            .into_iter()
+            .find(|colour| self.runs.iter().all(|(_, c)| c != colour))
+            .unwrap_or(SERIES_COLOURS[self.runs.len() % SERIES_COLOURS.len()]);
+        self.runs.push((run, colour));
+    }
+
+    fn load(&mut self) {
+        let Some(paths) = rfd::FileDialog::new()
+            .add_filter(tr("Comma-separated values"), &["csv"])
+            .pick_files()
+        else {
+            return;
+        };
+        self.error = None;
+        for path in paths {
+            match RunData::open(&path) {
+                Ok(run) => self.add(run),
+                Err(e) => {
+                    self.error = Some(format!("{} {}:\n{e}", tr("Could not open"), path.display()));
+                }
+            }
+        }
+    }
+
+    pub fn draw(&mut self, ui: &mut Ui) {
+        if ui.button(tr("Add runs…")).clicked() {
+            self.load();
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        if self.runs.is_empty() {
+            ui.label(tr(
+                "Add sweeps, energy exported from the Energy panel or values recorded by a diagnostic script, \
+                to compare runs on one plot.",
+            ));
+            return;
+        }
+        let mut removed = None;
+        for (i, (run, colour)) in self.runs.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.colored_label(*colour, &run.name);
+                if ui.small_button("✖").on_hover_text(tr("Remove")).clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            self.runs.remove(i);
+        }
+
+        let quantities = self.quantities();
+        if !self
+            .quantity
+            .as_ref()
+            .is_some_and(|quantity| quantities.contains(quantity))
+        {
+            self.quantity = quantities.first().cloned();
+        }
+        let Some((x, y)) = self.quantity.clone() else {
+            return;
+        };
+        let label = |(x, y): &(String, String)| format!("{y} {} {x}", tr("against"));
+        ComboBox::from_label(tr("Quantity"))
+            .selected_text(label(&(x.clone(), y.clone())))
+            .show_ui(ui, |ui| {
+                for quantity in quantities {
+                    let text = label(&quantity);
+                    ui.selectable_value(&mut self.quantity, Some(quantity), text);
+                }
+            });
+        let series: Vec<Series> = self
+            .runs
+            .iter()
+            .filter_map(|(run, colour)| {
+                run.curve(&x, &y).map(|curve| Series {
+                    name: &run.name,
+                    colour: *colour,
+                    points: curve.points.clone(),
+                })
+            })
+            .collect();
+        line_plot(ui, PLOT_HEIGHT, &series, &x);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::pos2;
+
+    use super::RunData;
+
+    // sweeps are plotted against the parameter each row names, other files against their first column
+    #[test]
+    fn runs_are_read_from_csv() {
+        let sweep = "parameter,value,n,T,R,A\n\
+            frequency,2,1.1,0.9,0.05,0.05\n\
+            frequency,1,1.2,0.8,0.1,0.1\n\
+            damping,0.5,1.3,0.7,0.1,0.2\n";
+        let run = RunData::from_csv("sweep".to_owned(), sweep).unwrap();
+        assert_eq!(run.curves.len(), 8);
+        let index = run.curve("frequency", "n").unwrap();
+        assert_eq!(index.points, [pos2(1.0, 1.2), pos2(2.0, 1.1)]);
+        assert_eq!(run.curve("damping", "A").unwrap().points, [pos2(0.5, 0.2)]);
+
+        let energy = "t,kinetic,potential,total\n0,0,0,0\n0.5,1,2,3\n";
+        let run = RunData::from_csv("energy".to_owned(), energy).unwrap();
+        assert_eq!(run.curves.len(), 3);
+        assert_eq!(
+            run.curve("t", "total").unwrap().points,
+            [pos2(0.0, 0.0), pos2(0.5, 3.0)]
+        );
+
+        let channels = "channel,t,value\n\"y₀\",0,1\n\"a, \"\"b\"\"\",0,2\n\"y₀\",0.5,3\n";
+        let run = RunData::from_csv("diagnostics".to_owned(), channels).unwrap();
+        assert_eq!(run.curves.len(), 2);
+        assert_eq!(
+            run.curve("t", "y₀").unwrap().points,
+            [pos2(0.0, 1.0), pos2(0.5, 3.0)]
+        );
+        assert_eq!(run.curve("t", "a, \"b\"").unwrap().points, [pos2(0.0, 2.0)]);
+
+        assert!(RunData::from_csv(String::new(), "t,E\n0,1\n1\n").is_err());
+        assert!(RunData::from_csv(String::new(), "t,E\n0,nan\n").is_err());
+        assert!(RunData::from_csv(String::new(), "t\n0\n").is_err());
+        assert!(RunData::from_csv(String::new(), "t,E\n").is_err());
+    }
+}
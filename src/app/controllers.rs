@@ -0,0 +1,146 @@
+//! Playback from devices egui doesn't report, so a presentation can be driven without the keyboard:
+//! the buttons of a gamepad, built with the `gamepad` feature, and the media keys of a keyboard or clicker,
+//! built with the `media-keys` feature. Media keys are taken from every other program, such as a music player,
+//! so they are only listened for while presenting.
+
+use std::time::Duration;
+
+use egui::Context;
+
+use crate::app::presentation::PlaybackCommand;
+
+// how often the devices are checked while nothing else is redrawing the window, e.g. while paused
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub struct Controllers {
+    #[cfg(feature = "gamepad")]
+    gamepads: Option<gilrs::Gilrs>, // None where gamepads can't be read
+    #[cfg(feature = "media-keys")]
+    media_keys: Option<MediaKeys>, // registered while presenting
+}
+
+#[allow(clippy::derivable_impls)] // gamepads are opened at the start, but only with the gamepad feature
+impl Default for Controllers {
+    fn default() -> Self {
+        Controllers {
+            #[cfg(feature = "gamepad")]
+            gamepads: gilrs::Gilrs::new()
+                .inspect_err(|e| log::warn!("Gamepads can't be used: {e}"))
+                .ok(),
+            #[cfg(feature = "media-keys")]
+            media_keys: None,
+        }
+    }
+}
+
+impl Controllers {
+    // the playback control pressed on any device since the last frame, if any. every event is taken each frame,
+    // so presses made while the window isn't focused, or the keyboard is in use (active is false), are dropped
+    // rather than carried out later
+    #[cfg_attr(not(feature = "media-keys"), allow(unused_variables))]
+    pub fn poll(
+        &mut self,
+        ctx: &Context,
+        presenting: bool,
+        active: bool,
+    ) -> Option<PlaybackCommand> {
+        let mut command = None;
+        #[cfg(feature = "gamepad")]
+        if let Some(gamepads) = &mut self.gamepads {
+            while let Some(event) = gamepads.next_event() {
+                if let gilrs::EventType::ButtonPressed(button, _) = event.event {
+                    command = command.or(gamepad_command(button));
+                }
+            }
+            if gamepads.gamepads().next().is_some() {
+                ctx.request_repaint_after(POLL_INTERVAL);
+            }
+        }
+        #[cfg(feature = "media-keys")]
+        {
+            if presenting != self.media_keys.is_some() {
+                self.media_keys = presenting.then(MediaKeys::register).flatten();
+            }
+            if let Some(media_keys) = &self.media_keys {
+                command = command.or(media_keys.poll());
+                ctx.request_repaint_after(POLL_INTERVAL);
+            }
+        }
+        command.filter(|_| active && ctx.input(|i| i.focused))
+    }
+}
+
+// A or Start to play or pause, right on the D-pad or the right shoulder button to step, left or the left shoulder
+// button to restart, like the next and previous slide of a clicker, and up and down to change the speed
+#[cfg(feature = "gamepad")]
+fn gamepad_command(button: gilrs::Button) -> Option<PlaybackCommand> {
+    use gilrs::Button;
+    match button {
+        Button::South | Button::Start => Some(PlaybackCommand::PlayPause),
+        Button::DPadRight | Button::RightTrigger => Some(PlaybackCommand::Step),
+        Button::DPadLeft | Button::LeftTrigger => Some(PlaybackCommand::Restart),
+        Button::DPadUp => Some(PlaybackCommand::Faster),
+        Button::DPadDown => Some(PlaybackCommand::Slower),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "media-keys")]
+struct MediaKeys {
+    manager: global_hotkey::GlobalHotKeyManager,
+    registered: Vec<(global_hotkey::hotkey::HotKey, PlaybackCommand)>,
+}
+
+#[cfg(feature = "media-keys")]
+impl MediaKeys {
+    // takes the media keys from other programs. platforms don't all have every key, so those that can't be
+    // registered are left out, and None is returned if none can be
+    fn register() -> Option<Self> {
+        use global_hotkey::hotkey::{Code, HotKey};
+        let manager = global_hotkey::GlobalHotKeyManager::new()
+            .inspect_err(|e| log::warn!("Media keys can't be used: {e}"))
+            .ok()?;
+        let keys = [
+            (Code::MediaPlayPause, PlaybackCommand::PlayPause),
+            (Code::MediaPlay, PlaybackCommand::PlayPause),
+            (Code::MediaTrackNext, PlaybackCommand::Step),
+            (Code::MediaTrackPrevious, PlaybackCommand::Restart),
+            (Code::MediaFastForward, PlaybackCommand::Faster),
+            (Code::MediaRewind, PlaybackCommand::Slower),
+        ];
+        let registered: Vec<(HotKey, PlaybackCommand)> = keys
+            .into_iter()
+            .map(|(code, command)| (HotKey::new(None, code), command))
+            .filter(|(hotkey, _)| manager.register(*hotkey).is_ok())
+            .collect();
+        (!registered.is_empty()).then_some(MediaKeys {
+            manager,
+            registered,
+        })
+    }
+
+    fn poll(&self) -> Option<PlaybackCommand> {
+        let mut command = None;
+        for event in global_hotkey::GlobalHotKeyEvent::receiver().try_iter() {
+            if event.state == global_hotkey::HotKeyState::Pressed {
+                let pressed = self
+                    .registered
+                    .iter()
+                    .find(|(hotkey, _)| hotkey.id() == event.id);
+                command = command.or(pressed.map(|(_, command)| *command));
+            }
+        }
+        command
+    }
+}
+
+// gives the media keys back to other programs
+#[cfg(feature = "media-keys")]
+impl Drop for MediaKeys {
+    fn drop(&mut self) {
+        let hotkeys: Vec<_> = self.registered.iter().map(|(hotkey, _)| *hotkey).collect();
+        if let Err(e) = self.manager.unregister_all(&hotkeys) {
+            log::warn!("Couldn't release the media keys: {e}");
+        }
+    }
+}
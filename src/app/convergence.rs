@@ -0,0 +1,94 @@
+//! Panel running a convergence study of the current scene in the background, and showing its results
+
+use egui::{Grid, Ui};
+
+use crate::app::background::BackgroundRun;
+use crate::app::i18n::tr;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::convergence::{ConvergenceStudy, Run, estimated_error};
+
+// a study, with the time the scene was run to
+type Study = (ConvergenceStudy, f32);
+
+#[derive(Default)]
+pub struct ConvergencePanel {
+    worker: Option<BackgroundRun<Result<Study, String>>>,
+    study: Option<Study>, // latest results
+}
+
+impl ConvergencePanel {
+    // starts a study of the simulation's scene, run to its current time
+    fn start(&mut self, simulation: &Simulation) {
+        let scene = simulation.scene();
+        let (time, time_step) = (simulation.time(), simulation.time_step());
+        self.worker = Some(BackgroundRun::start(move |progress| {
+            ConvergenceStudy::run(&scene, time, time_step, progress).map(|study| (study, time))
+        }));
+    }
+
+    pub fn draw(&mut self, ui: &mut Ui, simulation: &Simulation) {
+        match BackgroundRun::poll(&mut self.worker, "Convergence study") {
+            Some(Ok(study)) => self.study = Some(study),
+            Some(Err(e)) => log::error!("Convergence study couldn't set up the scene: {e}"),
+            None => {}
+        }
+        ui.label(tr(
+            "Reruns the scene up to the current time with the time step, and separately the grid spacing, halved several times, \
+            and compares the resultant fields to estimate the error due to the discretisation.",
+        ));
+
+        match &self.worker {
+            Some(worker) => worker.show_progress(ui, ConvergenceStudy::runs()),
+            None => {
+                let enabled = simulation.time() > 0.0;
+                if ui
+                    .add_enabled(enabled, egui::Button::new(tr("Run study")))
+                    .on_disabled_hover_text(tr("Run the simulation first"))
+                    .clicked()
+                {
+                    self.start(simulation);
+                }
+            }
+        }
+
+        if let Some((study, time)) = &self.study {
+            ui.separator();
+            ui.label(format!("{} t = {time:.2} s", tr("Results at")));
+            draw_series(
+                ui,
+                "TimeStepConvergence",
+                tr("Time step"),
+                &study.time_steps,
+            );
+            draw_series(ui, "GridConvergence", tr("Grid"), &study.grids);
+        }
+    }
+}
+
+// table of the runs refining one part of the discretisation, with the estimated error of the first
+fn draw_series(ui: &mut Ui, id: &str, title: &str, runs: &[Run]) {
+    ui.strong(title);
+    Grid::new(id).striped(true).show(ui, |ui| {
+        ui.label("Δt");
+        ui.label(tr("Divisions"));
+        ui.label(tr("Change")).on_hover_text(tr(
+            "RMS change in the resultant field when the step is halved, relative to the RMS field",
+        ));
+        ui.label(tr("Order")).on_hover_text(tr(
+            "Rate the change falls at each time the step is halved, e.g. 2 for second order accuracy",
+        ));
+        ui.end_row();
+        for run in runs {
+            ui.label(format!("{:.5}", run.time_step));
+            ui.label(run.divisions.to_string());
+            ui.label(format!("{:.2e}", run.change));
+            ui.label(run.order.map_or("-".to_string(), |order| format!("{order:.2}")));
+            ui.end_row();
+        }
+    });
+    let error = match estimated_error(runs) {
+        Some(error) => format!("{:.2} %", 100.0 * error),
+        None => tr("not converging").to_string(),
+    };
+    ui.label(format!("{} {error}", tr("Estimated error:")));
+}
@@ -0,0 +1,152 @@
+//! Scripted demos, which run a sequence of actions automatically so a lecture demonstration can run hands-free.
+//! A demo file lists actions to take in order, such as loading a preset, playing, zooming and showing annotations.
+//! Waits hold the sequence up until the simulation reaches a time, or for a number of seconds.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use egui::{Align2, Color32, FontId, Painter, Rect, vec2};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::app::simulation::preset::Preset;
+
+// extension of demo files written by the user
+pub const DEMO_EXTENSION: &str = "demo";
+
+const ANNOTATION_FONT_SIZE: f32 = 22.0;
+const ANNOTATION_MARGIN: f32 = 24.0;
+
+// one of the fields drawn on the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+pub enum Trace {
+    Applied,
+    Resultant,
+    Induced,
+    Intensity,
+    Scattered,
+}
+
+impl Trace {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Trace::Applied => "Initial electric field",
+            Trace::Resultant => "Resultant electric field",
+            Trace::Induced => "Induced electric field of particles",
+            Trace::Intensity => "Intensity",
+            Trace::Scattered => "Scattered field",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    LoadPreset(Preset),
+    Play,
+    Pause,
+    Restart,
+    Speed(f32),
+    Opacity(Trace, f32),
+    ZoomTo(f32, f32), // range of the x axis to show
+    ResetView,
+    Annotate(String), // text shown over the canvas, replacing any shown before. empty to hide it
+    WaitUntil(f32), // holds the sequence up until the simulation reaches this time, playing it if paused
+    WaitSeconds(f32), // holds the sequence up for this much real time
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Demo {
+    actions: Vec<Action>,
+}
+
+impl Demo {
+    pub fn open(path: &Path) -> Result<Demo, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        ron::from_str(&text).map_err(|e| e.to_string())
+    }
+}
+
+// a demo being run
+pub struct DemoRunner {
+    demo: Demo,
+    next: usize,                    // index of the next action to take
+    waiting_since: Option<Instant>, // when the current WaitSeconds started
+    annotation: Option<String>,
+}
+
+impl DemoRunner {
+    pub fn new(demo: Demo) -> Self {
+        DemoRunner {
+            demo,
+            next: 0,
+            waiting_since: None,
+            annotation: None,
+        }
+    }
+
+    // actions to take now, given the simulation's time, up to the next wait that hasn't finished.
+    // annotations are kept by the runner rather than returned
+    pub fn poll(&mut self, time: f32) -> Vec<Action> {
+        let mut actions = Vec::new();
+        while let Some(action) = self.demo.actions.get(self.next) {
+            match action {
+                Action::WaitUntil(until) if time < *until => {
+                    // the simulation has to be playing to get there
+                    actions.push(Action::Play);
+                    break;
+                }
+                Action::WaitSeconds(seconds) => {
+                    let started = *self.waiting_since.get_or_insert_with(Instant::now);
+                    if started.elapsed() < Duration::from_secs_f32(seconds.max(0.0)) {
+                        break;
+                    }
+                    self.waiting_since = None;
+                }
+                Action::WaitUntil(_) => {}
+                Action::Annotate(text) => {
+                    self.annotation = (!text.is_empty()).then(|| text.clone());
+                }
+                action => actions.push(action.clone()),
+            }
+            self.next += 1;
+        }
+        actions
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.demo.actions.len()
+    }
+
+    // progress through the actions, as a fraction
+    pub fn progress(&self) -> f32 {
+        self.next as f32 / self.demo.actions.len().max(1) as f32
+    }
+
+    // the annotation, centred at the top of the canvas
+    pub fn draw_annotation(&self, painter: &Painter, screen_extent: Rect) {
+        if let Some(text) = &self.annotation {
+            draw_banner(painter, screen_extent, text, Align2::CENTER_TOP);
+        }
+    }
+}
+
+// text on a dark banner, centred at the top or bottom of the canvas, as for annotations and the scene's captions
+pub fn draw_banner(painter: &Painter, screen_extent: Rect, text: &str, align: Align2) {
+    let galley = painter.layout(
+        text.to_owned(),
+        FontId::proportional(ANNOTATION_FONT_SIZE),
+        Color32::WHITE,
+        screen_extent.width() * 0.7,
+    );
+    let anchor = match align.y() {
+        egui::Align::Max => screen_extent.center_bottom() - vec2(0.0, ANNOTATION_MARGIN),
+        _ => screen_extent.center_top() + vec2(0.0, ANNOTATION_MARGIN),
+    };
+    let rect = align.anchor_size(anchor, galley.size());
+    painter.rect_filled(
+        rect.expand(10.0),
+        6.0,
+        Color32::from_rgba_unmultiplied(0, 0, 0, 200),
+    );
+    painter.galley(rect.min, galley, Color32::WHITE);
+}
@@ -0,0 +1,149 @@
+//! The Diagnostics panel, which loads a script of the user's to run after every update and plots the channels it
+//! records against time, to be exported as CSV or added to the runs compared. Built with the `scripting` feature.
+
+use egui::Ui;
+
+use crate::app::compare::RunData;
+use crate::app::i18n::tr;
+use crate::app::plot::{SERIES_COLOURS, Series, line_plot};
+use crate::app::simulation::Simulation;
+use crate::app::simulation::observer::StepObserver;
+use crate::app::simulation::script::DiagnosticScript;
+
+// seconds of simulated time shown on the plot, though every value recorded is exported
+const HISTORY_SECONDS: f32 = 20.0;
+const PLOT_HEIGHT: f32 = 200.0;
+
+#[derive(Default)]
+pub struct DiagnosticsPanel {
+    script: Option<DiagnosticScript>,
+    error: Option<String>, // why the latest file couldn't be loaded or saved
+}
+
+impl DiagnosticsPanel {
+    // runs the script, if one is loaded, after an update
+    pub fn observer(&mut self) -> Option<&mut dyn StepObserver> {
+        self.script
+            .as_mut()
+            .map(|script| script as &mut dyn StepObserver)
+    }
+
+    pub fn clear(&mut self) {
+        if let Some(script) = &mut self.script {
+            script.clear();
+        }
+    }
+
+    // returns the values recorded when they are to be compared with other runs
+    pub fn draw(&mut self, ui: &mut Ui, simulation: &Simulation) -> Option<RunData> {
+        ui.horizontal(|ui| {
+            if ui.button(tr("Load script…")).clicked() {
+                self.load();
+            }
+            if let Some(script) = &self.script {
+                ui.label(&script.name);
+                if ui.small_button("✖").on_hover_text(tr("Remove")).clicked() {
+                    self.script = None;
+                }
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        let Some(script) = &mut self.script else {
+            ui.label(tr(
+                "Load a Rhai script to run after every update. It can read the fields and particles, and record \
+                values to plot with record(name, value).",
+            ));
+            return None;
+        };
+        if let Some(error) = script.error() {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!("{}\n{error}", tr("The script stopped with an error")),
+            );
+            if ui.button(tr("Restart")).clicked() {
+                script.clear();
+            }
+        }
+
+        let t = simulation.time();
+        let channels = script.channels();
+        let series: Vec<Series> = channels
+            .iter()
+            .zip(SERIES_COLOURS.iter().cycle())
+            .map(|((name, values), colour)| Series {
+                name,
+                colour: *colour,
+                points: values
+                    .iter()
+                    .filter(|p| p.x >= t - HISTORY_SECONDS)
+                    .copied()
+                    .collect(),
+            })
+            .collect();
+        line_plot(ui, PLOT_HEIGHT, &series, "t");
+        let empty = channels.is_empty();
+        drop(channels);
+        let mut compared = None;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!empty, egui::Button::new(tr("Export CSV…")))
+                .clicked()
+            {
+                self.export();
+            }
+            if ui
+                .add_enabled(!empty, egui::Button::new(tr("Compare")))
+                .on_hover_text(tr(
+                    "Add the values recorded to the Compare runs panel, to plot them with other runs",
+                ))
+                .clicked()
+            {
+                compared = self.compared();
+            }
+        });
+        compared
+    }
+
+    // the values recorded so far, read as a file exported from the panel would be
+    fn compared(&mut self) -> Option<RunData> {
+        let script = self.script.as_ref()?;
+        let run = RunData::from_csv(script.name.clone(), &script.to_csv());
+        run.map_err(|e| self.error = Some(e)).ok()
+    }
+
+    fn load(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr("Rhai scripts"), &["rhai"])
+            .pick_file()
+        else {
+            return;
+        };
+        match DiagnosticScript::open(&path) {
+            Ok(script) => {
+                self.script = Some(script);
+                self.error = None;
+            }
+            Err(e) => {
+                self.error = Some(format!("{} {}:\n{e}", tr("Could not open"), path.display()));
+            }
+        }
+    }
+
+    fn export(&mut self) {
+        let Some(script) = &self.script else {
+            return;
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr("Comma-separated values"), &["csv"])
+            .set_file_name(format!("{}.csv", tr("Diagnostics")))
+            .save_file()
+        else {
+            return;
+        };
+        self.error = std::fs::write(&path, script.to_csv())
+            .err()
+            .map(|e| format!("{} {}:\n{e}", tr("Could not save"), path.display()));
+    }
+}
@@ -0,0 +1,116 @@
+//! Records the energy of the particles' oscillations over time, for the energy panel, which can export it as CSV to
+//! compare runs
+
+use std::collections::VecDeque;
+
+use egui::{Color32, Ui, pos2};
+
+use crate::app::i18n::tr;
+use crate::app::plot::{Series, line_plot};
+use crate::app::simulation::Simulation;
+use crate::app::simulation::observer::StepObserver;
+
+// seconds of simulated time shown on the plot
+const HISTORY_SECONDS: f32 = 20.0;
+const PLOT_HEIGHT: f32 = 200.0;
+
+const KINETIC_COLOUR: Color32 = Color32::from_rgb(230, 140, 40);
+const POTENTIAL_COLOUR: Color32 = Color32::from_rgb(60, 160, 230);
+const TOTAL_COLOUR: Color32 = Color32::from_gray(220);
+
+struct EnergySample {
+    t: f32,
+    kinetic: f32,
+    potential: f32,
+}
+
+#[derive(Default)]
+pub struct EnergyTracker {
+    samples: VecDeque<EnergySample>,
+    error: Option<String>, // why the latest export couldn't be saved
+}
+
+impl StepObserver for EnergyTracker {
+    // records the energy of the simulation's particles after an update
+    fn observe(&mut self, simulation: &Simulation) {
+        let (kinetic, potential) = simulation.oscillator_energy();
+        let t = simulation.time();
+        self.samples.push_back(EnergySample {
+            t,
+            kinetic,
+            potential,
+        });
+        while self
+            .samples
+            .front()
+            .is_some_and(|s| s.t < t - HISTORY_SECONDS)
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+impl EnergyTracker {
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    // the energy over the time shown, one sample per line
+    pub fn to_csv(&self) -> String {
+        let mut csv = "t,kinetic,potential,total\n".to_owned();
+        for s in &self.samples {
+            let total = s.kinetic + s.potential;
+            csv.push_str(&format!("{},{},{},{total}\n", s.t, s.kinetic, s.potential));
+        }
+        csv
+    }
+
+    pub fn draw(&mut self, ui: &mut Ui) {
+        let series = |name, colour, energy: fn(&EnergySample) -> f32| Series {
+            name,
+            colour,
+            points: self.samples.iter().map(|s| pos2(s.t, energy(s))).collect(),
+        };
+        line_plot(
+            ui,
+            PLOT_HEIGHT,
+            &[
+                series(tr("Kinetic"), KINETIC_COLOUR, |s| s.kinetic),
+                series(tr("Potential"), POTENTIAL_COLOUR, |s| s.potential),
+                series(tr("Total"), TOTAL_COLOUR, |s| s.kinetic + s.potential),
+            ],
+            "t",
+        );
+        ui.label(tr(
+            "Energy stored in the particles' oscillations. Energy absorbed from the wave is radiated away or lost to damping.",
+        ));
+        if ui
+            .add_enabled(
+                !self.samples.is_empty(),
+                egui::Button::new(tr("Export CSV…")),
+            )
+            .on_hover_text(tr(
+                "Save the energy over the time shown, to compare with other runs",
+            ))
+            .clicked()
+        {
+            self.export();
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+
+    fn export(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr("Comma-separated values"), &["csv"])
+            .set_file_name(format!("{}.csv", tr("Energy")))
+            .save_file()
+        else {
+            return;
+        };
+        self.error = std::fs::write(&path, self.to_csv())
+            .err()
+            .map(|e| format!("{} {}:\n{e}", tr("Could not save"), path.display()));
+    }
+}
@@ -0,0 +1,115 @@
+//! Panel running an ensemble of disordered copies of the current scene in the background, whose mean resultant field
+//! is drawn on the canvas with a band of one standard deviation either side
+
+use egui::{DragValue, Grid, Ui};
+
+use crate::app::background::BackgroundRun;
+use crate::app::i18n::tr;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::ensemble::{Ensemble, EnsembleSetup};
+
+// most realisations an ensemble can be made of
+const MAX_REALISATIONS: usize = 500;
+
+pub struct EnsemblePanel {
+    setup: EnsembleSetup,
+    worker: Option<BackgroundRun<Result<Ensemble, String>>>,
+    ensemble: Option<Ensemble>, // latest results
+    show: bool,                 // draw the results on the canvas
+}
+
+impl Default for EnsemblePanel {
+    fn default() -> Self {
+        EnsemblePanel {
+            setup: EnsembleSetup {
+                realisations: 20,
+                jitter: 0.5,
+                seed: 1,
+            },
+            worker: None,
+            ensemble: None,
+            show: true,
+        }
+    }
+}
+
+impl EnsemblePanel {
+    // starts an ensemble of the simulation's scene, run to its current time
+    fn start(&mut self, simulation: &Simulation) {
+        let scene = simulation.scene();
+        let (setup, time, time_step) = (self.setup, simulation.time(), simulation.time_step());
+        self.worker = Some(BackgroundRun::start(move |progress| {
+            Ensemble::run(&scene, setup, time, time_step, progress)
+        }));
+    }
+
+    // results to draw on the canvas, if there are any and they are shown
+    pub fn shown(&self) -> Option<&Ensemble> {
+        self.ensemble.as_ref().filter(|_| self.show)
+    }
+
+    pub fn draw(&mut self, ui: &mut Ui, simulation: &Simulation) {
+        match BackgroundRun::poll(&mut self.worker, "Ensemble") {
+            Some(Ok(ensemble)) => self.ensemble = Some(ensemble),
+            Some(Err(e)) => log::error!("Ensemble couldn't set up the scene: {e}"),
+            None => {}
+        }
+        ui.label(tr(
+            "Reruns the scene up to the current time many times, with every particle moved off its place by a \
+            random distance each time, and draws the mean resultant field with a band of one standard deviation \
+            either side of it.",
+        ));
+
+        Grid::new("EnsembleSetup").show(ui, |ui| {
+            ui.label(tr("Realisations"));
+            ui.add(DragValue::new(&mut self.setup.realisations).range(2..=MAX_REALISATIONS));
+            ui.end_row();
+            ui.label(tr("Disorder")).on_hover_text(tr(
+                "Standard deviation of the distance each particle is moved",
+            ));
+            ui.add(
+                DragValue::new(&mut self.setup.jitter)
+                    .range(0.0..=simulation.particle_spacing())
+                    .speed(0.01),
+            );
+            ui.end_row();
+            ui.label(tr("Seed"))
+                .on_hover_text(tr("The same seed gives the same realisations"));
+            ui.add(DragValue::new(&mut self.setup.seed));
+            ui.end_row();
+        });
+
+        match &self.worker {
+            Some(worker) => worker.show_progress(ui, self.setup.realisations),
+            None => {
+                let enabled = simulation.time() > 0.0 && !simulation.particles().is_empty();
+                if ui
+                    .add_enabled(enabled, egui::Button::new(tr("Run ensemble")))
+                    .on_disabled_hover_text(tr("Add a particle and run the simulation first"))
+                    .clicked()
+                {
+                    self.start(simulation);
+                }
+            }
+        }
+
+        if let Some(ensemble) = &self.ensemble {
+            ui.separator();
+            ui.label(format!(
+                "{} t = {:.2} s, {} {}",
+                tr("Mean at"),
+                ensemble.time,
+                ensemble.realisations,
+                tr("realisations"),
+            ));
+            let peak = ensemble.deviation.iter().fold(0.0, |a: f32, d| a.max(*d));
+            ui.label(format!("{}: σ = {peak:.4}", tr("Largest spread")));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.show, tr("Show on canvas"));
+                if ui.button(tr("Clear")).clicked() {
+                    self.ensemble = None;
+                }
+            });
+        }
+    }
+}
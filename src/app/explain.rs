@@ -0,0 +1,62 @@
+//! Overlays the equations being solved onto the canvas, with the current value of each term substituted in
+
+use egui::{Align2, Color32, pos2, vec2};
+
+use crate::app::canvas::Canvas;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::variables::C;
+
+const EQUATION_COLOUR: Color32 = Color32::from_rgb(230, 230, 230);
+
+// explains the motion of the particle closest to the pointer, and the field it radiates at the pointer
+pub fn draw_explanations(canvas: &Canvas, simulation: &Simulation, pointer_x: Option<f32>) {
+    let target_x = pointer_x.unwrap_or(0.0);
+    let Some(particle) = simulation.particles().iter().min_by(|a, b| {
+        let distance_a = (a.position().x - target_x).abs();
+        let distance_b = (b.position().x - target_x).abs();
+        distance_a.total_cmp(&distance_b)
+    }) else {
+        return;
+    };
+    let q = particle.particle_type().charge();
+
+    // driven, damped simple harmonic oscillator
+    let motion = format!(
+        "m·ÿ = qE - k·y - b·ẏ\n{:.2}·({:+.3}) = ({:+.0})·({:+.3}) - {:.2}·({:+.3}) - {:.2}·({:+.3})",
+        particle.mass(),
+        particle.acceleration(),
+        q,
+        particle.driving_field(),
+        particle.spring_constant(),
+        particle.position().y,
+        particle.damping(),
+        particle.velocity(),
+    );
+    canvas.draw_label(
+        particle.position(),
+        vec2(0.0, -24.0),
+        Align2::CENTER_BOTTOM,
+        motion,
+        EQUATION_COLOUR,
+    );
+
+    // radiation term of the Heaviside-Feynman formula, evaluated where the user is pointing
+    if let Some(x) = pointer_x {
+        let radiation = particle.latest_radiation(x);
+        let field = format!(
+            "E(x) = q·a⊥(t - r/c) / (c²·r)\n{:+.4} = ({:+.0})·({:+.3}) / ({:.1}·{:.2})",
+            radiation.field,
+            q,
+            radiation.a_perp,
+            C * C,
+            radiation.r,
+        );
+        canvas.draw_label(
+            &pos2(x, 0.0),
+            vec2(0.0, 24.0),
+            Align2::CENTER_TOP,
+            field,
+            EQUATION_COLOUR,
+        );
+    }
+}
@@ -0,0 +1,596 @@
+//! Exporting the simulation as PNG images: a single image of the current moment, for figures, or a sequence of
+//! numbered images, to be assembled into a video with other tools, of every Nth update.
+//! Images are drawn offscreen at a chosen resolution, such as 4K or 8K, independent of the size of the window,
+//! showing the part of the simulation in view through the same transforms as the canvas, with its gridlines, lanes,
+//! colours, opacities and line styles. Lines are scaled with the height of the image, so they keep their proportions.
+//! A caption of the parameters can be stamped in a corner, so images used elsewhere still say what they show.
+
+use std::path::PathBuf;
+
+use ab_glyph::{Font, FontRef, ScaleFont, point};
+use egui::{Color32, DragValue, FontDefinitions, Grid, Pos2, Rangef, Style, Ui, vec2};
+use tiny_skia::{Mask, Paint, PathBuilder, Pixmap, Rect, Stroke, StrokeDash, Transform};
+
+use crate::app::canvas::{CanvasTransform, VISIBLE_Y_SPAN, fill_tones};
+use crate::app::demo::Trace;
+use crate::app::i18n::tr;
+use crate::app::palette::{LinePattern, colours};
+use crate::app::settings::Settings;
+use crate::app::simulation::Simulation;
+use crate::app::{
+    PARTICLE_RADIUS, applied_field_colour, intensity_colour, particle_colour,
+    particle_field_colour, resultant_field_colour, scattered_field_colour, zoom_to,
+};
+
+// height in pixels that lines are drawn at their width on the canvas, scaled for other heights
+const REFERENCE_HEIGHT: f32 = 720.0;
+const LINE_WIDTH: f32 = 2.5;
+// height of the caption's text in pixels at the reference height
+const CAPTION_SIZE: f32 = 18.0;
+// common resolutions for figures and video, with their names
+const RESOLUTIONS: [(&str, u32, u32); 3] = [
+    ("1080p", 1920, 1080),
+    ("4K", 3840, 2160),
+    ("8K", 7680, 4320),
+];
+
+pub struct FrameExport {
+    directory: Option<PathBuf>,
+    every: usize, // updates between images
+    width: u32,
+    height: u32,
+    caption: bool,          // stamp the parameters in the bottom left corner
+    written: Option<usize>, // number of images written, while exporting
+}
+
+impl Default for FrameExport {
+    fn default() -> Self {
+        FrameExport {
+            directory: None,
+            every: 4,
+            width: 1920,
+            height: 1080,
+            caption: false,
+            written: None,
+        }
+    }
+}
+
+impl FrameExport {
+    // returns whether an image of the current moment was asked for
+    pub fn draw(&mut self, ui: &mut Ui) -> bool {
+        ui.label(tr(
+            "Saves an image of the current moment, or of every Nth update while the simulation runs, \
+            numbered in order, to be made into a video with other tools.",
+        ));
+        Grid::new("FrameExport").show(ui, |ui| {
+            ui.label(tr("Folder"));
+            ui.horizontal(|ui| {
+                if let Some(directory) = &self.directory {
+                    ui.monospace(directory.display().to_string());
+                }
+                if ui.button(tr("Choose…")).clicked() {
+                    self.directory = rfd::FileDialog::new()
+                        .pick_folder()
+                        .or(self.directory.take());
+                }
+            });
+            ui.end_row();
+            ui.label(tr("Every"));
+            ui.add(
+                DragValue::new(&mut self.every)
+                    .range(1..=1000)
+                    .suffix(tr(" updates")),
+            );
+            ui.end_row();
+            ui.label(tr("Resolution"));
+            ui.horizontal(|ui| {
+                ui.add(DragValue::new(&mut self.width).range(16..=7680));
+                ui.label("×");
+                ui.add(DragValue::new(&mut self.height).range(16..=4320));
+                for (name, width, height) in RESOLUTIONS {
+                    let selected = (self.width, self.height) == (width, height);
+                    if ui.selectable_label(selected, name).clicked() {
+                        (self.width, self.height) = (width, height);
+                    }
+                }
+            });
+            ui.end_row();
+            ui.label(tr("Caption"));
+            ui.checkbox(&mut self.caption, tr("Parameters in a corner"))
+                .on_hover_text(tr(
+                    "Stamps the waveform, number of particles, spring constant, mass, damping and time on each image",
+                ));
+            ui.end_row();
+        });
+
+        let save_image = ui
+            .button(tr("Save image…"))
+            .on_hover_text(tr(
+                "Saves the current moment at the resolution above, whatever the size of the window",
+            ))
+            .clicked();
+        match self.written {
+            Some(written) => {
+                ui.label(format!("{written} {}", tr("images written")));
+                if ui.button(tr("Stop export")).clicked() {
+                    self.written = None;
+                }
+            }
+            None => {
+                if ui
+                    .add_enabled(
+                        self.directory.is_some(),
+                        egui::Button::new(tr("Start export")),
+                    )
+                    .on_disabled_hover_text(tr("Choose a folder first"))
+                    .clicked()
+                {
+                    self.written = Some(0);
+                }
+            }
+        }
+        save_image
+    }
+
+    // saves an image of the current moment to a file chosen
+    pub fn save_image(&self, simulation: &Simulation, settings: &Settings) -> Result<(), String> {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("PNG", &["png"])
+            .set_file_name(format!("{}.png", tr("Simulation")))
+            .save_file()
+        else {
+            return Ok(());
+        };
+        self.image(simulation, settings)?
+            .save_png(&path)
+            .map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    fn image(&self, simulation: &Simulation, settings: &Settings) -> Result<Pixmap, String> {
+        let mut pixmap = render(simulation, settings, self.width, self.height)?;
+        if self.caption {
+            draw_caption(&mut pixmap, &caption(simulation))?;
+        }
+        Ok(pixmap)
+    }
+
+    pub fn stop(&mut self) {
+        self.written = None;
+    }
+
+    // saves an image of the simulation if exporting and it is on an update to be exported
+    pub fn record(&mut self, simulation: &Simulation, settings: &Settings) -> Result<(), String> {
+        let (Some(written), Some(directory)) = (self.written, &self.directory) else {
+            return Ok(());
+        };
+        if !simulation.steps().is_multiple_of(self.every) {
+            return Ok(());
+        }
+        let path = directory.join(format!("frame_{:05}.png", written + 1));
+        self.image(simulation, settings)?
+            .save_png(&path)
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        self.written = Some(written + 1);
+        Ok(())
+    }
+}
+
+// draws the part of the simulation in view as the canvas does, in lanes if the traces are stacked
+fn render(
+    simulation: &Simulation,
+    settings: &Settings,
+    width: u32,
+    height: u32,
+) -> Result<Pixmap, String> {
+    let mut pixmap = Pixmap::new(width, height).ok_or(tr("Invalid image size"))?;
+    let background = Style::default().visuals.extreme_bg_color;
+    let [r, g, b, a] = background.to_srgba_unmultiplied();
+    pixmap.fill(tiny_skia::Color::from_rgba8(r, g, b, a));
+
+    let transform = CanvasTransform::new(
+        egui::Rect::from_min_size(Pos2::ZERO, vec2(width as f32, height as f32)),
+        zoom_to(simulation.size(), settings.zoom, settings.world_centre),
+        zoom_to(
+            &Rangef::new(-VISIBLE_Y_SPAN / 2.0, VISIBLE_Y_SPAN / 2.0),
+            settings.y_zoom,
+            settings.y_centre,
+        ),
+        settings.y_scale,
+        simulation.applied_amplitude(),
+    );
+    // stacked traces draw the applied, induced and resultant fields in separate lanes
+    let lane = |index| match settings.stacked_traces {
+        true => transform.lane(index, 3),
+        false => transform,
+    };
+    let (applied_lane, particle_lane, resultant_lane) = (lane(0), lane(1), lane(2));
+    let lanes = match settings.stacked_traces {
+        true => vec![applied_lane, particle_lane, resultant_lane],
+        false => vec![transform],
+    };
+    let scale = height as f32 / REFERENCE_HEIGHT;
+    let line_width = LINE_WIDTH * scale;
+    let anti_alias = settings.feathering > 0.0;
+
+    // gridlines and axes, as thin as on the canvas relative to the traces
+    let (left, right) = (0.0, width as f32);
+    let (top, bottom) = (0.0, height as f32);
+    let rule = |pixmap: &mut Pixmap, from: (f32, f32), to: (f32, f32), colour, width| {
+        let mut builder = PathBuilder::new();
+        builder.move_to(from.0, from.1);
+        builder.line_to(to.0, to.1);
+        if let Some(path) = builder.finish() {
+            let stroke = Stroke {
+                width: width * scale,
+                ..Stroke::default()
+            };
+            let mut paint = paint(colour);
+            paint.anti_alias = anti_alias;
+            pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+        }
+    };
+    for lane in &lanes {
+        for (y, label) in lane.horizontal_gridlines() {
+            let y = lane.world_to_screen_y(y);
+            let colour = match label {
+                Some(_) => colours().ticks,
+                None => colours().grid,
+            };
+            rule(&mut pixmap, (left, y), (right, y), colour, 1.0);
+        }
+        for x in lane.vertical_gridlines() {
+            let x = lane.world_to_screen_x(x);
+            rule(&mut pixmap, (x, top), (x, bottom), colours().grid, 1.0);
+        }
+        let (x, y) = (lane.world_to_screen_x(0.0), lane.world_to_screen_y(0.0));
+        rule(&mut pixmap, (x, top), (x, bottom), colours().axes, 2.0);
+        rule(&mut pixmap, (left, y), (right, y), colours().axes, 2.0);
+    }
+
+    let x_points = simulation.x_intervals();
+    let style = |colour, trace| LineStyle {
+        colour,
+        width: line_width * settings.trace_styles.get(trace).width,
+        pattern: settings.trace_styles.pattern(trace, settings.line_styles),
+        filled: settings.filled_traces,
+        anti_alias,
+    };
+    for particle in simulation.particles() {
+        draw_line(
+            &mut pixmap,
+            &particle_lane,
+            x_points,
+            particle.field(),
+            style(
+                particle_field_colour(settings.particle_field_opacity),
+                Trace::Induced,
+            ),
+        );
+    }
+    let fields = [
+        (
+            simulation.applied_field(),
+            applied_field_colour(settings.applied_field_opacity, &simulation.waveform()),
+            Trace::Applied,
+            &applied_lane,
+        ),
+        (
+            simulation.resultant_field(),
+            resultant_field_colour(settings.resultant_field_opacity),
+            Trace::Resultant,
+            &resultant_lane,
+        ),
+        (
+            simulation.scattered_field(),
+            scattered_field_colour(settings.scattered_field_opacity),
+            Trace::Scattered,
+            &particle_lane,
+        ),
+        (
+            simulation.intensity(),
+            intensity_colour(settings.intensity_opacity),
+            Trace::Intensity,
+            &resultant_lane,
+        ),
+    ];
+    for (field, colour, trace, lane) in fields {
+        draw_line(&mut pixmap, lane, x_points, field, style(colour, trace));
+    }
+
+    for particle in simulation.particles() {
+        let position = particle_lane.world_to_screen_pos(particle.position());
+        let radius = PARTICLE_RADIUS * particle_lane.world_to_screen_scale();
+        if let Some(circle) = PathBuilder::from_circle(position.x, position.y, radius) {
+            let mut paint = paint(particle_colour(1.0, particle));
+            paint.anti_alias = anti_alias;
+            pixmap.fill_path(
+                &circle,
+                &paint,
+                tiny_skia::FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+    Ok(pixmap)
+}
+
+fn paint(colour: Color32) -> Paint<'static> {
+    let [r, g, b, a] = colour.to_srgba_unmultiplied();
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(r, g, b, a);
+    paint.anti_alias = true;
+    paint
+}
+
+// how a field is drawn
+struct LineStyle {
+    colour: Color32,
+    width: f32,
+    pattern: Option<LinePattern>, // dashes, with lengths in points on the canvas
+    filled: bool,                 // over the area between it and its zero line
+    anti_alias: bool,
+}
+
+// draws a field as a line, unless it is fully transparent
+fn draw_line(
+    pixmap: &mut Pixmap,
+    transform: &CanvasTransform,
+    x_points: &[f32],
+    y_points: &[f32],
+    style: LineStyle,
+) {
+    let LineStyle {
+        colour,
+        width,
+        pattern,
+        filled,
+        anti_alias,
+    } = style;
+    if colour.a() == 0 {
+        return;
+    }
+    if filled {
+        draw_area(pixmap, transform, x_points, y_points, colour, anti_alias);
+    }
+    let mut builder = PathBuilder::new();
+    for (i, (x, y)) in x_points.iter().zip(y_points).enumerate() {
+        let (x, y) = (
+            transform.world_to_screen_x(*x),
+            transform.world_to_screen_y(*y),
+        );
+        match i {
+            0 => builder.move_to(x, y),
+            _ => builder.line_to(x, y),
+        }
+    }
+    if let Some(path) = builder.finish() {
+        // the pattern's lengths are in points on the canvas, where lines are LINE_WIDTH wide
+        let dash = pattern.and_then(|pattern| {
+            let intervals = pattern
+                .dashes
+                .iter()
+                .zip(pattern.gaps)
+                .flat_map(|(dash, gap)| [*dash, *gap])
+                .map(|length| length * width / LINE_WIDTH)
+                .collect();
+            StrokeDash::new(intervals, 0.0)
+        });
+        let stroke = Stroke {
+            width,
+            dash,
+            ..Stroke::default()
+        };
+        let mut paint = paint(colour);
+        paint.anti_alias = anti_alias;
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+}
+
+// fills the area between a field and its zero line in the tones the canvas fills it in, each clipped to its side of
+// the line
+fn draw_area(
+    pixmap: &mut Pixmap,
+    transform: &CanvasTransform,
+    x_points: &[f32],
+    y_points: &[f32],
+    colour: Color32,
+    anti_alias: bool,
+) {
+    let zero = transform.world_to_screen_y(0.0);
+    let mut builder = PathBuilder::new();
+    for (x, y) in x_points.iter().zip(y_points) {
+        let (x, y) = (
+            transform.world_to_screen_x(*x),
+            transform.world_to_screen_y(*y),
+        );
+        if builder.is_empty() {
+            builder.move_to(x, zero);
+        }
+        builder.line_to(x, y);
+    }
+    if let Some(last) = x_points.last() {
+        builder.line_to(transform.world_to_screen_x(*last), zero);
+    }
+    builder.close();
+    let Some(path) = builder.finish() else {
+        return;
+    };
+    let (width, height) = (pixmap.width() as f32, pixmap.height() as f32);
+    let (positive, negative) = fill_tones(colour);
+    let sides = [
+        (Rect::from_ltrb(0.0, 0.0, width, zero), positive),
+        (Rect::from_ltrb(0.0, zero, width, height), negative),
+    ];
+    for (side, colour) in sides {
+        let (Some(side), Some(mut mask)) = (side, Mask::new(pixmap.width(), pixmap.height()))
+        else {
+            continue;
+        };
+        mask.fill_path(
+            &PathBuilder::from_rect(side),
+            tiny_skia::FillRule::Winding,
+            false,
+            Transform::identity(),
+        );
+        let mut paint = paint(colour);
+        paint.anti_alias = anti_alias;
+        pixmap.fill_path(
+            &path,
+            &paint,
+            tiny_skia::FillRule::Winding,
+            Transform::identity(),
+            Some(&mask),
+        );
+    }
+}
+
+// the parameters of the simulation in a single line
+fn caption(simulation: &Simulation) -> String {
+    format!(
+        "{} · N = {} · k = {:.2} · m = {:.2} · γ = {:.2} · t = {:.2} s",
+        tr(simulation.waveform().properties().name),
+        simulation.particles().len(),
+        simulation.spring_constant(),
+        simulation.particle_mass(),
+        simulation.damping(),
+        simulation.time(),
+    )
+}
+
+// draws a line of text on a dark box in the bottom left corner, in the monospace font of the interface
+fn draw_caption(pixmap: &mut Pixmap, text: &str) -> Result<(), String> {
+    let fonts = FontDefinitions::default();
+    let data = fonts.font_data.get("Hack").ok_or("Missing font")?;
+    let font = FontRef::try_from_slice(&data.font).map_err(|e| e.to_string())?;
+    let size = CAPTION_SIZE * pixmap.height() as f32 / REFERENCE_HEIGHT;
+    let font = font.as_scaled(size);
+
+    // glyphs along the line, from the top left corner of the text
+    let mut glyphs = Vec::new();
+    let mut x = 0.0;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(previous) = previous {
+            x += font.kern(previous, id);
+        }
+        glyphs.push(id.with_scale_and_position(size, point(x, font.ascent())));
+        x += font.h_advance(id);
+        previous = Some(id);
+    }
+
+    let margin = size / 2.0;
+    let (left, top) = (
+        margin,
+        pixmap.height() as f32 - font.height() - 3.0 * margin,
+    );
+    if let Some(rect) = Rect::from_xywh(left, top, x + 2.0 * margin, font.height() + 2.0 * margin) {
+        pixmap.fill_rect(
+            rect,
+            &paint(Color32::from_black_alpha(170)),
+            Transform::identity(),
+            None,
+        );
+    }
+
+    // the glyphs' coverage of each pixel, through which the text's colour is filled in
+    let (width, height) = (pixmap.width(), pixmap.height());
+    let mut mask = Mask::new(width, height).ok_or(tr("Invalid image size"))?;
+    let coverage = mask.data_mut();
+    for mut glyph in glyphs {
+        glyph.position.x += left + margin;
+        glyph.position.y += top + margin;
+        let Some(outline) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outline.px_bounds();
+        outline.draw(|x, y, c| {
+            let (x, y) = (
+                bounds.min.x as i64 + x as i64,
+                bounds.min.y as i64 + y as i64,
+            );
+            if (0..width as i64).contains(&x) && (0..height as i64).contains(&y) {
+                let pixel = &mut coverage[(y * width as i64 + x) as usize];
+                *pixel = (*pixel).max((c * 255.0) as u8);
+            }
+        });
+    }
+    if let Some(rect) = Rect::from_xywh(0.0, 0.0, width as f32, height as f32) {
+        pixmap.fill_rect(
+            rect,
+            &paint(Color32::from_gray(230)),
+            Transform::identity(),
+            Some(&mask),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tiny_skia::Pixmap;
+
+    use egui::{Color32, Pos2, Rangef, Rect, vec2};
+
+    use super::{draw_area, draw_caption, render};
+    use crate::app::canvas::{CanvasTransform, YScale};
+    use crate::app::particle_colour;
+    use crate::app::settings::Settings;
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+
+    // images of any resolution show the same part of the simulation, with the particles where the canvas puts them
+    #[test]
+    fn render_independent_of_resolution() {
+        let simulation = Simulation::from_config(&SimulationConfig::default()).unwrap();
+        let settings = Settings::default();
+        let particle = &simulation.particles()[0];
+        let colour = particle_colour(1.0, particle).to_srgba_unmultiplied();
+        for (width, height) in [(640, 360), (3840, 2160)] {
+            let pixmap = render(&simulation, &settings, width, height).unwrap();
+            let size = simulation.size();
+            let x = (particle.position().x - size.min) / size.span() * width as f32;
+            let pixel = pixmap.pixel(x as u32, height / 2).unwrap();
+            assert_eq!([pixel.red(), pixel.green(), pixel.blue()], colour[..3]);
+        }
+    }
+
+    // a field is filled down to its zero line, above it where positive and below it where negative
+    #[test]
+    fn area_filled_to_zero_line() {
+        let mut pixmap = Pixmap::new(200, 100).unwrap();
+        let transform = CanvasTransform::new(
+            Rect::from_min_size(Pos2::ZERO, vec2(200.0, 100.0)),
+            Rangef::new(0.0, 2.0),
+            Rangef::new(-2.0, 2.0),
+            YScale::Linear,
+            1.0,
+        );
+        let colour = Color32::from_rgb(200, 0, 0);
+        draw_area(
+            &mut pixmap,
+            &transform,
+            &[0.0, 0.9, 1.1, 2.0],
+            &[1.0, 1.0, -1.0, -1.0],
+            colour,
+            false,
+        );
+        let filled = |x, y| pixmap.pixel(x, y).unwrap().alpha() > 0;
+        assert!(filled(40, 40) && filled(160, 60));
+        assert!(!filled(40, 60) && !filled(160, 40) && !filled(40, 10));
+        let red = |x, y| pixmap.pixel(x, y).unwrap().demultiply().red();
+        assert!(red(40, 40) > red(160, 60));
+    }
+
+    // the caption's text is drawn in the bottom left corner, leaving the rest of the image as it was
+    #[test]
+    fn caption_stamped_in_corner() {
+        let mut pixmap = Pixmap::new(1280, 720).unwrap();
+        draw_caption(&mut pixmap, "t = 1.00 s").unwrap();
+        let bright = |x: u32, y: u32| pixmap.pixel(x, y).unwrap().red() > 128;
+        assert!((0..640).any(|x| (640..720).any(|y| bright(x, y))));
+        assert!((640..1280).all(|x| (0..640).all(|y| pixmap.pixel(x, y).unwrap().alpha() == 0)));
+    }
+}
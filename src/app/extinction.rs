@@ -0,0 +1,107 @@
+//! Panel illustrating the Ewald–Oseen extinction theorem. Inside the medium, the fields induced in the particles
+//! cancel the applied wave, which still travels at c, and what remains of their sum is the slower wave of the medium.
+//! The applied wave, the summed induced fields and the resultant are plotted across the slab of particles,
+//! with how much of the applied wave the induced fields cancel, averaged over the last few seconds.
+
+use egui::{Rangef, Ui, pos2};
+
+use crate::app::i18n::tr;
+use crate::app::plot::{Series, line_plot};
+use crate::app::simulation::Simulation;
+use crate::app::simulation::observer::StepObserver;
+use crate::app::simulation::variables::INTENSITY_WINDOW;
+use crate::app::{applied_field_colour, resultant_field_colour, scattered_field_colour};
+
+const PLOT_HEIGHT: f32 = 160.0;
+
+// x range spanned by the particles, if there are at least two
+fn slab(simulation: &Simulation) -> Option<(f32, f32)> {
+    let positions = simulation.particles().iter().map(|p| p.position().x);
+    let start = positions.clone().fold(f32::INFINITY, f32::min);
+    let end = positions.fold(f32::NEG_INFINITY, f32::max);
+    (simulation.particles().len() >= 2).then_some((start, end))
+}
+
+#[derive(Default)]
+pub struct ExtinctionTracker {
+    overlap: f32, // induced field times applied field, integrated over the slab and averaged over time
+    applied: f32, // applied field squared, integrated over the slab and averaged over time
+}
+
+impl StepObserver for ExtinctionTracker {
+    fn observe(&mut self, simulation: &Simulation) {
+        let Some((start, end)) = slab(simulation) else {
+            return;
+        };
+        let [applied, _, induced] = simulation.fields();
+        let slab = Rangef::new(start, end);
+        let overlap = (induced * applied).integral(slab);
+        let power = (applied * applied).integral(slab);
+        let fraction = (simulation.time_step() / INTENSITY_WINDOW).min(1.0);
+        self.overlap += fraction * (overlap - self.overlap);
+        self.applied += fraction * (power - self.applied);
+    }
+}
+
+impl ExtinctionTracker {
+    pub fn clear(&mut self) {
+        *self = ExtinctionTracker::default();
+    }
+
+    // fraction of the applied wave that the induced fields cancel, from the part of them in step with it
+    fn cancellation(&self) -> Option<f32> {
+        (self.applied > f32::EPSILON).then(|| -self.overlap / self.applied)
+    }
+
+    pub fn draw(&self, ui: &mut Ui, simulation: &Simulation) {
+        let Some((start, end)) = slab(simulation) else {
+            ui.label(tr("Add a row of particles to see the fields inside it"));
+            return;
+        };
+        let series = |name, colour, field: &[f32]| Series {
+            name,
+            colour,
+            points: simulation
+                .x_intervals()
+                .iter()
+                .zip(field)
+                .filter(|(x, _)| (start..=end).contains(*x))
+                .map(|(x, e)| pos2(*x, *e))
+                .collect(),
+        };
+        line_plot(
+            ui,
+            PLOT_HEIGHT,
+            &[
+                series(
+                    tr("Vacuum wave"),
+                    applied_field_colour(0.9, &simulation.waveform()),
+                    simulation.applied_field(),
+                ),
+                series(
+                    tr("Induced fields"),
+                    scattered_field_colour(0.9),
+                    simulation.scattered_field(),
+                ),
+                series(
+                    tr("Sum"),
+                    resultant_field_colour(0.9),
+                    simulation.resultant_field(),
+                ),
+            ],
+            "x",
+        );
+        if let Some(cancellation) = self.cancellation() {
+            ui.label(format!(
+                "{} {:.0} % {}",
+                tr("Inside the slab, the induced fields cancel"),
+                100.0 * cancellation,
+                tr("of the vacuum wave"),
+            ))
+            .on_hover_text(tr(
+                "The part of the summed induced fields in step with the vacuum wave, across the slab and over the last few seconds. \
+                The deeper into the medium, the more completely it is cancelled, leaving the slower wave of the medium.",
+            ));
+        }
+    }
+}
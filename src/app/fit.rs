@@ -0,0 +1,118 @@
+//! Panel fitting the particles' spring constant and damping to a target refractive index or attenuation, at a
+//! frequency of the plane wave, then loading them into the simulation
+
+use egui::{DragValue, Grid, Ui};
+
+use crate::app::i18n::tr;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::fit::{FitTarget, FittedMedium};
+use crate::app::simulation::variables::WAVE_FREQUENCY;
+
+pub struct FitPanel {
+    frequency: f32,
+    index: (bool, f32),       // whether the index is fitted to, and its target
+    attenuation: (bool, f32), // likewise for the attenuation coefficient
+    fitted: Option<Option<FittedMedium>>, // latest fit, None inside if the particles couldn't be fitted
+}
+
+impl Default for FitPanel {
+    fn default() -> Self {
+        FitPanel {
+            frequency: WAVE_FREQUENCY.initial,
+            index: (true, 1.05),
+            attenuation: (false, 0.05),
+            fitted: None,
+        }
+    }
+}
+
+impl FitPanel {
+    // returns the fitted properties, and the frequency they were fitted at, to load into the simulation
+    pub fn draw(&mut self, ui: &mut Ui, simulation: &Simulation) -> Option<(FittedMedium, f32)> {
+        let mut load = None;
+        ui.label(tr(
+            "Finds the spring constant and damping that give the particles a refractive index or attenuation \
+            in the Lorentz model, keeping their mass and spacing, and loads them into the simulation. \
+            With only one target, the values found are those closest to the particles' current ones.",
+        ));
+
+        Grid::new("FitTargets").show(ui, |ui| {
+            ui.label("ω")
+                .on_hover_text(tr("Angular frequency of the plane wave"));
+            ui.add(
+                DragValue::new(&mut self.frequency)
+                    .range(WAVE_FREQUENCY.min..=WAVE_FREQUENCY.max)
+                    .speed(0.01),
+            );
+            ui.end_row();
+            ui.checkbox(&mut self.index.0, "n")
+                .on_hover_text(tr("Refractive index"));
+            ui.add_enabled(
+                self.index.0,
+                DragValue::new(&mut self.index.1)
+                    .range(0.0..=f32::MAX)
+                    .speed(0.001),
+            );
+            ui.end_row();
+            ui.checkbox(&mut self.attenuation.0, "α").on_hover_text(tr(
+                "Attenuation coefficient, the fraction of intensity lost per unit distance",
+            ));
+            ui.add_enabled(
+                self.attenuation.0,
+                DragValue::new(&mut self.attenuation.1)
+                    .range(0.0..=f32::MAX)
+                    .speed(0.001),
+            );
+            ui.end_row();
+        });
+
+        let enabled = self.index.0 || self.attenuation.0;
+        if ui
+            .add_enabled(enabled, egui::Button::new(tr("Fit and load")))
+            .on_disabled_hover_text(tr("Choose a target first"))
+            .clicked()
+        {
+            let target = FitTarget {
+                frequency: self.frequency,
+                index: self.index.0.then_some(self.index.1),
+                attenuation: self.attenuation.0.then_some(self.attenuation.1),
+            };
+            let fitted = simulation.fit_medium(target);
+            load = fitted.map(|fitted| (fitted, self.frequency));
+            self.fitted = Some(fitted);
+        }
+
+        match self.fitted {
+            Some(Some(fitted)) => {
+                ui.separator();
+                if !fitted.matched() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        tr("The targets can't be matched exactly, these are the closest values"),
+                    );
+                }
+                Grid::new("FitResults").striped(true).show(ui, |ui| {
+                    ui.label("k").on_hover_text(tr("Particle spring constant"));
+                    ui.monospace(format!("{:.4}", fitted.spring_constant));
+                    ui.end_row();
+                    ui.label(tr("Damping"));
+                    ui.monospace(format!("{:.4}", fitted.damping));
+                    ui.end_row();
+                    ui.label("n");
+                    ui.monospace(format!("{:.4}", fitted.index));
+                    ui.end_row();
+                    ui.label("α");
+                    ui.monospace(format!("{:.4}", fitted.attenuation));
+                    ui.end_row();
+                });
+            }
+            Some(None) => {
+                ui.label(tr(
+                    "Fitting needs an evenly spaced row of at least two particles in the FDTD solver",
+                ));
+            }
+            None => {}
+        }
+        load
+    }
+}
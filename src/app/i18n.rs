@@ -0,0 +1,966 @@
+//! Translations of the text shown in the user interface.
+//! Text is looked up by its English wording, and is shown in English if no translation exists.
+//! To translate a new piece of text, wrap it in tr() where it is displayed, then add its
+//! translation to each language's table below.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumIter, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    // name of the language, in that language
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    // two letter ISO 639-1 code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    // guess the user's language from the environment, falling back to English
+    pub fn from_environment() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+            .unwrap_or_default();
+        Language::iter()
+            .find(|language| locale.starts_with(language.code()))
+            .unwrap_or_default()
+    }
+}
+
+// language used by tr(), shared by every part of the UI
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+pub fn language() -> Language {
+    let current = LANGUAGE.load(Ordering::Relaxed);
+    Language::iter()
+        .find(|language| *language as u8 == current)
+        .unwrap_or_default()
+}
+
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+// translate text into the current language
+pub fn tr(text: &'static str) -> &'static str {
+    match language() {
+        Language::English => text,
+        Language::German => german(text).unwrap_or(text),
+    }
+}
+
+fn german(text: &str) -> Option<&'static str> {
+    Some(match text {
+        // sessions
+        "File" => "Datei",
+        "Session" => "Sitzung",
+        "Scene" => "Szene",
+        "Sessions, scenes, waveforms and replays" => {
+            "Sitzungen, Szenen, Wellenformen und Aufzeichnungen"
+        }
+        "Drop a session, scene, waveform (CSV) or replay file to open it" => {
+            "Eine Sitzungs-, Szenen-, Wellenform- (CSV) oder Aufzeichnungsdatei hier ablegen, um sie zu öffnen"
+        }
+        "Replay" => "Aufzeichnung",
+        "Bookmarks" => "Lesezeichen",
+        "Snapshot" => "Momentaufnahme",
+        "finished" => "beendet",
+        "Stop the demo" => "Demo beenden",
+        "Freeze the resultant field as a ghost trace. \
+        After changing something and running again, the simulation pauses at the same time to compare the two." => {
+            "Das resultierende Feld als blasse Kurve festhalten. \
+            Wenn Sie danach etwas ändern und erneut starten, hält die Simulation zum selben Zeitpunkt an, um beide zu vergleichen."
+        }
+        "Remove the snapshot" => "Momentaufnahme entfernen",
+        "Image export" => "Bildexport",
+        "Copy link" => "Link kopieren",
+        "Copy a link to the scene and view, to share them. \
+        Pasting a link onto the canvas opens it" => {
+            "Einen Link zur Szene und Ansicht kopieren, um sie zu teilen. \
+            Ein auf die Zeichenfläche eingefügter Link wird geöffnet"
+        }
+        "Could not copy the link" => "Der Link konnte nicht kopiert werden",
+        "Could not open the link" => "Der Link konnte nicht geöffnet werden",
+        "Live stream" => "Live-Übertragung",
+        "Shows the fields live in a web browser, so others on the same network \
+        can follow along on their own devices." => {
+            "Zeigt die Felder live in einem Webbrowser, sodass andere im selben Netzwerk \
+            auf ihren eigenen Geräten mitverfolgen können."
+        }
+        "Open in a browser:" => "Im Browser öffnen:",
+        "Stop streaming" => "Übertragung beenden",
+        "Port" => "Port",
+        "Start streaming" => "Übertragung starten",
+        "Timelapse: run as fast as possible rather than in real time, \
+        only redrawing after a number of updates" => {
+            "Zeitraffer: so schnell wie möglich statt in Echtzeit rechnen \
+            und erst nach einer Anzahl von Aktualisierungen neu zeichnen"
+        }
+        "Updates between redraws" => "Aktualisierungen zwischen dem Neuzeichnen",
+        "Could not export image" => "Bild konnte nicht exportiert werden",
+        "Saves an image of the current moment, or of every Nth update while the simulation runs, \
+        numbered in order, to be made into a video with other tools." => {
+            "Speichert ein Bild des aktuellen Moments oder während der Simulation ein Bild jeder N-ten Aktualisierung, \
+            fortlaufend nummeriert, um daraus mit anderen Programmen ein Video zu erstellen."
+        }
+        "Folder" => "Ordner",
+        "Choose…" => "Auswählen…",
+        "Every" => "Alle",
+        " updates" => " Aktualisierungen",
+        "Resolution" => "Auflösung",
+        "images written" => "Bilder geschrieben",
+        "Stop export" => "Export beenden",
+        "Start export" => "Export starten",
+        "Choose a folder first" => "Wählen Sie zuerst einen Ordner",
+        "Invalid image size" => "Ungültige Bildgröße",
+        "Bookmark" => "Lesezeichen",
+        "Add at" => "Hinzufügen bei",
+        "No bookmarks yet" => "Noch keine Lesezeichen",
+        "Jump to this time" => "Zu diesem Zeitpunkt springen",
+        "Remove" => "Entfernen",
+        "Mark the start of a loop at the current time" => {
+            "Den Beginn einer Schleife zum aktuellen Zeitpunkt markieren"
+        }
+        "Mark the end of the loop at the current time. \
+        Each time the simulation gets there, it jumps back to the start of the loop" => {
+            "Das Ende der Schleife zum aktuellen Zeitpunkt markieren. \
+            Jedes Mal, wenn die Simulation dort ankommt, springt sie zum Beginn der Schleife zurück"
+        }
+        "Stop looping" => "Schleife beenden",
+        "Stop recording and save the replay" => "Aufnahme beenden und Aufzeichnung speichern",
+        "Record the run from the start to a replay file, to watch again later" => {
+            "Den Lauf von Beginn an in eine Aufzeichnungsdatei aufnehmen, um ihn später erneut anzusehen"
+        }
+        "Stop replaying, keeping the simulation as it is" => {
+            "Wiedergabe beenden und die Simulation im aktuellen Zustand belassen"
+        }
+        "Start screen…" => "Startbildschirm…",
+        "Open…" => "Öffnen…",
+        "Open recent" => "Zuletzt verwendet",
+        "Clear recent files" => "Liste leeren",
+        "Save session…" => "Sitzung speichern…",
+        "Save scene…" => "Szene speichern…",
+        "Unrecognised type of file" => "Unbekannter Dateityp",
+        "Untitled" => "Unbenannt",
+        "Could not open" => "Fehler beim Öffnen von",
+        "Could not save" => "Fehler beim Speichern von",
+        "OK" => "OK",
+        "What would you like to simulate?" => "Was möchten Sie simulieren?",
+        "Blank simulation" => "Leere Simulation",
+        "The applied wave with no particles, to build your own setup" => {
+            "Die angelegte Welle ohne Teilchen, für einen eigenen Aufbau"
+        }
+        "Recent files" => "Zuletzt verwendete Dateien",
+        "Show at startup" => "Beim Start anzeigen",
+        "Close" => "Schließen",
+
+        // settings
+        "Presets" => "Voreinstellungen",
+        "Waveform:" => "Wellenform:",
+        "Particles:" => "Teilchen:",
+        "Spacing:" => "Abstand:",
+        "Reset particles" => "Teilchen zurücksetzen",
+        "Particle Type:" => "Teilchenart:",
+        "Particle mass" => "Teilchenmasse",
+        "Reset" => "Zurücksetzen",
+        "Particle spring constant" => "Federkonstante der Teilchen",
+        "Damping" => "Dämpfung",
+        "Particle motion damping factor" => "Dämpfungsfaktor der Teilchenbewegung",
+        "Field opacities:" => "Deckkraft der Felder:",
+        "Initial electric field" => "Ursprüngliches elektrisches Feld",
+        "Resultant electric field" => "Resultierendes elektrisches Feld",
+        "Induced electric field of particles" => "Induziertes elektrisches Feld der Teilchen",
+        "Phase" => "Phase",
+        "Y scale" => "Y-Skala",
+        "Logarithmic scales show weak transmitted and reflected fields alongside strong ones" => {
+            "Logarithmische Skalen zeigen schwache durchgelassene und reflektierte Felder neben starken"
+        }
+        "Linear" => "Linear",
+        "Symmetric log" => "Symmetrisch logarithmisch",
+        "Decibels" => "Dezibel",
+        "Envelope" => "Hüllkurve",
+        "Theory" => "Theorie",
+        "For a plane wave, draw the resultant field the simulation settles to, calculated analytically, as a dashed line. \
+        It assumes the particles move little compared to their distance from each point, so is most accurate far from them, \
+        and can't be calculated for particles driven at resonance with no damping." => {
+            "Bei einer ebenen Welle das resultierende Feld, auf das sich die Simulation einpendelt, analytisch berechnet als gestrichelte Linie zeichnen. \
+            Es nimmt an, dass sich die Teilchen wenig im Vergleich zu ihrem Abstand von jedem Punkt bewegen, ist also weit entfernt von ihnen am genauesten, \
+            und lässt sich für ohne Dämpfung in Resonanz angeregte Teilchen nicht berechnen."
+        }
+        "Stacked" => "Gestapelt",
+        "Draw the applied, induced and resultant fields in separate lanes, each with its own zero line" => {
+            "Angelegtes, induziertes und resultierendes Feld in getrennten Bahnen zeichnen, jede mit eigener Nulllinie"
+        }
+        "Outline the resultant field with its envelope, which shows how pulses are delayed and spread out by the medium" => {
+            "Das resultierende Feld mit seiner Hüllkurve umrahmen, die zeigt, wie Pulse vom Medium verzögert und verbreitert werden"
+        }
+        "Scattered field: the resultant minus the applied field, radiated by all the particles together" => {
+            "Gestreutes Feld: das resultierende minus das angelegte Feld, von allen Teilchen zusammen abgestrahlt"
+        }
+        "Colour the resultant field by its instantaneous phase. \
+        The colours cycle faster where the wavelength is shorter." => {
+            "Das resultierende Feld nach seiner momentanen Phase einfärben. \
+            Die Farben wechseln schneller, wo die Wellenlänge kürzer ist."
+        }
+        "Intensity: the resultant field squared, averaged over the last few seconds" => {
+            "Intensität: das Quadrat des resultierenden Feldes, gemittelt über die letzten Sekunden"
+        }
+        "Large controls" => "Große Bedienelemente",
+        "Bigger buttons and sliders for touch screens. Switched on the first time the screen is touched" => {
+            "Größere Schaltflächen und Schieberegler für Touchscreens. Wird bei der ersten Berührung des Bildschirms eingeschaltet"
+        }
+        "Pause in background" => "Im Hintergrund anhalten",
+        "Stop the simulation while the window is minimised or not focused, to save power" => {
+            "Die Simulation anhalten, solange das Fenster minimiert ist oder keinen Fokus hat, um Energie zu sparen"
+        }
+        "Explain" => "Erklären",
+        "Show the equations being solved, with their current values" => {
+            "Die gelösten Gleichungen mit ihren aktuellen Werten anzeigen"
+        }
+        "Tour" => "Rundgang",
+        "Guided tour of the controls and the physics" => {
+            "Geführter Rundgang durch die Bedienelemente und die Physik"
+        }
+        "Language" => "Sprache",
+        "Switch between dark and light theme" => "Zwischen dunklem und hellem Design wechseln",
+        "Panels" => "Fenster",
+        "Presentation mode (F11)" => "Präsentationsmodus (F11)",
+        "F11 or Esc to exit, Space or B to play/pause, Page Down to step, L for the pointer" => {
+            "F11 oder Esc zum Beenden, Leertaste oder B zum Starten/Anhalten, Bild ab für Einzelschritte, L für den Zeiger"
+        }
+
+        // controls
+        "Play simulation" => "Simulation starten",
+        "Pause simulation" => "Simulation anhalten",
+        "Advance simulation by a number of frames" => "Simulation um einige Bilder vorspulen",
+        "Number of updates to advance per step" => "Anzahl der Aktualisierungen pro Schritt",
+        "[Elapsed time]s @ [number of frames]" => "[Vergangene Zeit]s @ [Anzahl der Bilder]",
+        "Restart simulation" => "Simulation neu starten",
+        "Speed" => "Tempo",
+        "Zoom" => "Zoom",
+        "You can also zoom using the mouse wheel, and move around by dragging with the mouse. \
+        Hold Ctrl (Cmd on a Mac) while dragging to zoom into a box." => {
+            "Sie können auch mit dem Mausrad zoomen und die Ansicht durch Ziehen mit der Maus verschieben. \
+            Halten Sie beim Ziehen Strg (Cmd auf einem Mac) gedrückt, um in einen Rahmen zu zoomen."
+        }
+        "Reset view" => "Ansicht zurücksetzen",
+        "Add particle here" => "Teilchen hier hinzufügen",
+        "Place probe here" => "Sonde hierher setzen",
+        "Add marker" => "Markierung hinzufügen",
+        "Copy selected particles" => "Ausgewählte Teilchen kopieren",
+        "Ctrl+C with the pointer over the canvas" => "Strg+C mit dem Zeiger über der Zeichenfläche",
+        "Paste particles here" => "Teilchen hier einfügen",
+        "Ctrl+V with the pointer over the canvas" => "Strg+V mit dem Zeiger über der Zeichenfläche",
+        "Remove markers" => "Markierungen entfernen",
+        "Marker" => "Markierung",
+        "Copy coordinates" => "Koordinaten kopieren",
+        "Copy the x coordinate and the position on the vertical axis" => {
+            "Die x-Koordinate und die Position auf der vertikalen Achse kopieren"
+        }
+        "Frames drawn per second (green) and simulation updates per second (orange) over the last five seconds" => {
+            "Gezeichnete Bilder pro Sekunde (grün) und Simulationsschritte pro Sekunde (orange) in den letzten fünf Sekunden"
+        }
+
+        // status bar
+        "Fraction of the older motion history kept. Older motion is only seen far from each particle, \
+        where its field is weak, so keeping less of it saves memory on long runs with little loss of accuracy." => {
+            "Anteil der älteren Bewegungsgeschichte, der behalten wird. Ältere Bewegung ist nur weit entfernt von jedem Teilchen sichtbar, \
+            wo sein Feld schwach ist, daher spart weniger davon Speicher bei langen Läufen, kaum auf Kosten der Genauigkeit."
+        }
+        "Grid:" => "Gitter:",
+        "points" => "Punkte",
+        "Solver:" => "Löser:",
+        "Retarded potentials" => "Retardierte Potentiale",
+        "FDTD" => "FDTD",
+        "Method used to calculate the fields. Retarded potentials treats each particle as a point charge, \
+        while FDTD steps Maxwell's equations forward on a grid, treating each particle as a sheet of charge. \
+        Changing it restarts the simulation." => {
+            "Verfahren zur Berechnung der Felder. Retardierte Potentiale behandeln jedes Teilchen als Punktladung, \
+            während FDTD die Maxwell-Gleichungen auf einem Gitter schrittweise löst und jedes Teilchen als geladene Schicht behandelt. \
+            Eine Änderung startet die Simulation neu."
+        }
+        "Display:" => "Anzeige:",
+        "Refresh rate of the display, measured at startup" => {
+            "Bildwiederholrate der Anzeige, beim Start gemessen"
+        }
+        "Clipping" => "Übersteuerung",
+        "Unstable time step" => "Instabiler Zeitschritt",
+        "Displacement" => "Auslenkung",
+        "Velocity" => "Geschwindigkeit",
+        "Acceleration" => "Beschleunigung",
+        "Resultant field" => "Resultierendes Feld",
+        "of particle" => "von Teilchen",
+        "became infinite or NaN" => "wurde unendlich oder NaN",
+        "grew out of control" => "wuchs unkontrolliert",
+        "at step" => "bei Schritt",
+        "The simulation was paused because it blew up. Try adding damping and restarting." => {
+            "Die Simulation wurde angehalten, weil sie instabil wurde. Erhöhen Sie die Dämpfung und starten Sie neu."
+        }
+        "The resultant field is too large to fit on the canvas." => {
+            "Das resultierende Feld ist zu groß für die Zeichenfläche."
+        }
+        "The particles oscillate too quickly for the simulation time step, so their motion is inaccurate. \
+        Try reducing the spring constant or increasing the mass." => {
+            "Die Teilchen schwingen zu schnell für den Zeitschritt der Simulation, daher ist ihre Bewegung ungenau. \
+            Verringern Sie die Federkonstante oder erhöhen Sie die Masse."
+        }
+
+        // session recovery
+        "Restore previous session?" => "Vorherige Sitzung wiederherstellen?",
+        "Refraction did not close normally last time. Your previous setup and settings can be restored." => {
+            "Refraction wurde beim letzten Mal nicht normal beendet. Ihr vorheriger Aufbau und Ihre Einstellungen können wiederhergestellt werden."
+        }
+        "Restore" => "Wiederherstellen",
+        "Discard" => "Verwerfen",
+
+        // analysis panels
+        "Attach" => "Andocken",
+        "Move this panel back into the main window" => {
+            "Dieses Fenster zurück ins Hauptfenster verschieben"
+        }
+        "Detach" => "Abdocken",
+        "Move this panel into a separate window" => {
+            "Dieses Fenster in ein eigenes Fenster verschieben"
+        }
+        "Energy" => "Energie",
+        "3D field history" => "3D-Feldverlauf",
+        "Phasors" => "Zeiger",
+        "Particle fields" => "Felder der Teilchen",
+        "Convergence" => "Konvergenz",
+        "Inspector" => "Inspektor",
+        "Click a particle on the canvas to inspect it. \
+        Shift-click or shift-drag to select several." => {
+            "Klicken Sie auf ein Teilchen auf der Zeichenfläche, um es zu untersuchen. \
+            Mit Umschalt-Klick oder Umschalt-Ziehen wählen Sie mehrere aus."
+        }
+        "particles selected" => "Teilchen ausgewählt",
+        "Group" => "Gruppe",
+        "Create group" => "Gruppe erstellen",
+        "Puts the selected particles in a group, whose properties can be changed together" => {
+            "Fasst die ausgewählten Teilchen zu einer Gruppe zusammen, deren Eigenschaften gemeinsam geändert werden können"
+        }
+        "particles" => "Teilchen",
+        "Select" => "Auswählen",
+        "Delete" => "Löschen",
+        "In group" => "In Gruppe",
+        "Position" => "Position",
+        "Driving field" => "Antreibendes Feld",
+        "Kinetic energy" => "Kinetische Energie",
+        "Potential energy" => "Potentielle Energie",
+        "Reruns the scene up to the current time with the time step, and separately the grid spacing, halved several times, \
+        and compares the resultant fields to estimate the error due to the discretisation." => {
+            "Wiederholt die Szene bis zur aktuellen Zeit mit mehrfach halbiertem Zeitschritt und, getrennt davon, halbiertem Gitterabstand, \
+            und vergleicht die resultierenden Felder, um den Diskretisierungsfehler abzuschätzen."
+        }
+        "Run study" => "Studie starten",
+        "Run the simulation first" => "Zuerst die Simulation laufen lassen",
+        "Results at" => "Ergebnisse bei",
+        "Time step" => "Zeitschritt",
+        "Grid" => "Gitter",
+        "Divisions" => "Unterteilungen",
+        "Change" => "Änderung",
+        "RMS change in the resultant field when the step is halved, relative to the RMS field" => {
+            "Effektivwert der Änderung des resultierenden Feldes beim Halbieren des Schritts, relativ zum Effektivwert des Feldes"
+        }
+        "Order" => "Ordnung",
+        "Rate the change falls at each time the step is halved, e.g. 2 for second order accuracy" => {
+            "Rate, mit der die Änderung bei jedem Halbieren des Schritts abnimmt, z. B. 2 für Genauigkeit zweiter Ordnung"
+        }
+        "not converging" => "konvergiert nicht",
+        "Estimated error:" => "Geschätzter Fehler:",
+        "There are no particles." => "Es gibt keine Teilchen.",
+        "Scale:" => "Skala:",
+        "Probe" => "Sonde",
+        "Probe position" => "Position der Sonde",
+        "Rotating" => "Rotierend",
+        "Show the phasors rotating at the wave's frequency, instead of relative to the applied field" => {
+            "Die Zeiger mit der Frequenz der Welle rotierend anzeigen, statt relativ zum angelegten Feld"
+        }
+        "Phasors need a wave with a single frequency. Choose the plane wave or Gaussian packet." => {
+            "Zeiger benötigen eine Welle mit einer einzigen Frequenz. Wählen Sie die ebene Welle oder das Gauß-Paket."
+        }
+        "Fields at probe" => "Felder an der Sonde",
+        "Applied" => "Angelegt",
+        "Radiated" => "Abgestrahlt",
+        "Resultant" => "Resultierend",
+        "Particle displacements" => "Auslenkung der Teilchen",
+        "Particle 1" => "Teilchen 1",
+        "Play the simulation to build up the field history" => {
+            "Starten Sie die Simulation, um den Feldverlauf aufzubauen"
+        }
+        "Resultant field over the last few seconds. Drag to rotate the view." => {
+            "Resultierendes Feld der letzten Sekunden. Zum Drehen der Ansicht ziehen."
+        }
+        "Kinetic" => "Kinetisch",
+        "Potential" => "Potentiell",
+        "Total" => "Gesamt",
+        "Energy stored in the particles' oscillations. Energy absorbed from the wave is radiated away or lost to damping." => {
+            "In den Schwingungen der Teilchen gespeicherte Energie. Von der Welle aufgenommene Energie wird abgestrahlt oder durch Dämpfung verloren."
+        }
+
+        // tour
+        "Back" => "Zurück",
+        "Next" => "Weiter",
+        "Finish" => "Fertig",
+        "Welcome" => "Willkommen",
+        "This tour walks through how light slows down in a material. \
+        The canvas shows the electric field along the x axis, with charged particles sitting on the axis." => {
+            "Dieser Rundgang zeigt, wie Licht in einem Material langsamer wird. \
+            Die Zeichenfläche zeigt das elektrische Feld entlang der x-Achse, auf der geladene Teilchen sitzen."
+        }
+        "Apply a pulse" => "Einen Puls anlegen",
+        "The applied wave is chosen here. A single Gaussian pulse has been selected, \
+        which travels from right to left at the speed of light." => {
+            "Hier wird die angelegte Welle gewählt. Ein einzelner Gauß-Puls ist ausgewählt, \
+            der sich mit Lichtgeschwindigkeit von rechts nach links bewegt."
+        }
+        "Play the simulation" => "Die Simulation starten",
+        "These controls run, pause, step and restart the simulation. \
+        The simulation is now playing - watch the pulse approach the electron." => {
+            "Mit diesen Bedienelementen wird die Simulation gestartet, angehalten, schrittweise ausgeführt und neu gestartet. \
+            Die Simulation läuft jetzt - beobachten Sie, wie sich der Puls dem Elektron nähert."
+        }
+        "The electron responds" => "Das Elektron reagiert",
+        "The electron is bound in place like a mass on a spring. \
+        When the pulse arrives it is pushed up and down, and keeps oscillating after the pulse has passed." => {
+            "Das Elektron ist wie eine Masse an einer Feder gebunden. \
+            Wenn der Puls ankommt, wird es auf und ab bewegt und schwingt weiter, nachdem der Puls vorbei ist."
+        }
+        "The electron re-radiates" => "Das Elektron strahlt ab",
+        "An accelerating charge creates its own electric field. \
+        Its induced field (blue) spreads out from the electron at the speed of light. \
+        Use these sliders to make each field easier to see." => {
+            "Eine beschleunigte Ladung erzeugt ihr eigenes elektrisches Feld. \
+            Ihr induziertes Feld (blau) breitet sich mit Lichtgeschwindigkeit vom Elektron aus. \
+            Mit diesen Schiebereglern lässt sich jedes Feld besser sichtbar machen."
+        }
+        "Observe the phase shift" => "Die Phasenverschiebung beobachten",
+        "A plane wave now passes through a row of electrons. Each one re-radiates, \
+        and the resultant field (purple) falls behind the applied field: it is phase shifted. \
+        This delay is what we see as light travelling slower in a material." => {
+            "Eine ebene Welle durchläuft jetzt eine Reihe von Elektronen. Jedes strahlt ab, \
+            und das resultierende Feld (violett) fällt hinter das angelegte Feld zurück: es ist phasenverschoben. \
+            Diese Verzögerung nehmen wir als langsameres Licht im Material wahr."
+        }
+        "Experiment" => "Experimentieren",
+        "The size of the phase shift depends on the particles' mass, spring constant and damping. \
+        Try changing them and see how the resultant field changes." => {
+            "Die Größe der Phasenverschiebung hängt von Masse, Federkonstante und Dämpfung der Teilchen ab. \
+            Ändern Sie diese und beobachten Sie, wie sich das resultierende Feld verändert."
+        }
+
+        // names of waveforms, particles and presets
+        "Gaussian" => "Gauß",
+        "Gaussian Packet" => "Gauß-Paket",
+        "Plane Wave" => "Ebene Welle",
+        "Beat" => "Schwebung",
+        "Sampled" => "Abgetastet",
+        "Electron" => "Elektron",
+        "Proton" => "Proton",
+        "Single electron" => "Einzelnes Elektron",
+        "A wave packet hitting one electron" => "Ein Wellenpaket trifft auf ein Elektron",
+        "Electron pulse" => "Elektron und Puls",
+        "A single short pulse hitting one electron" => {
+            "Ein einzelner kurzer Puls trifft auf ein Elektron"
+        }
+        "Electron chain" => "Elektronenkette",
+        "A plane wave passing through a row of electrons" => {
+            "Eine ebene Welle durchläuft eine Reihe von Elektronen"
+        }
+        "Dilute gas" => "Verdünntes Gas",
+        "Many weak oscillators slowing a plane wave slightly, \
+        with the refractive index measured beyond them" => {
+            "Viele schwache Oszillatoren bremsen eine ebene Welle leicht ab, \
+            dahinter wird die Brechzahl gemessen"
+        }
+        "Anomalous dispersion" => "Anomale Dispersion",
+        "Oscillators with a resonance the plane wave can be tuned through, \
+        with the refractive index rising, peaking and dipping below 1 on the way" => {
+            "Oszillatoren mit einer Resonanz, durch die die ebene Welle abgestimmt werden kann; \
+            dabei steigt die Brechzahl, erreicht ein Maximum und fällt unter 1"
+        }
+        "Absorbing medium" => "Absorbierendes Medium",
+        "A dense slab of strongly damped oscillators, \
+        with the intensity falling exponentially through it" => {
+            "Eine dichte Schicht stark gedämpfter Oszillatoren, \
+            in der die Intensität exponentiell abfällt"
+        }
+        "Attenuation" => "Abschwächung",
+        "Fit an exponential to the intensity between the first and last particles, \
+        and show the attenuation coefficient: the fraction of intensity absorbed per unit distance" => {
+            "Eine Exponentialfunktion an die Intensität zwischen dem ersten und letzten Teilchen anpassen \
+            und den Abschwächungskoeffizienten anzeigen: den pro Längeneinheit absorbierten Anteil der Intensität"
+        }
+        "Rayleigh scattering" => "Rayleigh-Streuung",
+        "A plane wave scattered by a stiffly bound electron, \
+        comparing the power scattered at two frequencies" => {
+            "Eine ebene Welle wird an einem fest gebundenen Elektron gestreut, \
+            die Streuleistung bei zwei Frequenzen wird verglichen"
+        }
+        "Scattering" => "Streuung",
+        "Runs the scene with a plane wave at each of two frequencies, and compares the power the particles scatter. \
+        Well below resonance it grows as the fourth power of the frequency, which is why the sky is blue." => {
+            "Führt die Szene mit einer ebenen Welle bei zwei Frequenzen aus und vergleicht die von den Teilchen gestreute Leistung. \
+            Weit unterhalb der Resonanz wächst sie mit der vierten Potenz der Frequenz, deshalb ist der Himmel blau."
+        }
+        "Lower frequency, e.g. of red light" => "Niedrigere Frequenz, z. B. von rotem Licht",
+        "Higher frequency, e.g. of blue light" => "Höhere Frequenz, z. B. von blauem Licht",
+        "Compare" => "Vergleichen",
+        "Add a particle first" => "Fügen Sie zuerst ein Teilchen hinzu",
+        "Scattered power at" => "Streuleistung bei",
+        "Ratio" => "Verhältnis",
+        "Rayleigh's law:" => "Rayleigh-Gesetz:",
+        "Driven damped oscillator:" => "Getriebener gedämpfter Oszillator:",
+        "Ratio expected of a single particle, including how its response changes nearer resonance" => {
+            "Für ein einzelnes Teilchen erwartetes Verhältnis, einschließlich der Änderung seiner Antwort nahe der Resonanz"
+        }
+        "Lorentz model" => "Lorentz-Modell",
+        "n − 1, Lorentz model" => "n − 1, Lorentz-Modell",
+        "at resonance" => "in Resonanz",
+        "below resonance" => "unterhalb der Resonanz",
+        "above resonance" => "oberhalb der Resonanz",
+        "Resonant frequency of the particles, √(k/m), marked on the frequency slider" => {
+            "Resonanzfrequenz der Teilchen, √(k/m), auf dem Frequenzregler markiert"
+        }
+        "Extinction theorem" => "Extinktionstheorem",
+        "Add a row of particles to see the fields inside it" => {
+            "Fügen Sie eine Reihe von Teilchen hinzu, um die Felder darin zu sehen"
+        }
+        "Vacuum wave" => "Vakuumwelle",
+        "Induced fields" => "Induzierte Felder",
+        "Sum" => "Summe",
+        "Inside the slab, the induced fields cancel" => {
+            "In der Schicht löschen die induzierten Felder"
+        }
+        "of the vacuum wave" => "der Vakuumwelle aus",
+        "The part of the summed induced fields in step with the vacuum wave, across the slab and over the last few seconds. \
+        The deeper into the medium, the more completely it is cancelled, leaving the slower wave of the medium." => {
+            "Der mit der Vakuumwelle gleichphasige Anteil der summierten induzierten Felder, über die Schicht und die letzten Sekunden gemittelt. \
+            Je tiefer im Medium, desto vollständiger wird sie ausgelöscht, und es bleibt die langsamere Welle des Mediums."
+        }
+        "Slow light" => "Langsames Licht",
+        "A wave packet slowed by a dense slab with a narrow resonance, \
+        with the delay of its peak measured" => {
+            "Ein Wellenpaket wird von einer dichten Schicht mit schmaler Resonanz gebremst, \
+            die Verzögerung seines Maximums wird gemessen"
+        }
+        "Pulse delay" => "Pulsverzögerung",
+        "Times the peak of a pulse passing through the particles, \
+        and compares it with light crossing the same distance in vacuum." => {
+            "Misst die Zeit, die das Maximum eines Pulses durch die Teilchen braucht, \
+            und vergleicht sie mit Licht, das dieselbe Strecke im Vakuum zurücklegt."
+        }
+        "Choose a pulse, such as the Gaussian packet, to follow its peak" => {
+            "Wählen Sie einen Puls, z. B. das Gaußsche Paket, um seinem Maximum zu folgen"
+        }
+        "Add a row of particles for the pulse to pass through" => {
+            "Fügen Sie eine Reihe von Teilchen hinzu, die der Puls durchlaufen kann"
+        }
+        "Waiting for the pulse to pass the particles…" => {
+            "Warten, bis der Puls die Teilchen passiert hat …"
+        }
+        "Group delay" => "Gruppenlaufzeit",
+        "Vacuum transit time" => "Laufzeit im Vakuum",
+        "Group index" => "Gruppenindex",
+        "How many times slower than light the peak crossed the particles, \
+        from how much longer it took than in vacuum" => {
+            "Um wie viel langsamer als Licht das Maximum die Teilchen durchquert hat, \
+            aus der zusätzlichen Zeit gegenüber dem Vakuum"
+        }
+        "Phase fronts" => "Phasenfronten",
+        "Speed of the zero crossings of the field near the pulse's peak, \
+        followed while the peak was among the particles" => {
+            "Geschwindigkeit der Nulldurchgänge des Feldes nahe dem Maximum des Pulses, \
+            verfolgt, während sich das Maximum zwischen den Teilchen befand"
+        }
+        "Lorentz model, c / n" => "Lorentz-Modell, c / n",
+        "Peak" => "Maximum",
+        "Speed of the pulse's peak, c divided by the group index" => {
+            "Geschwindigkeit des Pulsmaximums, c geteilt durch den Gruppenindex"
+        }
+        "The phase fronts outran light, but the peak, which carries the pulse's energy, did not. \
+        No signal travels faster than light." => {
+            "Die Phasenfronten waren schneller als das Licht, das Maximum, das die Energie des Pulses trägt, jedoch nicht. \
+            Kein Signal ist schneller als das Licht."
+        }
+        "faster than light" => "schneller als das Licht",
+        "slower than light" => "langsamer als das Licht",
+        "Superluminal phase" => "Überlichtschnelle Phase",
+        "A wave packet driving a slab above its resonance, \
+        where the phase fronts travel faster than light but the peak does not" => {
+            "Ein Wellenpaket regt eine Schicht oberhalb ihrer Resonanz an, \
+            wo die Phasenfronten schneller als das Licht laufen, das Maximum jedoch nicht"
+        }
+        "Could not restore the session" => "Die Sitzung konnte nicht wiederhergestellt werden",
+        "Match refresh rate" => "Bildwiederholrate anpassen",
+        "Update the simulation once per frame of the display, for smooth motion on high refresh rate displays, \
+        rather than 60 times a second. Takes effect when the simulation restarts" => {
+            "Die Simulation einmal pro Bild des Bildschirms aktualisieren, für flüssige Bewegung auf Bildschirmen mit hoher Bildwiederholrate, \
+            statt 60-mal pro Sekunde. Wirkt ab dem nächsten Neustart der Simulation"
+        }
+        "Angular frequency of the beat's second wave above the first" => {
+            "Kreisfrequenz der zweiten Welle der Schwebung über der ersten"
+        }
+        "Strength of each wave of the beat" => "Stärke jeder Welle der Schwebung",
+        "Time between the swells of the beat, 2π/Δω" => {
+            "Zeit zwischen den Anschwellungen der Schwebung, 2π/Δω"
+        }
+        "Changes:" => "Änderungen:",
+        "When changes to mass, k and damping reach the particles while the simulation runs" => {
+            "Wann Änderungen an Masse, k und Dämpfung die Teilchen erreichen, während die Simulation läuft"
+        }
+        "Immediately" => "Sofort",
+        "Ramped" => "Allmählich",
+        "On restart" => "Beim Neustart",
+        " steps" => " Schritte",
+        "Number of steps changes are ramped in over" => {
+            "Anzahl der Schritte, über die Änderungen eingeführt werden"
+        }
+        "⏳ pending" => "⏳ ausstehend",
+        "The particles haven't reached the properties set yet" => {
+            "Die Teilchen haben die eingestellten Eigenschaften noch nicht erreicht"
+        }
+        "Smooth slow motion" => "Flüssige Zeitlupe",
+        "Below normal speed, draw the particles and fields between simulation updates, \
+        rather than holding them still until the next update" => {
+            "Unter normaler Geschwindigkeit die Teilchen und Felder zwischen den Simulationsschritten zeichnen, \
+            statt sie bis zum nächsten Schritt stillstehen zu lassen"
+        }
+        "Memory cap reached" => "Speichergrenze erreicht",
+        "The particles' histories have been thinned out as far as they can be, and motion too old to be \
+        seen anywhere forgotten, but more memory is used than the cap allows. Try fewer particles or a higher cap." => {
+            "Die Verläufe der Teilchen wurden so weit wie möglich ausgedünnt und Bewegungen, die nirgends mehr zu \
+            sehen sind, vergessen, aber es wird mehr Speicher belegt als die Grenze erlaubt. Versuchen Sie weniger Teilchen oder eine höhere Grenze."
+        }
+        "Memory:" => "Speicher:",
+        "Memory used by what grows as the simulation runs" => {
+            "Speicher für das, was mit der Laufzeit der Simulation wächst"
+        }
+        "Particle histories:" => "Teilchenverläufe:",
+        "Recording:" => "Aufnahme:",
+        "3D field history:" => "3D-Feldverlauf:",
+        "Most memory to use. Beyond it the older motion history is thinned out further, \
+        and motion too old to be seen anywhere is forgotten, so long runs left unattended can't use up the memory." => {
+            "Höchster zu belegender Speicher. Darüber hinaus wird der ältere Bewegungsverlauf weiter ausgedünnt \
+            und Bewegung, die nirgends mehr zu sehen ist, vergessen, sodass lange unbeaufsichtigte Läufe den Speicher nicht aufbrauchen können."
+        }
+        "Field gradient" => "Feldgradient",
+        "Local RMS field" => "Lokaler Effektivwert des Feldes",
+        "Limiter:" => "Begrenzer:",
+        "Limiter" => "Begrenzer",
+        "Safeguard for demonstrations, e.g. at resonance. When a particle moves further than the displacement bound, \
+        or the resultant field grows beyond its bound times the amplitude of the applied wave, \
+        the limiter holds them at the bounds, raises the damping until the motion settles, or pauses." => {
+            "Schutz für Vorführungen, z. B. bei Resonanz. Wenn sich ein Teilchen weiter als die Auslenkungsgrenze bewegt \
+            oder das resultierende Feld über seine Grenze mal der Amplitude der eingestrahlten Welle wächst, \
+            hält der Begrenzer sie an den Grenzen, erhöht die Dämpfung, bis sich die Bewegung beruhigt, oder pausiert."
+        }
+        "Off" => "Aus",
+        "Clamp" => "Abschneiden",
+        "Raise damping" => "Dämpfung erhöhen",
+        "Pause" => "Pausieren",
+        "Largest displacement of any particle" => "Größte Auslenkung eines Teilchens",
+        "Largest resultant field, as a multiple of the amplitude of the applied wave" => {
+            "Größtes resultierendes Feld, als Vielfaches der Amplitude der eingestrahlten Welle"
+        }
+        "damping raised to" => "Dämpfung erhöht auf",
+        "paused" => "pausiert",
+        "held at its bound" => "an der Grenze gehalten",
+        "out of bounds" => "außerhalb der Grenzen",
+        "UI scale" => "Oberflächengröße",
+        "Enlarge the text, controls and traces of the whole interface. Ctrl and + or - change it too" => {
+            "Text, Bedienelemente und Kurven der gesamten Oberfläche vergrößern. Auch mit Strg und + oder - änderbar"
+        }
+        "📽 Projector" => "📽 Beamer",
+        "Enlarge everything to be readable from the back of a lecture hall, with large controls" => {
+            "Alles so vergrößern, dass es aus der letzten Reihe eines Hörsaals lesbar ist, mit großen Bedienelementen"
+        }
+        "Laser pointer (L): press or drag on the canvas to point things out with a halo or a stroke \
+        that fades away, instead of moving the view" => {
+            "Laserpointer (L): Auf der Leinwand drücken oder ziehen, um mit einem Lichthof oder einem verblassenden \
+            Strich auf etwas hinzuweisen, statt die Ansicht zu verschieben"
+        }
+        "Prediction quiz" => "Vorhersage-Quiz",
+        "Pauses just before the pulse reaches the particles, to guess what the resultant field will be once it \
+        has passed them. Play on to see whether you were right." => {
+            "Hält kurz bevor der Puls die Teilchen erreicht an, damit Sie raten können, wie das resultierende Feld \
+            aussehen wird, wenn er sie passiert hat. Spielen Sie weiter, um zu sehen, ob Sie richtig lagen."
+        }
+        "Start" => "Starten",
+        "New question" => "Neue Frage",
+        "Restarts the simulation" => "Startet die Simulation neu",
+        "Score:" => "Punkte:",
+        "Preparing the question…" => "Frage wird vorbereitet…",
+        "The quiz needs a Gaussian pulse or packet, and at least one particle." => {
+            "Das Quiz benötigt einen Gauß-Puls oder ein Gauß-Paket und mindestens ein Teilchen."
+        }
+        "Play until the pulse reaches the particles." => {
+            "Abspielen, bis der Puls die Teilchen erreicht."
+        }
+        "Play" => "Abspielen",
+        "Which will the resultant field be once the pulse has passed the particles?" => {
+            "Welches wird das resultierende Feld sein, wenn der Puls die Teilchen passiert hat?"
+        }
+        "Play on" => "Weiterspielen",
+        "Choose an answer first" => "Wählen Sie zuerst eine Antwort",
+        "Right!" => "Richtig!",
+        "Not quite: the right answer is outlined in green." => {
+            "Nicht ganz: Die richtige Antwort ist grün umrandet."
+        }
+        "Caption" => "Beschriftung",
+        "Parameters in a corner" => "Parameter in einer Ecke",
+        "Stamps the waveform, number of particles, spring constant, mass, damping and time on each image" => {
+            "Versieht jedes Bild mit Wellenform, Teilchenzahl, Federkonstante, Masse, Dämpfung und Zeit"
+        }
+        "Colour palette" => "Farbpalette",
+        "Standard colours" => "Standardfarben",
+        "High contrast" => "Hoher Kontrast",
+        "Colour-blind safe" => "Für Farbenblinde geeignet",
+        "Line styles" => "Linienstile",
+        "Draw the initial, induced and scattered fields and the intensity in dashed and dotted lines, \
+        so they can be told apart without colour" => {
+            "Das anfängliche, das induzierte und das gestreute Feld sowie die Intensität gestrichelt und gepunktet \
+            zeichnen, damit sie sich ohne Farbe unterscheiden lassen"
+        }
+        "Layout" => "Anordnung",
+        "Settings bar" => "Einstellungsleiste",
+        "Controls bar" => "Steuerleiste",
+        "Waveform" => "Wellenform",
+        "Particles" => "Teilchen",
+        "Particle type" => "Teilchenart",
+        "Particle properties" => "Teilcheneigenschaften",
+        "Field opacities" => "Feld-Deckkraft",
+        "Options" => "Optionen",
+        "Language and appearance" => "Sprache und Darstellung",
+        "Time" => "Zeit",
+        "Frame and update rates" => "Bild- und Aktualisierungsrate",
+        "Plot of the rates" => "Diagramm der Raten",
+        "Move earlier" => "Nach vorne",
+        "Move later" => "Nach hinten",
+        "Saved layouts" => "Gespeicherte Anordnungen",
+        "Use this layout" => "Diese Anordnung verwenden",
+        "Name" => "Name",
+        "Save layout" => "Anordnung speichern",
+        "Saving under the name of a saved layout replaces it" => {
+            "Speichern unter dem Namen einer gespeicherten Anordnung ersetzt diese"
+        }
+        "Reset layout" => "Anordnung zurücksetzen",
+        "Energy of the particles' oscillations, kinetic and potential" => {
+            "Energie der Schwingungen der Teilchen, kinetisch und potentiell"
+        }
+        "Fit to a medium" => "An ein Medium anpassen",
+        "Finds the spring constant and damping that give the particles a refractive index or attenuation \
+        in the Lorentz model, keeping their mass and spacing, and loads them into the simulation. \
+        With only one target, the values found are those closest to the particles' current ones." => {
+            "Sucht die Federkonstante und Dämpfung, die den Teilchen im Lorentz-Modell einen Brechungsindex \
+            oder eine Abschwächung geben, behält ihre Masse und ihren Abstand bei und lädt sie in die Simulation. \
+            Mit nur einem Ziel werden die Werte gefunden, die den aktuellen der Teilchen am nächsten sind."
+        }
+        "Refractive index" => "Brechungsindex",
+        "Attenuation coefficient, the fraction of intensity lost per unit distance" => {
+            "Abschwächungskoeffizient, der Anteil der Intensität, der je Längeneinheit verloren geht"
+        }
+        "Fit and load" => "Anpassen und laden",
+        "Choose a target first" => "Wählen Sie zuerst ein Ziel",
+        "The targets can't be matched exactly, these are the closest values" => {
+            "Die Ziele lassen sich nicht genau erreichen, dies sind die nächstliegenden Werte"
+        }
+        "Fitting needs an evenly spaced row of at least two particles in the FDTD solver" => {
+            "Die Anpassung braucht eine gleichmäßig verteilte Reihe von mindestens zwei Teilchen im FDTD-Löser"
+        }
+        "Ensemble" => "Ensemble",
+        "Reruns the scene up to the current time many times, with every particle moved off its place by a \
+        random distance each time, and draws the mean resultant field with a band of one standard deviation \
+        either side of it." => {
+            "Lässt die Szene viele Male bis zur aktuellen Zeit laufen, wobei jedes Teilchen jedes Mal um eine \
+            zufällige Strecke von seinem Platz verschoben wird, und zeichnet das mittlere resultierende Feld mit \
+            einem Band von einer Standardabweichung zu beiden Seiten."
+        }
+        "Realisations" => "Realisierungen",
+        "Disorder" => "Unordnung",
+        "Standard deviation of the distance each particle is moved" => {
+            "Standardabweichung der Strecke, um die jedes Teilchen verschoben wird"
+        }
+        "Seed" => "Startwert",
+        "The same seed gives the same realisations" => {
+            "Derselbe Startwert ergibt dieselben Realisierungen"
+        }
+        "Run ensemble" => "Ensemble starten",
+        "Add a particle and run the simulation first" => {
+            "Fügen Sie zuerst ein Teilchen hinzu und lassen Sie die Simulation laufen"
+        }
+        "Mean at" => "Mittelwert bei",
+        "realisations" => "Realisierungen",
+        "Largest spread" => "Größte Streuung",
+        "Show on canvas" => "Auf der Zeichenfläche zeigen",
+        "Temperature of the heat bath giving the particles thermal motion, \
+        through their damping" => {
+            "Temperatur des Wärmebads, das den Teilchen über ihre Dämpfung eine thermische Bewegung gibt"
+        }
+        "Noise statistics" => "Rauschstatistik",
+        "There is no thermal motion. Set a temperature under the particles' properties to add noise." => {
+            "Es gibt keine thermische Bewegung. Stellen Sie bei den Eigenschaften der Teilchen eine Temperatur \
+            ein, um Rauschen hinzuzufügen."
+        }
+        "Thermal motion needs damping, which couples the particles to the heat bath" => {
+            "Thermische Bewegung braucht Dämpfung, die die Teilchen an das Wärmebad koppelt"
+        }
+        "Only after" => "Erst nach",
+        "Leave out the samples from before the settling time, while the wave arrives and the particles' \
+        motion settles" => {
+            "Die Messwerte vor der Einschwingzeit auslassen, während die Welle ankommt und sich die Bewegung \
+            der Teilchen einschwingt"
+        }
+        "Restart statistics" => "Statistik neu beginnen",
+        "Waiting for the settling time to pass" => "Warten, bis die Einschwingzeit vorüber ist",
+        "Samples" => "Messwerte",
+        "Mean" => "Mittelwert",
+        "Variance" => "Varianz",
+        "Standard deviation" => "Standardabweichung",
+        "Signal to noise" => "Signal-Rausch-Verhältnis",
+        "Power of the mean and the oscillation at the wave's frequency, over that of the rest of \
+        the field" => {
+            "Leistung des Mittelwerts und der Schwingung bei der Frequenz der Welle, geteilt durch die des \
+            restlichen Felds"
+        }
+        "Square of the mean, over the variance" => {
+            "Quadrat des Mittelwerts, geteilt durch die Varianz"
+        }
+        "no noise" => "kein Rauschen",
+        "Reference" => "Referenz",
+        "Draw two columns of numbers from a CSV file as a reference trace: a field against x on the canvas, \
+        or a refractive index against ω on the dispersion plot of the Phasors panel" => {
+            "Zwei Zahlenspalten aus einer CSV-Datei als Referenzkurve zeichnen: ein Feld über x auf der \
+            Zeichenfläche oder einen Brechungsindex über ω im Dispersionsdiagramm des Zeiger-Panels"
+        }
+        "Field E against x" => "Feld E über x",
+        "Index n against ω" => "Index n über ω",
+        "Remove the reference" => "Referenz entfernen",
+        "Comma-separated values" => "Kommagetrennte Werte",
+        "Diagnostics" => "Diagnose",
+        "Load script…" => "Skript laden…",
+        "Load a Rhai script to run after every update. It can read the fields and particles, and record \
+        values to plot with record(name, value)." => {
+            "Laden Sie ein Rhai-Skript, das nach jedem Schritt ausgeführt wird. Es kann die Felder und Teilchen \
+            lesen und mit record(name, value) Werte zum Darstellen aufzeichnen."
+        }
+        "The script stopped with an error" => "Das Skript wurde mit einem Fehler beendet",
+        "Restart" => "Neu starten",
+        "Export CSV…" => "CSV exportieren…",
+        "Rhai scripts" => "Rhai-Skripte",
+        "Save checkpoint…" => "Sicherungspunkt speichern…",
+        "Checkpoint" => "Sicherungspunkt",
+        "Save checkpoints every" => "Sicherungspunkte speichern alle",
+        "Save the whole state of the simulation to a file as it runs, to resume a long run from after a crash or \
+        in a later session" => {
+            "Speichern Sie den gesamten Zustand der Simulation während des Laufs in einer Datei, um einen langen \
+            Lauf nach einem Absturz oder in einer späteren Sitzung fortzusetzen"
+        }
+        "Saving to" => "Speichern in",
+        "Resume from checkpoint…" => "Von Sicherungspunkt fortsetzen…",
+        "Could not save a checkpoint" => "Sicherungspunkt konnte nicht gespeichert werden",
+        "This app differs from the one that exported the manifest, so may not reproduce the run exactly:" => {
+            "Diese App unterscheidet sich von der, die das Manifest exportiert hat, und gibt den Lauf daher \
+            möglicherweise nicht genau wieder:"
+        }
+        "Export manifest…" => "Manifest exportieren…",
+        "Save everything the run is set up from, with the version of the app, so it can be cited and run again" => {
+            "Speichern Sie alles, woraus der Lauf aufgebaut ist, mit der Version der App, damit er zitiert und \
+            erneut ausgeführt werden kann"
+        }
+        "Manifest" => "Manifest",
+        "Load manifest…" => "Manifest laden…",
+        "Compare runs" => "Läufe vergleichen",
+        "Save the energy over the time shown, to compare with other runs" => {
+            "Speichern Sie die Energie über die gezeigte Zeit, um sie mit anderen Läufen zu vergleichen"
+        }
+        "Add runs…" => "Läufe hinzufügen…",
+        "Add sweeps, energy exported from the Energy panel or values recorded by a diagnostic script, \
+        to compare runs on one plot." => {
+            "Fügen Sie Parameterläufe, aus dem Energiefenster exportierte Energie oder von einem Diagnoseskript \
+            aufgezeichnete Werte hinzu, um Läufe in einem Diagramm zu vergleichen."
+        }
+        "against" => "gegen",
+        "Quantity" => "Größe",
+        "Automatic" => "Automatisch",
+        "Solid" => "Durchgezogen",
+        "Dashed" => "Gestrichelt",
+        "Dotted" => "Gepunktet",
+        "Intensity" => "Intensität",
+        "Scattered field" => "Gestreutes Feld",
+        "Width, relative to the usual width" => "Breite, relativ zur üblichen Breite",
+        "Anti-aliasing" => "Kantenglättung",
+        "Width of the softened edges of lines, 0 for hard edges. Exported images are anti-aliased unless it is 0" => {
+            "Breite der geglätteten Kanten von Linien, 0 für harte Kanten. \
+            Exportierte Bilder werden geglättet, außer bei 0"
+        }
+        "Line widths, dashes and anti-aliasing" => "Linienbreiten, Strichelung und Kantenglättung",
+        "Save image…" => "Bild speichern…",
+        "Saves the current moment at the resolution above, whatever the size of the window" => {
+            "Speichert den aktuellen Moment in der obigen Auflösung, unabhängig von der Größe des Fensters"
+        }
+        "Simulation" => "Simulation",
+        "Filled" => "Gefüllt",
+        "Fill the area between each field and its zero line, lighter where the field is positive \
+        and darker where it is negative, which reads better than thin lines when projected or printed small" => {
+            "Die Fläche zwischen jedem Feld und seiner Nulllinie füllen, heller, wo das Feld positiv ist, \
+            und dunkler, wo es negativ ist, was projiziert oder klein gedruckt besser lesbar ist als dünne Linien"
+        }
+        "Add the values recorded to the Compare runs panel, to plot them with other runs" => {
+            "Fügen Sie die aufgezeichneten Werte dem Fenster Läufe vergleichen hinzu, um sie mit anderen Läufen \
+            darzustellen"
+        }
+        "Spectrum" => "Spektrum",
+        "Collecting samples at the probe" => "Messwerte an der Sonde werden gesammelt",
+        "Amplitude of each angular frequency in the fields at the probe, over the latest samples. \
+        The peaks sharpen as more samples are taken." => {
+            "Amplitude jeder Kreisfrequenz in den Feldern an der Sonde, über die letzten Messwerte. \
+            Die Spitzen werden schärfer, je mehr Messwerte genommen werden."
+        }
+        "Angular frequency of the plane wave" => "Kreisfrequenz der ebenen Welle",
+        "Dispersion" => "Dispersion",
+        "n − 1, dilute theory" => "n − 1, Theorie verdünnter Medien",
+        "n − 1, measured" => "n − 1, gemessen",
+        "Add to plot" => "Zum Diagramm hinzufügen",
+        "Adds the measured index at this frequency. \
+        Wait for it to settle after changing the frequency." => {
+            "Fügt die bei dieser Frequenz gemessene Brechzahl hinzu. \
+            Warten Sie nach einer Frequenzänderung, bis sie sich eingependelt hat."
+        }
+        "Clear" => "Leeren",
+        "Frequencies to try" => "Empfohlene Frequenzen",
+        "index peaks" => "Brechzahl maximal",
+        "resonance" => "Resonanz",
+        "index dips below 1" => "Brechzahl minimal, unter 1",
+        "Measured index" => "Gemessene Brechzahl",
+        "From the phase lag of the resultant behind the applied field at the probe, \
+        taking each particle as a slab of medium as thick as the spacing between them" => {
+            "Aus der Phasenverzögerung des resultierenden gegenüber dem angelegten Feld an der Sonde, \
+            wobei jedes Teilchen als Schicht des Mediums so dick wie der Abstand zwischen ihnen gilt"
+        }
+        "dilute theory" => "Theorie verdünnter Medien",
+        "Place the probe beyond the particles to measure their refractive index" => {
+            "Platzieren Sie die Sonde hinter den Teilchen, um ihre Brechzahl zu messen"
+        }
+        _ => return None,
+    })
+}
@@ -0,0 +1,157 @@
+//! Panel showing the state of the particles selected on the canvas, and editing the groups they can be put in
+
+use std::f32::consts::TAU;
+
+use egui::{Grid, Slider, SliderClamping, Ui};
+
+use crate::app::i18n::tr;
+use crate::app::simulation::variables::{C, ELECTRON_DAMPING, ELECTRON_MASS, SPRING_CONSTANT};
+use crate::app::simulation::{ParticleId, Simulation};
+
+#[derive(Default)]
+pub struct Inspector {
+    group_name: String, // name typed for the next group
+}
+
+impl Inspector {
+    pub fn draw(
+        &mut self,
+        ui: &mut Ui,
+        simulation: &mut Simulation,
+        selected: &mut Vec<ParticleId>,
+    ) {
+        selected.retain(|id| simulation.particle(*id).is_some());
+        match selected.as_slice() {
+            [] => {
+                ui.label(tr("Click a particle on the canvas to inspect it. \
+                    Shift-click or shift-drag to select several."));
+            }
+            [id] => draw_particle(ui, simulation, *id),
+            _ => {
+                ui.strong(format!("{} {}", selected.len(), tr("particles selected")));
+            }
+        }
+
+        if !selected.is_empty() {
+            ui.horizontal(|ui| {
+                if self.group_name.is_empty() {
+                    self.group_name = format!("{} {}", tr("Group"), simulation.groups().len() + 1);
+                }
+                ui.text_edit_singleline(&mut self.group_name);
+                if ui
+                    .button(tr("Create group"))
+                    .on_hover_text(tr(
+                        "Puts the selected particles in a group, whose properties can be changed together",
+                    ))
+                    .clicked()
+                {
+                    simulation.create_group(std::mem::take(&mut self.group_name), selected.clone());
+                }
+            });
+        }
+
+        if simulation.groups().is_empty() {
+            return;
+        }
+        ui.separator();
+        let mut removed = None;
+        for (i, group) in simulation.groups_mut().iter_mut().enumerate() {
+            ui.push_id(i, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong(&group.name);
+                    ui.label(format!("({} {})", group.members().len(), tr("particles")));
+                    if ui.button(tr("Select")).clicked() {
+                        *selected = group.members().to_vec();
+                    }
+                    if ui.button(tr("Delete")).clicked() {
+                        removed = Some(i);
+                    }
+                });
+                let properties = &mut group.properties;
+                Grid::new("GroupProperties").show(ui, |ui| {
+                    ui.label("M").on_hover_text(tr("Particle mass"));
+                    ui.add(
+                        Slider::new(&mut properties.mass, ELECTRON_MASS.min..=ELECTRON_MASS.max)
+                            .clamping(SliderClamping::Edits),
+                    );
+                    ui.end_row();
+                    ui.label("k").on_hover_text(tr("Particle spring constant"));
+                    ui.add(
+                        Slider::new(
+                            &mut properties.spring_constant,
+                            SPRING_CONSTANT.min..=SPRING_CONSTANT.max,
+                        )
+                        .clamping(SliderClamping::Edits),
+                    );
+                    ui.end_row();
+                    ui.label(tr("Damping"))
+                        .on_hover_text(tr("Particle motion damping factor"));
+                    ui.add(
+                        Slider::new(
+                            &mut properties.damping,
+                            ELECTRON_DAMPING.min..=ELECTRON_DAMPING.max,
+                        )
+                        .clamping(SliderClamping::Edits),
+                    );
+                    ui.end_row();
+                });
+            });
+        }
+        if let Some(i) = removed {
+            simulation.remove_group(i);
+        }
+    }
+}
+
+fn draw_particle(ui: &mut Ui, simulation: &Simulation, id: ParticleId) {
+    let Some((index, particle)) = simulation.particle(id) else {
+        return;
+    };
+    let (kinetic, potential) = particle.energy();
+    let x = particle.position().x;
+    let [_, resultant, _] = simulation.fields();
+    let mut rows = vec![
+        (tr("Position"), format!("x = {x:.3}")),
+        (tr("Displacement"), format!("{:+.4}", particle.position().y)),
+        (tr("Velocity"), format!("{:+.4}", particle.velocity())),
+        (
+            tr("Acceleration"),
+            format!("{:+.4}", particle.acceleration()),
+        ),
+        (
+            tr("Driving field"),
+            format!("{:+.4}", particle.driving_field()),
+        ),
+        (
+            tr("Field gradient"),
+            format!("{:+.4}", resultant.derivative().value_at(x)),
+        ),
+        (tr("Kinetic energy"), format!("{kinetic:.4}")),
+        (tr("Potential energy"), format!("{potential:.4}")),
+        ("M", format!("{:.3}", particle.mass())),
+        ("k", format!("{:.3}", particle.spring_constant())),
+        (tr("Damping"), format!("{:.3}", particle.damping())),
+    ];
+    // over a wavelength of the wave, the field around the particle averages out to its local strength
+    if let Some(omega) = simulation.carrier() {
+        let wavelength = TAU * C / omega;
+        let rms = resultant.windowed_rms(wavelength).value_at(x);
+        rows.insert(6, (tr("Local RMS field"), format!("{rms:.4}")));
+    }
+    let group = simulation.groups().iter().find(|group| group.contains(id));
+    ui.strong(format!(
+        "{} {}",
+        tr(particle.particle_type().name()),
+        index + 1
+    ));
+    if let Some(group) = group {
+        ui.label(format!("{} {}", tr("In group"), group.name));
+    }
+    Grid::new("Inspector").striped(true).show(ui, |ui| {
+        for (name, value) in rows {
+            ui.label(name);
+            ui.monospace(value);
+            ui.end_row();
+        }
+    });
+}
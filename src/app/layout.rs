@@ -0,0 +1,258 @@
+//! Arrangement of the controls: which sections of the settings bar are shown and in what order, and which
+//! measurements are shown in the controls bar. Arrangements can be saved under a name with the settings, e.g. a
+//! sparse "teaching" layout with only the wave and particles, and a "research" one with every control.
+
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::app::i18n::tr;
+
+// a group of controls in the settings bar at the top of the window
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum Section {
+    Waveform,
+    Particles,
+    ParticleType,
+    ParticleProperties,
+    Opacities,
+    Options,
+    Explain,
+    Tour,
+    Appearance,
+}
+
+impl Section {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Section::Waveform => "Waveform",
+            Section::Particles => "Particles",
+            Section::ParticleType => "Particle type",
+            Section::ParticleProperties => "Particle properties",
+            Section::Opacities => "Field opacities",
+            Section::Options => "Options",
+            Section::Explain => "Explain",
+            Section::Tour => "Tour",
+            Section::Appearance => "Language and appearance",
+        }
+    }
+
+    // row of the settings bar the section is in: the first, with the simulation's setup after the menus, or the
+    // second, with how it is shown
+    pub fn row(&self) -> usize {
+        match self {
+            Section::Waveform
+            | Section::Particles
+            | Section::ParticleType
+            | Section::ParticleProperties => 0,
+            _ => 1,
+        }
+    }
+}
+
+// a reading shown in the controls bar at the bottom of the window
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum Measurement {
+    Time,     // simulated time and number of frames
+    Energy,   // energy of the particles' oscillations
+    Rates,    // frames and updates a second
+    RatePlot, // recent frame and update rates
+}
+
+impl Measurement {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Measurement::Time => "Time",
+            Measurement::Energy => "Energy",
+            Measurement::Rates => "Frame and update rates",
+            Measurement::RatePlot => "Plot of the rates",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Layout {
+    pub sections: Vec<(Section, bool)>, // sections in the order they are drawn, and whether each is shown
+    pub measurements: Vec<Measurement>, // measurements shown, drawn in the order they are declared
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout {
+            sections: Section::iter().map(|section| (section, true)).collect(),
+            measurements: vec![Measurement::Time, Measurement::Rates, Measurement::RatePlot],
+        }
+    }
+}
+
+impl Layout {
+    // sections of a row to draw, in order. sections missing from a layout saved by an older version are shown
+    // at the end of their row
+    pub fn shown(&self, row: usize) -> Vec<Section> {
+        let missing = Section::iter()
+            .filter(|section| !self.sections.iter().any(|(s, _)| s == section))
+            .map(|section| (section, true));
+        self.sections
+            .iter()
+            .copied()
+            .chain(missing)
+            .filter(|(section, shown)| *shown && section.row() == row)
+            .map(|(section, _)| section)
+            .collect()
+    }
+
+    pub fn shows(&self, measurement: Measurement) -> bool {
+        self.measurements.contains(&measurement)
+    }
+
+    // swaps a section with the next one in the same row, if there is one
+    fn move_down(&mut self, i: usize) {
+        let row = self.sections[i].0.row();
+        if let Some(next) = (i + 1..self.sections.len()).find(|j| self.sections[*j].0.row() == row)
+        {
+            self.sections.swap(i, next);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedLayout {
+    pub name: String,
+    pub layout: Layout,
+}
+
+#[derive(Default)]
+pub struct LayoutMenu {
+    name: String, // name typed to save the layout under
+}
+
+impl LayoutMenu {
+    // contents of the layout menu: the sections and measurements shown, and the layouts saved
+    pub fn draw(&mut self, ui: &mut Ui, layout: &mut Layout, saved: &mut Vec<NamedLayout>) {
+        // every section is listed, so one hidden can be shown again
+        for section in Section::iter() {
+            if !layout.sections.iter().any(|(s, _)| *s == section) {
+                layout.sections.push((section, true));
+            }
+        }
+        ui.strong(tr("Settings bar"));
+        let mut moved = None;
+        for row in 0..2 {
+            let rows: Vec<usize> = (0..layout.sections.len())
+                .filter(|i| layout.sections[*i].0.row() == row)
+                .collect();
+            for (position, &i) in rows.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    let (section, shown) = &mut layout.sections[i];
+                    ui.checkbox(shown, tr(section.name()));
+                    if ui
+                        .add_enabled(position > 0, egui::Button::new("⏶").small())
+                        .on_hover_text(tr("Move earlier"))
+                        .clicked()
+                    {
+                        moved = Some(rows[position - 1]);
+                    }
+                    if ui
+                        .add_enabled(position + 1 < rows.len(), egui::Button::new("⏷").small())
+                        .on_hover_text(tr("Move later"))
+                        .clicked()
+                    {
+                        moved = Some(i);
+                    }
+                });
+            }
+            if row == 0 {
+                ui.separator();
+            }
+        }
+        if let Some(i) = moved {
+            layout.move_down(i);
+        }
+
+        ui.separator();
+        ui.strong(tr("Controls bar"));
+        for measurement in Measurement::iter() {
+            let mut shown = layout.shows(measurement);
+            if ui.checkbox(&mut shown, tr(measurement.name())).changed() {
+                layout.measurements.retain(|m| *m != measurement);
+                if shown {
+                    layout.measurements.push(measurement);
+                }
+            }
+        }
+
+        ui.separator();
+        ui.strong(tr("Saved layouts"));
+        let mut removed = None;
+        for (i, named) in saved.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .button(&named.name)
+                    .on_hover_text(tr("Use this layout"))
+                    .clicked()
+                {
+                    *layout = named.layout.clone();
+                }
+                if ui.button("🗑").on_hover_text(tr("Remove")).clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            saved.remove(i);
+        }
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.name).hint_text(tr("Name")));
+            let name = self.name.trim();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new(tr("Save layout")))
+                .on_hover_text(tr("Saving under the name of a saved layout replaces it"))
+                .clicked()
+            {
+                let named = NamedLayout {
+                    name: name.to_owned(),
+                    layout: layout.clone(),
+                };
+                match saved.iter_mut().find(|saved| saved.name == named.name) {
+                    Some(existing) => *existing = named,
+                    None => saved.push(named),
+                }
+                self.name.clear();
+            }
+        });
+        if ui.button(tr("Reset layout")).clicked() {
+            *layout = Layout::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Layout, Section};
+
+    // sections stay in their rows when moved, and ones missing from an older layout are still shown
+    #[test]
+    fn sections_move_within_their_rows() {
+        let mut layout = Layout::default();
+        let waveform = layout
+            .sections
+            .iter()
+            .position(|(s, _)| *s == Section::Waveform)
+            .unwrap();
+        layout.move_down(waveform);
+        assert_eq!(
+            layout.shown(0)[..2],
+            [Section::Particles, Section::Waveform]
+        );
+        let last = layout.sections.len() - 1;
+        layout.move_down(last);
+        assert_eq!(layout.shown(1).last(), Some(&Section::Appearance));
+
+        layout.sections.retain(|(s, _)| *s != Section::Tour);
+        layout.sections[0].1 = false;
+        assert!(layout.shown(1).contains(&Section::Tour));
+        assert_eq!(layout.shown(0).len(), 3);
+    }
+}
@@ -0,0 +1,70 @@
+//! Links that share a setup, with the scene and view encoded into the fragment of a URL: the web equivalent
+//! of session files. The fragment is meant for a web build, which would restore it from the address opened;
+//! until there is one, the native app copies the fragment to the clipboard and opens one pasted onto the canvas.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+
+use crate::app::canvas::YScale;
+use crate::app::settings::Settings;
+use crate::app::simulation::scene::Scene;
+
+// start of the fragment, which the encoded setup follows
+pub const LINK_PREFIX: &str = "#refraction=";
+
+// the part of the simulation in view
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct View {
+    zoom: f32,
+    world_centre: f32,
+    y_zoom: f32,
+    y_centre: f32,
+    y_scale: YScale,
+}
+
+impl View {
+    pub fn of(settings: &Settings) -> Self {
+        View {
+            zoom: settings.zoom,
+            world_centre: settings.world_centre,
+            y_zoom: settings.y_zoom,
+            y_centre: settings.y_centre,
+            y_scale: settings.y_scale,
+        }
+    }
+
+    pub fn apply(&self, settings: &mut Settings) {
+        settings.zoom = self.zoom;
+        settings.world_centre = self.world_centre;
+        settings.y_zoom = self.y_zoom;
+        settings.y_centre = self.y_centre;
+        settings.y_scale = self.y_scale;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Link {
+    pub scene: Scene,
+    pub view: View,
+}
+
+impl Link {
+    // the fragment of a URL holding the setup, as compact RON in URL-safe base64
+    pub fn encode(&self) -> Result<String, String> {
+        let text = ron::to_string(self).map_err(|e| e.to_string())?;
+        Ok(format!("{LINK_PREFIX}{}", URL_SAFE_NO_PAD.encode(text)))
+    }
+
+    // the setup in a link, or in just its fragment. None if the text isn't a link to a setup
+    pub fn decode(text: &str) -> Option<Result<Link, String>> {
+        let (_, encoded) = text.trim().split_once(LINK_PREFIX)?;
+        Some(
+            URL_SAFE_NO_PAD
+                .decode(encoded)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| String::from_utf8(bytes).map_err(|e| e.to_string()))
+                .and_then(|text| ron::from_str(&text).map_err(|e| e.to_string())),
+        )
+    }
+}
@@ -0,0 +1,52 @@
+//! A–B looping, replaying one interval of the simulation over and over, e.g. the pulse hitting the first particle.
+//! The state of the simulation is copied when the start of the loop is marked, and put back each time the
+//! simulation passes the end, so the interval is watched again without rerunning everything before it.
+
+use crate::app::simulation::Simulation;
+
+#[derive(Default)]
+pub struct LoopWindow {
+    start: Option<Simulation>, // checkpoint at the start of the loop
+    end: Option<f32>,          // time the simulation jumps back at
+}
+
+impl LoopWindow {
+    pub fn start(&self) -> Option<f32> {
+        self.start.as_ref().map(|start| start.time())
+    }
+
+    pub fn end(&self) -> Option<f32> {
+        self.end
+    }
+
+    // marks the start of the loop at the simulation's current state. an end that is no longer after it is cleared
+    pub fn set_start(&mut self, simulation: &Simulation) {
+        if self.end.is_some_and(|end| end <= simulation.time()) {
+            self.end = None;
+        }
+        self.start = Some(simulation.clone());
+    }
+
+    // marks the end of the loop, which must be after its start
+    pub fn set_end(&mut self, time: f32) {
+        if self.start().is_some_and(|start| start < time) {
+            self.end = Some(time);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = LoopWindow::default();
+    }
+
+    // the state to jump back to, once the simulation has reached the end of the loop.
+    // changes made to the setup while looping are kept, so their effect on the interval can be watched
+    pub fn rewind(&self, simulation: &Simulation) -> Option<Simulation> {
+        let end = self.end?;
+        let start = self.start.as_ref()?;
+        (simulation.time() >= end).then(|| {
+            let mut rewound = start.clone();
+            rewound.apply_changes(&simulation.scene());
+            rewound
+        })
+    }
+}
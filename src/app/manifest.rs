@@ -0,0 +1,137 @@
+//! Manifests of runs, for citing results: everything a run is set up from, the world, its discretisation, the time
+//! step and the scene with its solver, along with the seed of the thermal motion and the version and commit of the app
+//! that ran it. Loading a manifest sets the same run up again from the start, noting any way the app differs from the
+//! one that exported it, as a different build may not reproduce the run exactly.
+
+use std::path::Path;
+
+use egui::Rangef;
+use serde::{Deserialize, Serialize};
+
+use crate::app::simulation::Simulation;
+use crate::app::simulation::config::SimulationConfig;
+use crate::app::simulation::scene::Scene;
+use crate::app::simulation::thermal::THERMAL_SEED;
+
+pub const MANIFEST_EXTENSION: &str = "manifest";
+// commit the app was built from, with "-modified" after it if the sources had been changed
+const BUILD: &str = env!("REFRACTION_BUILD");
+const PRECISION: &str = if cfg!(feature = "f64") { "f64" } else { "f32" };
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: String,   // of the app
+    pub build: String,     // commit the app was built from
+    pub precision: String, // of time and the particles' motion
+    pub thermal_seed: u64, // seed the thermal kicks are drawn from
+    pub world_size: Rangef,
+    pub divisions: usize, // number of evenly spaced divisions, before refinement near the particles
+    pub time_step: f32,
+    pub memory_cap: usize, // MiB, beyond which the particles' older motion is thinned out
+    pub time: f32,         // time the run had reached when the manifest was exported
+    pub steps: usize,      // number of updates made by then
+    pub scene: Scene,
+}
+
+impl Manifest {
+    pub fn new(simulation: &Simulation, memory_cap: usize) -> Self {
+        Manifest {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            build: BUILD.to_owned(),
+            precision: PRECISION.to_owned(),
+            thermal_seed: THERMAL_SEED,
+            world_size: *simulation.size(),
+            divisions: simulation.divisions(),
+            time_step: simulation.time_step(),
+            memory_cap,
+            time: simulation.time(),
+            steps: simulation.steps(),
+            scene: simulation.scene(),
+        }
+    }
+
+    // reads a manifest, rejecting it if the run it describes couldn't be set up
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let manifest: Manifest = ron::from_str(&text).map_err(|e| e.to_string())?;
+        manifest.config().validate()?;
+        Ok(manifest)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    pub fn config(&self) -> SimulationConfig {
+        SimulationConfig {
+            world_size: self.world_size,
+            divisions: self.divisions,
+            time_step: self.time_step,
+            scene: self.scene.clone(),
+        }
+    }
+
+    // ways this app differs from the one that exported the manifest, each of which may change the run
+    pub fn differences(&self) -> Vec<String> {
+        let compared = [
+            ("version", self.version.as_str(), env!("CARGO_PKG_VERSION")),
+            ("build", &self.build, BUILD),
+            ("precision", &self.precision, PRECISION),
+        ];
+        let mut differences: Vec<String> = compared
+            .into_iter()
+            .filter(|(_, exported, current)| exported != current)
+            .map(|(name, exported, current)| format!("{name} {exported}, now {current}"))
+            .collect();
+        if self.thermal_seed != THERMAL_SEED {
+            differences.push(format!(
+                "thermal seed {}, now {THERMAL_SEED}",
+                self.thermal_seed
+            ));
+        }
+        if self.build.ends_with("-modified") {
+            differences.push("the sources had been changed from the commit exported".to_owned());
+        }
+        differences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Manifest;
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::preset::Preset;
+
+    // a run set up from its manifest is the same run, and the manifest notes a different build
+    #[test]
+    fn manifest_reproduces_run() {
+        let config = SimulationConfig::default()
+            .divisions(500)
+            .time_step(0.02)
+            .scene(Preset::DiluteGas.scene());
+        let mut simulation = Simulation::from_config(&config).unwrap();
+        for _ in 0..50 {
+            simulation.update();
+        }
+        let manifest = Manifest::new(&simulation, 256);
+        let read: Manifest = ron::from_str(&ron::to_string(&manifest).unwrap()).unwrap();
+        assert_eq!(read, manifest);
+        assert_eq!(read.config(), config);
+
+        let mut reproduced = Simulation::from_config(&read.config()).unwrap();
+        for _ in 0..read.steps {
+            reproduced.update();
+        }
+        assert_eq!(reproduced.time(), read.time);
+        assert_eq!(reproduced.resultant_field(), simulation.resultant_field());
+
+        let other = Manifest {
+            version: "0.1.0".to_owned(),
+            ..manifest.clone()
+        };
+        assert!(other.differences()[0].starts_with("version 0.1.0"));
+    }
+}
@@ -0,0 +1,73 @@
+//! Small multiples of the induced field of each particle, one strip per particle.
+//! With many particles their fields overlap on the canvas, so here each gets its own strip,
+//! all sharing the canvas' visible x range and a common y scale so they can be compared.
+
+use egui::{Align2, Color32, FontId, Rangef, Sense, Stroke, Ui, pos2, vec2};
+
+use crate::app::i18n::tr;
+use crate::app::particle_field_colour;
+use crate::app::simulation::Simulation;
+
+const STRIP_HEIGHT: f32 = 48.0;
+const AXIS_COLOUR: Color32 = Color32::from_gray(60);
+
+pub fn draw_particle_fields(ui: &mut Ui, simulation: &Simulation, visible_world: Rangef) {
+    let particles = simulation.particles();
+    if particles.is_empty() {
+        ui.label(tr("There are no particles."));
+        return;
+    }
+    let x_points = simulation.x_intervals();
+
+    // largest field of any particle within view sets the scale of every strip
+    let visible = |x: &f32| visible_world.contains(*x);
+    let scale = particles
+        .iter()
+        .flat_map(|p| x_points.iter().zip(p.field()))
+        .filter(|(x, _)| visible(x))
+        .map(|(_, e)| e.abs())
+        .fold(0.0, f32::max)
+        .max(1e-6);
+    ui.label(format!("{} ±{scale:.3}", tr("Scale:")));
+
+    let colour = particle_field_colour(0.9);
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for (i, particle) in particles.iter().enumerate() {
+            let size = vec2(ui.available_width(), STRIP_HEIGHT);
+            let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+            let painter = ui.painter_at(rect);
+            let to_screen_x = |x: f32| {
+                rect.left() + rect.width() * (x - visible_world.min) / visible_world.span()
+            };
+            let to_screen_y = |e: f32| rect.center().y - 0.45 * rect.height() * e / scale;
+
+            painter.rect_filled(rect, 0.0, Color32::from_rgb(10, 10, 10));
+            painter.hline(
+                rect.x_range(),
+                rect.center().y,
+                Stroke::new(1.0, AXIS_COLOUR),
+            );
+            painter.vline(
+                to_screen_x(particle.position().x),
+                rect.y_range(),
+                Stroke::new(1.0, AXIS_COLOUR),
+            );
+
+            let points = x_points
+                .iter()
+                .zip(particle.field())
+                .filter(|(x, _)| visible(x))
+                .map(|(x, e)| pos2(to_screen_x(*x), to_screen_y(*e)))
+                .collect();
+            painter.line(points, Stroke::new(1.5, colour));
+
+            painter.text(
+                rect.left_top() + vec2(4.0, 2.0),
+                Align2::LEFT_TOP,
+                format!("{}", i + 1),
+                FontId::monospace(11.0),
+                Color32::GRAY,
+            );
+        }
+    });
+}
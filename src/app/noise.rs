@@ -0,0 +1,244 @@
+//! Statistics of the resultant field at the probe, for when the particles' thermal motion adds noise to it: its
+//! running mean and variance, and its signal-to-noise ratio. The signal is the part of the field the applied wave
+//! drives, the oscillation at the carrier frequency where the wave has one, found over every sample so far as a
+//! lock-in amplifier would, along with the mean; the noise is the rest of the variance. The statistics can be left to
+//! start after a settling time, once the wave has arrived and the particles' motion has settled.
+
+use egui::{DragValue, Grid, Ui};
+
+use crate::app::i18n::tr;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::variables::WORLD_SIZE;
+
+// time from the start before samples are taken, when the statistics are left to start after settling
+const SETTLING_TIME: f32 = 20.0;
+
+// mean, variance and carrier component of a signal, updated one sample at a time. the sums are kept in f64, as they
+// run over every update
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RunningStatistics {
+    count: usize,
+    mean: f64,
+    squares: f64, // sum of squared differences from the running mean (Welford's method)
+    carrier: Option<f32>, // angular frequency the signal is demodulated at
+    mixed: (f64, f64), // sums of the samples times cos and sin of the carrier's phase
+    phases: (f64, f64), // sums of cos and sin of the carrier's phase, to take the mean out of the mixed sums
+}
+
+impl RunningStatistics {
+    pub fn new(carrier: Option<f32>) -> Self {
+        RunningStatistics {
+            carrier,
+            ..Default::default()
+        }
+    }
+
+    // adds a sample of the signal taken at time t
+    pub fn add(&mut self, value: f32, t: f32) {
+        let value = f64::from(value);
+        self.count += 1;
+        let difference = value - self.mean;
+        self.mean += difference / self.count as f64;
+        self.squares += difference * (value - self.mean);
+        if let Some(omega) = self.carrier {
+            let (sin, cos) = (f64::from(omega) * f64::from(t)).sin_cos();
+            self.mixed.0 += value * cos;
+            self.mixed.1 += value * sin;
+            self.phases.0 += cos;
+            self.phases.1 += sin;
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f32 {
+        self.mean as f32
+    }
+
+    // sample variance, once there are at least two samples
+    pub fn variance(&self) -> Option<f32> {
+        (self.count >= 2).then(|| (self.squares / (self.count - 1) as f64) as f32)
+    }
+
+    // mean power of the oscillation at the carrier, half its amplitude squared. the mean is taken out first, as
+    // over a part of a period it doesn't mix down to nothing
+    fn carrier_power(&self) -> f64 {
+        let n = self.count.max(1) as f64;
+        let re = 2.0 * (self.mixed.0 - self.mean * self.phases.0) / n;
+        let im = 2.0 * (self.mixed.1 - self.mean * self.phases.1) / n;
+        (re * re + im * im) / 2.0
+    }
+
+    // ratio of the power of the signal, the mean and carrier oscillation, to that of the rest, in decibels.
+    // None until there are two samples, or if there is no noise left once the signal is taken away
+    pub fn signal_to_noise(&self) -> Option<f32> {
+        self.variance()?;
+        // the mean power about the mean, rather than the unbiased variance, as the carrier power is a mean power too
+        let variance = self.squares / self.count as f64;
+        let noise = variance - self.carrier_power();
+        let signal = self.mean * self.mean + self.carrier_power();
+        (noise > f64::EPSILON * signal.max(variance))
+            .then(|| (10.0 * (signal / noise).log10()) as f32)
+    }
+}
+
+pub struct NoiseTracker {
+    statistics: RunningStatistics,
+    settle: bool, // only take samples from the settling time on
+    settling_time: f32,
+    probe_x: f32, // position the statistics are of, restarted when the probe moves
+    steps: usize, // number of updates of the simulation at the latest sample, to tell when it is reset
+}
+
+impl Default for NoiseTracker {
+    fn default() -> Self {
+        NoiseTracker {
+            statistics: RunningStatistics::default(),
+            settle: false,
+            settling_time: SETTLING_TIME,
+            probe_x: f32::NAN,
+            steps: 0,
+        }
+    }
+}
+
+impl NoiseTracker {
+    pub fn record(&mut self, simulation: &Simulation, probe_x: f32) {
+        let carrier = simulation.carrier();
+        if probe_x != self.probe_x
+            || carrier != self.statistics.carrier
+            || simulation.steps() <= self.steps
+        {
+            self.statistics = RunningStatistics::new(carrier);
+            self.probe_x = probe_x;
+        }
+        self.steps = simulation.steps();
+        // sample time of the fields just calculated
+        let t = simulation.time() - simulation.time_step();
+        if self.settle && t < self.settling_time {
+            return;
+        }
+        let resultant = simulation
+            .resultant_at(probe_x, t)
+            .unwrap_or_else(|| simulation.resultant_field_at(probe_x));
+        self.statistics.add(resultant, t);
+    }
+
+    pub fn clear(&mut self) {
+        self.statistics = RunningStatistics::default();
+        self.probe_x = f32::NAN;
+        self.steps = 0;
+    }
+
+    pub fn draw(&mut self, ui: &mut Ui, simulation: &Simulation, probe_x: &mut f32) {
+        if simulation.temperature() == 0.0 {
+            ui.label(tr(
+                "There is no thermal motion. Set a temperature under the particles' properties to add noise.",
+            ));
+        } else if simulation.damping() == 0.0 {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                tr("Thermal motion needs damping, which couples the particles to the heat bath"),
+            );
+        }
+        ui.horizontal(|ui| {
+            ui.label(tr("Probe position"));
+            ui.add(egui::Slider::new(probe_x, WORLD_SIZE.min..=WORLD_SIZE.max));
+        });
+        let settling = ui.horizontal(|ui| {
+            let settle = ui.checkbox(&mut self.settle, tr("Only after"));
+            let time = ui.add_enabled(
+                self.settle,
+                DragValue::new(&mut self.settling_time)
+                    .range(0.0..=f32::MAX)
+                    .suffix(" s"),
+            );
+            settle.changed() || time.changed()
+        });
+        settling.response.on_hover_text(tr(
+            "Leave out the samples from before the settling time, while the wave arrives and the particles' \
+            motion settles",
+        ));
+        if settling.inner || ui.button(tr("Restart statistics")).clicked() {
+            self.clear();
+        }
+
+        let statistics = &self.statistics;
+        if statistics.count() == 0 {
+            if self.settle {
+                ui.label(tr("Waiting for the settling time to pass"));
+            }
+            return;
+        }
+        ui.separator();
+        Grid::new("NoiseStatistics").striped(true).show(ui, |ui| {
+            ui.label(tr("Samples"));
+            ui.monospace(statistics.count().to_string());
+            ui.end_row();
+            ui.label(tr("Mean"));
+            ui.monospace(format!("{:.5}", statistics.mean()));
+            ui.end_row();
+            if let Some(variance) = statistics.variance() {
+                ui.label(tr("Variance"));
+                ui.monospace(format!("{variance:.3e}"));
+                ui.end_row();
+                ui.label("σ").on_hover_text(tr("Standard deviation"));
+                ui.monospace(format!("{:.5}", variance.sqrt()));
+                ui.end_row();
+                ui.label(tr("Signal to noise")).on_hover_text(match statistics.carrier {
+                    Some(_) => tr(
+                        "Power of the mean and the oscillation at the wave's frequency, over that of the rest of \
+                        the field",
+                    ),
+                    None => tr("Square of the mean, over the variance"),
+                });
+                ui.monospace(match statistics.signal_to_noise() {
+                    Some(ratio) => format!("{ratio:.1} dB"),
+                    None => tr("no noise").to_owned(),
+                });
+                ui.end_row();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use super::RunningStatistics;
+
+    // an oscillation at the carrier is taken as signal, and anything else as noise
+    #[test]
+    fn signal_and_noise_are_told_apart() {
+        let (omega, time_step) = (1.0, 0.01);
+        let periods = 50.0;
+        let samples = (periods * TAU / omega / time_step) as usize;
+        let mut clean = RunningStatistics::new(Some(omega));
+        let mut noisy = RunningStatistics::new(Some(omega));
+        let mut rng = fastrand::Rng::with_seed(3);
+        for i in 0..samples {
+            let t = i as f32 * time_step;
+            let signal = 0.5 + (omega * t).cos();
+            clean.add(signal, t);
+            noisy.add(signal + 0.1 * (rng.f32() - 0.5), t);
+        }
+        assert!((clean.mean() - 0.5).abs() < 1e-3);
+        assert!((clean.variance().unwrap() - 0.5).abs() < 1e-2);
+        // a variance of 0.1² / 12 against a signal power of 0.5² + ½
+        let expected = 10.0 * (0.75f32 / (0.01 / 12.0)).log10();
+        let ratio = noisy.signal_to_noise().unwrap();
+        assert!(
+            (ratio - expected).abs() < 1.0,
+            "{ratio} dB, expected {expected} dB"
+        );
+        // what little is left of a clean signal comes of the samples ending part way through a period
+        let clean = clean.signal_to_noise();
+        assert!(
+            clean.is_none_or(|ratio| ratio > expected + 10.0),
+            "{clean:?}"
+        );
+    }
+}
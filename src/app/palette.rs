@@ -0,0 +1,333 @@
+//! Colour schemes for the traces, particles and grid, for anyone who can't tell the standard colours apart.
+//! Like the language of tr(), the palette in use is shared by every part of the UI that draws them.
+//! Traces can also be told apart without colour at all, by giving each its own line style, and the width and dashes
+//! of each can be chosen, e.g. for figures to match a journal's style.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::app::demo::Trace;
+use crate::app::simulation::particle::ChargedParticleType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumIter, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum Palette {
+    #[default]
+    Standard,
+    HighContrast, // bright, saturated traces and clearer gridlines, e.g. for a washed out projector
+    ColourBlind,  // the Okabe–Ito colours, which stay distinct with deuteranopia and protanopia
+}
+
+// RGB colours of everything drawn on the canvas
+pub struct Colours {
+    pub applied: Option<(u8, u8, u8)>, // None to draw each waveform in its own colour
+    pub resultant: (u8, u8, u8),
+    pub particle_field: (u8, u8, u8),
+    pub scattered: (u8, u8, u8),
+    pub intensity: (u8, u8, u8),
+    pub envelope: (u8, u8, u8),
+    pub snapshot: (u8, u8, u8),
+    pub reference: (u8, u8, u8), // data loaded from a file to compare against
+    pub electron: (u8, u8, u8),
+    pub proton: (u8, u8, u8),
+    pub grid: Color32,  // fine background lines
+    pub ticks: Color32, // lines at the labelled values of a nonlinear scale
+    pub axes: Color32,
+}
+
+const STANDARD: Colours = Colours {
+    applied: None,
+    resultant: (180, 20, 180),
+    particle_field: (20, 100, 255),
+    scattered: (40, 210, 200),
+    intensity: (240, 210, 40),
+    envelope: (235, 235, 235),
+    snapshot: (120, 230, 120),
+    reference: (255, 140, 60),
+    electron: (0, 0, 255),
+    proton: (255, 0, 0),
+    grid: Color32::from_rgb(15, 15, 15),
+    ticks: Color32::from_rgb(30, 30, 30),
+    axes: Color32::from_rgb(20, 20, 20),
+};
+
+const HIGH_CONTRAST: Colours = Colours {
+    applied: Some((255, 255, 0)),
+    resultant: (255, 0, 255),
+    particle_field: (0, 200, 255),
+    scattered: (0, 255, 0),
+    intensity: (255, 140, 0),
+    envelope: (255, 255, 255),
+    snapshot: (170, 255, 170),
+    reference: (255, 170, 170),
+    electron: (90, 170, 255),
+    proton: (255, 80, 80),
+    grid: Color32::from_gray(55),
+    ticks: Color32::from_gray(75),
+    axes: Color32::from_gray(120),
+};
+
+const COLOUR_BLIND: Colours = Colours {
+    applied: Some((230, 159, 0)),
+    resultant: (204, 121, 167),
+    particle_field: (0, 114, 178),
+    scattered: (0, 158, 115),
+    intensity: (240, 228, 66),
+    envelope: (235, 235, 235),
+    snapshot: (86, 180, 233),
+    reference: (213, 94, 0),
+    electron: (86, 180, 233),
+    proton: (213, 94, 0),
+    grid: Color32::from_rgb(15, 15, 15),
+    ticks: Color32::from_rgb(30, 30, 30),
+    axes: Color32::from_gray(40),
+};
+
+impl Palette {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Standard => "Standard colours",
+            Palette::HighContrast => "High contrast",
+            Palette::ColourBlind => "Colour-blind safe",
+        }
+    }
+
+    pub fn colours(&self) -> &'static Colours {
+        match self {
+            Palette::Standard => &STANDARD,
+            Palette::HighContrast => &HIGH_CONTRAST,
+            Palette::ColourBlind => &COLOUR_BLIND,
+        }
+    }
+}
+
+impl Colours {
+    pub fn particle(&self, particle_type: ChargedParticleType) -> (u8, u8, u8) {
+        match particle_type {
+            ChargedParticleType::Electron => self.electron,
+            ChargedParticleType::Proton => self.proton,
+        }
+    }
+}
+
+// palette used to draw the canvas, panels and exported images
+static PALETTE: AtomicU8 = AtomicU8::new(Palette::Standard as u8);
+
+pub fn palette() -> Palette {
+    let current = PALETTE.load(Ordering::Relaxed);
+    Palette::iter()
+        .find(|palette| *palette as u8 == current)
+        .unwrap_or_default()
+}
+
+pub fn set_palette(palette: Palette) {
+    PALETTE.store(palette as u8, Ordering::Relaxed);
+}
+
+// colours of the palette in use
+pub fn colours() -> &'static Colours {
+    palette().colours()
+}
+
+// lengths of the dashes of a line and the gaps after each, in points, repeated along it
+#[derive(Debug, PartialEq)]
+pub struct LinePattern {
+    pub dashes: &'static [f32],
+    pub gaps: &'static [f32],
+}
+
+// range of the width of a trace's line, relative to the usual width
+pub const TRACE_WIDTH: std::ops::RangeInclusive<f32> = 0.25..=4.0;
+
+const DASHED: LinePattern = LinePattern {
+    dashes: &[8.0],
+    gaps: &[5.0],
+};
+const DOTTED: LinePattern = LinePattern {
+    dashes: &[1.5],
+    gaps: &[3.5],
+};
+
+// curves drawn over the simulation's own traces, which are solid unless styled otherwise. each is drawn thinner than
+// the traces and in a pattern of its own, so it can be told from them and from the other overlays without colour
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum Overlay {
+    Theory, // analytic curves, such as the steady state and the exponential fitted to the intensity
+    Envelope, // the magnitude of the resultant field's analytic signal
+    Snapshot, // the resultant field frozen earlier
+    Reference, // data loaded from a file
+    Ensemble, // the mean resultant field of the ensemble's runs
+}
+
+impl Overlay {
+    pub fn pattern(&self) -> LinePattern {
+        match self {
+            Overlay::Theory => DASHED,
+            Overlay::Envelope => DOTTED,
+            Overlay::Snapshot => LinePattern {
+                dashes: &[8.0, 1.5],
+                gaps: &[3.5, 3.5],
+            },
+            Overlay::Reference => LinePattern {
+                dashes: &[8.0, 1.5, 1.5],
+                gaps: &[3.5, 3.5, 3.5],
+            },
+            Overlay::Ensemble => LinePattern {
+                dashes: &[14.0],
+                gaps: &[6.0],
+            },
+        }
+    }
+}
+
+// dashes of a trace's line, as chosen for it
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumIter, Serialize, Deserialize)]
+pub enum LineDash {
+    #[default]
+    Automatic, // solid, or the trace's own pattern when traces are told apart by line style
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl LineDash {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineDash::Automatic => "Automatic",
+            LineDash::Solid => "Solid",
+            LineDash::Dashed => "Dashed",
+            LineDash::Dotted => "Dotted",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TraceStyle {
+    pub width: f32, // relative to the usual width of a line
+    pub dash: LineDash,
+}
+
+impl Default for TraceStyle {
+    fn default() -> Self {
+        TraceStyle {
+            width: 1.0,
+            dash: LineDash::default(),
+        }
+    }
+}
+
+// how each of the fields is drawn
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraceStyles {
+    pub applied: TraceStyle,
+    pub resultant: TraceStyle,
+    pub induced: TraceStyle,
+    pub intensity: TraceStyle,
+    pub scattered: TraceStyle,
+}
+
+impl TraceStyles {
+    pub fn get(&self, trace: Trace) -> TraceStyle {
+        match trace {
+            Trace::Applied => self.applied,
+            Trace::Resultant => self.resultant,
+            Trace::Induced => self.induced,
+            Trace::Intensity => self.intensity,
+            Trace::Scattered => self.scattered,
+        }
+    }
+
+    pub fn get_mut(&mut self, trace: Trace) -> &mut TraceStyle {
+        match trace {
+            Trace::Applied => &mut self.applied,
+            Trace::Resultant => &mut self.resultant,
+            Trace::Induced => &mut self.induced,
+            Trace::Intensity => &mut self.intensity,
+            Trace::Scattered => &mut self.scattered,
+        }
+    }
+
+    // pattern of a trace's dashes, if it isn't solid, given whether traces are told apart by line style
+    pub fn pattern(&self, trace: Trace, line_styles: bool) -> Option<LinePattern> {
+        match self.get(trace).dash {
+            LineDash::Automatic => line_styles.then(|| line_pattern(trace)).flatten(),
+            LineDash::Solid => None,
+            LineDash::Dashed => Some(DASHED),
+            LineDash::Dotted => Some(DOTTED),
+        }
+    }
+}
+
+// line style of a trace when they are told apart by style, with the resultant field left solid
+pub fn line_pattern(trace: Trace) -> Option<LinePattern> {
+    let (dashes, gaps): (&[f32], &[f32]) = match trace {
+        Trace::Resultant => return None,
+        Trace::Applied => (&[14.0], &[6.0]),
+        Trace::Induced => (&[2.0], &[4.0]),
+        Trace::Scattered => (&[10.0, 2.0], &[4.0, 4.0]),
+        Trace::Intensity => (&[5.0], &[5.0]),
+    };
+    Some(LinePattern { dashes, gaps })
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::{DASHED, LineDash, Overlay, Palette, TraceStyles, line_pattern};
+    use crate::app::demo::Trace;
+
+    // the traces drawn over each other can be told apart in every palette
+    #[test]
+    fn trace_colours_are_distinct() {
+        for palette in Palette::iter() {
+            let colours = palette.colours();
+            // the standard palette draws the initial field in its waveform's colour
+            let traces: Vec<_> = colours
+                .applied
+                .into_iter()
+                .chain([
+                    colours.particle_field,
+                    colours.scattered,
+                    colours.intensity,
+                    colours.reference,
+                ])
+                .collect();
+            for (i, a) in traces.iter().enumerate() {
+                assert_ne!(*a, colours.resultant, "{palette:?}");
+                assert!(!traces[i + 1..].contains(a), "{palette:?}");
+            }
+        }
+    }
+
+    // each kind of overlay has a pattern of its own
+    #[test]
+    fn overlay_patterns_are_distinct() {
+        let patterns: Vec<_> = Overlay::iter().map(|overlay| overlay.pattern()).collect();
+        for (i, pattern) in patterns.iter().enumerate() {
+            assert!(!patterns[i + 1..].contains(pattern), "{pattern:?}");
+        }
+    }
+
+    // a trace's own dash overrides the line styles, which only apply to traces left automatic
+    #[test]
+    fn trace_dash_overrides_line_styles() {
+        let mut styles = TraceStyles::default();
+        styles.get_mut(Trace::Resultant).dash = LineDash::Dashed;
+        styles.get_mut(Trace::Induced).dash = LineDash::Solid;
+        for line_styles in [false, true] {
+            assert_eq!(styles.pattern(Trace::Resultant, line_styles), Some(DASHED));
+            assert_eq!(styles.pattern(Trace::Induced, line_styles), None);
+        }
+        assert_eq!(styles.pattern(Trace::Applied, false), None);
+        assert_eq!(
+            styles.pattern(Trace::Applied, true),
+            line_pattern(Trace::Applied)
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Analysis panels, which float over the canvas or can be detached into a window of their own.
+//! Detached panels can be moved to a second screen, leaving the canvas uncovered.
+
+use egui::{Context, Id, Ui, ViewportBuilder, ViewportClass, ViewportId, vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n::tr;
+
+const DEFAULT_PANEL_SIZE: [f32; 2] = [480.0, 300.0];
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct PanelState {
+    pub open: bool,
+    pub detached: bool, // shown in a separate window instead of the main window
+}
+
+// draws a panel if it is open. The title is in English, and is used to identify the panel
+pub fn show(
+    ctx: &Context,
+    state: &mut PanelState,
+    title: &'static str,
+    mut add_contents: impl FnMut(&mut Ui),
+) {
+    if !state.open {
+        return;
+    }
+
+    if state.detached {
+        let builder = ViewportBuilder::default()
+            .with_title(tr(title))
+            .with_inner_size(DEFAULT_PANEL_SIZE);
+        ctx.show_viewport_immediate(ViewportId::from_hash_of(title), builder, |ctx, class| {
+            if class == ViewportClass::Embedded {
+                // this platform can't open more windows, so show the panel in the main window instead
+                state.detached = false;
+                return;
+            }
+            egui::CentralPanel::default().show(ctx, |ui| {
+                if ui
+                    .button(tr("Attach"))
+                    .on_hover_text(tr("Move this panel back into the main window"))
+                    .clicked()
+                {
+                    state.detached = false;
+                }
+                ui.separator();
+                add_contents(ui);
+            });
+            if ctx.input(|i| i.viewport().close_requested()) {
+                state.open = false;
+            }
+        });
+    } else {
+        let mut open = true;
+        egui::Window::new(tr(title))
+            .id(Id::new(title))
+            .open(&mut open)
+            .default_size(vec2(DEFAULT_PANEL_SIZE[0], DEFAULT_PANEL_SIZE[1]))
+            .show(ctx, |ui| {
+                if ui
+                    .button(tr("Detach"))
+                    .on_hover_text(tr("Move this panel into a separate window"))
+                    .clicked()
+                {
+                    state.detached = true;
+                }
+                ui.separator();
+                add_contents(ui);
+            });
+        state.open = open;
+    }
+}
@@ -0,0 +1,171 @@
+//! Records how quickly frames are drawn and the simulation is updated, and plots their recent history
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use egui::{Color32, Pos2, Sense, Stroke, Ui, pos2, vec2};
+
+use crate::app::i18n::tr;
+
+// length of history kept, in seconds
+const HISTORY_SECONDS: f32 = 5.0;
+// updates per second is averaged over this window, as a single frame may have zero or several updates
+const UPS_WINDOW_SECONDS: f32 = 0.25;
+
+// frames ignored while the window settles after launch, then the number of frames timed to measure the refresh rate
+const REFRESH_WARMUP_FRAMES: usize = 30;
+const REFRESH_SAMPLE_FRAMES: usize = 60;
+// refresh rate assumed until it has been measured
+const DEFAULT_REFRESH_RATE: f32 = 60.0;
+
+pub const FPS_COLOUR: Color32 = Color32::from_rgb(80, 200, 80);
+pub const UPS_COLOUR: Color32 = Color32::from_rgb(230, 150, 30);
+
+struct FrameSample {
+    time: f32,    // seconds since recording started
+    fps: f32,     // instantaneous frame rate, from the time taken by this frame
+    updates: u32, // number of simulation updates performed during this frame
+}
+
+pub struct FrameStats {
+    start: Instant,
+    last_frame: Instant,
+    samples: VecDeque<FrameSample>,
+    frame_count: usize,
+    refresh_intervals: Vec<f32>, // frame times collected to measure the refresh rate
+    refresh_rate: Option<f32>,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        FrameStats {
+            start: now,
+            last_frame: now,
+            samples: VecDeque::new(),
+            frame_count: 0,
+            refresh_intervals: Vec::with_capacity(REFRESH_SAMPLE_FRAMES),
+            refresh_rate: None,
+        }
+    }
+
+    // record the end of a frame, during which the simulation was updated the given number of times
+    pub fn end_frame(&mut self, updates: u32) {
+        let now = Instant::now();
+        let frame_time = now.duration_since(self.last_frame).as_secs_f32();
+        self.last_frame = now;
+        let time = now.duration_since(self.start).as_secs_f32();
+        self.frame_count += 1;
+        if self.refresh_rate.is_none() && self.frame_count > REFRESH_WARMUP_FRAMES {
+            self.measure_refresh_rate(frame_time);
+        }
+        self.samples.push_back(FrameSample {
+            time,
+            fps: 1.0 / frame_time.max(1e-6),
+            updates,
+        });
+        while self
+            .samples
+            .front()
+            .is_some_and(|s| s.time < time - HISTORY_SECONDS)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    // frames are redrawn continuously and limited by vsync, so the typical frame time is the refresh interval
+    fn measure_refresh_rate(&mut self, frame_time: f32) {
+        self.refresh_intervals.push(frame_time);
+        if self.refresh_intervals.len() == REFRESH_SAMPLE_FRAMES {
+            // median is robust against the occasional slow frame
+            self.refresh_intervals.sort_by(f32::total_cmp);
+            let median = self.refresh_intervals[REFRESH_SAMPLE_FRAMES / 2];
+            let rate = (1.0 / median.max(1e-3)).round();
+            log::info!("Measured display refresh rate as {rate} Hz");
+            self.refresh_rate = Some(rate);
+        }
+    }
+
+    // refresh rate of the display in Hz
+    pub fn refresh_rate(&self) -> f32 {
+        self.refresh_rate.unwrap_or(DEFAULT_REFRESH_RATE)
+    }
+
+    // refresh rate of the display in Hz, once it has been measured
+    pub fn measured_refresh_rate(&self) -> Option<f32> {
+        self.refresh_rate
+    }
+
+    // simulation updates per second over the window ending at the given sample
+    fn ups_at(&self, index: usize) -> f32 {
+        let end = self.samples[index].time;
+        let updates: u32 = self
+            .samples
+            .range(..=index)
+            .rev()
+            .take_while(|s| s.time > end - UPS_WINDOW_SECONDS)
+            .map(|s| s.updates)
+            .sum();
+        updates as f32 / UPS_WINDOW_SECONDS
+    }
+
+    // average frame rate and update rate over the last second
+    pub fn averages(&self) -> (f32, f32) {
+        let Some(latest) = self.samples.back() else {
+            return (0.0, 0.0);
+        };
+        let recent = self
+            .samples
+            .iter()
+            .filter(|s| s.time > latest.time - 1.0)
+            .collect::<Vec<_>>();
+        let span = (latest.time - recent[0].time).max(1e-6);
+        let frames = (recent.len() - 1) as f32;
+        let updates: u32 = recent.iter().skip(1).map(|s| s.updates).sum();
+        (frames / span, updates as f32 / span)
+    }
+
+    // draws a small plot of frame rate and update rate over the recorded history
+    pub fn plot(&self, ui: &mut Ui) {
+        let size = vec2(160.0, ui.spacing().interact_size.y);
+        let (response, painter) = ui.allocate_painter(size, Sense::hover());
+        let rect = response.rect;
+        painter.rect_filled(rect, 2.0, Color32::from_rgb(10, 10, 10));
+
+        if let Some(latest) = self.samples.back() {
+            let max_rate = self.samples.iter().map(|s| s.fps).fold(60.0_f32, f32::max) * 1.1;
+            let to_screen = |time: f32, rate: f32| -> Pos2 {
+                pos2(
+                    rect.right() - rect.width() * (latest.time - time) / HISTORY_SECONDS,
+                    rect.bottom() - rect.height() * (rate / max_rate).min(1.0),
+                )
+            };
+            let fps_points = self
+                .samples
+                .iter()
+                .map(|s| to_screen(s.time, s.fps))
+                .collect();
+            let ups_points = (0..self.samples.len())
+                .map(|i| to_screen(self.samples[i].time, self.ups_at(i)))
+                .collect();
+            painter.line(fps_points, Stroke::new(1.0, FPS_COLOUR));
+            painter.line(ups_points, Stroke::new(1.0, UPS_COLOUR));
+            painter.text(
+                rect.left_top() + vec2(2.0, 0.0),
+                egui::Align2::LEFT_TOP,
+                format!("{max_rate:.0}"),
+                egui::FontId::monospace(9.0),
+                Color32::GRAY,
+            );
+        }
+        painter.rect_stroke(
+            rect,
+            2.0,
+            Stroke::new(1.0, Color32::from_gray(40)),
+            egui::StrokeKind::Inside,
+        );
+        response.on_hover_text(tr(
+            "Frames drawn per second (green) and simulation updates per second (orange) over the last five seconds",
+        ));
+    }
+}
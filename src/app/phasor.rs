@@ -0,0 +1,439 @@
+//! Phasor diagrams of the fields at a probe point and the displacement of each particle.
+//! Each quantity is demodulated at the carrier frequency of the applied wave (as a lock-in amplifier would),
+//! giving its amplitude and phase. Drawn as arrows, the phase lag of the particles behind the driving field,
+//! and the radiated field adding to the applied field to give the resultant, can be seen directly.
+
+use std::f32::consts::TAU;
+
+use egui::{Align2, Color32, FontId, Pos2, Sense, Stroke, Ui, Vec2, pos2, vec2};
+
+use crate::app::i18n::tr;
+use crate::app::plot::{Series, line_plot};
+use crate::app::reference::ReferenceTrace;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::variables::{C, WAVE_FREQUENCY, WORLD_SIZE};
+use crate::app::{
+    applied_field_colour, particle_field_colour, reference_colour, resultant_field_colour,
+};
+
+// number of carrier periods the demodulation averages over
+const AVERAGING_PERIODS: f32 = 2.0;
+const DIAGRAM_SIZE: f32 = 220.0;
+const PARTICLE_COLOUR: Color32 = Color32::from_rgb(120, 200, 255);
+const THEORY_COLOUR: Color32 = Color32::from_gray(160);
+const LORENTZ_COLOUR: Color32 = Color32::from_rgb(230, 150, 60);
+// number of frequencies the dilute theory is plotted at
+const DISPERSION_POINTS: usize = 200;
+const DISPERSION_PLOT_HEIGHT: f32 = 140.0;
+
+// amplitude and phase of an oscillation, as a complex number
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Phasor {
+    pub re: f32,
+    pub im: f32,
+}
+
+impl Phasor {
+    pub fn from_polar(amplitude: f32, phase: f32) -> Self {
+        let (sin, cos) = phase.sin_cos();
+        Phasor {
+            re: amplitude * cos,
+            im: amplitude * sin,
+        }
+    }
+
+    pub fn amplitude(&self) -> f32 {
+        self.re.hypot(self.im)
+    }
+
+    pub fn phase(&self) -> f32 {
+        self.im.atan2(self.re)
+    }
+
+    // phasor rotated by an angle
+    pub fn rotate(&self, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Phasor {
+            re: self.re * cos - self.im * sin,
+            im: self.re * sin + self.im * cos,
+        }
+    }
+
+    // moves towards another phasor by a fraction of the distance between them
+    fn approach(&mut self, target: Phasor, fraction: f32) {
+        self.re += fraction * (target.re - self.re);
+        self.im += fraction * (target.im - self.im);
+    }
+}
+
+// recovers the amplitude and phase of a signal oscillating at a known frequency
+#[derive(Default)]
+pub struct LockIn {
+    phasor: Phasor,
+}
+
+impl LockIn {
+    // adds a sample of the signal taken at time t, time_step after the last
+    pub fn sample(&mut self, value: f32, t: f32, omega: f32, time_step: f32) {
+        // mixing with the carrier shifts the oscillation at omega down to a constant, which the
+        // exponential moving average extracts. the factor of 2 restores the amplitude lost in mixing
+        let mixed = Phasor::from_polar(2.0 * value, -omega * t);
+        let period = TAU / omega;
+        let fraction = (time_step / (AVERAGING_PERIODS * period)).min(1.0);
+        self.phasor.approach(mixed, fraction);
+    }
+
+    // amplitude and phase of the signal relative to cos(omega t)
+    pub fn phasor(&self) -> Phasor {
+        self.phasor
+    }
+}
+
+#[derive(Default)]
+pub struct PhasorTracker {
+    applied: LockIn,
+    radiated: LockIn,
+    resultant: LockIn,
+    displacements: Vec<LockIn>,
+    omega: Option<f32>, // carrier frequency being demodulated
+    t: f32,
+    rotating: bool, // draw phasors rotating with the carrier, rather than frozen
+    dispersion: Vec<Pos2>, // measured n − 1 at each frequency added to the plot, kept as the frequency changes
+}
+
+impl PhasorTracker {
+    pub fn record(&mut self, simulation: &Simulation, probe_x: f32) {
+        let omega = simulation.carrier();
+        if omega != self.omega {
+            // frequency changed, previous measurements no longer apply
+            self.clear();
+            self.omega = omega;
+        }
+        let Some(omega) = omega else {
+            return;
+        };
+        // sample time of the fields just calculated
+        let time_step = simulation.time_step();
+        let t = simulation.time() - time_step;
+        self.t = t;
+
+        let applied = simulation.applied_field_at(probe_x);
+        // exactly at the probe where the particles' histories allow, rather than between divisions of the grid
+        let resultant = simulation
+            .resultant_at(probe_x, t)
+            .unwrap_or_else(|| simulation.resultant_field_at(probe_x));
+        self.applied.sample(applied, t, omega, time_step);
+        self.resultant.sample(resultant, t, omega, time_step);
+        self.radiated
+            .sample(resultant - applied, t, omega, time_step);
+
+        let particles = simulation.particles();
+        self.displacements
+            .resize_with(particles.len(), LockIn::default);
+        for (lock_in, particle) in self.displacements.iter_mut().zip(particles) {
+            lock_in.sample(particle.position().y, t, omega, time_step);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = PhasorTracker {
+            rotating: self.rotating,
+            dispersion: std::mem::take(&mut self.dispersion),
+            ..Default::default()
+        };
+    }
+
+    // angle to draw the phasors at. frozen phasors are drawn relative to the applied field, which points right
+    fn display_rotation(&self) -> f32 {
+        match (self.rotating, self.omega) {
+            (true, Some(omega)) => omega * self.t,
+            _ => -self.applied.phasor().phase(),
+        }
+    }
+
+    // reference is an index against frequency loaded to compare the dispersion plot with
+    pub fn draw(
+        &mut self,
+        ui: &mut Ui,
+        simulation: &Simulation,
+        probe_x: &mut f32,
+        reference: Option<&ReferenceTrace>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Probe position"));
+            ui.add(egui::Slider::new(probe_x, WORLD_SIZE.min..=WORLD_SIZE.max));
+            ui.checkbox(&mut self.rotating, tr("Rotating"))
+                .on_hover_text(tr(
+                    "Show the phasors rotating at the wave's frequency, instead of relative to the applied field",
+                ));
+        });
+
+        if self.omega.is_none() {
+            ui.label(tr(
+                "Phasors need a wave with a single frequency. Choose the plane wave or Gaussian packet.",
+            ));
+            return;
+        }
+
+        let rotation = self.display_rotation();
+        let rotated = |lock_in: &LockIn| lock_in.phasor().rotate(rotation);
+        let applied = rotated(&self.applied);
+        let radiated = rotated(&self.radiated);
+        let resultant = rotated(&self.resultant);
+
+        ui.horizontal(|ui| {
+            // fields at the probe: the radiated field is drawn from the tip of the applied field,
+            // so the two visibly add up to the resultant
+            ui.vertical(|ui| {
+                ui.strong(tr("Fields at probe"));
+                let scale = [applied, resultant, add(applied, radiated)]
+                    .iter()
+                    .map(Phasor::amplitude)
+                    .fold(0.0, f32::max);
+                let applied_colour = applied_field_colour(0.9, &simulation.waveform());
+                let (origin, radius) = draw_axes(ui);
+                let to_screen = |p: Phasor| origin + radius * vec2(p.re, -p.im) / scale.max(1e-6);
+                draw_arrow(ui, origin, to_screen(applied), applied_colour);
+                draw_arrow(
+                    ui,
+                    to_screen(applied),
+                    to_screen(add(applied, radiated)),
+                    particle_field_colour(0.9),
+                );
+                draw_arrow(
+                    ui,
+                    origin,
+                    to_screen(resultant),
+                    resultant_field_colour(0.9),
+                );
+                describe(ui, tr("Applied"), applied, None, applied_colour);
+                describe(
+                    ui,
+                    tr("Radiated"),
+                    radiated,
+                    Some(applied),
+                    particle_field_colour(0.9),
+                );
+                describe(
+                    ui,
+                    tr("Resultant"),
+                    resultant,
+                    Some(applied),
+                    resultant_field_colour(0.9),
+                );
+            });
+
+            // displacement of each particle, drawn relative to the largest
+            ui.vertical(|ui| {
+                ui.strong(tr("Particle displacements"));
+                let displacements: Vec<Phasor> = self.displacements.iter().map(rotated).collect();
+                let scale = displacements
+                    .iter()
+                    .map(Phasor::amplitude)
+                    .fold(0.0, f32::max);
+                let (origin, radius) = draw_axes(ui);
+                for (i, displacement) in displacements.iter().enumerate() {
+                    // fade particles further along the chain
+                    let fade = 1.0 - 0.6 * i as f32 / displacements.len().max(1) as f32;
+                    let colour = PARTICLE_COLOUR.gamma_multiply(fade);
+                    let tip =
+                        origin + radius * vec2(displacement.re, -displacement.im) / scale.max(1e-6);
+                    draw_arrow(ui, origin, tip, colour);
+                    ui.painter().text(
+                        tip,
+                        Align2::LEFT_BOTTOM,
+                        format!("{}", i + 1),
+                        FontId::proportional(11.0),
+                        colour,
+                    );
+                }
+                if let Some(first) = displacements.first() {
+                    describe(ui, tr("Particle 1"), *first, Some(applied), PARTICLE_COLOUR);
+                }
+            });
+        });
+
+        // how far the wave is delayed by passing the particles, as the refractive index of a medium
+        let lag = phase_difference(self.applied.phasor(), self.resultant.phasor());
+        let measured = measured_index(simulation, *probe_x, lag);
+        match measured {
+            Some(index) => {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!("{}: n = {index:.4}", tr("Measured index")))
+                            .monospace(),
+                    )
+                    .on_hover_text(tr(
+                        "From the phase lag of the resultant behind the applied field at the probe, \
+                        taking each particle as a slab of medium as thick as the spacing between them",
+                    ));
+                    if let (Some(theory), Some(lorentz)) =
+                        (simulation.dilute_index(), simulation.lorentz_index())
+                    {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "({}: {theory:.4}, {}: {lorentz:.4})",
+                                tr("dilute theory"),
+                                tr("Lorentz model")
+                            ))
+                            .monospace(),
+                        );
+                    }
+                });
+            }
+            None => {
+                ui.label(tr(
+                    "Place the probe beyond the particles to measure their refractive index",
+                ));
+            }
+        }
+        if let Some(frequencies) = simulation.dispersion_frequencies() {
+            self.draw_dispersion(ui, simulation, measured, frequencies, reference);
+        }
+    }
+
+    // refractive index against the frequency of the wave, from the dilute theory and as measured at the frequencies
+    // the user has tried, with the frequencies to try to see it rise, peak and dip below 1 across resonance
+    fn draw_dispersion(
+        &mut self,
+        ui: &mut Ui,
+        simulation: &Simulation,
+        measured: Option<f32>,
+        [peak, resonance, dip]: [f32; 3],
+        reference: Option<&ReferenceTrace>,
+    ) {
+        let (Some(omega), Some(_)) = (self.omega, simulation.dilute_index()) else {
+            return;
+        };
+        egui::CollapsingHeader::new(tr("Dispersion"))
+            .default_open(true)
+            .show(ui, |ui| {
+                let curve = |index_at: &dyn Fn(f32) -> Option<f32>| {
+                    (0..=DISPERSION_POINTS)
+                        .filter_map(|i| {
+                            let omega = WAVE_FREQUENCY.min
+                                + (WAVE_FREQUENCY.max - WAVE_FREQUENCY.min) * i as f32
+                                    / DISPERSION_POINTS as f32;
+                            Some(pos2(omega, index_at(omega)? - 1.0))
+                        })
+                        .collect()
+                };
+                let mut series = vec![
+                    Series {
+                        name: tr("n − 1, dilute theory"),
+                        colour: THEORY_COLOUR,
+                        points: curve(&|omega| simulation.dilute_index_at(omega)),
+                    },
+                    Series {
+                        name: tr("n − 1, Lorentz model"),
+                        colour: LORENTZ_COLOUR,
+                        points: curve(&|omega| simulation.lorentz_index_at(omega)),
+                    },
+                    Series {
+                        name: tr("n − 1, measured"),
+                        colour: PARTICLE_COLOUR,
+                        points: self.dispersion.clone(),
+                    },
+                ];
+                let reference_name = reference.map(|r| format!("n − 1, {}", r.name));
+                if let (Some(reference), Some(name)) = (reference, &reference_name) {
+                    series.push(Series {
+                        name,
+                        colour: reference_colour(1.0),
+                        points: reference
+                            .points
+                            .iter()
+                            .map(|p| pos2(p.x, p.y - 1.0))
+                            .collect(),
+                    });
+                }
+                line_plot(ui, DISPERSION_PLOT_HEIGHT, &series, "ω");
+                ui.horizontal(|ui| {
+                    let add = ui
+                        .add_enabled(measured.is_some(), egui::Button::new(tr("Add to plot")))
+                        .on_hover_text(tr("Adds the measured index at this frequency. \
+                            Wait for it to settle after changing the frequency."));
+                    if let (true, Some(index)) = (add.clicked(), measured) {
+                        self.dispersion.retain(|point| point.x != omega);
+                        self.dispersion.push(pos2(omega, index - 1.0));
+                        self.dispersion.sort_by(|a, b| a.x.total_cmp(&b.x));
+                    }
+                    if ui.button(tr("Clear")).clicked() {
+                        self.dispersion.clear();
+                    }
+                });
+                ui.label(format!(
+                    "{}: ω = {peak:.2} ({}), {resonance:.2} ({}), {dip:.2} ({})",
+                    tr("Frequencies to try"),
+                    tr("index peaks"),
+                    tr("resonance"),
+                    tr("index dips below 1"),
+                ));
+            });
+    }
+}
+
+// refractive index of the particles taken as a uniform slab, from the phase the resultant lags the applied field by
+// after passing them all. the wave travels towards -x, so the probe must be to the left of every particle.
+// None if it isn't, or there aren't enough particles to tell their spacing
+fn measured_index(simulation: &Simulation, probe_x: f32, lag: f32) -> Option<f32> {
+    let omega = simulation.carrier()?;
+    let positions: Vec<f32> = simulation
+        .particles()
+        .iter()
+        .map(|p| p.position().x)
+        .collect();
+    let first = positions.iter().copied().fold(f32::INFINITY, f32::min);
+    let last = positions.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    if positions.len() < 2 || probe_x >= first {
+        return None;
+    }
+    let spacing = (last - first) / (positions.len() - 1) as f32;
+    let thickness = spacing * positions.len() as f32;
+    Some(1.0 + lag / (omega / C * thickness))
+}
+
+// phase of one phasor relative to another, between -π and π
+fn phase_difference(phasor: Phasor, reference: Phasor) -> f32 {
+    (phasor.phase() - reference.phase() + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0
+}
+
+fn add(a: Phasor, b: Phasor) -> Phasor {
+    Phasor {
+        re: a.re + b.re,
+        im: a.im + b.im,
+    }
+}
+
+// draws the axes of a phasor diagram, returning its centre and radius on screen
+fn draw_axes(ui: &mut Ui) -> (Pos2, f32) {
+    let (rect, _) = ui.allocate_exact_size(Vec2::splat(DIAGRAM_SIZE), Sense::hover());
+    let painter = ui.painter();
+    let axis = Stroke::new(1.0, Color32::from_gray(70));
+    let radius = 0.45 * DIAGRAM_SIZE;
+    painter.circle_stroke(rect.center(), radius, axis);
+    painter.hline(rect.x_range(), rect.center().y, axis);
+    painter.vline(rect.center().x, rect.y_range(), axis);
+    (rect.center(), radius)
+}
+
+fn draw_arrow(ui: &Ui, from: Pos2, to: Pos2, colour: Color32) {
+    ui.painter()
+        .arrow(from, to - from, Stroke::new(2.0, colour));
+}
+
+// amplitude of a phasor, and its phase relative to a reference
+fn describe(ui: &mut Ui, name: &str, phasor: Phasor, reference: Option<Phasor>, colour: Color32) {
+    let text = match reference {
+        Some(reference) => {
+            let phase = phase_difference(phasor, reference);
+            format!(
+                "{name}: {:.3} ∠ {:+.0}°",
+                phasor.amplitude(),
+                phase.to_degrees()
+            )
+        }
+        None => format!("{name}: {:.3}", phasor.amplitude()),
+    };
+    ui.label(egui::RichText::new(text).color(colour).monospace());
+}
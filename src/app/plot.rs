@@ -0,0 +1,109 @@
+//! Minimal line plots for analysis panels, drawn directly with the painter
+
+use egui::{Align2, Color32, FontId, Pos2, Rect, Sense, Stroke, StrokeKind, Ui, pos2, vec2};
+
+const AXIS_COLOUR: Color32 = Color32::from_gray(90);
+const LABEL_FONT_SIZE: f32 = 11.0;
+// colours given in turn to series that have no colour of their own, such as those named by the user
+pub const SERIES_COLOURS: [Color32; 6] = [
+    Color32::from_rgb(230, 140, 40),
+    Color32::from_rgb(60, 160, 230),
+    Color32::from_rgb(120, 200, 90),
+    Color32::from_rgb(220, 90, 160),
+    Color32::from_rgb(230, 210, 80),
+    Color32::from_gray(220),
+];
+
+// a named line on a plot, with points in plot coordinates
+pub struct Series<'a> {
+    pub name: &'a str,
+    pub colour: Color32,
+    pub points: Vec<Pos2>,
+}
+
+// range covered by all points in all series, padded vertically and always including y = 0
+fn bounds(series: &[Series]) -> Option<Rect> {
+    let mut points = series.iter().flat_map(|s| s.points.iter());
+    let first = points.next()?;
+    let mut bounds = Rect::from_min_max(*first, *first);
+    for point in points {
+        bounds.extend_with(*point);
+    }
+    bounds.extend_with_y(0.0);
+    let y_padding = (0.05 * bounds.height()).max(1e-6);
+    bounds.min.y -= y_padding;
+    bounds.max.y += y_padding;
+    if bounds.width() <= 0.0 {
+        bounds.max.x = bounds.min.x + 1.0;
+    }
+    Some(bounds)
+}
+
+// draws the series, filling the available width, with a legend and the axis ranges labelled
+pub fn line_plot(ui: &mut Ui, height: f32, series: &[Series], x_label: &str) {
+    let size = vec2(ui.available_width(), height);
+    let (response, painter) = ui.allocate_painter(size, Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, Color32::from_rgb(10, 10, 10));
+    painter.rect_stroke(rect, 2.0, Stroke::new(1.0, AXIS_COLOUR), StrokeKind::Inside);
+
+    let Some(bounds) = bounds(series) else {
+        return;
+    };
+    let plot_area = rect.shrink(4.0);
+    let to_screen = |p: &Pos2| -> Pos2 {
+        pos2(
+            plot_area.left() + plot_area.width() * (p.x - bounds.min.x) / bounds.width(),
+            plot_area.bottom() - plot_area.height() * (p.y - bounds.min.y) / bounds.height(),
+        )
+    };
+
+    // zero line
+    painter.hline(
+        plot_area.x_range(),
+        to_screen(&pos2(0.0, 0.0)).y,
+        Stroke::new(1.0, AXIS_COLOUR),
+    );
+    for s in series {
+        let points = s.points.iter().map(to_screen).collect();
+        painter.line(points, Stroke::new(1.5, s.colour));
+    }
+
+    // axis ranges
+    let font = FontId::proportional(LABEL_FONT_SIZE);
+    let text = |pos: Pos2, anchor: Align2, label: String| {
+        painter.text(pos, anchor, label, font.clone(), Color32::GRAY);
+    };
+    text(
+        plot_area.right_top(),
+        Align2::RIGHT_TOP,
+        format!("{:.3}", bounds.max.y),
+    );
+    text(
+        plot_area.right_bottom(),
+        Align2::RIGHT_BOTTOM,
+        format!("{:.3}", bounds.min.y),
+    );
+    text(
+        plot_area.left_bottom(),
+        Align2::LEFT_BOTTOM,
+        format!("{x_label} {:.2} - {:.2}", bounds.min.x, bounds.max.x),
+    );
+
+    // legend
+    let mut legend_pos = plot_area.left_top();
+    for s in series {
+        let galley = painter.layout_no_wrap(s.name.to_owned(), font.clone(), s.colour);
+        let height = galley.size().y;
+        painter.galley(legend_pos, galley, s.colour);
+        legend_pos.y += height;
+    }
+
+    // value under the pointer
+    if let Some(pointer) = response.hover_pos() {
+        let x = bounds.min.x + bounds.width() * (pointer.x - plot_area.left()) / plot_area.width();
+        let y =
+            bounds.min.y + bounds.height() * (plot_area.bottom() - pointer.y) / plot_area.height();
+        response.on_hover_text_at_pointer(format!("({x:.3}, {y:.3})"));
+    }
+}
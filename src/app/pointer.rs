@@ -0,0 +1,82 @@
+//! Laser pointer for presenting. While it is on, pressing on the canvas leaves a bright halo and dragging draws a
+//! freehand stroke, instead of moving the view. Each part of a stroke fades away a second after it is drawn, and
+//! strokes are drawn over the traces, so attention can be drawn to any part of them on a projector or shared screen.
+
+use std::time::{Duration, Instant};
+
+use egui::{Color32, Painter, Pos2, Stroke};
+
+// time a stroke takes to fade away after it is drawn
+const FADE_TIME: Duration = Duration::from_secs(1);
+// shortest distance the pointer must move for another point of the stroke, in points
+const MIN_STEP: f32 = 2.0;
+
+const POINTER_COLOUR: (u8, u8, u8) = (255, 40, 40);
+const STROKE_WIDTH: f32 = 4.0;
+const HALO_RADIUS: f32 = 14.0;
+
+#[derive(Default)]
+pub struct LaserPointer {
+    pub active: bool,
+    strokes: Vec<Vec<(Pos2, Instant)>>, // screen positions, with when each was drawn
+    drawing: bool,                      // whether the last stroke is still being drawn
+}
+
+fn colour(alpha: f32) -> Color32 {
+    let (r, g, b) = POINTER_COLOUR;
+    Color32::from_rgba_unmultiplied(r, g, b, (alpha * 255.0) as u8)
+}
+
+impl LaserPointer {
+    // follows the pointer while it is pressed on the canvas, starting a new stroke each time it is pressed.
+    // the halo stays where the pointer is held still, only fading once it moves on or is let go of
+    pub fn trace(&mut self, position: Pos2, pressed: bool) {
+        if !pressed {
+            self.drawing = false;
+            return;
+        }
+        let now = Instant::now();
+        match self.strokes.last_mut() {
+            Some(stroke) if self.drawing => match stroke.last_mut() {
+                Some((last, drawn)) if last.distance(position) < MIN_STEP => *drawn = now,
+                _ => stroke.push((position, now)),
+            },
+            _ => self.strokes.push(vec![(position, now)]),
+        }
+        self.drawing = true;
+    }
+
+    // whether anything is still to be drawn, so the canvas keeps being redrawn while it fades
+    pub fn is_visible(&self) -> bool {
+        !self.strokes.is_empty()
+    }
+
+    // draws the strokes, each part fading with its age, and a halo at the end of each, dropping what has faded away
+    pub fn draw(&mut self, painter: &Painter) {
+        let now = Instant::now();
+        let fade = |drawn: Instant| {
+            1.0 - now.duration_since(drawn).as_secs_f32() / FADE_TIME.as_secs_f32()
+        };
+        for stroke in &mut self.strokes {
+            stroke.retain(|(_, drawn)| fade(*drawn) > 0.0);
+        }
+        self.strokes.retain(|stroke| !stroke.is_empty());
+
+        for stroke in &self.strokes {
+            for pair in stroke.windows(2) {
+                let [(from, _), (to, drawn)] = pair else {
+                    continue;
+                };
+                painter.line_segment(
+                    [*from, *to],
+                    Stroke::new(STROKE_WIDTH, colour(fade(*drawn))),
+                );
+            }
+            if let Some((position, drawn)) = stroke.last() {
+                let alpha = fade(*drawn);
+                painter.circle_filled(*position, HALO_RADIUS, colour(0.25 * alpha));
+                painter.circle_filled(*position, HALO_RADIUS / 3.0, colour(alpha));
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Presentation mode, for projecting the simulation in a lecture hall.
+//! The window goes fullscreen with only the canvas visible, drawn with larger text and thicker lines,
+//! and a small heads-up display in the corner shows the elapsed time and whether the simulation is playing.
+//! Playback can be driven from a presentation clicker, which sends Page Down and Page Up for the next and
+//! previous slide and B or . to blank the screen. Media keys and gamepads, which egui doesn't report, are read
+//! in controllers.rs when built with the `media-keys` and `gamepad` features.
+
+use egui::{Align2, Color32, FontId, InputState, Key, Painter, Rect, vec2};
+
+use crate::app::i18n::tr;
+
+// factor by which text and line thickness on the canvas are enlarged
+pub const PRESENTATION_SCALE: f32 = 2.0;
+
+const HUD_FONT_SIZE: f32 = 28.0;
+const HUD_MARGIN: f32 = 16.0;
+
+// factor the speed is changed by for each press of + or -
+pub const SPEED_STEP: f32 = 1.5;
+
+// playback controls that can be used from a clicker or the keyboard, without the mouse
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaybackCommand {
+    PlayPause,
+    Step,
+    Restart,
+    Faster,
+    Slower,
+}
+
+// the playback control pressed this frame, if any. keys pressed with ctrl or alt are left for other shortcuts,
+// such as ctrl and + to enlarge the UI
+pub fn playback_command(input: &InputState) -> Option<PlaybackCommand> {
+    if input.modifiers.command || input.modifiers.alt {
+        return None;
+    }
+    let commands = [
+        (Key::B, PlaybackCommand::PlayPause),
+        (Key::Period, PlaybackCommand::PlayPause),
+        (Key::PageDown, PlaybackCommand::Step),
+        (Key::PageUp, PlaybackCommand::Restart),
+        (Key::Plus, PlaybackCommand::Faster),
+        (Key::Equals, PlaybackCommand::Faster),
+        (Key::Minus, PlaybackCommand::Slower),
+    ];
+    commands
+        .iter()
+        .find(|(key, _)| input.key_pressed(*key))
+        .map(|(_, command)| *command)
+}
+
+// elapsed time and play state, in the top left corner of the canvas
+pub fn draw_hud(painter: &Painter, screen_extent: Rect, time: f32, paused: bool) {
+    let text = format!("{} {time:.2} s", if paused { "⏸" } else { "▶" });
+    let galley = painter.layout_no_wrap(text, FontId::monospace(HUD_FONT_SIZE), Color32::WHITE);
+    let rect = Align2::LEFT_TOP.anchor_size(
+        screen_extent.left_top() + vec2(HUD_MARGIN, HUD_MARGIN),
+        galley.size(),
+    );
+    painter.rect_filled(
+        rect.expand(6.0),
+        4.0,
+        Color32::from_rgba_unmultiplied(0, 0, 0, 160),
+    );
+    painter.galley(rect.min, galley, Color32::WHITE);
+
+    // reminder of how to leave presentation mode, kept faint so as not to distract
+    painter.text(
+        screen_extent.right_bottom() - vec2(HUD_MARGIN, HUD_MARGIN),
+        Align2::RIGHT_BOTTOM,
+        tr("F11 or Esc to exit, Space or B to play/pause, Page Down to step, L for the pointer"),
+        FontId::proportional(14.0),
+        Color32::from_gray(90),
+    );
+}
@@ -0,0 +1,254 @@
+//! Panel tracking the peak of a pulse through the particles, to measure its group delay.
+//! The envelope of the resultant field is watched just before and just after the row of particles, and the times
+//! its peak passes each are compared with the time light takes to cross the same distance in vacuum.
+//! The phase fronts inside the pulse are followed too, by tracking the zero crossings of the field near its peak,
+//! showing that they can outrun light where the index is below 1 while the peak itself does not.
+
+use egui::{Grid, Ui};
+
+use crate::app::i18n::tr;
+use crate::app::signal::AnalyticSignal;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::observer::StepObserver;
+use crate::app::simulation::variables::C;
+use crate::app::simulation::waveform::Waveform;
+
+// distance outside the particles at which the pulse is watched, so their near fields aren't mistaken for it
+const MARGIN: f32 = 0.5;
+// fraction of its peak the envelope must have fallen to after it, for the peak to be taken as passed
+const PASSED: f32 = 0.5;
+// distance from the pulse's peak within which a phase front is followed, before moving to one nearer the peak
+const FRONT_RANGE: f32 = 1.0;
+// furthest a phase front may move in one update and still be taken as the same one
+const FRONT_STEP: f32 = 0.1;
+// fraction of the entering peak the pulse must keep for its phase fronts to be followed,
+// so that the ringing it leaves behind in the particles isn't mistaken for it
+const FRONT_ENVELOPE: f32 = 0.1;
+// shortest time phase fronts must be followed for to give their speed
+const MIN_FRONT_TIME: f32 = 1.0;
+
+// largest envelope seen at a point, and when
+#[derive(Default, Clone, Copy)]
+struct Peak {
+    envelope: f32,
+    time: f32,
+    passed: bool,
+}
+
+impl Peak {
+    fn sample(&mut self, envelope: f32, time: f32) {
+        if envelope > self.envelope {
+            *self = Peak {
+                envelope,
+                time,
+                passed: false,
+            };
+        } else if envelope < PASSED * self.envelope {
+            self.passed = true;
+        }
+    }
+}
+
+// zero crossings of the field near the pulse's peak, followed one at a time as they move through the particles
+#[derive(Default, Clone, Copy)]
+struct PhaseFront {
+    position: Option<f32>, // x coordinate of the crossing being followed
+    time: f32,             // when it was last found
+    distance: f32,         // total distance crossings have been followed over
+    duration: f32,         // total time they have been followed for
+}
+
+impl PhaseFront {
+    // follows the crossing from the last update to its nearest crossing now, or starts again from the crossing
+    // nearest the peak if it has moved too far, or drifted too far from the peak through the envelope
+    fn sample(&mut self, crossings: &[f32], peak: f32, time: f32) {
+        let nearest = |x: f32| {
+            crossings
+                .iter()
+                .copied()
+                .min_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs()))
+        };
+        let followed = self
+            .position
+            .and_then(|last| Some((last, nearest(last)?)))
+            .filter(|(last, now)| {
+                (now - last).abs() < FRONT_STEP && (now - peak).abs() < FRONT_RANGE
+            });
+        match followed {
+            Some((last, now)) => {
+                // travelling towards -x
+                self.distance += last - now;
+                self.duration += time - self.time;
+                self.position = Some(now);
+            }
+            None => self.position = nearest(peak),
+        }
+        self.time = time;
+    }
+
+    fn speed(&self) -> Option<f32> {
+        (self.duration >= MIN_FRONT_TIME).then(|| self.distance / self.duration)
+    }
+}
+
+#[derive(Default)]
+pub struct PulseTracker {
+    entry: Peak,
+    exit: Peak,
+    front: PhaseFront,
+    analytic_signal: AnalyticSignal,
+}
+
+// x coordinates at which the pulse enters and leaves the particles, travelling towards -x
+fn watch_points(simulation: &Simulation) -> Option<(f32, f32)> {
+    let positions = simulation.particles().iter().map(|p| p.position().x);
+    let first = positions.clone().fold(f32::INFINITY, f32::min);
+    let last = positions.fold(f32::NEG_INFINITY, f32::max);
+    let size = simulation.size();
+    (simulation.particles().len() >= 2 && first - MARGIN > size.min && last + MARGIN < size.max)
+        .then_some((last + MARGIN, first - MARGIN))
+}
+
+// whether the applied wave is a pulse, whose peak can be followed
+fn is_pulse(simulation: &Simulation) -> bool {
+    !matches!(simulation.waveform(), Waveform::PlaneWave | Waveform::Beat)
+}
+
+impl StepObserver for PulseTracker {
+    fn observe(&mut self, simulation: &Simulation) {
+        let Some((entry, exit)) = watch_points(simulation).filter(|_| is_pulse(simulation)) else {
+            return;
+        };
+        let x = simulation.even_intervals();
+        let field = simulation.even_resultant_field();
+        // a pulse with a carrier oscillates inside its envelope, which the analytic signal recovers
+        let envelope: Vec<f32> = match simulation.carrier() {
+            Some(_) => self
+                .analytic_signal
+                .compute(&field)
+                .iter()
+                .map(|z| z.norm())
+                .collect(),
+            None => field.iter().map(|e| e.abs()).collect(),
+        };
+        let at = |point: f32| {
+            let i = x.partition_point(|x| *x < point).min(x.len() - 1);
+            envelope[i]
+        };
+        let time = simulation.time();
+        self.entry.sample(at(entry), time);
+        self.exit.sample(at(exit), time);
+
+        // phase fronts are followed while the peak is inside the particles, where the pulse has a carrier
+        let (peak, peak_envelope) = x
+            .iter()
+            .zip(&envelope)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap_or((&0.0, &0.0));
+        if simulation.carrier().is_none()
+            || !(exit + FRONT_RANGE..=entry - FRONT_RANGE).contains(peak)
+            || *peak_envelope < FRONT_ENVELOPE * self.entry.envelope
+        {
+            self.front.position = None;
+            return;
+        }
+        let crossings: Vec<f32> = x
+            .windows(2)
+            .zip(field.windows(2))
+            .filter(|(_, e)| (e[0] < 0.0) != (e[1] < 0.0))
+            .map(|(x, e)| x[0] + (x[1] - x[0]) * e[0] / (e[0] - e[1]))
+            .collect();
+        self.front.sample(&crossings, *peak, time);
+    }
+}
+
+impl PulseTracker {
+    pub fn clear(&mut self) {
+        self.entry = Peak::default();
+        self.exit = Peak::default();
+        self.front = PhaseFront::default();
+    }
+
+    pub fn draw(&self, ui: &mut Ui, simulation: &Simulation) {
+        ui.label(tr(
+            "Times the peak of a pulse passing through the particles, \
+            and compares it with light crossing the same distance in vacuum.",
+        ));
+        if !is_pulse(simulation) {
+            ui.label(tr(
+                "Choose a pulse, such as the Gaussian packet, to follow its peak",
+            ));
+            return;
+        }
+        let Some((entry, exit)) = watch_points(simulation) else {
+            ui.label(tr("Add a row of particles for the pulse to pass through"));
+            return;
+        };
+        let phase_speed = self.front.speed();
+        if let Some(speed) = phase_speed {
+            Grid::new("PhaseVelocity").striped(true).show(ui, |ui| {
+                ui.strong(tr("Phase fronts")).on_hover_text(tr(
+                    "Speed of the zero crossings of the field near the pulse's peak, \
+                    followed while the peak was among the particles",
+                ));
+                ui.strong(egui::RichText::new(speed_text(speed)).monospace());
+                ui.end_row();
+                if let Some(index) = simulation.lorentz_index() {
+                    ui.label(tr("Lorentz model, c / n"));
+                    ui.monospace(format!("{:.2} c", 1.0 / index));
+                    ui.end_row();
+                }
+            });
+        }
+        if !(self.entry.passed && self.exit.passed) {
+            ui.label(tr("Waiting for the pulse to pass the particles…"));
+            return;
+        }
+        let delay = self.exit.time - self.entry.time;
+        let vacuum = (entry - exit) / C;
+        // as for the measured refractive index, each particle is taken as a slab of medium as thick as the spacing
+        let count = simulation.particles().len() as f32;
+        let thickness = (entry - exit - 2.0 * MARGIN) * count / (count - 1.0);
+        let group_index = 1.0 + (delay - vacuum) * C / thickness;
+        Grid::new("PulseDelay").striped(true).show(ui, |ui| {
+            ui.label(tr("Group delay"));
+            ui.monospace(format!("{delay:.2} s"));
+            ui.end_row();
+            ui.label(tr("Vacuum transit time"));
+            ui.monospace(format!("{vacuum:.2} s"));
+            ui.end_row();
+            ui.strong(tr("Group index")).on_hover_text(tr(
+                "How many times slower than light the peak crossed the particles, \
+                    from how much longer it took than in vacuum",
+            ));
+            ui.strong(egui::RichText::new(format!("{group_index:.3}")).monospace());
+            ui.end_row();
+            if let Some(theory) = simulation.group_index() {
+                ui.label(tr("Lorentz model"));
+                ui.monospace(format!("{theory:.3}"));
+                ui.end_row();
+            }
+            ui.strong(tr("Peak")).on_hover_text(tr(
+                "Speed of the pulse's peak, c divided by the group index",
+            ));
+            ui.strong(egui::RichText::new(speed_text(C / group_index)).monospace());
+            ui.end_row();
+        });
+        if phase_speed.is_some_and(|speed| speed > C) && group_index > 1.0 {
+            ui.label(tr(
+                "The phase fronts outran light, but the peak, which carries the pulse's energy, did not. \
+                No signal travels faster than light.",
+            ));
+        }
+    }
+}
+
+// speed as a multiple of c, and how it compares with light
+fn speed_text(speed: f32) -> String {
+    let comparison = if speed > C {
+        tr("faster than light")
+    } else {
+        tr("slower than light")
+    };
+    format!("{:.2} c, {comparison}", speed / C)
+}
@@ -0,0 +1,246 @@
+//! Prediction quiz. The scene is run ahead in the background to just after its pulse has passed the particles,
+//! then the simulation pauses just before the pulse reaches them, asking which of a few thumbnails the resultant
+//! field will be. Playing on shows the answer on the canvas, and the panel marks the choice and keeps a score.
+
+use std::hash::{BuildHasher, RandomState};
+
+use egui::{Align2, Color32, FontId, Sense, Stroke, Ui, pos2, vec2};
+
+use crate::app::background::BackgroundRun;
+use crate::app::i18n::tr;
+use crate::app::resultant_field_colour;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::prediction::Prediction;
+
+const THUMBNAIL_HEIGHT: f32 = 64.0;
+const AXIS_COLOUR: Color32 = Color32::from_gray(60);
+const SELECTED_COLOUR: Color32 = Color32::from_rgb(240, 210, 40);
+const RIGHT_COLOUR: Color32 = Color32::from_rgb(60, 220, 90);
+const WRONG_COLOUR: Color32 = Color32::from_rgb(230, 60, 60);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum Stage {
+    #[default]
+    Idle,
+    Unavailable, // the scene has no pulse to ask about, or no particles
+    Waiting,     // for the simulation to reach the question
+    Asking,
+    Revealed,
+}
+
+// what the app is asked to do from the panel
+pub enum QuizAction {
+    Start, // restart the simulation, then call start
+    Play,
+}
+
+#[derive(Default)]
+pub struct Quiz {
+    stage: Stage,
+    worker: Option<BackgroundRun<Result<Option<Prediction>, String>>>,
+    prediction: Option<Prediction>,
+    order: Vec<usize>, // order the choices are shown in, shuffled for each question
+    choice: Option<usize>, // index of the choice picked
+    score: (usize, usize), // right answers, and questions answered
+}
+
+impl Quiz {
+    // prepares a question about the simulation's scene, which should be at its start
+    pub fn start(&mut self, simulation: &Simulation) {
+        let (scene, time_step) = (simulation.scene(), simulation.time_step());
+        self.worker = Some(BackgroundRun::start(move |progress| {
+            Prediction::run(&scene, time_step, progress)
+        }));
+        self.stage = Stage::Idle;
+        self.prediction = None;
+        self.choice = None;
+    }
+
+    // moves on to the question or the answer as an update from one time to another reaches them.
+    // returns whether to pause there
+    pub fn reached(&mut self, before: f32, after: f32) -> bool {
+        let Some(prediction) = &self.prediction else {
+            return false;
+        };
+        match self.stage {
+            Stage::Waiting if Prediction::reached(prediction.ask_at, before, after) => {
+                self.stage = Stage::Asking;
+                true
+            }
+            Stage::Asking if Prediction::reached(prediction.reveal_at, before, after) => {
+                self.stage = Stage::Revealed;
+                self.score.1 += 1;
+                if self.choice == Some(Prediction::correct()) {
+                    self.score.0 += 1;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // takes the prepared question once it is ready, asking it straight away if the pulse is already close
+    fn receive(&mut self, prediction: Option<Prediction>, simulation: &Simulation) {
+        let Some(prediction) = prediction else {
+            self.stage = Stage::Unavailable;
+            return;
+        };
+        let hasher = RandomState::new();
+        self.order = (0..prediction.choices.len()).collect();
+        self.order.sort_by_key(|i| hasher.hash_one(i));
+        self.stage = match simulation.time() >= prediction.ask_at {
+            true => Stage::Asking,
+            false => Stage::Waiting,
+        };
+        self.prediction = Some(prediction);
+    }
+
+    pub fn draw(&mut self, ui: &mut Ui, simulation: &Simulation) -> Option<QuizAction> {
+        match BackgroundRun::poll(&mut self.worker, "Prediction quiz") {
+            Some(Ok(prediction)) => self.receive(prediction, simulation),
+            Some(Err(e)) => log::error!("Prediction quiz couldn't set up the scene: {e}"),
+            None => {}
+        }
+        let mut action = None;
+        ui.label(tr(
+            "Pauses just before the pulse reaches the particles, to guess what the resultant field will be once it \
+            has passed them. Play on to see whether you were right.",
+        ));
+        ui.horizontal(|ui| {
+            let label = match self.stage {
+                Stage::Idle | Stage::Unavailable => tr("Start"),
+                _ => tr("New question"),
+            };
+            if ui
+                .add_enabled(self.worker.is_none(), egui::Button::new(label))
+                .on_hover_text(tr("Restarts the simulation"))
+                .clicked()
+            {
+                action = Some(QuizAction::Start);
+            }
+            let (right, answered) = self.score;
+            ui.label(format!("{} {right} / {answered}", tr("Score:")));
+        });
+        if let Some(worker) = &self.worker {
+            ui.label(tr("Preparing the question…"));
+            worker.show_progress(ui, Prediction::parts());
+            return action;
+        }
+
+        ui.separator();
+        match self.stage {
+            Stage::Idle => {}
+            Stage::Unavailable => {
+                ui.label(tr(
+                    "The quiz needs a Gaussian pulse or packet, and at least one particle.",
+                ));
+            }
+            Stage::Waiting => {
+                ui.label(tr("Play until the pulse reaches the particles."));
+                if ui.button(tr("Play")).clicked() {
+                    action = Some(QuizAction::Play);
+                }
+            }
+            Stage::Asking => {
+                ui.strong(tr(
+                    "Which will the resultant field be once the pulse has passed the particles?",
+                ));
+                self.draw_choices(ui);
+                if ui
+                    .add_enabled(self.choice.is_some(), egui::Button::new(tr("Play on")))
+                    .on_disabled_hover_text(tr("Choose an answer first"))
+                    .clicked()
+                {
+                    action = Some(QuizAction::Play);
+                }
+            }
+            Stage::Revealed => {
+                match self.choice == Some(Prediction::correct()) {
+                    true => ui.colored_label(RIGHT_COLOUR, tr("Right!")),
+                    false => ui.colored_label(
+                        WRONG_COLOUR,
+                        tr("Not quite: the right answer is outlined in green."),
+                    ),
+                };
+                self.draw_choices(ui);
+            }
+        }
+        action
+    }
+
+    // thumbnails of the choices side by side, all on the same scale, which can be clicked while being asked
+    fn draw_choices(&mut self, ui: &mut Ui) {
+        let Some(prediction) = &self.prediction else {
+            return;
+        };
+        let scale = prediction
+            .choices
+            .iter()
+            .flatten()
+            .map(|e| e.abs())
+            .fold(0.0, f32::max)
+            .max(1e-6);
+        let (first, last) = match (prediction.x_points.first(), prediction.x_points.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return,
+        };
+        let asking = self.stage == Stage::Asking;
+        let count = self.order.len() as f32;
+        let width = (ui.available_width() - ui.spacing().item_spacing.x * (count - 1.0)) / count;
+
+        ui.horizontal(|ui| {
+            for (label, &i) in ('A'..).zip(&self.order) {
+                let sense = match asking {
+                    true => Sense::click(),
+                    false => Sense::hover(),
+                };
+                let (rect, response) =
+                    ui.allocate_exact_size(vec2(width.max(1.0), THUMBNAIL_HEIGHT), sense);
+                if response.clicked() {
+                    self.choice = Some(i);
+                }
+                let painter = ui.painter_at(rect);
+                let to_screen_x =
+                    |x: f32| rect.left() + rect.width() * (x - first) / (last - first);
+                let to_screen_y = |e: f32| rect.center().y - 0.45 * rect.height() * e / scale;
+
+                painter.rect_filled(rect, 0.0, Color32::from_rgb(10, 10, 10));
+                painter.hline(
+                    rect.x_range(),
+                    rect.center().y,
+                    Stroke::new(1.0, AXIS_COLOUR),
+                );
+                let points = prediction
+                    .x_points
+                    .iter()
+                    .zip(&prediction.choices[i])
+                    .map(|(x, e)| pos2(to_screen_x(*x), to_screen_y(*e)))
+                    .collect();
+                painter.line(points, Stroke::new(1.5, resultant_field_colour(1.0)));
+                painter.text(
+                    rect.left_top() + vec2(4.0, 2.0),
+                    Align2::LEFT_TOP,
+                    label,
+                    FontId::monospace(11.0),
+                    Color32::GRAY,
+                );
+
+                let outline = match self.stage {
+                    Stage::Revealed if i == Prediction::correct() => Some(RIGHT_COLOUR),
+                    Stage::Revealed if self.choice == Some(i) => Some(WRONG_COLOUR),
+                    _ if self.choice == Some(i) => Some(SELECTED_COLOUR),
+                    _ if response.hovered() && asking => Some(Color32::GRAY),
+                    _ => None,
+                };
+                if let Some(colour) = outline {
+                    painter.rect_stroke(
+                        rect.shrink(1.0),
+                        0.0,
+                        Stroke::new(2.0, colour),
+                        egui::StrokeKind::Inside,
+                    );
+                }
+            }
+        });
+    }
+}
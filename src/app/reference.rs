@@ -0,0 +1,110 @@
+//! Reference data loaded from a two-column CSV file and drawn over the simulation's output, to compare it against a
+//! textbook curve or measurements without leaving the app: a field strength against x on the canvas, or a refractive
+//! index against angular frequency on the dispersion plot of the Phasors panel.
+
+use std::path::Path;
+
+use egui::{Pos2, pos2};
+
+use crate::app::session::display_name;
+
+// what the two columns of a reference file are, and so where it is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReferenceKind {
+    Field,      // x and field strength E, drawn on the canvas
+    Dispersion, // angular frequency ω and refractive index n, drawn on the dispersion plot
+}
+
+impl ReferenceKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReferenceKind::Field => "Field E against x",
+            ReferenceKind::Dispersion => "Index n against ω",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceTrace {
+    pub name: String,
+    pub points: Vec<Pos2>, // pairs of values from the file, in increasing order of the first
+}
+
+impl ReferenceTrace {
+    // reads two columns of numbers, one point per line, in any order.
+    // as for sampled waves, columns may be separated by commas, semicolons or whitespace, and lines that aren't
+    // numbers (e.g. headers) are skipped
+    pub fn from_csv(name: String, text: &str) -> Result<Self, String> {
+        let mut points = Vec::new();
+        for (number, line) in (1..).zip(text.lines()) {
+            let columns: Vec<f32> = line
+                .split([',', ';', ' ', '\t'])
+                .filter(|column| !column.is_empty())
+                .map_while(|column| column.trim().parse().ok())
+                .collect();
+            let [x, y] = columns[..] else {
+                continue;
+            };
+            if !(x.is_finite() && y.is_finite()) {
+                return Err(format!("line {number}: values must be finite numbers"));
+            }
+            points.push(pos2(x, y));
+        }
+        if points.len() < 2 {
+            return Err("at least two points are needed to draw a reference".to_owned());
+        }
+        points.sort_by(|a, b| a.x.total_cmp(&b.x));
+        Ok(ReferenceTrace { name, points })
+    }
+
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        ReferenceTrace::from_csv(display_name(path), &text)
+    }
+
+    // first and second columns, for drawing on the canvas
+    pub fn columns(&self) -> (Vec<f32>, Vec<f32>) {
+        self.points.iter().map(|p| (p.x, p.y)).unzip()
+    }
+}
+
+// the reference of each kind that has been loaded, if any
+#[derive(Default)]
+pub struct References {
+    pub field: Option<ReferenceTrace>,
+    pub dispersion: Option<ReferenceTrace>,
+}
+
+impl References {
+    pub fn get_mut(&mut self, kind: ReferenceKind) -> &mut Option<ReferenceTrace> {
+        match kind {
+            ReferenceKind::Field => &mut self.field,
+            ReferenceKind::Dispersion => &mut self.dispersion,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::pos2;
+
+    use super::ReferenceTrace;
+
+    // headers are skipped and the points put in order, while values that can't be drawn are rejected
+    #[test]
+    fn reference_is_read_from_csv() {
+        let reference =
+            ReferenceTrace::from_csv("n".to_owned(), "ω, n\n2.0, 0.9\n1.0;1.1\n\n1.5\t1.3\n")
+                .unwrap();
+        assert_eq!(
+            reference.points,
+            [pos2(1.0, 1.1), pos2(1.5, 1.3), pos2(2.0, 0.9)]
+        );
+        assert_eq!(
+            reference.columns(),
+            (vec![1.0, 1.5, 2.0], vec![1.1, 1.3, 0.9])
+        );
+        assert!(ReferenceTrace::from_csv(String::new(), "x,E\n1,2\n").is_err());
+        assert!(ReferenceTrace::from_csv(String::new(), "1,2\n2,inf\n").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Panel comparing the power scattered at two frequencies of the plane wave, run in the background,
+//! to show how much more strongly higher frequencies are scattered
+
+use egui::{DragValue, Grid, Ui};
+
+use crate::app::background::BackgroundRun;
+use crate::app::i18n::tr;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::scattering::ScatteringComparison;
+use crate::app::simulation::variables::WAVE_FREQUENCY;
+
+pub struct ScatteringPanel {
+    frequencies: [f32; 2],
+    worker: Option<BackgroundRun<Result<ScatteringComparison, String>>>,
+    comparison: Option<ScatteringComparison>,
+}
+
+impl Default for ScatteringPanel {
+    fn default() -> Self {
+        ScatteringPanel {
+            // in the ratio of the frequencies of blue and red light
+            frequencies: [1.0, 1.75],
+            worker: None,
+            comparison: None,
+        }
+    }
+}
+
+impl ScatteringPanel {
+    // starts a comparison of the simulation's scene at the two frequencies
+    fn start(&mut self, simulation: &Simulation) {
+        let scene = simulation.scene();
+        let frequencies = self.frequencies;
+        self.worker = Some(BackgroundRun::start(move |progress| {
+            ScatteringComparison::run(&scene, frequencies, progress)
+        }));
+    }
+
+    pub fn draw(&mut self, ui: &mut Ui, simulation: &Simulation) {
+        match BackgroundRun::poll(&mut self.worker, "Scattering comparison") {
+            Some(Ok(comparison)) => self.comparison = Some(comparison),
+            Some(Err(e)) => log::error!("Scattering comparison couldn't set up the scene: {e}"),
+            None => {}
+        }
+        ui.label(tr(
+            "Runs the scene with a plane wave at each of two frequencies, and compares the power the particles scatter. \
+            Well below resonance it grows as the fourth power of the frequency, which is why the sky is blue.",
+        ));
+
+        Grid::new("ScatteringFrequencies").show(ui, |ui| {
+            ui.label("ω₁")
+                .on_hover_text(tr("Lower frequency, e.g. of red light"));
+            ui.add(
+                DragValue::new(&mut self.frequencies[0])
+                    .range(WAVE_FREQUENCY.min..=WAVE_FREQUENCY.max)
+                    .speed(0.01),
+            );
+            ui.end_row();
+            ui.label("ω₂")
+                .on_hover_text(tr("Higher frequency, e.g. of blue light"));
+            ui.add(
+                DragValue::new(&mut self.frequencies[1])
+                    .range(WAVE_FREQUENCY.min..=WAVE_FREQUENCY.max)
+                    .speed(0.01),
+            );
+            ui.end_row();
+        });
+
+        match &self.worker {
+            Some(worker) => worker.show_progress(ui, ScatteringComparison::runs()),
+            None => {
+                let enabled = !simulation.particles().is_empty();
+                if ui
+                    .add_enabled(enabled, egui::Button::new(tr("Compare")))
+                    .on_disabled_hover_text(tr("Add a particle first"))
+                    .clicked()
+                {
+                    self.start(simulation);
+                }
+            }
+        }
+
+        if let Some(comparison) = &self.comparison {
+            ui.separator();
+            let [low, high] = comparison.frequencies;
+            Grid::new("ScatteringResults").striped(true).show(ui, |ui| {
+                for (omega, power) in comparison.frequencies.iter().zip(comparison.powers) {
+                    ui.label(format!("{} ω = {omega:.2}", tr("Scattered power at")));
+                    ui.monospace(format!("{power:.3e}"));
+                    ui.end_row();
+                }
+                ui.strong(tr("Ratio"));
+                ui.strong(egui::RichText::new(format!("{:.2}", comparison.ratio())).monospace());
+                ui.end_row();
+                ui.label(format!("{} ({high:.2} / {low:.2})⁴", tr("Rayleigh's law:")));
+                ui.monospace(format!("{:.2}", comparison.rayleigh_ratio()));
+                ui.end_row();
+                if let Some(ratio) = comparison.oscillator_ratio {
+                    ui.label(tr("Driven damped oscillator:"))
+                        .on_hover_text(tr(
+                            "Ratio expected of a single particle, including how its response changes nearer resonance",
+                        ));
+                    ui.monospace(format!("{ratio:.2}"));
+                    ui.end_row();
+                }
+            });
+        }
+    }
+}
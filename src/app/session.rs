@@ -0,0 +1,129 @@
+//! Saving and opening session files, which hold the setup of the simulation along with the user's settings.
+//! The current session is also periodically autosaved to a recovery file, so that it can be recovered if the
+//! application is killed. The recovery file is deleted when the application closes normally, so one found at
+//! startup means the previous session ended unexpectedly.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::APP_NAME;
+use crate::app::settings::Settings;
+use crate::app::simulation::scene::Scene;
+
+// time between writes of the recovery file
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(20);
+const RECOVERY_FILE_NAME: &str = "recovery.ron";
+// extension of session files saved by the user
+pub const SESSION_EXTENSION: &str = "session";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub scene: Scene,
+    pub settings: Settings,
+}
+
+impl Session {
+    // reads a session file, rejecting it if its scene isn't valid
+    pub fn open(path: &Path) -> Result<Session, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let session: Session = ron::from_str(&text).map_err(|e| e.to_string())?;
+        session.scene.validate()?;
+        Ok(session)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
+
+// name of a saved file to show the user, without its directory or extension
+pub fn display_name(path: &Path) -> String {
+    path.file_stem().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into(),
+    )
+}
+
+fn recovery_path() -> Option<PathBuf> {
+    eframe::storage_dir(APP_NAME).map(|dir| dir.join(RECOVERY_FILE_NAME))
+}
+
+// session left behind by a previous launch that did not close normally, if any
+pub fn find_recovery() -> Option<Session> {
+    let path = recovery_path()?;
+    let text = std::fs::read_to_string(&path).ok()?;
+    match ron::from_str::<Session>(&text)
+        .map_err(|e| e.to_string())
+        .and_then(|session| session.scene.validate().map(|()| session))
+    {
+        Ok(session) => Some(session),
+        Err(e) => {
+            log::warn!("Ignoring unreadable recovery file {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+// deletes the recovery file, called when the application closes normally
+pub fn remove_recovery() {
+    let Some(path) = recovery_path().filter(|path| path.exists()) else {
+        return;
+    };
+    if let Err(e) = std::fs::remove_file(&path) {
+        log::warn!("Failed to remove recovery file {}: {e}", path.display());
+    }
+}
+
+pub struct Autosave {
+    last_save: Instant,
+    last_written: String, // contents of the recovery file, to avoid rewriting it when nothing has changed
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Autosave {
+            last_save: Instant::now(),
+            last_written: String::new(),
+        }
+    }
+}
+
+impl Autosave {
+    // writes the session to the recovery file if the autosave interval has passed
+    pub fn update(&mut self, session: impl FnOnce() -> Session) {
+        if self.last_save.elapsed() < AUTOSAVE_INTERVAL {
+            return;
+        }
+        self.last_save = Instant::now();
+
+        let text = match ron::ser::to_string_pretty(&session(), ron::ser::PrettyConfig::default()) {
+            Ok(text) => text,
+            Err(e) => {
+                log::error!("Failed to serialise session for autosave: {e}");
+                return;
+            }
+        };
+        if text == self.last_written {
+            return;
+        }
+        match write_recovery(&text) {
+            Ok(()) => self.last_written = text,
+            Err(e) => log::error!("Failed to write recovery file: {e}"),
+        }
+    }
+}
+
+// writes to a temporary file first, so a crash part way through writing can't corrupt the previous recovery file
+fn write_recovery(text: &str) -> std::io::Result<()> {
+    let path = recovery_path().ok_or(std::io::ErrorKind::NotFound)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let temporary = path.with_extension("ron.tmp");
+    std::fs::write(&temporary, text)?;
+    std::fs::rename(&temporary, &path)
+}
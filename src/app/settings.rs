@@ -0,0 +1,218 @@
+//! User settings that are remembered between launches of the application
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::bookmarks::Bookmark;
+use crate::app::canvas::YScale;
+use crate::app::i18n::Language;
+use crate::app::layout::{Layout, NamedLayout};
+use crate::app::palette::{Palette, TraceStyles};
+use crate::app::panels::PanelState;
+use crate::app::simulation::preset::Preset;
+use crate::app::simulation::variables::WORLD_SIZE;
+
+// number of opened or saved files remembered for reopening
+const MAX_RECENT_FILES: usize = 10;
+
+// range of the scale of the whole interface, as a factor of its normal size
+pub const UI_SCALE: std::ops::RangeInclusive<f32> = 0.75..=3.0;
+// scale of the interface in the projector profile, readable from the back of a lecture hall
+const PROJECTOR_UI_SCALE: f32 = 1.75;
+// range of the width of the anti-aliased edges of lines, in pixels
+pub const FEATHERING: std::ops::RangeInclusive<f32> = 0.0..=4.0;
+// egui's own width of the edges
+const DEFAULT_FEATHERING: f32 = 1.0;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)] // settings missing from older saves take their default value
+pub struct Settings {
+    pub dark_theme: bool,
+    pub language: Option<Language>, // None to follow the system locale
+    pub last_preset: Option<Preset>,
+    pub pause_in_background: bool, // stop simulating while minimised or unfocused
+    pub large_controls: bool,      // bigger buttons and sliders, for touch screens
+    pub ui_scale: f32, // factor the text, controls and lines of the whole interface are enlarged by
+    pub match_refresh_rate: bool, // one simulation update per frame of the display, rather than SIMULATION_FPS a second
+    pub smooth_slow_motion: bool, // draw between simulation updates below normal speed
+    pub show_start_screen: bool,
+    pub memory_cap: usize, // MiB the particles' histories, recordings and the 3D field history may take together
+    #[serde(alias = "recent_sessions")]
+    pub recent_files: Vec<PathBuf>, // session and scene files, most recent first
+
+    // view
+    pub zoom: f32,
+    pub y_scale: YScale,
+    pub world_centre: f32,
+    pub y_zoom: f32,
+    pub y_centre: f32, // position on the vertical axis at the middle of the canvas
+
+    // visuals
+    pub applied_field_opacity: f32,
+    pub resultant_field_opacity: f32,
+    pub particle_field_opacity: f32,
+    pub intensity_opacity: f32,
+    pub scattered_field_opacity: f32,
+    pub colour_by_phase: bool, // colour the resultant field by its instantaneous phase
+    pub palette: Palette,
+    pub line_styles: bool, // draw each kind of field in its own line style, besides its colour
+    pub trace_styles: TraceStyles, // width and dashes of each field's line
+    pub feathering: f32,   // width in pixels of the anti-aliased edges of lines, 0 for none
+    pub layout: Layout, // sections of the settings bar and measurements in the controls bar shown
+    pub saved_layouts: Vec<NamedLayout>,
+    pub show_envelope: bool,     // draw the envelope of the resultant field
+    pub stacked_traces: bool,    // draw each kind of field in its own lane
+    pub filled_traces: bool,     // fill the area between each field and its zero line
+    pub show_steady_state: bool, // draw the analytic steady state for a plane wave
+    pub show_attenuation: bool,  // draw an exponential fitted to the intensity over the particles
+    pub explain: bool,
+
+    // analysis panels
+    pub energy_panel: PanelState,
+    pub surface_panel: PanelState,
+    pub phasor_panel: PanelState,
+    pub noise_panel: PanelState,
+    pub spectrum_panel: PanelState,
+    pub extinction_panel: PanelState,
+    pub pulse_panel: PanelState,
+    pub multiples_panel: PanelState,
+    pub convergence_panel: PanelState,
+    pub scattering_panel: PanelState,
+    pub fit_panel: PanelState,
+    pub ensemble_panel: PanelState,
+    pub compare_panel: PanelState,
+    pub quiz_panel: PanelState,
+    pub inspector_panel: PanelState,
+    pub bookmarks_panel: PanelState,
+    pub export_panel: PanelState,
+    pub stream_panel: PanelState,
+    pub diagnostics_panel: PanelState,
+    pub probe_x: f32, // position at which the phasor diagram measures the fields
+    pub markers: Vec<f32>, // x coordinates of markers added by the user
+    pub bookmarks: Vec<Bookmark>, // times marked by the user, in order
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            dark_theme: true,
+            language: None,
+            last_preset: None,
+            pause_in_background: true,
+            large_controls: false,
+            ui_scale: 1.0,
+            match_refresh_rate: true,
+            smooth_slow_motion: true,
+            show_start_screen: true,
+            memory_cap: 1024,
+            recent_files: Vec::new(),
+
+            zoom: 1.0,
+            y_scale: YScale::default(),
+            world_centre: WORLD_SIZE.center(),
+            y_zoom: 1.0,
+            y_centre: 0.0,
+
+            applied_field_opacity: 0.8,
+            resultant_field_opacity: 0.7,
+            particle_field_opacity: 0.2,
+            intensity_opacity: 0.0,
+            scattered_field_opacity: 0.0,
+            colour_by_phase: false,
+            palette: Palette::default(),
+            line_styles: false,
+            trace_styles: TraceStyles::default(),
+            feathering: DEFAULT_FEATHERING,
+            layout: Layout::default(),
+            saved_layouts: Vec::new(),
+            show_envelope: false,
+            stacked_traces: false,
+            filled_traces: false,
+            show_steady_state: false,
+            show_attenuation: false,
+            explain: false,
+
+            energy_panel: PanelState::default(),
+            surface_panel: PanelState::default(),
+            phasor_panel: PanelState::default(),
+            noise_panel: PanelState::default(),
+            spectrum_panel: PanelState::default(),
+            extinction_panel: PanelState::default(),
+            pulse_panel: PanelState::default(),
+            multiples_panel: PanelState::default(),
+            convergence_panel: PanelState::default(),
+            scattering_panel: PanelState::default(),
+            fit_panel: PanelState::default(),
+            ensemble_panel: PanelState::default(),
+            compare_panel: PanelState::default(),
+            quiz_panel: PanelState::default(),
+            inspector_panel: PanelState::default(),
+            bookmarks_panel: PanelState::default(),
+            export_panel: PanelState::default(),
+            stream_panel: PanelState::default(),
+            diagnostics_panel: PanelState::default(),
+            probe_x: -5.0,
+            markers: Vec::new(),
+            bookmarks: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    // read settings saved by a previous launch, if there are any
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, self);
+    }
+
+    // moves a file to the top of the recent files
+    pub fn add_recent_file(&mut self, path: PathBuf) {
+        self.recent_files.retain(|p| *p != path);
+        self.recent_files.insert(0, path);
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    // sizes of the controls, enlarged so they can be hit with a finger when large_controls is set
+    pub fn spacing(&self) -> egui::style::Spacing {
+        let spacing = egui::style::Spacing::default();
+        match self.large_controls {
+            true => egui::style::Spacing {
+                item_spacing: egui::vec2(10.0, 6.0),
+                button_padding: egui::vec2(12.0, 8.0),
+                interact_size: egui::vec2(56.0, 40.0),
+                slider_width: 160.0,
+                icon_width: 24.0,
+                icon_width_inner: 14.0,
+                ..spacing
+            },
+            false => spacing,
+        }
+    }
+
+    // enlarges the interface and its controls for showing on a projector
+    pub fn use_projector_profile(&mut self) {
+        self.ui_scale = PROJECTOR_UI_SCALE;
+        self.large_controls = true;
+    }
+
+    // anti-aliasing of the lines the interface draws, including the traces
+    pub fn apply_feathering(&self, ctx: &egui::Context) {
+        ctx.tessellation_options_mut(|options| {
+            options.feathering = self.feathering > 0.0;
+            options.feathering_size_in_pixels = self.feathering.max(f32::EPSILON);
+        });
+    }
+
+    pub fn visuals(&self) -> egui::Visuals {
+        match self.dark_theme {
+            true => egui::Visuals::dark(),
+            false => egui::Visuals::light(),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Analytic signal of a field, computed with the Hilbert transform.
+//! The analytic signal of a real wave is a complex wave whose magnitude is the envelope of the original,
+//! and whose argument is its instantaneous phase.
+
+use std::sync::Arc;
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+
+// forward and inverse transforms of one length
+type Plans = (Arc<dyn Fft<f32>>, Arc<dyn Fft<f32>>);
+
+pub struct AnalyticSignal {
+    planner: FftPlanner<f32>,
+    plans: Option<Plans>,
+    buffer: Vec<Complex<f32>>,
+}
+
+impl Default for AnalyticSignal {
+    fn default() -> Self {
+        AnalyticSignal {
+            planner: FftPlanner::new(),
+            plans: None,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl AnalyticSignal {
+    // calculates the analytic signal of evenly spaced samples of a real signal
+    pub fn compute(&mut self, samples: &[f32]) -> &[Complex<f32>] {
+        let n = samples.len();
+        let (forward, inverse) = match &self.plans {
+            Some(plans) if plans.0.len() == n => plans.clone(),
+            _ => {
+                let plans = (
+                    self.planner.plan_fft_forward(n),
+                    self.planner.plan_fft_inverse(n),
+                );
+                self.plans = Some(plans.clone());
+                plans
+            }
+        };
+
+        self.buffer.clear();
+        self.buffer
+            .extend(samples.iter().map(|s| Complex::new(*s, 0.0)));
+        forward.process(&mut self.buffer);
+
+        // remove the negative frequencies, doubling the positive ones to keep the same energy.
+        // the zero (and for even lengths, Nyquist) frequency is shared by both halves so is kept as is
+        let half = n / 2;
+        for (i, value) in self.buffer.iter_mut().enumerate() {
+            if i == 0 || (n.is_multiple_of(2) && i == half) {
+                continue;
+            }
+            *value *= if i < n.div_ceil(2) { 2.0 } else { 0.0 };
+        }
+
+        inverse.process(&mut self.buffer);
+        // rustfft doesn't normalise the inverse transform
+        let scale = 1.0 / n as f32;
+        for value in &mut self.buffer {
+            *value *= scale;
+        }
+        &self.buffer
+    }
+}
@@ -1,109 +1,298 @@
 //! Contains all simulation logic
 
-mod field;
+pub mod arrangement;
+pub mod attenuation;
+pub mod command;
+pub mod config;
+pub mod convergence;
+pub mod divergence;
+pub mod ensemble;
+pub mod field;
+pub mod fit;
+pub mod group;
+pub mod limiter;
+pub mod observer;
 pub mod particle;
+pub mod prediction;
+pub mod preset;
+pub mod ramp;
+pub mod replay;
+pub mod scattering;
+pub mod scene;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod solver;
+pub mod sweep;
+mod theory;
+pub mod thermal;
+mod travelling;
 pub mod variables;
 pub mod waveform;
 
-use field::Field;
+use command::SimulationCommand;
+use divergence::Divergence;
+use field::{Field, Interpolation, refined_grid, uniform_grid};
+use group::ParticleGroup;
+use limiter::{Limited, Limiter};
 use particle::ChargedParticleType;
-use variables::{C, DIVISIONS, INV_C_2, PARTICLE_SPACING, TIME_STEP, WORLD_SIZE};
+use preset::Preset;
+use ramp::{ParameterChanges, Ramp};
+use scene::{Caption, Scene};
+use solver::{Solver, SolverType, in_parallel};
+use thermal::THERMAL_SEED;
+use travelling::TravellingWave;
+use variables::{
+    C, DECIMATION_BLOCK, FULL_HISTORY_TIME, Float, INTENSITY_WINDOW, INV_C_2,
+    MAX_HISTORY_DECIMATION, PARALLEL_PARTICLES, PARTICLE_SPACING, WAVE_FREQUENCY, narrow,
+};
 use waveform::*;
 
 use egui::{Pos2, Rangef, pos2, vec2};
+#[cfg(feature = "serde-state")]
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /*
 == Logic relating to the particles =========================================================
 */
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
 struct PointInTime {
-    t: f32, // point in time
-    y: f32, // y displacement as t
-    v: f32, // y velocity at t
-    a: f32, // y acceleration at t
+    t: Float, // point in time
+    y: Float, // y displacement as t
+    v: Float, // y velocity at t
+    a: Float, // y acceleration at t
 }
 
+// terms of the radiation field of a particle seen at a point on the x axis
+pub struct Radiation {
+    pub r: f32,      // distance to the particle at the retarded time
+    pub a_perp: f32, // retarded acceleration perpendicular to r
+    pub field: f32,  // resulting field strength
+}
+
+// identifies a particle for as long as it exists, including across resets of the simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-state", serde(from = "u64", into = "u64"))]
+pub struct ParticleId(u64);
+
+// next ID to give a particle
+static NEXT_PARTICLE_ID: AtomicU64 = AtomicU64::new(0);
+
+impl ParticleId {
+    // an ID that hasn't been given to any other particle
+    fn unique() -> Self {
+        ParticleId(NEXT_PARTICLE_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+// a restored particle keeps its ID, and no particle made afterwards is given it again
+#[cfg(feature = "serde-state")]
+impl From<u64> for ParticleId {
+    fn from(id: u64) -> Self {
+        NEXT_PARTICLE_ID.fetch_max(id + 1, Ordering::Relaxed);
+        ParticleId(id)
+    }
+}
+
+#[cfg(feature = "serde-state")]
+impl From<ParticleId> for u64 {
+    fn from(id: ParticleId) -> Self {
+        id.0
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
 pub struct ChargedParticle {
+    id: ParticleId,
     particle_type: ChargedParticleType,
     mass: f32,
-    position: Pos2,
-    velocity: f32,             // enforce always in y direction
-    acceleration: f32,         // enforce always in y direction
+    position: Pos2,            // y is a copy of displacement, for drawing
+    displacement: Float,       // enforce always in y direction
+    velocity: Float,           // enforce always in y direction
+    acceleration: Float,       // enforce always in y direction
     spring_constant: f32,      // treat particle as SHO with this k
     damping: f32,              // SHO damping factor
+    driving_field: f32,        // applied field strength felt during the last update
+    thermal_force: f32,        // random force from the heat bath during the coming update
     field: Field,              // induced electric field from acceleration
+    grid_step: f32,            // distance between the field divisions either side of the particle
+    time_step: Float,          // time between updates
     history: Vec<PointInTime>, // for implementing retarded time
+    decimated: usize, // number of samples at the start of history that have been thinned out
+    decimation: usize, // one in this many samples older than FULL_HISTORY_TIME are kept
+    configured: bool, // whether the particle has been given the properties set in the UI
+    ramp: Option<Ramp>, // change of properties being ramped in
 }
 
 impl ChargedParticle {
-    pub fn new(position: Pos2, field_size: Rangef, particle_type: ChargedParticleType) -> Self {
+    // grid is the x coordinates to calculate the particle's field at, and time_step the time between updates
+    pub fn new(
+        position: Pos2,
+        grid: &[f32],
+        particle_type: ChargedParticleType,
+        time_step: f32,
+    ) -> Self {
+        let field = Field::from_points(grid);
         ChargedParticle {
+            id: ParticleId::unique(),
             particle_type,
             mass: particle_type.mass(),
             spring_constant: particle_type.default_spring_constant(),
             damping: particle_type.default_damping(),
             position,
+            displacement: Float::from(position.y),
             velocity: 0.0,
             acceleration: 0.0,
-            field: Field::new(field_size),
+            driving_field: 0.0,
+            thermal_force: 0.0,
+            grid_step: field.step_at(position.x),
+            time_step: Float::from(time_step),
+            field,
             history: Vec::new(),
+            decimated: 0,
+            decimation: 1,
+            configured: false,
+            ramp: None,
         }
     }
 
-    pub fn update(&mut self, applied_field_strength: f32, t: f32) {
-        self.update_position(applied_field_strength, t);
-        self.update_induced_field(t);
+    pub fn id(&self) -> ParticleId {
+        self.id
     }
 
     pub fn particle_type(&self) -> &ChargedParticleType {
         &self.particle_type
     }
 
-    // based on motion of particle, calculate the field seen by all points on x axis
-    fn update_induced_field(&mut self, t: f32) {
+    // based on motion of particle, calculate the field seen by all points on x axis.
+    // points further from the particle see it further in the past, so rather than looking up each point's
+    // retarded time in the history afresh, the grid is walked outwards from the particle on each side
+    // with a cursor into the history that only moves back in time
+    pub fn update_induced_field(&mut self, t: Float) {
+        let points = self.field.intervals();
+        let mut values = vec![0.0; points.len()];
+        let centre = points.partition_point(|x| *x < self.position.x);
+        let now = self.snapshot(t);
+        let outwards = [
+            (0..centre).rev().collect::<Vec<usize>>(),
+            (centre..points.len()).collect(),
+        ];
+        for side in outwards {
+            let mut cursor = self.history.len().saturating_sub(1);
+            for i in side {
+                let x = points[i];
+                let past_t = self.retarded_time(x, t);
+                while cursor > 0 && self.history[cursor].t > past_t {
+                    cursor -= 1;
+                }
+                let e_rva = match self.history.len() < 2 {
+                    true => now.clone(),
+                    false => self.interpolate_history(cursor, past_t, &now),
+                };
+                values[i] = self.radiation_from(x, &e_rva).field;
+            }
+        }
+        self.field.values_mut().copy_from_slice(&values);
+    }
+
+    // field radiated by this particle as seen by the point at (x, 0) at time t
+    fn radiation(&self, x: f32, t: Float) -> Radiation {
+        // past motion of particle as seen by point at (x, 0)
+        let e_rva = self.retarded_rva(x, t);
+        self.radiation_from(x, &e_rva)
+    }
+
+    // field radiated by this particle as seen by the point at (x, 0), given its motion at the retarded time
+    fn radiation_from(&self, x: f32, e_rva: &PointInTime) -> Radiation {
         let charge = self.particle_type.charge();
-        for i in 0..DIVISIONS {
-            let x = self.field.position_at(i);
-            // past motion of particle as seen by point at (x, 0)
-            let e_rva = self.retarded_rva(x, t);
-
-            let r = vec2(self.position.x - x, e_rva.y);
-            let mod_r = r.length();
-            // get perpendicular components of motion
-            let cos_theta = r.x.abs() / mod_r;
-            let a_perp = e_rva.a * cos_theta;
-
-            // prevent big spikes in field close to the particle, this factor isn't physical but spikes make it look bad and makes it hard to understand what's going on.
-            //let w = 2.0 * mod_r;
-            //let pretty_factor = 1.0 / (1.0 / (w * w * w.exp()) + 1.0);
-
-            // derived from second time-derivative term of Heaviside-Feynman formula
-            // include charge in the field calculation
-            self.field[i] = match r.x.abs() < self.field.size() / (DIVISIONS - 1) as f32 {
-                true => 0.0,
-                false => INV_C_2 * (charge * a_perp / mod_r), // * pretty_factor,
-            };
+        let r = vec2(self.position.x - x, narrow(e_rva.y));
+        let mod_r = r.length();
+        // get perpendicular components of motion
+        let cos_theta = r.x.abs() / mod_r;
+        let a_perp = narrow(e_rva.a) * cos_theta;
+
+        // prevent big spikes in field close to the particle, this factor isn't physical but spikes make it look bad and makes it hard to understand what's going on.
+        //let w = 2.0 * mod_r;
+        //let pretty_factor = 1.0 / (1.0 / (w * w * w.exp()) + 1.0);
+
+        // derived from second time-derivative term of Heaviside-Feynman formula
+        // include charge in the field calculation
+        let field = match r.x.abs() < self.grid_step {
+            true => 0.0,
+            false => INV_C_2 * (charge * a_perp / mod_r), // * pretty_factor,
+        };
+        Radiation {
+            r: mod_r,
+            a_perp,
+            field,
         }
     }
 
+    // radiation seen at (x, 0) at the time of the most recent update
+    pub fn latest_radiation(&self, x: f32) -> Radiation {
+        let t = self.history.last().map_or(0.0, |p| p.t);
+        self.radiation(x, t)
+    }
+
     // update motion of particle based on the field it is experiencing
-    fn update_position(&mut self, applied_field_strength: f32, t: f32) {
+    fn update_position(&mut self, applied_field_strength: f32, t: Float) {
         // simple harmonic motion
         let charge = self.particle_type.charge();
-        let force = charge * applied_field_strength
-            - self.spring_constant * self.position.y
-            - self.damping * self.velocity;
-        self.acceleration = force / self.mass;
-        self.velocity += TIME_STEP * self.acceleration;
-        self.position.y += TIME_STEP * self.velocity;
+        self.driving_field = applied_field_strength;
+        let force = Float::from(charge * applied_field_strength)
+            - Float::from(self.spring_constant) * self.displacement
+            - Float::from(self.damping) * self.velocity
+            + Float::from(self.thermal_force);
+        self.acceleration = force / Float::from(self.mass);
+        self.velocity += self.time_step * self.acceleration;
+        self.displacement += self.time_step * self.velocity;
+        self.position.y = narrow(self.displacement);
         // record this instant for retarded time lookup
         self.history.push(self.snapshot(t));
+        self.decimate(t);
     }
 
-    fn snapshot(&self, t: f32) -> PointInTime {
+    // thins out the history older than FULL_HISTORY_TIME, keeping one in every `decimation` samples
+    fn decimate(&mut self, t: Float) {
+        if self.decimation <= 1 {
+            return;
+        }
+        let cutoff = t - Float::from(FULL_HISTORY_TIME);
+        let old = self.history.partition_point(|p| p.t < cutoff);
+        if old < self.decimated + DECIMATION_BLOCK * self.decimation {
+            return;
+        }
+        let kept: Vec<PointInTime> = self
+            .history
+            .drain(self.decimated..old)
+            .step_by(self.decimation)
+            .collect();
+        let start = self.decimated;
+        self.decimated += kept.len();
+        self.history.splice(start..start, kept);
+    }
+
+    // drops the history from before a time, other than the last instant before it, which is still needed to
+    // interpolate to the time itself. the memory it took is given back
+    fn forget_before(&mut self, cutoff: Float) {
+        let old = self
+            .history
+            .partition_point(|p| p.t < cutoff)
+            .saturating_sub(1);
+        self.history.drain(..old);
+        self.decimated = self.decimated.saturating_sub(old);
+        self.history.shrink_to_fit();
+    }
+
+    fn snapshot(&self, t: Float) -> PointInTime {
         PointInTime {
             t,
-            y: self.position.y,
+            y: self.displacement,
             v: self.velocity,
             a: self.acceleration,
         }
@@ -117,32 +306,96 @@ impl ChargedParticle {
         self.field.values()
     }
 
+    // field this particle radiates as a point charge at (x, 0) and time t, anywhere rather than only on the grid,
+    // from its motion at the retarded time. t is no later than the particle's latest update
+    pub fn field_at(&self, x: f32, t: f32) -> f32 {
+        self.radiation(x, Float::from(t)).field
+    }
+
+    pub fn mass(&self) -> f32 {
+        self.mass
+    }
+
+    pub fn spring_constant(&self) -> f32 {
+        self.spring_constant
+    }
+
+    pub fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    pub fn velocity(&self) -> f32 {
+        narrow(self.velocity)
+    }
+
+    pub fn acceleration(&self) -> f32 {
+        narrow(self.acceleration)
+    }
+
+    pub fn driving_field(&self) -> f32 {
+        self.driving_field
+    }
+
+    // kinetic and potential energy of the particle's oscillation
+    pub fn energy(&self) -> (f32, f32) {
+        let (v, y) = (self.velocity(), self.position.y);
+        (0.5 * self.mass * v * v, 0.5 * self.spring_constant * y * y)
+    }
+
+    // bytes allocated to this particle's motion history
+    pub fn history_memory(&self) -> usize {
+        self.history.capacity() * std::mem::size_of::<PointInTime>()
+    }
+
     // motion of this particle as seen by point at (x,0) at time t, due to light delay
-    fn retarded_rva(&self, x: f32, t: f32) -> PointInTime {
+    fn retarded_rva(&self, x: f32, t: Float) -> PointInTime {
         let now = self.snapshot(t);
         if self.history.len() < 2 {
             return now;
         }
 
-        // calculate the retarded time that this point is 'seeing' the particle at
-        let distance = (x - self.position.x).abs();
-        let past_t = (t - distance / C).max(0.0);
+        let past_t = self.retarded_time(x, t);
+
+        // get index of this time point in particle's history. after the thinned out part the simulation
+        // increments time by a constant amount, so the index can be calculated. before it the spacing varies so it is searched for
+        let i = match self.history.get(self.decimated) {
+            Some(first) if past_t >= first.t => {
+                let steps = ((past_t - first.t) / self.time_step).floor() as usize;
+                (self.decimated + steps).min(self.history.len() - 1)
+            }
+            _ => self.history[..self.decimated]
+                .partition_point(|p| p.t <= past_t)
+                .saturating_sub(1),
+        };
 
-        // get index of this time point in particle's history, possible because the simulation increments time by a constant amount
-        let i = ((self.history.len() as f32) * past_t / t).floor() as usize;
+        self.interpolate_history(i, past_t, &now)
+    }
+
+    // the retarded time that the point at (x, 0) is 'seeing' the particle at, at time t
+    fn retarded_time(&self, x: f32, t: Float) -> Float {
+        let distance = (x - self.position.x).abs();
+        (t - Float::from(distance / C)).max(0.0)
+    }
 
+    // motion of the particle at past_t, interpolated between the instants of its history at i and after it
+    fn interpolate_history(&self, i: usize, past_t: Float, now: &PointInTime) -> PointInTime {
         // closest time points recorded by particle
-        let t1 = self.history.get(i).unwrap_or(&now);
-        let t2 = self.history.get(i + 1).unwrap_or(&now);
+        let t1 = self.history.get(i).unwrap_or(now);
+        let t2 = self.history.get(i + 1).unwrap_or(now);
 
         // interpolate between the two closest recorded instants
-        let interpolation_factor = (past_t - t1.t) / (t2.t - t1.t);
-        return PointInTime {
+        // points closer than a time step of light travel, as on the grid refined around the particle,
+        // can land on the latest instant where there is nothing to interpolate between
+        let interpolation_factor = match t2.t > t1.t {
+            true => (past_t - t1.t) / (t2.t - t1.t),
+            false => 0.0,
+        };
+        PointInTime {
             t: past_t,
             y: t1.y * (1.0 - interpolation_factor) + t2.y * interpolation_factor,
             v: t1.v * (1.0 - interpolation_factor) + t2.v * interpolation_factor,
             a: t1.a * (1.0 - interpolation_factor) + t2.a * interpolation_factor,
-        };
+        }
     }
 }
 
@@ -150,55 +403,153 @@ impl ChargedParticle {
 =================================================================================
 */
 
+// simulations restored from a saved state run in parallel, as new ones do
+#[cfg(feature = "serde-state")]
+fn parallel_by_default() -> bool {
+    true
+}
+
+// with the serde-state feature, the whole state can be saved and restored, other than the applied wave's samples
+// moved along with it, which are taken again, and changes sent but not yet applied
+#[derive(Clone)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
 pub struct Simulation {
-    t: f32,                 // time
-    size: Rangef,           // dimensions of x axis
-    pub waveform: Waveform, // applied wave
-    applied_field: Field,   // applied wave intensity at each x
+    t: Float,                       // time
+    steps: usize,                   // number of updates since the start
+    divergence: Option<Divergence>, // first value found to have blown up, if any
+    limiter: Limiter,               // safeguard against the motion growing too large to show
+    limited: Option<Limited>,       // latest action of the limiter, if it has acted
+    size: Rangef,                   // dimensions of x axis
+    waveform: Waveform,             // applied wave
+    frequency: f32, // angular frequency of the applied wave, if it is a plane wave or beat
+    beat: Beat,     // second wave of a beat, and the strength of both
+    #[cfg_attr(feature = "serde-state", serde(skip))]
+    applied_wave: TravellingWave, // shape of the applied wave, moved along as time passes
+    applied_field: Field, // applied wave intensity at each x
     resultant_field: Field, // applied wave plus all particle fields
+    scattered_field: Field, // resultant minus applied, i.e. all particle fields together
+    intensity: Field, // time average of the resultant field squared
+    even_grid: Vec<f32>, // evenly spaced divisions, before refinement near the particles
+    time_step: f32, // time between updates, from the display's refresh rate or TIME_STEP
+    divisions: usize, // number of evenly spaced divisions, DIVISIONS other than in convergence studies
+    solver: Box<dyn Solver>, // moves the particles and calculates their fields
+    #[cfg_attr(feature = "serde-state", serde(skip, default = "parallel_by_default"))]
+    parallel: bool, // updates particles and adds up their fields on several threads, once there are enough of them
 
     particles: Vec<ChargedParticle>,
-    pub particle_count: usize, // used for updating self.particles
-    pub particle_spacing: f32, // used for updating self.particles
-    pub particle_type: ChargedParticleType, // type of particles in the simulation
+    particle_count: usize,              // used for updating self.particles
+    particle_spacing: f32,              // used for updating self.particles
+    particle_type: ChargedParticleType, // type of particles in the simulation
+    placed: Vec<f32>, // x coordinates of particles placed individually, which follow the evenly spaced row
+    groups: Vec<ParticleGroup>, // particles given their own properties
 
-    pub spring_constant: f32, // need to record this on simulation for slider, updates particles once per frame
-    pub particle_mass: f32, // need to record this on simulation for slider, updates particles once per frame
-    pub damping: f32, // need to record this on simulation for slider, updates particles once per frame
+    spring_constant: f32, // of particles not in a group, which take it on as parameter_changes allows
+    particle_mass: f32,   // likewise
+    damping: f32,         // likewise
+    temperature: f32,     // of the heat bath giving the particles thermal motion, 0 for none
+    #[cfg_attr(feature = "serde-state", serde(with = "thermal::rng_state"))]
+    thermal_noise: fastrand::Rng, // draws the random kicks of the heat bath
+    history_decimation: usize, // older particle history keeps one in this many samples
+    parameter_changes: ParameterChanges, // how changes to the particles' properties reach them while running
+    samples: Option<Arc<SampledWave>>, // wave loaded from a file, used when waveform is Waveform::Sampled
+    captions: Vec<Caption>,            // text shown over the canvas during ranges of time
+    #[cfg_attr(feature = "serde-state", serde(skip))]
+    commands: Vec<SimulationCommand>, // changes sent to the simulation, not made yet
 }
 
 impl Simulation {
-    pub fn new(waveform: Waveform) -> Self {
-        let size = WORLD_SIZE;
-        let particle_type = ChargedParticleType::default(); // Default to electron
+    // an empty world, for from_config to set a scene up in
+    fn with_world(size: Rangef, divisions: usize, time_step: f32) -> Self {
+        let even_grid = uniform_grid(size, divisions);
+        let particle_type = ChargedParticleType::default();
         Simulation {
             t: 0.0,
+            steps: 0,
+            divergence: None,
+            limiter: Limiter::default(),
+            limited: None,
             size,
-            waveform,
-            particle_count: 1,
+            waveform: Waveform::GaussianPacket,
+            frequency: WAVE_FREQUENCY.initial,
+            beat: Beat::default(),
+            particle_count: 0,
             particle_type,
             damping: particle_type.default_damping(),
             spring_constant: particle_type.default_spring_constant(),
             particle_mass: particle_type.mass(),
+            temperature: 0.0,
+            thermal_noise: fastrand::Rng::with_seed(THERMAL_SEED),
             particle_spacing: PARTICLE_SPACING.initial,
-            applied_field: Field::new(size),
-            resultant_field: Field::new(size),
-            particles: vec![ChargedParticle::new(pos2(0.0, 0.0), size, particle_type)],
+            placed: Vec::new(),
+            groups: Vec::new(),
+            history_decimation: 4,
+            parameter_changes: ParameterChanges::default(),
+            applied_wave: TravellingWave::default(),
+            applied_field: Field::from_points(&even_grid),
+            resultant_field: Field::from_points(&even_grid),
+            scattered_field: Field::from_points(&even_grid),
+            intensity: Field::from_points(&even_grid),
+            solver: SolverType::default().create(&even_grid, time_step),
+            parallel: true,
+            even_grid,
+            time_step,
+            divisions,
+            particles: Vec::new(),
+            samples: None,
+            captions: Vec::new(),
+            commands: Vec::new(),
         }
     }
 
     pub fn reset(&mut self) {
         self.t = 0.0;
-        self.applied_field = Field::new(self.size);
-        self.resultant_field = Field::new(self.size);
-        self.particles.clear();
-        for i in 0..self.particle_count {
-            // space particles evenly starting from origin
-            self.particles.push(ChargedParticle::new(
-                pos2(-(i as f32) * self.particle_spacing, 0.0),
-                self.size,
-                self.particle_type,
-            ));
+        self.steps = 0;
+        self.divergence = None;
+        self.limited = None;
+        self.thermal_noise.seed(THERMAL_SEED);
+        // space particles evenly starting from origin, followed by those placed individually
+        let positions: Vec<f32> = (0..self.particle_count)
+            .map(|i| -(i as f32) * self.particle_spacing)
+            .chain(self.placed.iter().copied())
+            .collect();
+        let grid = refined_grid(self.size, self.divisions, &positions);
+        self.even_grid = uniform_grid(self.size, self.divisions);
+        self.applied_field = Field::from_points(&grid);
+        self.resultant_field = Field::from_points(&grid);
+        self.scattered_field = Field::from_points(&grid);
+        self.intensity = Field::from_points(&grid);
+        let ids: Vec<ParticleId> = self.particles.iter().map(|p| p.id).collect();
+        self.particles = positions
+            .iter()
+            .map(|x| ChargedParticle::new(pos2(*x, 0.0), &grid, self.particle_type, self.time_step))
+            .collect();
+        // the particles keep their IDs in order, so anything referring to them carries on doing so after a reset
+        for (particle, id) in self.particles.iter_mut().zip(ids) {
+            particle.id = id;
+        }
+        self.prune_groups();
+        self.solver.reset(&self.even_grid, self.time_step);
+    }
+
+    // refines the grid around the particles' current positions, and coarsens it where they have left.
+    // fields are interpolated onto the new grid so nothing jumps while paused
+    fn regrid(&mut self) {
+        let positions: Vec<f32> = self.particles.iter().map(|p| p.position.x).collect();
+        let grid = refined_grid(self.size, self.divisions, &positions);
+        if grid == self.applied_field.intervals() {
+            return;
+        }
+        for field in [
+            &mut self.applied_field,
+            &mut self.resultant_field,
+            &mut self.scattered_field,
+            &mut self.intensity,
+        ] {
+            *field = field.resample(&grid);
+        }
+        for particle in &mut self.particles {
+            particle.field = particle.field.resample(&grid);
+            particle.grid_step = particle.field.step_at(particle.position.x);
         }
     }
 
@@ -206,53 +557,106 @@ impl Simulation {
         &self.size
     }
 
-    pub fn update_particles(&mut self, update_all: bool) {
+    pub(super) fn update_particles(&mut self, update_all: bool) {
+        // particles placed individually are set aside while the row is changed, then put back after it
+        let row_length = self.particles.len() - self.placed.len();
+        let placed = self.particles.split_off(row_length);
         if update_all {
             // erase all but the origin particle, to be refilled in the next step
             self.particles.resize_with(1, || -> ChargedParticle {
-                ChargedParticle::new(pos2(0.0, 0.0), self.size, self.particle_type)
+                ChargedParticle::new(
+                    pos2(0.0, 0.0),
+                    self.applied_field.intervals(),
+                    self.particle_type,
+                    self.time_step,
+                )
             });
         }
         // update number of particles, keeping existing if possible
         if self.particle_count != self.particles.len() {
-            let mut i = self.particles.len() - 1;
+            let mut i = self.particles.len();
             self.particles
                 .resize_with(self.particle_count, || -> ChargedParticle {
+                    let position = pos2(-(i as f32) * self.particle_spacing, 0.0);
                     i += 1;
                     ChargedParticle::new(
-                        pos2(-(i as f32) * self.particle_spacing, 0.0),
-                        self.size,
+                        position,
+                        self.applied_field.intervals(),
                         self.particle_type,
+                        self.time_step,
                     )
                 });
         }
+        self.particles.extend(placed);
+        // particles taken out of the row take their place in groups with them
+        self.prune_groups();
+        self.regrid();
+    }
+
+    // adds a particle at a point on the x axis, besides the evenly spaced row
+    pub fn add_particle(&mut self, x: f32) {
+        self.placed.push(x);
+        self.particles.push(ChargedParticle::new(
+            pos2(x, 0.0),
+            self.applied_field.intervals(),
+            self.particle_type,
+            self.time_step,
+        ));
+        self.regrid();
     }
 
     // move simulation forward by one time interval
     pub fn update(&mut self) -> bool {
-        // set applied and resultant fields from waveform
-        self.applied_field
-            .set_from_function(self.waveform.properties().function, self.t);
-        self.resultant_field
-            .set_from_function(self.waveform.properties().function, self.t);
-
-        for i in 0..self.particles.len() {
-            let e_y = self.resultant_field.value_at(self.particles[i].position.x);
-            let p = self.particles.get_mut(i).unwrap();
-            // set particle properties to those set in the UI
-            p.mass = self.particle_mass;
-            p.spring_constant = self.spring_constant;
-            p.damping = self.damping;
-            p.update(e_y, self.t);
-            // combine this particle's contribution
-            self.resultant_field.add(&p.field);
+        self.apply_commands();
+        self.update_properties();
+        for p in &mut self.particles {
+            p.decimation = self.history_decimation;
         }
+        // set applied and resultant fields from the shape of the wave, moved along to the current time
+        self.set_applied_fields();
+        self.kick_particles();
+
+        let parallel = self.parallel && self.particles.len() >= PARALLEL_PARTICLES;
+        self.solver
+            .step(&mut self.particles, &self.applied_field, self.t, parallel);
+        // combine the particles' contributions. each division adds them up in the same order, whichever thread
+        // it is on, so the sum is the same however many threads there are
+        let fields: Vec<&[f32]> = self.particles.iter().map(|p| p.field()).collect();
+        in_parallel(
+            self.resultant_field.values_mut(),
+            parallel,
+            |start, chunk| {
+                for field in &fields {
+                    for (e, contribution) in chunk.iter_mut().zip(&field[start..]) {
+                        *e += contribution;
+                    }
+                }
+            },
+        );
+
+        self.apply_limiter();
+        self.scattered_field = &self.resultant_field - &self.applied_field;
 
-        self.t += TIME_STEP;
+        // exponential moving average of |E|², which smooths out the oscillation of the wave
+        let fraction = self.time_step / INTENSITY_WINDOW;
+        self.intensity *= 1.0 - fraction;
+        self.intensity += &(&self.resultant_field * &self.resultant_field * fraction);
+
+        self.steps += 1;
+        if self.divergence.is_none() {
+            self.divergence = self.find_divergence().map(|(quantity, kind)| Divergence {
+                quantity,
+                kind,
+                step: self.steps,
+                time: narrow(self.t),
+            });
+        }
+
+        self.t += Float::from(self.time_step);
 
         // returning true indiates the end and stops the simulation.
         //return self.t > (1.3 * self.size.span() / C); // terminate simulation after wave has cleared the screen
-        return false;
+        false
     }
 
     pub fn max_particles(&self) -> u32 {
@@ -264,7 +668,12 @@ impl Simulation {
         &self.particles
     }
 
-    pub fn set_particle_type(&mut self, particle_type: ChargedParticleType) {
+    // the particle with an ID and its index, if it still exists
+    pub fn particle(&self, id: ParticleId) -> Option<(usize, &ChargedParticle)> {
+        self.particles.iter().enumerate().find(|(_, p)| p.id == id)
+    }
+
+    fn set_particle_type(&mut self, particle_type: ChargedParticleType) {
         self.particle_type = particle_type;
         // Update default values for the new particle type
         self.particle_mass = particle_type.mass();
@@ -274,8 +683,163 @@ impl Simulation {
         self.reset();
     }
 
+    pub fn apply_preset(&mut self, preset: Preset) {
+        self.set_up(&preset.scene());
+    }
+
+    // current setup of the simulation
+    pub fn scene(&self) -> Scene {
+        Scene {
+            waveform: self.waveform,
+            frequency: self.frequency,
+            beat: self.beat,
+            parameter_changes: self.parameter_changes,
+            limiter: self.limiter,
+            particle_type: self.particle_type,
+            particle_count: self.particle_count,
+            particle_spacing: self.particle_spacing,
+            mass: self.particle_mass,
+            spring_constant: self.spring_constant,
+            damping: self.damping,
+            temperature: self.temperature,
+            samples: self.samples.clone(),
+            solver: self.solver.solver_type(),
+            placed: self.placed.clone(),
+            groups: self.group_setups(),
+            captions: self.captions.clone(),
+        }
+    }
+
+    // replaces the setup of the simulation with one from outside it, such as a file or link, and restarts it.
+    // the scene is checked first, and rejected if it couldn't be set up with the controls
+    pub fn apply_scene(&mut self, scene: &Scene) -> Result<(), String> {
+        scene.validate()?;
+        self.set_up(scene);
+        Ok(())
+    }
+
+    // replaces the setup of the simulation with a scene known to be valid, such as a preset or one taken
+    // from a running simulation, and restarts it
+    fn set_up(&mut self, scene: &Scene) {
+        self.samples = scene.samples.clone();
+        self.waveform = match (scene.waveform, &self.samples) {
+            (Waveform::Sampled, None) => Waveform::GaussianPacket,
+            (waveform, _) => waveform,
+        };
+        self.frequency = scene.frequency;
+        self.beat = scene.beat;
+        self.parameter_changes = scene.parameter_changes;
+        self.limiter = scene.limiter;
+        self.particle_type = scene.particle_type;
+        self.particle_count = scene.particle_count;
+        self.particle_spacing = scene.particle_spacing;
+        self.particle_mass = scene.mass;
+        self.spring_constant = scene.spring_constant;
+        self.damping = scene.damping;
+        self.temperature = scene.temperature;
+        if scene.solver != self.solver.solver_type() {
+            self.solver = scene.solver.create(&self.even_grid, self.time_step);
+        }
+        self.placed = scene.placed.clone();
+        self.captions = scene.captions.clone();
+        self.reset();
+        self.set_group_setups(&scene.groups);
+    }
+
+    // uses a wave loaded from a file as the applied wave, and restarts
+    pub fn set_sampled_wave(&mut self, samples: SampledWave) {
+        self.samples = Some(Arc::new(samples));
+        self.waveform = Waveform::Sampled;
+        self.reset();
+    }
+
+    pub fn sampled_wave(&self) -> Option<&SampledWave> {
+        self.samples.as_deref()
+    }
+
+    // text of the caption for the current time, the one that started latest if several overlap
+    pub fn caption(&self) -> Option<&str> {
+        let t = self.time();
+        self.captions
+            .iter()
+            .filter(|c| (c.start..c.end).contains(&t))
+            .max_by(|a, b| a.start.total_cmp(&b.start))
+            .map(|c| c.text.as_str())
+    }
+
+    // the first value found to have blown up since the start, if any
+    pub fn divergence(&self) -> Option<&Divergence> {
+        self.divergence.as_ref()
+    }
+
     pub fn time(&self) -> f32 {
-        self.t
+        narrow(self.t)
+    }
+
+    // number of updates since the simulation was reset
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    // time between updates
+    pub fn time_step(&self) -> f32 {
+        self.time_step
+    }
+
+    pub fn waveform(&self) -> Waveform {
+        self.waveform
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    pub fn beat(&self) -> Beat {
+        self.beat
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particle_count
+    }
+
+    pub fn particle_spacing(&self) -> f32 {
+        self.particle_spacing
+    }
+
+    pub fn particle_type(&self) -> ChargedParticleType {
+        self.particle_type
+    }
+
+    pub fn particle_mass(&self) -> f32 {
+        self.particle_mass
+    }
+
+    pub fn spring_constant(&self) -> f32 {
+        self.spring_constant
+    }
+
+    pub fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    pub fn history_decimation(&self) -> usize {
+        self.history_decimation
+    }
+
+    pub fn parameter_changes(&self) -> ParameterChanges {
+        self.parameter_changes
+    }
+
+    // whether the particles are updated, and their fields added up, on several threads once there are enough of
+    // them. the fields are the same either way, only found faster
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    // changes the time between updates, from when the simulation is next reset, as the particles' histories
+    // are recorded at the old one
+    pub fn set_time_step(&mut self, time_step: f32) {
+        self.time_step = time_step;
     }
 
     pub fn x_intervals(&self) -> &[f32] {
@@ -289,4 +853,332 @@ impl Simulation {
     pub fn resultant_field(&self) -> &[f32] {
         self.resultant_field.values()
     }
+
+    // field radiated by all the particles together
+    pub fn scattered_field(&self) -> &[f32] {
+        self.scattered_field.values()
+    }
+
+    // the applied, resultant and scattered fields along with their divisions, for analysis such as integrals
+    pub fn fields(&self) -> [&Field; 3] {
+        [
+            &self.applied_field,
+            &self.resultant_field,
+            &self.scattered_field,
+        ]
+    }
+
+    // resultant field squared, averaged over the last few seconds
+    pub fn intensity(&self) -> &[f32] {
+        self.intensity.values()
+    }
+
+    // angular frequency of the oscillation inside the applied wave, if it has a single one
+    pub fn carrier(&self) -> Option<f32> {
+        self.waveform
+            .properties_with(self.samples.as_deref(), self.frequency, self.beat)
+            .carrier
+    }
+
+    // peak strength of the applied wave, which is 1 for the built-in waveforms other than a beat
+    pub fn applied_amplitude(&self) -> f32 {
+        match (self.waveform, &self.samples) {
+            (Waveform::Sampled, Some(samples)) => samples.peak().max(f32::MIN_POSITIVE),
+            (Waveform::Beat, _) => self
+                .beat
+                .amplitudes
+                .iter()
+                .sum::<f32>()
+                .max(f32::MIN_POSITIVE),
+            _ => 1.0,
+        }
+    }
+
+    pub fn applied_field_at(&self, x: f32) -> f32 {
+        self.applied_field.value_at(x)
+    }
+
+    pub fn resultant_field_at(&self, x: f32) -> f32 {
+        self.resultant_field.value_at(x)
+    }
+
+    // resultant field at (x, 0) and time t, worked out afresh from the applied wave and the particles' histories
+    // rather than interpolated from the grid, at any time from the start to that of the fields last calculated.
+    // None outside that time, and in the FDTD solver, whose sheets of charge only have fields on the grid as they
+    // are now
+    pub fn resultant_at(&self, x: f32, t: f32) -> Option<f32> {
+        let latest = narrow(self.t) - self.time_step;
+        if self.solver_type() != SolverType::RetardedPotentials || !(0.0..=latest).contains(&t) {
+            return None;
+        }
+        let wave = wave_function(
+            self.waveform,
+            self.samples.as_deref(),
+            self.frequency,
+            self.beat,
+        );
+        Some(wave(x, t) + self.particles.iter().map(|p| p.field_at(x, t)).sum::<f32>())
+    }
+
+    // evenly spaced x coordinates, for analysis that can't use the refined grid
+    pub fn even_intervals(&self) -> &[f32] {
+        &self.even_grid
+    }
+
+    // resultant field interpolated at even_intervals
+    pub fn even_resultant_field(&self) -> Vec<f32> {
+        self.resultant_field
+            .resampled(self.size, self.divisions, Interpolation::Linear)
+            .values()
+            .to_vec()
+    }
+
+    // number of evenly spaced divisions, before refinement near the particles
+    pub fn divisions(&self) -> usize {
+        self.divisions
+    }
+
+    // number of field divisions, including those added near the particles
+    pub fn grid_points(&self) -> usize {
+        self.applied_field.intervals().len()
+    }
+
+    // smallest distance between neighbouring field divisions
+    pub fn grid_step(&self) -> f32 {
+        self.applied_field.step()
+    }
+
+    // name of the method used to calculate the fields
+    pub fn solver_name(&self) -> &'static str {
+        self.solver.solver_type().name()
+    }
+
+    pub fn solver_type(&self) -> SolverType {
+        self.solver.solver_type()
+    }
+
+    // switches the method used to calculate the fields, and restarts
+    pub fn set_solver(&mut self, solver_type: SolverType) {
+        self.solver = solver_type.create(&self.even_grid, self.time_step);
+        self.reset();
+    }
+
+    // natural angular frequency of the particles' simple harmonic motion
+    pub fn natural_frequency(&self) -> f32 {
+        (self.spring_constant / self.particle_mass).sqrt()
+    }
+
+    // total kinetic and potential energy of all the particles' oscillations
+    pub fn oscillator_energy(&self) -> (f32, f32) {
+        self.particles
+            .iter()
+            .map(|p| p.energy())
+            .fold((0.0, 0.0), |(k, u), (pk, pu)| (k + pk, u + pu))
+    }
+
+    // bytes allocated to the motion histories of all particles
+    pub fn history_memory(&self) -> usize {
+        self.particles.iter().map(|p| p.history_memory()).sum()
+    }
+
+    // keeps the particles' motion histories within a number of bytes, first by thinning out their older motion
+    // further, then once that is at MAX_HISTORY_DECIMATION by forgetting motion so old it is no longer seen
+    // anywhere in the world. returns whether they fit
+    pub fn limit_history(&mut self, bytes: usize) -> bool {
+        let t = self.t;
+        while self.history_memory() > bytes && self.history_decimation < MAX_HISTORY_DECIMATION {
+            self.history_decimation = (self.history_decimation * 2).min(MAX_HISTORY_DECIMATION);
+            for p in &mut self.particles {
+                p.decimation = self.history_decimation;
+                p.decimate(t);
+                p.history.shrink_to_fit();
+            }
+        }
+        if self.history_memory() > bytes {
+            // the furthest any point is from a particle is the width of the world
+            let cutoff = t - Float::from(self.size.span() / C);
+            for p in &mut self.particles {
+                p.forget_before(cutoff);
+            }
+        }
+        self.history_memory() <= bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use proptest::test_runner::TestRunner;
+
+    use super::config::SimulationConfig;
+    use super::scene::{Caption, Scene};
+    use super::variables::MAX_HISTORY_DECIMATION;
+    use super::variables::{Float, WORLD_SIZE};
+    use super::waveform::Waveform;
+    use super::{ChargedParticle, Simulation};
+
+    // the field worked out anywhere matches the grid where the two meet
+    #[test]
+    fn resultant_at_matches_grid() {
+        let scene = Scene {
+            waveform: Waveform::PlaneWave,
+            particle_count: 3,
+            ..Scene::blank()
+        };
+        let mut simulation =
+            Simulation::from_config(&SimulationConfig::default().scene(scene)).unwrap();
+        assert_eq!(simulation.resultant_at(0.0, 0.0), None);
+        for _ in 0..600 {
+            simulation.update();
+        }
+        let t = simulation.time() - simulation.time_step();
+        for (x, e) in simulation
+            .x_intervals()
+            .iter()
+            .zip(simulation.resultant_field())
+            .step_by(50)
+        {
+            let at = simulation.resultant_at(*x, t).unwrap();
+            assert!((at - e).abs() < 1e-3, "{at} != {e} at x = {x}");
+        }
+        assert_eq!(simulation.resultant_at(0.0, simulation.time()), None);
+    }
+
+    // captions come and go with the simulation time, and are kept in the scene taken from the simulation
+    #[test]
+    fn captions_follow_time() {
+        let caption = |start, end, text: &str| Caption {
+            start,
+            end,
+            text: text.to_owned(),
+        };
+        let scene = Scene {
+            captions: vec![caption(0.5, 2.0, "first"), caption(1.0, 1.5, "second")],
+            ..Scene::blank()
+        };
+        let mut simulation =
+            Simulation::from_config(&SimulationConfig::default().scene(scene.clone())).unwrap();
+        let mut shown = Vec::new();
+        while simulation.time() < 2.5 {
+            let caption = simulation.caption().map(str::to_owned);
+            if shown.last() != Some(&caption) {
+                shown.push(caption);
+            }
+            simulation.update();
+        }
+        let expected = [None, Some("first"), Some("second"), Some("first"), None];
+        assert_eq!(shown, expected.map(|text| text.map(str::to_owned)));
+        assert_eq!(simulation.scene().captions, scene.captions);
+    }
+
+    // motion forgotten to keep within a memory cap is too old to be seen anywhere, so the fields don't change
+    #[test]
+    fn forgetting_history_leaves_fields_unchanged() {
+        let scene = Scene {
+            waveform: Waveform::PlaneWave,
+            particle_count: 3,
+            ..Scene::blank()
+        };
+        let [mut kept, mut capped] = [(); 2].map(|_| {
+            let mut simulation =
+                Simulation::from_config(&SimulationConfig::default().scene(scene.clone())).unwrap();
+            simulation.history_decimation = MAX_HISTORY_DECIMATION;
+            simulation
+        });
+        while kept.time() < 60.0 {
+            kept.update();
+            capped.update();
+            capped.limit_history(0);
+        }
+        assert!(capped.history_memory() < kept.history_memory() / 2);
+        assert_eq!(kept.resultant_field(), capped.resultant_field());
+    }
+
+    // a saved simulation carries on just as the one it was saved from
+    #[cfg(feature = "serde-state")]
+    #[test]
+    fn restored_simulation_carries_on_unchanged() {
+        let scene = Scene {
+            waveform: Waveform::PlaneWave,
+            particle_count: 3,
+            ..Scene::blank()
+        };
+        let mut simulation =
+            Simulation::from_config(&SimulationConfig::default().scene(scene)).unwrap();
+        for _ in 0..300 {
+            simulation.update();
+        }
+        let mut restored: Simulation =
+            ron::from_str(&ron::to_string(&simulation).unwrap()).unwrap();
+        for _ in 0..300 {
+            simulation.update();
+            restored.update();
+        }
+        assert_eq!(simulation.resultant_field(), restored.resultant_field());
+        assert!(
+            simulation
+                .particles()
+                .iter()
+                .zip(restored.particles())
+                .all(|(a, b)| a.id() == b.id() && a.position() == b.position())
+        );
+    }
+
+    // the retarded motion of a particle, whether its history is thinned out or not, is never from later than asked
+    // for, is from further back the further away the point is, lies between the two recorded instants either side,
+    // and clamps to the start of the history close to t = 0
+    #[test]
+    fn retarded_lookup_invariants() {
+        let scene = Scene {
+            waveform: Waveform::PlaneWave,
+            particle_count: 2,
+            ..Scene::blank()
+        };
+        let mut simulation =
+            Simulation::from_config(&SimulationConfig::default().scene(scene)).unwrap();
+        simulation.history_decimation = MAX_HISTORY_DECIMATION;
+        while simulation.time() < 40.0 {
+            simulation.update();
+        }
+        let mut thinned = simulation.clone();
+        thinned.limit_history(simulation.history_memory() / 4);
+        let now = Float::from(simulation.time());
+        let particles: Vec<ChargedParticle> = [simulation, thinned]
+            .iter()
+            .flat_map(|s| s.particles().to_vec())
+            .collect();
+        assert!(particles.iter().any(|p| p.decimated > 0));
+
+        let points = (
+            0..particles.len(),
+            WORLD_SIZE.min..=WORLD_SIZE.max,
+            0.0..=1.0f32,
+            prop_oneof![0.0..=Float::from(0.1f32), 0.0..=now],
+        );
+        TestRunner::default()
+            .run(&points, |(i, x, further, t)| {
+                let p = &particles[i];
+                let seen = p.retarded_rva(x, t);
+                prop_assert!(seen.t <= t);
+                prop_assert!(seen.t >= 0.0);
+
+                let far = x + (x - p.position.x).signum() * further;
+                prop_assert!(p.retarded_rva(far, t).t <= seen.t);
+
+                let latest = p.snapshot(t);
+                let after = p.history.partition_point(|h| h.t <= seen.t);
+                let before = &p.history[after.saturating_sub(1)];
+                let next = p.history.get(after).unwrap_or(&latest);
+                let (low, high) = (before.y.min(next.y), before.y.max(next.y));
+                let tolerance = 1e-5 * (1.0 + high.abs());
+                prop_assert!(
+                    seen.y >= low - tolerance && seen.y <= high + tolerance,
+                    "{} outside {low}..{high} at t = {}",
+                    seen.y,
+                    seen.t
+                );
+                Ok(())
+            })
+            .unwrap();
+    }
 }
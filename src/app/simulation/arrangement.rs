@@ -0,0 +1,66 @@
+//! Copying and pasting arrangements of particles, so that periodic structures such as Bragg stacks
+//! can be built by pasting the same few particles over and over.
+//! Particles keep their spacing and the group they were in, so pasted layers share the properties of the originals.
+
+use super::group::GroupProperties;
+use super::{ParticleId, Simulation};
+
+// a copied particle, at an offset from the leftmost one copied
+struct CopiedParticle {
+    offset: f32,
+    group: Option<(String, GroupProperties)>,
+}
+
+pub struct Arrangement {
+    particles: Vec<CopiedParticle>,
+}
+
+impl Arrangement {
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    // never true of an arrangement copied from a simulation, which gives None rather than an empty one
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+}
+
+impl Simulation {
+    // copies the positions and groups of some particles, or None if none of them exist
+    pub fn copy_arrangement(&self, ids: &[ParticleId]) -> Option<Arrangement> {
+        let copied: Vec<_> = ids.iter().filter_map(|id| self.particle(*id)).collect();
+        let left = copied.iter().map(|(_, p)| p.position.x).reduce(f32::min)?;
+        let particles = copied
+            .iter()
+            .map(|(_, p)| CopiedParticle {
+                offset: p.position.x - left,
+                group: self
+                    .groups
+                    .iter()
+                    .find(|group| group.contains(p.id))
+                    .map(|group| (group.name.clone(), group.properties)),
+            })
+            .collect();
+        Some(Arrangement { particles })
+    }
+
+    // adds the particles of an arrangement with the leftmost at x, returning their IDs.
+    // any that would fall outside the simulation are left out
+    pub fn paste_arrangement(&mut self, arrangement: &Arrangement, x: f32) -> Vec<ParticleId> {
+        let mut pasted = Vec::new();
+        for particle in &arrangement.particles {
+            let position = x + particle.offset;
+            if !self.size.contains(position) {
+                continue;
+            }
+            self.add_particle(position);
+            let id = self.particles[self.particles.len() - 1].id;
+            if let Some((name, properties)) = &particle.group {
+                self.join_group(name, *properties, vec![id]);
+            }
+            pasted.push(id);
+        }
+        pasted
+    }
+}
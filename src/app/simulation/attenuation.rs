@@ -0,0 +1,109 @@
+//! Exponential fit to the intensity inside a slab of particles, measuring how strongly the slab absorbs the wave.
+//! In an absorbing medium the intensity falls by the same fraction over each unit of distance travelled,
+//! I(d) = I₀ e^(−αd) as in the Beer–Lambert law, so its logarithm falls in a straight line fitted by least squares.
+
+use crate::app::simulation::Simulation;
+
+// intensity below which points are left out of the fit, as their logarithm is dominated by noise
+const MIN_INTENSITY: f32 = 1e-6;
+
+// intensity falling exponentially from where the wave enters the slab, at the right, to where it leaves
+#[derive(Debug, Clone, Copy)]
+pub struct ExponentialFit {
+    pub start: f32,       // x coordinate of the left edge of the slab
+    pub end: f32,         // x coordinate of the right edge of the slab, where the wave enters
+    pub entering: f32,    // intensity where the wave enters the slab
+    pub coefficient: f32, // attenuation coefficient α, the fraction of intensity lost per unit distance
+}
+
+impl ExponentialFit {
+    pub fn value_at(&self, x: f32) -> f32 {
+        self.entering * (-self.coefficient * (self.end - x)).exp()
+    }
+}
+
+impl Simulation {
+    // fits an exponential to the intensity between the first and last particles.
+    // None if there are fewer than two particles, or too little intensity among them to fit
+    pub fn attenuation_fit(&self) -> Option<ExponentialFit> {
+        if self.particles.len() < 2 {
+            return None;
+        }
+        let positions = self.particles.iter().map(|p| p.position.x);
+        let start = positions.clone().fold(f32::INFINITY, f32::min);
+        let end = positions.fold(f32::NEG_INFINITY, f32::max);
+
+        // least squares fit of ln(I) = a + b (x − end), where b is the attenuation coefficient
+        let points: Vec<(f32, f32)> = self
+            .x_intervals()
+            .iter()
+            .zip(self.intensity())
+            .filter(|(x, intensity)| (start..=end).contains(*x) && **intensity > MIN_INTENSITY)
+            .map(|(x, intensity)| (x - end, intensity.ln()))
+            .collect();
+        if points.len() < 2 {
+            return None;
+        }
+        let n = points.len() as f32;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f32>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f32>() / n;
+        let covariance: f32 = points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let variance: f32 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        let coefficient = covariance / variance;
+        let fit = ExponentialFit {
+            start,
+            end,
+            entering: (mean_y - coefficient * mean_x).exp(),
+            coefficient,
+        };
+        (fit.coefficient.is_finite() && fit.entering.is_finite()).then_some(fit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::waveform::Waveform;
+
+    // a row of particles from x = -12 to 0, with the intensity among them set directly
+    fn row(intensity: impl Fn(f32) -> f32) -> Simulation {
+        let mut simulation = Simulation::from_config(&SimulationConfig::default().scene(Scene {
+            waveform: Waveform::PlaneWave,
+            particle_count: 5,
+            particle_spacing: 3.0,
+            ..Scene::blank()
+        }))
+        .unwrap();
+        simulation
+            .intensity
+            .set_from_function(|x, _| intensity(x), 0.0);
+        simulation
+    }
+
+    #[test]
+    fn fit_recovers_exponential_decay() {
+        let (entering, coefficient) = (0.8, 0.15);
+        let fit = row(|x| entering * (coefficient * x).exp())
+            .attenuation_fit()
+            .unwrap();
+        assert_eq!((fit.start, fit.end), (-12.0, 0.0));
+        assert!((fit.coefficient - coefficient).abs() < 1e-4);
+        assert!((fit.entering - entering).abs() < 1e-4);
+        assert!((fit.value_at(-6.0) - entering * (-6.0 * coefficient).exp()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn fit_leaves_out_points_without_intensity() {
+        // only the right half of the slab has any intensity, which the fit is made to
+        let fit = row(|x| if x > -6.0 { (0.2 * x).exp() } else { 0.0 })
+            .attenuation_fit()
+            .unwrap();
+        assert!((fit.coefficient - 0.2).abs() < 1e-4);
+        assert!(row(|_| 0.0).attenuation_fit().is_none());
+    }
+}
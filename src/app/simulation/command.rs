@@ -0,0 +1,72 @@
+//! Changes to the setup of a simulation made from outside it, such as from the controls.
+//! Rather than changing the simulation's fields directly, they are sent to it and queued, then applied together at
+//! the start of the next update, or when apply_commands is called between updates, so the simulation only changes at
+//! known points between steps.
+
+use super::Simulation;
+use super::limiter::Limiter;
+use super::particle::ChargedParticleType;
+use super::ramp::ParameterChanges;
+use super::waveform::{Beat, Waveform};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimulationCommand {
+    Waveform(Waveform),
+    Frequency(f32), // angular frequency of a plane wave, and of a beat's first wave
+    Beat(Beat),
+    ParticleCount(usize),
+    ParticleSpacing(f32),
+    ParticleType(ChargedParticleType), // also sets the type's mass, spring constant and damping, and resets
+    Mass(f32),
+    SpringConstant(f32),
+    Damping(f32),
+    Temperature(f32), // of the heat bath giving the particles thermal motion
+    HistoryDecimation(usize),
+    ParameterChanges(ParameterChanges),
+    Limiter(Limiter),
+}
+
+impl Simulation {
+    // queues a change, to be made at the start of the next update or when apply_commands is called
+    pub fn send(&mut self, command: SimulationCommand) {
+        self.commands.push(command);
+    }
+
+    // makes the changes queued since they were last made, in the order they were sent
+    pub fn apply_commands(&mut self) {
+        let (mut count_changed, mut spacing_changed) = (false, false);
+        for command in std::mem::take(&mut self.commands) {
+            match command {
+                // a sampled wave can only be chosen once one has been loaded
+                SimulationCommand::Waveform(Waveform::Sampled) if self.samples.is_none() => {}
+                SimulationCommand::Waveform(waveform) => self.waveform = waveform,
+                SimulationCommand::Frequency(frequency) => self.frequency = frequency,
+                SimulationCommand::Beat(beat) => self.beat = beat,
+                SimulationCommand::ParticleCount(count) => {
+                    count_changed |= count != self.particle_count;
+                    self.particle_count = count;
+                }
+                SimulationCommand::ParticleSpacing(spacing) => {
+                    spacing_changed |= spacing != self.particle_spacing;
+                    self.particle_spacing = spacing;
+                }
+                SimulationCommand::ParticleType(particle_type) => {
+                    self.set_particle_type(particle_type)
+                }
+                SimulationCommand::Mass(mass) => self.particle_mass = mass,
+                SimulationCommand::SpringConstant(k) => self.spring_constant = k,
+                SimulationCommand::Damping(damping) => self.damping = damping,
+                SimulationCommand::Temperature(temperature) => self.temperature = temperature,
+                SimulationCommand::HistoryDecimation(decimation) => {
+                    self.history_decimation = decimation
+                }
+                SimulationCommand::ParameterChanges(changes) => self.parameter_changes = changes,
+                SimulationCommand::Limiter(limiter) => self.limiter = limiter,
+            }
+        }
+        // the particles are only rearranged once, however many changes were made to the row
+        if count_changed || spacing_changed {
+            self.update_particles(spacing_changed);
+        }
+    }
+}
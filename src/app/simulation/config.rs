@@ -0,0 +1,183 @@
+//! Everything a simulation is constructed from: the world and its discretisation, along with the scene set up in it.
+//! Simulations are only made through Simulation::from_config, which checks the configuration first, so the app,
+//! studies rerunning a scene and tests all start from the same validated defaults.
+
+use egui::Rangef;
+
+use super::Simulation;
+use super::particle::ChargedParticleType;
+use super::scene::Scene;
+use super::variables::{DIVISIONS, TIME_STEP, WORLD_SIZE};
+use super::waveform::Waveform;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationConfig {
+    pub world_size: Rangef, // dimensions of the x axis
+    pub divisions: usize, // number of evenly spaced divisions, before refinement near the particles
+    pub time_step: f32,   // time between updates
+    pub scene: Scene,     // applied wave, particles and the medium they make up
+}
+
+impl Default for SimulationConfig {
+    // a Gaussian packet meeting a single electron, as the app starts with
+    fn default() -> Self {
+        SimulationConfig {
+            world_size: WORLD_SIZE,
+            divisions: DIVISIONS,
+            time_step: TIME_STEP,
+            scene: Scene {
+                particle_count: 1,
+                ..Scene::blank()
+            },
+        }
+    }
+}
+
+impl SimulationConfig {
+    pub fn world_size(mut self, world_size: Rangef) -> Self {
+        self.world_size = world_size;
+        self
+    }
+
+    pub fn divisions(mut self, divisions: usize) -> Self {
+        self.divisions = divisions;
+        self
+    }
+
+    pub fn time_step(mut self, time_step: f32) -> Self {
+        self.time_step = time_step;
+        self
+    }
+
+    pub fn waveform(mut self, waveform: Waveform) -> Self {
+        self.scene.waveform = waveform;
+        self
+    }
+
+    // an evenly spaced row of particles from the origin towards -x, as the particle count and spacing controls set
+    pub fn layout(mut self, particle_count: usize, particle_spacing: f32) -> Self {
+        self.scene.particle_count = particle_count;
+        self.scene.particle_spacing = particle_spacing;
+        self
+    }
+
+    // the particles the wave passes through, and their properties, which make up the medium
+    pub fn medium(
+        mut self,
+        particle_type: ChargedParticleType,
+        mass: f32,
+        spring_constant: f32,
+        damping: f32,
+    ) -> Self {
+        self.scene.particle_type = particle_type;
+        self.scene.mass = mass;
+        self.scene.spring_constant = spring_constant;
+        self.scene.damping = damping;
+        self
+    }
+
+    // replaces everything set up in the world, i.e. the wave, the particles and their properties, with a scene
+    // such as a preset or one opened from a file
+    pub fn scene(mut self, scene: Scene) -> Self {
+        self.scene = scene;
+        self
+    }
+
+    // checks the world can be simulated, then that the scene could have been set up with the controls
+    pub fn validate(&self) -> Result<(), String> {
+        let Rangef { min, max } = self.world_size;
+        if !(min.is_finite() && max.is_finite() && min <= 0.0 && 0.0 < max) {
+            return Err("the world must be finite and contain the origin".to_owned());
+        }
+        if self.divisions < 2 {
+            return Err("the world must have at least 2 divisions".to_owned());
+        }
+        if !(self.time_step.is_finite() && self.time_step > 0.0) {
+            return Err("the time step must be positive".to_owned());
+        }
+        self.scene.validate()?;
+        // the scene is checked against the default world, which this one may be smaller than
+        let max_particles = (min.abs() / self.scene.particle_spacing).floor() as usize;
+        if self.scene.particle_count > max_particles {
+            return Err(format!(
+                "at most {max_particles} particles fit in the world at this spacing"
+            ));
+        }
+        if let Some(x) = self
+            .scene
+            .placed
+            .iter()
+            .find(|x| !self.world_size.contains(**x))
+        {
+            return Err(format!("a particle placed at {x} is outside the world"));
+        }
+        Ok(())
+    }
+}
+
+impl Simulation {
+    // a simulation at the start of a configuration, or why the configuration can't be simulated
+    pub fn from_config(config: &SimulationConfig) -> Result<Self, String> {
+        config.validate()?;
+        let mut simulation =
+            Simulation::with_world(config.world_size, config.divisions, config.time_step);
+        simulation.set_up(&config.scene);
+        Ok(simulation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Rangef;
+
+    use super::SimulationConfig;
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::particle::ChargedParticleType;
+    use crate::app::simulation::scene::Scene;
+
+    #[test]
+    fn invalid_configurations_are_rejected() {
+        assert!(Simulation::from_config(&SimulationConfig::default()).is_ok());
+        for config in [
+            SimulationConfig::default().divisions(1),
+            SimulationConfig::default().time_step(0.0),
+            SimulationConfig::default().time_step(f32::NAN),
+            SimulationConfig::default().world_size(Rangef::new(1.0, 2.0)),
+            SimulationConfig::default().world_size(Rangef::new(-1.0, f32::INFINITY)),
+            SimulationConfig::default().scene(Scene {
+                particle_spacing: -1.0,
+                ..Scene::blank()
+            }),
+            SimulationConfig::default().layout(3, 0.0),
+            SimulationConfig::default().layout(10, 3.0),
+            SimulationConfig::default()
+                .world_size(Rangef::new(-5.0, 4.0))
+                .layout(3, 2.0),
+            SimulationConfig::default().medium(ChargedParticleType::Electron, 0.0, 1.0, 0.1),
+            SimulationConfig::default().medium(ChargedParticleType::Electron, 1.0, -1.0, 0.1),
+            SimulationConfig::default().medium(ChargedParticleType::Electron, 1.0, 1.0, f32::NAN),
+        ] {
+            assert!(Simulation::from_config(&config).is_err(), "{config:?}");
+        }
+    }
+
+    // every property set with the builder reaches the simulation made from it
+    #[test]
+    fn simulation_follows_configuration() {
+        let config = SimulationConfig::default()
+            .world_size(Rangef::new(-10.0, 5.0))
+            .divisions(300)
+            .time_step(0.01)
+            .layout(4, 2.0)
+            .medium(ChargedParticleType::Proton, 2.0, 0.5, 0.05);
+        let simulation = Simulation::from_config(&config).unwrap();
+        assert_eq!(*simulation.size(), Rangef::new(-10.0, 5.0));
+        assert_eq!(simulation.time_step(), 0.01);
+        assert_eq!(simulation.particles().len(), 4);
+        assert_eq!(simulation.particle_spacing(), 2.0);
+        assert_eq!(simulation.particle_type(), ChargedParticleType::Proton);
+        assert_eq!(simulation.particle_mass(), 2.0);
+        assert_eq!(simulation.spring_constant(), 0.5);
+        assert_eq!(simulation.damping(), 0.05);
+    }
+}
@@ -0,0 +1,116 @@
+//! Convergence study, estimating how far the results are from those of a perfectly fine discretisation.
+//! The scene is rerun with the time step, and separately the grid spacing, halved several times.
+//! As the discretisation is refined the resultant field should change less and less each time,
+//! and the rate it settles at gives the order of accuracy and an estimate of the remaining error.
+
+use super::Simulation;
+use super::config::SimulationConfig;
+use super::field::Interpolation;
+use super::scene::Scene;
+use super::variables::DIVISIONS;
+
+// number of times the time step and grid spacing are halved
+pub const REFINEMENTS: usize = 3;
+
+// one run of the scene
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub time_step: f32,
+    pub divisions: usize,
+    pub change: f32, // RMS change in the resultant field on halving the time step or grid spacing, relative to its RMS
+    pub order: Option<f32>, // rate the change falls at, e.g. 2 for second order accuracy
+}
+
+// results of refining the time step and the grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvergenceStudy {
+    pub time_steps: Vec<Run>,
+    pub grids: Vec<Run>,
+}
+
+impl ConvergenceStudy {
+    // reruns a scene to a time, at the current time step and grid and finer ones, unless it can't be set up.
+    // progress is called with the number of runs finished
+    pub fn run(
+        scene: &Scene,
+        time: f32,
+        time_step: f32,
+        progress: impl Fn(usize),
+    ) -> Result<Self, String> {
+        let steps = (time / time_step).round().max(1.0) as usize;
+        let config = SimulationConfig::default().scene(scene.clone());
+        let mut finished = 0;
+        let mut series = |refine: &dyn Fn(usize) -> (usize, usize)| {
+            let results = (0..=REFINEMENTS)
+                .map(|level| {
+                    let (time_divisions, divisions) = refine(level);
+                    let time_step = time_step / time_divisions as f32;
+                    let config = config.clone().time_step(time_step).divisions(divisions);
+                    let field = resultant_field(&config, steps * time_divisions)?;
+                    finished += 1;
+                    progress(finished);
+                    Ok((time_step, divisions, field))
+                })
+                .collect::<Result<Vec<(f32, usize, Vec<f32>)>, String>>()?;
+            Ok::<_, String>(compare(&results))
+        };
+        let time_steps = series(&|level| (1 << level, DIVISIONS))?;
+        let grids = series(&|level| (1, (DIVISIONS - 1) * (1 << level) + 1))?;
+        Ok(ConvergenceStudy { time_steps, grids })
+    }
+
+    // number of runs made by a study
+    pub fn runs() -> usize {
+        2 * (REFINEMENTS + 1)
+    }
+}
+
+// estimated error of the coarsest run in a series, relative to the RMS field.
+// the changes between runs shrink geometrically, so the error is the sum of all the changes that would follow
+pub fn estimated_error(runs: &[Run]) -> Option<f32> {
+    let first = runs.first()?;
+    let ratio = 0.5f32.powf(first.order?);
+    (ratio < 1.0).then(|| first.change / (1.0 - ratio))
+}
+
+// resultant field of a configuration after a number of steps, on DIVISIONS evenly spaced divisions whatever the
+// grid it was run on. the smooth curve between divisions keeps the coarser grids' interpolation from adding
+// to their error
+fn resultant_field(config: &SimulationConfig, steps: usize) -> Result<Vec<f32>, String> {
+    let mut simulation = Simulation::from_config(config)?;
+    for _ in 0..steps {
+        simulation.update();
+    }
+    let field =
+        simulation
+            .resultant_field
+            .resampled(config.world_size, DIVISIONS, Interpolation::Cubic);
+    Ok(field.values().to_vec())
+}
+
+// changes between consecutive runs of a series, from coarsest to finest
+fn compare(results: &[(f32, usize, Vec<f32>)]) -> Vec<Run> {
+    let rms = |values: &mut dyn Iterator<Item = f32>| {
+        let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v * v, count + 1));
+        (sum / count.max(1) as f32).sqrt()
+    };
+    let finest = &results[results.len() - 1].2;
+    let scale = rms(&mut finest.iter().copied()).max(f32::MIN_POSITIVE);
+    let changes: Vec<f32> = results
+        .windows(2)
+        .map(|pair| rms(&mut pair[0].2.iter().zip(&pair[1].2).map(|(a, b)| a - b)) / scale)
+        .collect();
+    changes
+        .iter()
+        .enumerate()
+        .map(|(i, change)| Run {
+            time_step: results[i].0,
+            divisions: results[i].1,
+            change: *change,
+            order: changes
+                .get(i + 1)
+                .map(|next| (change / next).log2())
+                .filter(|order| order.is_finite()),
+        })
+        .collect()
+}
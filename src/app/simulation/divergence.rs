@@ -0,0 +1,104 @@
+//! Detection of the simulation blowing up, e.g. when an undamped particle is driven at resonance.
+//! Values that have become infinite or NaN, or grown far beyond anything the applied wave could cause,
+//! are caught on the step they first appear, so the simulation can be stopped with a report of what went wrong.
+
+#[cfg(feature = "serde-state")]
+use serde::{Deserialize, Serialize};
+
+use super::variables::{RUNAWAY_DISPLACEMENT, RUNAWAY_FIELD, narrow};
+use super::{ChargedParticle, Simulation};
+
+// a value that can diverge. particles are identified by their index
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
+pub enum Quantity {
+    Displacement(usize),
+    Velocity(usize),
+    Acceleration(usize),
+    Field,
+}
+
+impl Quantity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quantity::Displacement(_) => "Displacement",
+            Quantity::Velocity(_) => "Velocity",
+            Quantity::Acceleration(_) => "Acceleration",
+            Quantity::Field => "Resultant field",
+        }
+    }
+
+    // index of the particle the value belongs to, if any
+    pub fn particle(&self) -> Option<usize> {
+        match self {
+            Quantity::Displacement(i) | Quantity::Velocity(i) | Quantity::Acceleration(i) => {
+                Some(*i)
+            }
+            Quantity::Field => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
+pub enum Kind {
+    NonFinite, // infinite or NaN
+    Runaway,   // finite, but too large to be physical
+}
+
+impl Kind {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Kind::NonFinite => "became infinite or NaN",
+            Kind::Runaway => "grew out of control",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
+pub struct Divergence {
+    pub quantity: Quantity,
+    pub kind: Kind,
+    pub step: usize, // number of the update it was found after, counting from 1
+    pub time: f32,
+}
+
+impl ChargedParticle {
+    // the first part of this particle's motion found to have diverged, if any
+    fn divergence(&self, index: usize) -> Option<(Quantity, Kind)> {
+        let motion = [
+            (Quantity::Displacement(index), self.displacement),
+            (Quantity::Velocity(index), self.velocity),
+            (Quantity::Acceleration(index), self.acceleration),
+        ];
+        if let Some((quantity, _)) = motion.iter().find(|(_, value)| !value.is_finite()) {
+            return Some((*quantity, Kind::NonFinite));
+        }
+        (narrow(self.displacement).abs() > RUNAWAY_DISPLACEMENT)
+            .then_some((Quantity::Displacement(index), Kind::Runaway))
+    }
+}
+
+impl Simulation {
+    // the first value found to have diverged in the latest update, if any
+    pub(super) fn find_divergence(&self) -> Option<(Quantity, Kind)> {
+        if let Some(divergence) = self
+            .particles
+            .iter()
+            .enumerate()
+            .find_map(|(i, p)| p.divergence(i))
+        {
+            return Some(divergence);
+        }
+        let limit = RUNAWAY_FIELD * self.applied_amplitude();
+        let field = self.resultant_field.values();
+        if field.iter().any(|e| !e.is_finite()) {
+            Some((Quantity::Field, Kind::NonFinite))
+        } else if field.iter().any(|e| e.abs() > limit) {
+            Some((Quantity::Field, Kind::Runaway))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Ensemble of disordered copies of a scene, for statements about the scattering from disorder that hold on average
+//! rather than for one arrangement. Each realisation moves every particle off its place by a random distance drawn
+//! from a normal distribution, from its own seed, so the same ensemble can be run again. The realisations are run to
+//! the same time, and the mean and standard deviation of their resultant fields taken at each point.
+
+use super::Simulation;
+use super::config::SimulationConfig;
+use super::scene::Scene;
+use super::thermal::standard_normal;
+use super::variables::WORLD_SIZE;
+
+// how the realisations of an ensemble are made
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnsembleSetup {
+    pub realisations: usize,
+    pub jitter: f32, // standard deviation of the distance each particle is moved from its place
+    pub seed: u64,   // seed of the first realisation, with the others following on from it
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ensemble {
+    pub x_points: Vec<f32>, // evenly spaced, as the grids of the realisations are refined in different places
+    pub mean: Vec<f32>,     // mean resultant field of the realisations
+    pub deviation: Vec<f32>, // standard deviation of the resultant field about the mean
+    pub realisations: usize,
+    pub time: f32, // time the realisations were run to
+}
+
+impl Ensemble {
+    // runs disordered copies of a scene to a time. progress is called with the number of realisations finished
+    pub fn run(
+        scene: &Scene,
+        setup: EnsembleSetup,
+        time: f32,
+        time_step: f32,
+        progress: impl Fn(usize),
+    ) -> Result<Self, String> {
+        if setup.realisations < 2 {
+            return Err("an ensemble needs at least two realisations".to_owned());
+        }
+        let steps = (time / time_step).round() as usize;
+        let mut x_points = Vec::new();
+        // running mean and sum of squared differences from it, updated one realisation at a time (Welford's method)
+        let (mut mean, mut squares) = (Vec::new(), Vec::new());
+        for i in 0..setup.realisations {
+            let seed = setup.seed.wrapping_add(i as u64);
+            let config = SimulationConfig::default()
+                .time_step(time_step)
+                .scene(disordered(scene, setup.jitter, seed));
+            let mut simulation = Simulation::from_config(&config)?;
+            for _ in 0..steps {
+                simulation.update();
+            }
+            let field = simulation.even_resultant_field();
+            if i == 0 {
+                x_points = simulation.even_intervals().to_vec();
+                mean = vec![0.0; field.len()];
+                squares = vec![0.0; field.len()];
+            }
+            for ((value, mean), squares) in field.iter().zip(&mut mean).zip(&mut squares) {
+                let difference = value - *mean;
+                *mean += difference / (i + 1) as f32;
+                *squares += difference * (value - *mean);
+            }
+            progress(i + 1);
+        }
+        let deviation = squares
+            .iter()
+            .map(|s| (s / (setup.realisations - 1) as f32).sqrt())
+            .collect();
+        Ok(Ensemble {
+            x_points,
+            mean,
+            deviation,
+            realisations: setup.realisations,
+            time: steps as f32 * time_step,
+        })
+    }
+}
+
+// copy of a scene with every particle moved off its place, the evenly spaced row becoming particles placed
+// individually. they keep their order, so the members of groups are the same particles
+pub fn disordered(scene: &Scene, jitter: f32, seed: u64) -> Scene {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let row = (0..scene.particle_count).map(|i| -(i as f32) * scene.particle_spacing);
+    let placed = row
+        .chain(scene.placed.iter().copied())
+        .map(|x| (x + jitter * standard_normal(&mut rng)).clamp(WORLD_SIZE.min, WORLD_SIZE.max))
+        .collect();
+    Scene {
+        particle_count: 0,
+        placed,
+        ..scene.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ensemble, EnsembleSetup, disordered};
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::variables::TIME_STEP;
+    use crate::app::simulation::waveform::Waveform;
+
+    // realisations are repeatable from their seeds, and differ from each other where the wave has met the particles
+    #[test]
+    fn ensemble_spreads_where_wave_meets_disorder() {
+        let scene = Scene {
+            waveform: Waveform::Gaussian,
+            particle_count: 3,
+            ..Scene::blank()
+        };
+        assert_eq!(disordered(&scene, 0.5, 7), disordered(&scene, 0.5, 7));
+        assert_ne!(disordered(&scene, 0.5, 7), disordered(&scene, 0.5, 8));
+        assert_eq!(disordered(&scene, 0.0, 7).placed, [0.0, -3.0, -6.0]);
+
+        let setup = EnsembleSetup {
+            realisations: 4,
+            jitter: 0.5,
+            seed: 1,
+        };
+        let ensemble = Ensemble::run(&scene, setup, 8.0, TIME_STEP, |_| {}).unwrap();
+        assert_eq!(ensemble.mean.len(), ensemble.x_points.len());
+        assert!(ensemble.deviation.iter().any(|d| *d > 1e-4));
+        // the pulse hasn't reached the far end of the world, where every realisation is the same
+        assert!(ensemble.deviation[..10].iter().all(|d| *d < 1e-6));
+
+        let ordered = EnsembleSetup {
+            jitter: 0.0,
+            ..setup
+        };
+        let ensemble = Ensemble::run(&scene, ordered, 2.0, TIME_STEP, |_| {}).unwrap();
+        assert!(ensemble.deviation.iter().all(|d| *d < 1e-6));
+    }
+}
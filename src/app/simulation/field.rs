@@ -1,31 +1,139 @@
 use egui::Rangef;
 use ndarray::{Array, Array1, Ix1, s};
-use std::ops::{Index, IndexMut};
+#[cfg(feature = "serde-state")]
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Sub, SubAssign};
 
-use crate::app::simulation::variables::DIVISIONS;
+use crate::app::simulation::variables::{REFINEMENT_FACTOR, REFINEMENT_WINDOW};
+
+// largest distance of a division from even spacing, relative to the step, for a field to count as evenly spaced
+const UNIFORM_TOLERANCE: f32 = 1e-4;
+
+// x coordinates of a number of evenly spaced divisions across an extent
+pub fn uniform_grid(extent: Rangef, divisions: usize) -> Vec<f32> {
+    Array::linspace(extent.min, extent.max, divisions).to_vec()
+}
+
+// evenly spaced divisions, each split into finer ones where it comes within REFINEMENT_WINDOW of a centre
+pub fn refined_grid(extent: Rangef, divisions: usize, centres: &[f32]) -> Vec<f32> {
+    let coarse = uniform_grid(extent, divisions);
+    let near_centre = |a: f32, b: f32| {
+        centres
+            .iter()
+            .any(|c| a <= c + REFINEMENT_WINDOW && b >= c - REFINEMENT_WINDOW)
+    };
+    let mut grid = Vec::with_capacity(coarse.len());
+    for pair in coarse.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        grid.push(a);
+        if near_centre(a, b) {
+            grid.extend(
+                (1..REFINEMENT_FACTOR).map(|i| a + (b - a) * i as f32 / REFINEMENT_FACTOR as f32),
+            );
+        }
+    }
+    grid.extend(coarse.last());
+    grid
+}
+
+// how values between the divisions of a field are found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    Linear, // straight lines between neighbouring divisions
+    Cubic, // a smooth curve through them, which follows the shape of a wave more closely between coarse divisions
+}
 
 // represents a 1D vector field
+#[derive(Clone)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
 pub struct Field {
     field: Array1<f32>,  // values of field at each point
     extent: Rangef,      // dimensions of field
-    step: f32,           // distance between divisions
+    step: Option<f32>,   // distance between divisions, if they are evenly spaced
     points: Array1<f32>, // x coordinates of each division
 }
 
 impl Field {
-    pub fn new(extent: Rangef) -> Self {
-        let f = Field {
+    // field with divisions at the given x coordinates, which must be increasing.
+    // divisions may be closer together where more detail is needed
+    pub fn from_points(points: &[f32]) -> Self {
+        assert!(
+            points.len() >= 2 && points.windows(2).all(|pair| pair[0] < pair[1]),
+            "field divisions must be increasing"
+        );
+        let extent = Rangef::new(points[0], points[points.len() - 1]);
+        let step = extent.span() / (points.len() - 1) as f32;
+        let uniform = points
+            .iter()
+            .enumerate()
+            .all(|(i, x)| (x - (extent.min + i as f32 * step)).abs() <= UNIFORM_TOLERANCE * step);
+        Field {
             extent,
-            step: extent.span() / ((DIVISIONS - 1) as f32),
-            field: Array::zeros(Ix1(DIVISIONS)),
-            points: Array::linspace(extent.min, extent.max, DIVISIONS),
+            step: uniform.then_some(step),
+            field: Array::zeros(Ix1(points.len())),
+            points: Array1::from_vec(points.to_vec()),
+        }
+    }
+
+    // copy of this field with divisions at other x coordinates, interpolating between the current ones
+    pub fn resample(&self, points: &[f32]) -> Self {
+        let mut field = Field::from_points(points);
+        field.set_from_function(|x, _| self.value_at(x), 0.0);
+        field
+    }
+
+    // copy of this field with a number of evenly spaced divisions across an extent, e.g. at another resolution.
+    // it is 0 outside this field
+    pub fn resampled(
+        &self,
+        extent: Rangef,
+        divisions: usize,
+        interpolation: Interpolation,
+    ) -> Self {
+        let mut field = Field::from_points(&uniform_grid(extent, divisions));
+        let inside = self.extent;
+        field.set_from_function(
+            |x, _| match interpolation {
+                _ if !inside.contains(x) => 0.0,
+                Interpolation::Linear => self.value_at(x),
+                Interpolation::Cubic => self.cubic_at(x),
+            },
+            0.0,
+        );
+        field
+    }
+
+    // value at x, within the field, of the cubic through the divisions either side of it with the slopes found
+    // from their neighbours, so the curve is smooth across divisions however unevenly they are spaced
+    fn cubic_at(&self, x: f32) -> f32 {
+        let (points, values) = (self.intervals(), self.values());
+        let n = points.len();
+        let i = (self.index_of(x).max(0.0) as usize).min(n - 2);
+        let slope = |j: usize| {
+            let (before, after) = (j.saturating_sub(1), (j + 1).min(n - 1));
+            (values[after] - values[before]) / (points[after] - points[before])
         };
-        f
+        let h = points[i + 1] - points[i];
+        let s = (x - points[i]) / h;
+        let (s2, s3) = (s * s, s * s * s);
+        (2.0 * s3 - 3.0 * s2 + 1.0) * values[i]
+            + (s3 - 2.0 * s2 + s) * h * slope(i)
+            + (3.0 * s2 - 2.0 * s3) * values[i + 1]
+            + (s3 - s2) * h * slope(i + 1)
     }
 
     // get fractional index of value at this x coordinate
     fn index_of(&self, x: f32) -> f32 {
-        (x - self.extent.min) / self.step
+        if let Some(step) = self.step {
+            return (x - self.extent.min) / step;
+        }
+        // find the divisions either side with a binary search, extrapolating from the end ones outside the field
+        let points = self.intervals();
+        let upper = points
+            .partition_point(|p| *p <= x)
+            .clamp(1, points.len() - 1);
+        let (x1, x2) = (points[upper - 1], points[upper]);
+        (upper - 1) as f32 + (x - x1) / (x2 - x1)
     }
 
     pub fn values(&self) -> &[f32] {
@@ -39,7 +147,18 @@ impl Field {
     // return interpolated value of field at a given x coordinate
     pub fn value_at(&self, x: f32) -> f32 {
         // closure returns value of field at a given index, or 0 if index is out of bounds
-        let get_value = |i: usize| -> f32 { *self.values().get(i).unwrap_or(&0.0) };
+        self.interpolate(x, |i: usize| -> f32 {
+            *self.values().get(i).unwrap_or(&0.0)
+        })
+    }
+
+    // interpolated value at x of another function evaluated at this field's divisions, as if it were stored in the field.
+    // saves calculating a whole field when only one value is needed
+    pub fn value_of_function_at(&self, x: f32, f: impl Fn(f32) -> f32) -> f32 {
+        self.interpolate(x, |i: usize| self.points.get(i).map_or(0.0, |x| f(*x)))
+    }
+
+    fn interpolate(&self, x: f32, get_value: impl Fn(usize) -> f32) -> f32 {
         // fractional index of coordinate
         let idx = self.index_of(x);
         // closest true indices to the fractional index
@@ -52,8 +171,8 @@ impl Field {
 
     // given a function of x coordinate and time, fill this field with values at time t
     pub fn set_from_function(&mut self, f: impl Fn(f32, f32) -> f32, t: f32) {
-        for i in 0..DIVISIONS {
-            self.field[i] = f(self.points[i], t);
+        for (value, x) in self.field.iter_mut().zip(&self.points) {
+            *value = f(*x, t);
         }
     }
 
@@ -62,19 +181,104 @@ impl Field {
         self.points.slice(s![..]).to_slice().unwrap()
     }
 
-    // add another field to this field
-    pub fn add(&mut self, rhs: &Field) {
-        self.field += &rhs.field;
-    }
-
     // x coordinate of a division index
     pub fn position_at(&self, idx: usize) -> f32 {
         self.points[idx]
     }
 
-    // dimensions in simulation space
-    pub fn size(&self) -> f32 {
-        self.extent.span()
+    // smallest distance between neighbouring divisions
+    pub fn step(&self) -> f32 {
+        self.step.unwrap_or_else(|| {
+            self.intervals()
+                .windows(2)
+                .map(|pair| pair[1] - pair[0])
+                .fold(f32::INFINITY, f32::min)
+        })
+    }
+
+    // multiplies every value by scale then adds offset, e.g. to normalise a trace
+    pub fn scale_and_offset(&mut self, scale: f32, offset: f32) {
+        self.field.mapv_inplace(|value| value * scale + offset);
+    }
+
+    // rate of change along x at each division, from central differences with the divisions either side,
+    // and one-sided differences at the ends
+    pub fn derivative(&self) -> Field {
+        let (points, values) = (self.intervals(), self.values());
+        let n = points.len();
+        let mut derivative = self.clone();
+        for (i, slope) in derivative.values_mut().iter_mut().enumerate() {
+            let (before, after) = (i.saturating_sub(1), (i + 1).min(n - 1));
+            *slope = (values[after] - values[before]) / (points[after] - points[before]);
+        }
+        derivative
+    }
+
+    // integral of the field over the part of a range inside it, by the trapezoid rule
+    pub fn integral(&self, range: Rangef) -> f32 {
+        self.running_integral(range.max.min(self.extent.max))
+            - self.running_integral(range.min.max(self.extent.min))
+    }
+
+    // integral from the start of the field to x, by the trapezoid rule, with the division x is in cut short
+    fn running_integral(&self, x: f32) -> f32 {
+        let (points, values) = (self.intervals(), self.values());
+        let mut total = 0.0;
+        for i in 0..points.len() - 1 {
+            if points[i] >= x {
+                break;
+            }
+            let end = points[i + 1].min(x);
+            let value = match end < points[i + 1] {
+                true => self.value_at(end),
+                false => values[i + 1],
+            };
+            total += (values[i] + value) / 2.0 * (end - points[i]);
+        }
+        total
+    }
+
+    // root mean square of the field over a window of a width centred on each division, cut short at the ends
+    pub fn windowed_rms(&self, width: f32) -> Field {
+        let squared = self * self;
+        // the integral up to each division, so each window only needs its ends found
+        let mut cumulative = vec![0.0; self.points.len()];
+        for i in 1..cumulative.len() {
+            let (a, b) = (squared.points[i - 1], squared.points[i]);
+            cumulative[i] =
+                cumulative[i - 1] + (squared.field[i - 1] + squared.field[i]) / 2.0 * (b - a);
+        }
+        let integral_to = |x: f32| {
+            let x = x.clamp(self.extent.min, self.extent.max);
+            let i = (squared.index_of(x).max(0.0) as usize).min(cumulative.len() - 2);
+            let start = squared.points[i];
+            cumulative[i] + (squared.field[i] + squared.value_at(x)) / 2.0 * (x - start)
+        };
+        let mut rms = self.clone();
+        for (value, x) in rms.field.iter_mut().zip(&self.points) {
+            let window = Rangef::new(x - width / 2.0, x + width / 2.0).intersection(self.extent);
+            let mean = (integral_to(window.max) - integral_to(window.min)) / window.span();
+            *value = mean.max(0.0).sqrt();
+        }
+        rms
+    }
+
+    // copy of this field with values from an operation on two arrays of values on the same divisions
+    fn combined(&self, rhs: &Field, values: Array1<f32>) -> Field {
+        debug_assert_eq!(self.points, rhs.points, "fields must share their divisions");
+        Field {
+            field: values,
+            ..self.clone()
+        }
+    }
+
+    // distance between the divisions either side of an x coordinate
+    pub fn step_at(&self, x: f32) -> f32 {
+        self.step.unwrap_or_else(|| {
+            let points = self.intervals();
+            let i = (self.index_of(x).max(0.0) as usize).min(points.len() - 2);
+            points[i + 1] - points[i]
+        })
     }
 }
 
@@ -91,3 +295,153 @@ impl IndexMut<usize> for Field {
         &mut self.values_mut()[index]
     }
 }
+
+// arithmetic on fields works division by division, so both fields must have the same divisions
+impl Add for &Field {
+    type Output = Field;
+
+    fn add(self, rhs: &Field) -> Field {
+        self.combined(rhs, &self.field + &rhs.field)
+    }
+}
+
+impl Sub for &Field {
+    type Output = Field;
+
+    fn sub(self, rhs: &Field) -> Field {
+        self.combined(rhs, &self.field - &rhs.field)
+    }
+}
+
+// product at each division, e.g. a field squared
+impl Mul for &Field {
+    type Output = Field;
+
+    fn mul(self, rhs: &Field) -> Field {
+        self.combined(rhs, &self.field * &rhs.field)
+    }
+}
+
+impl Mul<f32> for Field {
+    type Output = Field;
+
+    fn mul(mut self, rhs: f32) -> Field {
+        self *= rhs;
+        self
+    }
+}
+
+impl AddAssign<&Field> for Field {
+    fn add_assign(&mut self, rhs: &Field) {
+        debug_assert_eq!(self.points, rhs.points, "fields must share their divisions");
+        self.field += &rhs.field;
+    }
+}
+
+impl SubAssign<&Field> for Field {
+    fn sub_assign(&mut self, rhs: &Field) {
+        debug_assert_eq!(self.points, rhs.points, "fields must share their divisions");
+        self.field -= &rhs.field;
+    }
+}
+
+impl MulAssign<f32> for Field {
+    fn mul_assign(&mut self, rhs: f32) {
+        self.scale_and_offset(rhs, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_points_detects_even_spacing() {
+        let extent = Rangef::new(-2.0, 2.0);
+        let even = Field::from_points(&uniform_grid(extent, 9));
+        assert_eq!(even.step, Some(0.5));
+        assert_eq!(even.step_at(1.3), 0.5);
+        let refined = Field::from_points(&refined_grid(extent, 9, &[0.0]));
+        assert_eq!(refined.step, None);
+        assert_eq!(refined.step(), 0.5 / REFINEMENT_FACTOR as f32);
+        assert_eq!(refined.step_at(-1.8), 0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "field divisions must be increasing")]
+    fn from_points_rejects_unordered_divisions() {
+        Field::from_points(&[0.0, 1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn index_of_is_fractional_on_uneven_grid() {
+        let field = Field::from_points(&[0.0, 1.0, 1.5, 3.5]);
+        assert_eq!(field.index_of(0.5), 0.5);
+        assert_eq!(field.index_of(1.25), 1.5);
+        assert_eq!(field.index_of(2.0), 2.25);
+        // extrapolated from the end divisions outside the field
+        assert_eq!(field.index_of(-1.0), -1.0);
+        assert_eq!(field.index_of(4.5), 3.5);
+    }
+
+    #[test]
+    fn resampled_onto_even_divisions() {
+        let mut field = Field::from_points(&uniform_grid(Rangef::new(0.0, 4.0), 5));
+        field.set_from_function(|x, _| x * x, 0.0);
+        let extent = Rangef::new(1.0, 5.0);
+        let linear = field.resampled(extent, 9, Interpolation::Linear);
+        assert_eq!(linear.values()[1], 2.5);
+        // the cubic's slopes are exact for a parabola away from the ends
+        let cubic = field.resampled(extent, 9, Interpolation::Cubic);
+        assert_eq!(cubic.values()[1], 2.25);
+        assert_eq!(cubic.values()[3], 6.25);
+        // nothing beyond the field
+        assert_eq!(cubic.values()[8], 0.0);
+    }
+
+    #[test]
+    fn calculus_on_uneven_divisions() {
+        let mut field = Field::from_points(&[0.0, 0.5, 1.0, 1.25, 1.5, 2.0, 3.0, 4.0]);
+        field.set_from_function(|x, _| 3.0 * x + 1.0, 0.0);
+        for slope in field.derivative().values() {
+            assert!((slope - 3.0).abs() < 1e-5);
+        }
+        // exact for a straight line, including the parts of divisions cut off by the range
+        assert!((field.integral(Rangef::new(0.25, 3.5)) - 21.53125).abs() < 1e-4);
+        // only the part of the range inside the field counts
+        assert_eq!(
+            field.integral(Rangef::new(-1.0, 1.0)),
+            field.integral(Rangef::new(0.0, 1.0))
+        );
+
+        field.set_from_function(|_, _| -2.0, 0.0);
+        for rms in field.windowed_rms(1.0).values() {
+            assert!((rms - 2.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn arithmetic_works_division_by_division() {
+        let mut a = Field::from_points(&[0.0, 1.0, 3.0]);
+        a.set_from_function(|x, _| x, 0.0);
+        let mut b = a.clone() * 2.0;
+        assert_eq!((&a + &b).values(), [0.0, 3.0, 9.0]);
+        assert_eq!((&b - &a).values(), [0.0, 1.0, 3.0]);
+        assert_eq!((&a * &b).values(), [0.0, 2.0, 18.0]);
+        b -= &a;
+        b += &a;
+        b.scale_and_offset(0.5, 1.0);
+        assert_eq!(b.values(), [1.0, 2.0, 4.0]);
+        assert_eq!(b.intervals(), a.intervals());
+    }
+
+    #[test]
+    fn resample_interpolates_linearly() {
+        let mut field = Field::from_points(&uniform_grid(Rangef::new(0.0, 4.0), 5));
+        field.set_from_function(|x, _| 2.0 * x - 1.0, 0.0);
+        let resampled = field.resample(&[0.0, 0.3, 1.7, 2.0, 3.9, 4.0]);
+        for (x, value) in resampled.intervals().iter().zip(resampled.values()) {
+            assert!((value - (2.0 * x - 1.0)).abs() < 1e-5, "{value} at {x}");
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Fitting the particles' spring constant and damping to a medium with a given refractive index or attenuation.
+//! The Lorentz model gives both at a frequency from the particles' properties, so the properties are searched for
+//! with the Nelder–Mead method, which needs nothing but the values of the error to be minimised: a triangle of trial
+//! points is reflected, stretched and shrunk across the plane of the two properties until it closes on the minimum.
+//! Given only one target, many pairs match it, and the search settles on one near the particles' current properties.
+
+use super::Simulation;
+use super::theory::sheet_susceptibility;
+use super::variables::C;
+
+// number of trial points the search may move through before giving up
+const MAX_ITERATIONS: usize = 500;
+// spread of the errors at the corners of the triangle at which the search has closed on the minimum
+const TOLERANCE: f32 = 1e-10;
+// error below which a fit counts as matching its targets
+const MATCHED_ERROR: f32 = 1e-4;
+
+// what the medium should be like at a frequency of the plane wave
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitTarget {
+    pub frequency: f32,           // angular frequency the medium is matched at
+    pub index: Option<f32>,       // refractive index n
+    pub attenuation: Option<f32>, // attenuation coefficient α of the intensity, per unit distance
+}
+
+// properties found for the particles, and the medium they make up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FittedMedium {
+    pub spring_constant: f32,
+    pub damping: f32,
+    pub index: f32,
+    pub attenuation: f32,
+    pub error: f32, // sum of the squared errors relative to the targets
+}
+
+impl FittedMedium {
+    // whether the fit matches its targets, rather than getting as close as the particles allow
+    pub fn matched(&self) -> bool {
+        self.error < MATCHED_ERROR
+    }
+}
+
+impl Simulation {
+    // spring constant and damping giving the particles, with their mass and spacing unchanged, the target index and
+    // attenuation in the Lorentz model. None without a target, or unless the particles make up a medium the model
+    // covers, an evenly spaced row of sheets in the FDTD solver
+    pub fn fit_medium(&self, target: FitTarget) -> Option<FittedMedium> {
+        let omega = target.frequency;
+        self.susceptibility(omega)?;
+        if target.index.is_none() && target.attenuation.is_none() {
+            return None;
+        }
+        let (charge, spacing, mass) = (
+            self.particle_type.charge(),
+            self.particle_spacing,
+            self.particle_mass,
+        );
+        // index and attenuation coefficient of the intensity, from n + iκ = √(1 + χ)
+        let medium = |spring_constant: f32, damping: f32| {
+            let chi =
+                sheet_susceptibility(charge, spacing, [mass, spring_constant, damping], omega);
+            let root = (1.0 + chi).sqrt();
+            (root.re, 2.0 * omega / C * root.im.abs())
+        };
+        // errors relative to the targets, taking that of the index relative to n − 1, how far it is from a vacuum
+        let relative =
+            |value: f32, target: f32, scale: f32| ((value - target) / scale.max(1e-3)).powi(2);
+        let error = |spring_constant: f32, damping: f32| {
+            let (index, attenuation) = medium(spring_constant, damping);
+            target
+                .index
+                .map_or(0.0, |n| relative(index, n, (n - 1.0).abs()))
+                + target
+                    .attenuation
+                    .map_or(0.0, |alpha| relative(attenuation, alpha, alpha))
+        };
+
+        // searching over the square roots keeps the properties from going negative
+        let [root_k, root_damping] = nelder_mead(
+            |[root_k, root_damping]| {
+                let e = error(root_k * root_k, root_damping * root_damping);
+                if e.is_finite() { e } else { f32::INFINITY }
+            },
+            [self.spring_constant.sqrt(), self.damping.sqrt()],
+        );
+        let (spring_constant, damping) = (root_k * root_k, root_damping * root_damping);
+        let (index, attenuation) = medium(spring_constant, damping);
+        Some(FittedMedium {
+            spring_constant,
+            damping,
+            index,
+            attenuation,
+            error: error(spring_constant, damping),
+        })
+    }
+}
+
+// point minimising a function of N variables, searched for from a starting point by the Nelder–Mead method
+fn nelder_mead<const N: usize>(f: impl Fn([f32; N]) -> f32, start: [f32; N]) -> [f32; N] {
+    // a corner of the starting simplex a step along each axis from the start
+    let mut simplex: Vec<([f32; N], f32)> = vec![(start, f(start))];
+    for i in 0..N {
+        let mut corner = start;
+        corner[i] += (0.1 * start[i].abs()).max(0.1);
+        simplex.push((corner, f(corner)));
+    }
+    let along = |from: [f32; N], to: [f32; N], t: f32| -> [f32; N] {
+        std::array::from_fn(|i| from[i] + t * (to[i] - from[i]))
+    };
+
+    for _ in 0..MAX_ITERATIONS {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best, worst) = (simplex[0].1, simplex[N].1);
+        if worst - best < TOLERANCE && best.is_finite() {
+            break;
+        }
+        // the worst corner is moved through the centre of the others
+        let centre: [f32; N] =
+            std::array::from_fn(|i| simplex[..N].iter().map(|(p, _)| p[i]).sum::<f32>() / N as f32);
+        let far = simplex[N].0;
+        let reflected = along(centre, far, -1.0);
+        let reflected_value = f(reflected);
+        if reflected_value < best {
+            let expanded = along(centre, far, -2.0);
+            let expanded_value = f(expanded);
+            simplex[N] = match expanded_value < reflected_value {
+                true => (expanded, expanded_value),
+                false => (reflected, reflected_value),
+            };
+        } else if reflected_value < simplex[N - 1].1 {
+            simplex[N] = (reflected, reflected_value);
+        } else {
+            let contracted = along(centre, far, 0.5);
+            let contracted_value = f(contracted);
+            if contracted_value < worst {
+                simplex[N] = (contracted, contracted_value);
+            } else {
+                // nowhere along the line is better, so the simplex shrinks towards its best corner
+                let best = simplex[0].0;
+                for corner in &mut simplex[1..] {
+                    corner.0 = along(best, corner.0, 0.5);
+                    corner.1 = f(corner.0);
+                }
+            }
+        }
+    }
+    simplex
+        .iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(start, |(p, _)| *p)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_complex::Complex32;
+
+    use super::FitTarget;
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::solver::SolverType;
+    use crate::app::simulation::waveform::Waveform;
+
+    fn row(spring_constant: f32, damping: f32) -> Simulation {
+        Simulation::from_config(&SimulationConfig::default().scene(Scene {
+            waveform: Waveform::PlaneWave,
+            particle_count: 4,
+            particle_spacing: 2.0,
+            spring_constant,
+            damping,
+            solver: SolverType::Fdtd,
+            ..Scene::blank()
+        }))
+        .unwrap()
+    }
+
+    // the properties of a medium are found again from its index and attenuation, starting from others
+    #[test]
+    fn fit_recovers_properties() {
+        let (spring_constant, damping, omega) = (1.2, 0.3, 1.0);
+        let simulation = row(spring_constant, damping);
+        let charge = simulation.particle_type.charge();
+        let chi = charge * charge
+            / (2.0
+                * Complex32::new(
+                    spring_constant - simulation.particle_mass * omega * omega,
+                    damping * omega,
+                ));
+        let root = (1.0 + chi).sqrt();
+        let (index, attenuation) = (root.re, 2.0 * omega * root.im.abs());
+
+        let fitted = row(0.5, 0.0)
+            .fit_medium(FitTarget {
+                frequency: omega,
+                index: Some(index),
+                attenuation: Some(attenuation),
+            })
+            .unwrap();
+        assert!(fitted.matched(), "{fitted:?}");
+        assert!(
+            (fitted.spring_constant - spring_constant).abs() < 1e-2,
+            "{fitted:?}"
+        );
+        assert!((fitted.damping - damping).abs() < 1e-2, "{fitted:?}");
+
+        // given the index alone, the damping is left as it was
+        let fitted = row(0.5, 0.3)
+            .fit_medium(FitTarget {
+                frequency: omega,
+                index: Some(index),
+                attenuation: None,
+            })
+            .unwrap();
+        assert!(
+            fitted.matched() && (fitted.index - index).abs() < 1e-3,
+            "{fitted:?}"
+        );
+        assert!(
+            row(0.5, 0.3)
+                .fit_medium(FitTarget {
+                    frequency: omega,
+                    index: None,
+                    attenuation: None,
+                })
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Named groups of particles, whose properties are edited together.
+//! A particle in a group takes its mass, spring constant and damping from the group instead of the simulation,
+//! so that parts of the medium can be given different properties.
+
+use serde::{Deserialize, Serialize};
+
+use super::{ParticleId, Simulation};
+
+// properties shared by every particle in a group
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GroupProperties {
+    pub mass: f32,
+    pub spring_constant: f32,
+    pub damping: f32,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
+pub struct ParticleGroup {
+    pub name: String,
+    pub properties: GroupProperties,
+    members: Vec<ParticleId>,
+}
+
+impl ParticleGroup {
+    pub fn members(&self) -> &[ParticleId] {
+        &self.members
+    }
+
+    pub fn contains(&self, id: ParticleId) -> bool {
+        self.members.contains(&id)
+    }
+}
+
+// a group as saved in a scene, where particles are identified by their index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupSetup {
+    pub name: String,
+    pub properties: GroupProperties,
+    pub members: Vec<usize>,
+}
+
+impl Simulation {
+    pub fn groups(&self) -> &[ParticleGroup] {
+        &self.groups
+    }
+
+    pub fn groups_mut(&mut self) -> &mut [ParticleGroup] {
+        &mut self.groups
+    }
+
+    // puts particles into a new group, taking them out of any group they were in. the group starts with
+    // the properties of the simulation, so nothing changes until they are edited
+    pub fn create_group(&mut self, name: String, members: Vec<ParticleId>) {
+        for group in &mut self.groups {
+            group.members.retain(|id| !members.contains(id));
+        }
+        self.groups.retain(|group| !group.members.is_empty());
+        self.groups.push(ParticleGroup {
+            name,
+            properties: self.properties(),
+            members,
+        });
+    }
+
+    // adds particles to the group with a name, creating it with the given properties if there isn't one
+    pub(super) fn join_group(
+        &mut self,
+        name: &str,
+        properties: GroupProperties,
+        members: Vec<ParticleId>,
+    ) {
+        match self.groups.iter_mut().find(|group| group.name == name) {
+            Some(group) => group.members.extend(members),
+            None => self.groups.push(ParticleGroup {
+                name: name.to_string(),
+                properties,
+                members,
+            }),
+        }
+    }
+
+    pub fn remove_group(&mut self, index: usize) {
+        if index < self.groups.len() {
+            self.groups.remove(index);
+        }
+    }
+
+    // takes particles that no longer exist out of their groups, dropping groups left empty
+    pub(super) fn prune_groups(&mut self) {
+        let particles = &self.particles;
+        for group in &mut self.groups {
+            group
+                .members
+                .retain(|id| particles.iter().any(|p| p.id == *id));
+        }
+        self.groups.retain(|group| !group.members.is_empty());
+    }
+
+    // properties of the particles that aren't in a group
+    fn properties(&self) -> GroupProperties {
+        GroupProperties {
+            mass: self.particle_mass,
+            spring_constant: self.spring_constant,
+            damping: self.damping,
+        }
+    }
+
+    // properties a particle should have, from its group if it is in one
+    pub(super) fn properties_of(&self, id: ParticleId) -> GroupProperties {
+        self.groups
+            .iter()
+            .find(|group| group.contains(id))
+            .map_or_else(|| self.properties(), |group| group.properties)
+    }
+
+    // the groups, for saving in a scene
+    pub(super) fn group_setups(&self) -> Vec<GroupSetup> {
+        self.groups
+            .iter()
+            .map(|group| GroupSetup {
+                name: group.name.clone(),
+                properties: group.properties,
+                members: self
+                    .particles
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| group.contains(p.id))
+                    .map(|(i, _)| i)
+                    .collect(),
+            })
+            .collect()
+    }
+
+    // recreates groups saved in a scene, once its particles have been created
+    pub(super) fn set_group_setups(&mut self, setups: &[GroupSetup]) {
+        self.groups = setups
+            .iter()
+            .map(|setup| ParticleGroup {
+                name: setup.name.clone(),
+                properties: setup.properties,
+                members: setup
+                    .members
+                    .iter()
+                    .filter_map(|i| self.particles.get(*i).map(|p| p.id))
+                    .collect(),
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,235 @@
+//! Optional safeguard against the motion growing too large to show, e.g. an undamped particle driven at resonance
+//! during a demonstration. Unlike the detection of divergence, which only catches values that are no longer physical,
+//! the limiter acts at bounds chosen to keep the traces on screen: it holds the particles and field at the bounds,
+//! raises the damping until the motion settles, or pauses the simulation with a warning.
+
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use super::divergence::Quantity;
+use super::variables::{
+    ELECTRON_DAMPING, Float, LIMIT_DISPLACEMENT, LIMIT_FIELD, LIMITER_DAMPING_STEP, narrow,
+};
+use super::{ChargedParticle, Simulation};
+
+// what the limiter does when a bound is exceeded
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, Serialize, Deserialize)]
+pub enum LimitAction {
+    #[default]
+    Off,
+    Clamp, // particles are held at the displacement bound, and the field cut off at its bound
+    Damp, // the damping of every particle is raised a little each update until the motion is back within bounds
+    Pause, // the simulation pauses when a bound is first exceeded
+}
+
+impl LimitAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LimitAction::Off => "Off",
+            LimitAction::Clamp => "Clamp",
+            LimitAction::Damp => "Raise damping",
+            LimitAction::Pause => "Pause",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Limiter {
+    pub action: LimitAction,
+    pub displacement: f32, // largest displacement of any particle
+    pub field: f32,        // largest resultant field, relative to the amplitude of the applied wave
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Limiter {
+            action: LimitAction::default(),
+            displacement: LIMIT_DISPLACEMENT.initial,
+            field: LIMIT_FIELD.initial,
+        }
+    }
+}
+
+impl Limiter {
+    // checks the bounds could have been set with the controls
+    pub fn validate(&self) -> Result<(), String> {
+        if !(LIMIT_DISPLACEMENT.min..=LIMIT_DISPLACEMENT.max).contains(&self.displacement) {
+            return Err(format!(
+                "the limiter's displacement bound must be between {} and {}",
+                LIMIT_DISPLACEMENT.min, LIMIT_DISPLACEMENT.max
+            ));
+        }
+        if !(LIMIT_FIELD.min..=LIMIT_FIELD.max).contains(&self.field) {
+            return Err(format!(
+                "the limiter's field bound must be between {} and {}",
+                LIMIT_FIELD.min, LIMIT_FIELD.max
+            ));
+        }
+        Ok(())
+    }
+}
+
+// the limiter acting on a value beyond its bound
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
+pub struct Limited {
+    pub quantity: Quantity, // first value found beyond its bound
+    pub action: LimitAction,
+    pub since: usize, // number of the update the bounds have been exceeded since, counting from 1
+    pub step: usize,  // number of the latest update they were exceeded in
+}
+
+impl ChargedParticle {
+    // holds the particle at a displacement, stopping it there
+    fn hold_within(&mut self, bound: f32) {
+        let bound = Float::from(bound);
+        if self.displacement.abs() <= bound {
+            return;
+        }
+        self.displacement = self.displacement.clamp(-bound, bound);
+        self.velocity = 0.0;
+        self.position.y = narrow(self.displacement);
+        if let Some(latest) = self.history.last_mut() {
+            latest.y = self.displacement;
+            latest.v = 0.0;
+        }
+    }
+}
+
+impl Simulation {
+    // the first value beyond the limiter's bounds in the update being made, once the resultant field is found
+    fn beyond_limits(&self) -> Option<Quantity> {
+        let bound = self.limiter.displacement;
+        if let Some(i) = self
+            .particles
+            .iter()
+            .position(|p| narrow(p.displacement).abs() > bound)
+        {
+            return Some(Quantity::Displacement(i));
+        }
+        let bound = self.limiter.field * self.applied_amplitude();
+        self.resultant_field
+            .values()
+            .iter()
+            .any(|e| e.abs() > bound)
+            .then_some(Quantity::Field)
+    }
+
+    // acts on any value beyond the limiter's bounds, before the scattered field and intensity are found from the
+    // resultant field
+    pub(super) fn apply_limiter(&mut self) {
+        if self.limiter.action == LimitAction::Off {
+            return;
+        }
+        let Some(quantity) = self.beyond_limits() else {
+            return;
+        };
+        let step = self.steps + 1;
+        match self.limiter.action {
+            LimitAction::Off | LimitAction::Pause => {}
+            LimitAction::Clamp => {
+                for p in &mut self.particles {
+                    p.hold_within(self.limiter.displacement);
+                }
+                let bound = self.limiter.field * self.applied_amplitude();
+                for e in self.resultant_field.values_mut() {
+                    *e = e.clamp(-bound, bound);
+                }
+            }
+            LimitAction::Damp => {
+                // the settings are raised along with the particles, so they aren't changed back before the next update
+                let raise = |damping: &mut f32| {
+                    *damping = (*damping + LIMITER_DAMPING_STEP).min(ELECTRON_DAMPING.max)
+                };
+                raise(&mut self.damping);
+                for group in &mut self.groups {
+                    raise(&mut group.properties.damping);
+                }
+                for p in &mut self.particles {
+                    raise(&mut p.damping);
+                }
+            }
+        }
+        let since = match self.limited {
+            Some(limited) if limited.step + 1 == step => limited.since,
+            _ => step,
+        };
+        self.limited = Some(Limited {
+            quantity,
+            action: self.limiter.action,
+            since,
+            step,
+        });
+    }
+
+    pub fn limiter(&self) -> Limiter {
+        self.limiter
+    }
+
+    // the limiter acting in the latest update, if it did
+    pub fn limited(&self) -> Option<&Limited> {
+        self.limited
+            .as_ref()
+            .filter(|limited| limited.step == self.steps)
+    }
+
+    // whether the limiter paused the simulation in the latest update, as a bound was exceeded for the first time
+    // since the values were last within them
+    pub fn limiter_paused(&self) -> bool {
+        self.limited().is_some_and(|limited| {
+            limited.action == LimitAction::Pause && limited.since == limited.step
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LimitAction, Limiter};
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::waveform::Waveform;
+
+    // an undamped particle driven at resonance, which would otherwise swing further and further
+    fn resonant(action: LimitAction) -> Simulation {
+        let scene = Scene {
+            waveform: Waveform::PlaneWave,
+            frequency: 1.0,
+            particle_count: 1,
+            mass: 0.5,
+            spring_constant: 0.5,
+            damping: 0.0,
+            limiter: Limiter {
+                action,
+                displacement: 0.5,
+                ..Limiter::default()
+            },
+            ..Scene::blank()
+        };
+        let mut simulation =
+            Simulation::from_config(&SimulationConfig::default().scene(scene)).unwrap();
+        while simulation.time() < 60.0 && !simulation.limiter_paused() {
+            simulation.update();
+        }
+        simulation
+    }
+
+    fn largest_displacement(simulation: &Simulation) -> f32 {
+        simulation
+            .particles()
+            .iter()
+            .map(|p| p.position().y.abs())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn limiter_keeps_resonance_in_check() {
+        assert!(largest_displacement(&resonant(LimitAction::Off)) > 0.5);
+        assert!(largest_displacement(&resonant(LimitAction::Clamp)) <= 0.5);
+        assert!(resonant(LimitAction::Damp).damping() > 0.0);
+
+        let paused = resonant(LimitAction::Pause);
+        assert!(paused.limiter_paused());
+        assert!(paused.time() < 60.0);
+    }
+}
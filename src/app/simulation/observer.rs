@@ -0,0 +1,47 @@
+//! Code run after every update of a simulation, with read access to its fields and particles, such as the trackers
+//! behind the plots. Whatever drives the simulation passes the observers it wants run to update_observed, rather
+//! than calling each of them itself around the update, and they stay with their owners, such as the app's panels,
+//! which go on to draw what they gathered.
+
+use super::Simulation;
+
+pub trait StepObserver {
+    // called after each update, with the simulation as it is after it
+    fn observe(&mut self, simulation: &Simulation);
+}
+
+impl<F: FnMut(&Simulation)> StepObserver for F {
+    fn observe(&mut self, simulation: &Simulation) {
+        self(simulation)
+    }
+}
+
+impl Simulation {
+    // updates the simulation, then runs each observer in order
+    pub fn update_observed(&mut self, observers: &mut [&mut dyn StepObserver]) -> bool {
+        let finished = self.update();
+        for observer in observers {
+            observer.observe(self);
+        }
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+
+    // observers see the simulation as it is after every update
+    #[test]
+    fn observers_run_after_each_update() {
+        let mut simulation = Simulation::from_config(&SimulationConfig::default()).unwrap();
+        let mut seen = Vec::new();
+        let mut first = |simulation: &Simulation| seen.push(simulation.steps());
+        for _ in 0..2 {
+            let mut second = |simulation: &Simulation| assert!(simulation.steps() > 0);
+            simulation.update_observed(&mut [&mut first, &mut second]);
+        }
+        assert_eq!(seen, [1, 2]);
+    }
+}
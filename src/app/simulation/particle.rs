@@ -1,14 +1,17 @@
 //! Charged particle types and derived properties for the simulation
 //! To add a new particle, simply add it to the ChargedParticleType enum,
-//! then enter its properties in to a corresponding branch of the match statement in ChargedParticleType::retrieve_properties()
+//! then enter its properties in to a corresponding branch of the match statement in ChargedParticleType::retrieve_properties(),
+//! and its colour in each palette to Colours::particle in app/palette.rs
 
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
 use super::variables::{ELECTRON_DAMPING, ELECTRON_MASS, SPRING_CONSTANT};
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumIter, Serialize, Deserialize)]
 pub enum ChargedParticleType {
+    #[default]
     Electron,
     Proton,
 }
@@ -22,7 +25,6 @@ impl ChargedParticleType {
             default_spring_constant: props.default_spring_constant,
             default_damping: props.default_damping,
             name: props.name,
-            colour: props.colour,
         }
     }
 
@@ -34,7 +36,6 @@ impl ChargedParticleType {
                 default_spring_constant: SPRING_CONSTANT.initial,
                 default_damping: ELECTRON_DAMPING.initial,
                 name: "Electron",
-                colour: (0, 0, 255),
             },
             ChargedParticleType::Proton => ParticleProperties {
                 mass: 1836.0,
@@ -42,7 +43,6 @@ impl ChargedParticleType {
                 default_spring_constant: 2.0,
                 default_damping: 0.8,
                 name: "Proton",
-                colour: (255, 0, 0),
             },
         }
     }
@@ -66,16 +66,6 @@ impl ChargedParticleType {
     pub fn name(&self) -> &'static str {
         self.properties().name
     }
-
-    pub fn colour(&self) -> (u8, u8, u8) {
-        self.properties().colour
-    }
-}
-
-impl Default for ChargedParticleType {
-    fn default() -> Self {
-        ChargedParticleType::Electron
-    }
 }
 
 impl fmt::Display for ChargedParticleType {
@@ -91,5 +81,4 @@ pub struct ParticleProperties {
     pub default_spring_constant: f32,
     pub default_damping: f32,
     pub name: &'static str,
-    pub colour: (u8, u8, u8), //RGB
 }
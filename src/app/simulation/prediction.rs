@@ -0,0 +1,132 @@
+//! Questions for the prediction quiz: just before a pulse reaches the particles, which of a few fields will the
+//! resultant be once it has passed them? The scene is run ahead to that moment to find the right answer, which is
+//! shown among fields it could be mistaken for: the pulse passing as if the particles weren't there, and the
+//! particles' response with its sign flipped.
+
+use super::Simulation;
+use super::config::SimulationConfig;
+use super::scene::Scene;
+use super::variables::{C, WORLD_SIZE};
+use super::waveform::Waveform;
+
+// time before the pulse reaches the first particle the question is asked
+const LEAD_TIME: f32 = 1.5;
+// time after the pulse has passed the last particle the answer is shown, once the reflection has moved away
+const FOLLOW_TIME: f32 = 2.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prediction {
+    pub ask_at: f32,            // time the simulation pauses for the question
+    pub reveal_at: f32,         // time of the fields being chosen between
+    pub x_points: Vec<f32>,     // x coordinates of the values of each choice
+    pub choices: Vec<Vec<f32>>, // fields to choose between, with the resultant first
+}
+
+impl Prediction {
+    // runs a scene to the time its pulse has passed the particles, with the time step of the run the question is
+    // asked in, so the answer matches it. there is no question unless the scene has a single pulse and particles
+    pub fn run(
+        scene: &Scene,
+        time_step: f32,
+        progress: impl Fn(usize),
+    ) -> Result<Option<Self>, String> {
+        if !matches!(
+            scene.waveform,
+            Waveform::Gaussian | Waveform::GaussianPacket
+        ) {
+            return Ok(None);
+        }
+        let config = SimulationConfig::default()
+            .time_step(time_step)
+            .scene(scene.clone());
+        let mut simulation = Simulation::from_config(&config)?;
+
+        // the centre of the pulse starts at the right edge of the world, and travels to the left
+        let positions = simulation.particles().iter().map(|p| p.position().x);
+        let Some((first, last)) = positions.fold(None, |range: Option<(f32, f32)>, x| {
+            Some(range.map_or((x, x), |(first, last)| (first.max(x), last.min(x))))
+        }) else {
+            return Ok(None);
+        };
+        let arrival = |x: f32| (WORLD_SIZE.max - x) / C;
+        let ask_at = (arrival(first) - LEAD_TIME).max(0.0);
+        let reveal_at = arrival(last) + FOLLOW_TIME;
+
+        while simulation.time() < reveal_at {
+            simulation.update();
+            progress((Self::parts() as f32 * simulation.time() / reveal_at) as usize);
+        }
+        let applied = simulation.applied_field();
+        let scattered = simulation.scattered_field();
+        Ok(Some(Prediction {
+            ask_at,
+            reveal_at: simulation.time(),
+            x_points: simulation.x_intervals().to_vec(),
+            choices: vec![
+                simulation.resultant_field().to_vec(),
+                applied.to_vec(),
+                applied.iter().zip(scattered).map(|(a, s)| a - s).collect(),
+            ],
+        }))
+    }
+
+    // index of the right answer among the choices
+    pub fn correct() -> usize {
+        0
+    }
+
+    // number of parts progress is reported in
+    pub fn parts() -> usize {
+        100
+    }
+
+    // whether an update from one time to another has just reached a time
+    pub fn reached(time: f32, before: f32, after: f32) -> bool {
+        before < time && time <= after
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Prediction;
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::variables::TIME_STEP;
+    use crate::app::simulation::waveform::Waveform;
+
+    // the question comes before the answer, and the right answer is what the scene goes on to show
+    #[test]
+    fn prediction_matches_run() {
+        let scene = Scene {
+            waveform: Waveform::Gaussian,
+            particle_count: 3,
+            ..Scene::blank()
+        };
+        let prediction = Prediction::run(&scene, TIME_STEP, |_| {}).unwrap().unwrap();
+        assert!(prediction.ask_at < prediction.reveal_at);
+        assert_eq!(prediction.choices.len(), 3);
+
+        let mut simulation =
+            Simulation::from_config(&SimulationConfig::default().scene(scene.clone())).unwrap();
+        while simulation.time() < prediction.reveal_at {
+            simulation.update();
+        }
+        assert_eq!(
+            simulation.resultant_field(),
+            prediction.choices[Prediction::correct()]
+        );
+        // the particles change the field, so the right answer can be told apart from the others
+        assert_ne!(prediction.choices[0], prediction.choices[1]);
+
+        let plane = Scene {
+            waveform: Waveform::PlaneWave,
+            ..scene
+        };
+        assert!(
+            Prediction::run(&plane, TIME_STEP, |_| {})
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! Built-in starting configurations for the simulation
+//! To add a new preset, simply add it to the Preset enum,
+//! then enter its settings in to a corresponding branch of the match statement in Preset::retrieve_properties()
+
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use super::limiter::Limiter;
+use super::particle::ChargedParticleType;
+use super::ramp::ParameterChanges;
+use super::scene::Scene;
+use super::solver::SolverType;
+use super::variables::WAVE_FREQUENCY;
+use super::waveform::{Beat, Waveform};
+
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum Preset {
+    SingleElectron,      // gaussian packet hitting a single electron
+    ElectronPulse,       // single gaussian pulse hitting a single electron
+    ElectronChain,       // plane wave passing through a row of electrons
+    DiluteGas, // plane wave slowed slightly by many weak oscillators, as in the textbook derivation of n
+    AnomalousDispersion, // plane wave tuned through the oscillators' resonance, where n rises, peaks and dips below 1
+    AbsorbingMedium, // plane wave absorbed exponentially by a dense slab of strongly damped oscillators
+    RayleighScattering, // plane wave scattered by an electron bound far more stiffly than its resonance with the wave
+    SlowLight, // wave packet slowed by a dense slab with a narrow resonance just above its carrier frequency
+    SuperluminalPhase, // wave packet driving a slab above its resonance, where n < 1 and the phase outruns light
+}
+
+impl Preset {
+    pub fn properties(&self) -> PresetProperties {
+        // function separated out to allow for future flexibility
+        self.retrieve_properties()
+    }
+
+    // setup of the simulation for this preset
+    pub fn scene(&self) -> Scene {
+        let properties = self.properties();
+        Scene {
+            waveform: properties.waveform,
+            frequency: properties.frequency,
+            beat: Beat::default(),
+            particle_type: properties.particle_type,
+            particle_count: properties.particle_count,
+            particle_spacing: properties.particle_spacing,
+            mass: properties.mass,
+            spring_constant: properties.spring_constant,
+            damping: properties.damping,
+            temperature: 0.0,
+            parameter_changes: ParameterChanges::default(),
+            limiter: Limiter::default(),
+            samples: None,
+            solver: properties.solver,
+            placed: Vec::new(),
+            groups: Vec::new(),
+            captions: Vec::new(),
+        }
+    }
+
+    fn retrieve_properties(&self) -> PresetProperties {
+        let electron = ChargedParticleType::Electron;
+        match self {
+            Preset::SingleElectron => PresetProperties {
+                name: "Single electron",
+                description: "A wave packet hitting one electron",
+                waveform: Waveform::GaussianPacket,
+                frequency: WAVE_FREQUENCY.initial,
+                particle_type: electron,
+                particle_count: 1,
+                particle_spacing: 3.0,
+                mass: electron.mass(),
+                spring_constant: electron.default_spring_constant(),
+                damping: electron.default_damping(),
+                solver: SolverType::default(),
+                probe: None,
+                attenuation: false,
+                scattering: false,
+                delay: false,
+            },
+            Preset::ElectronPulse => PresetProperties {
+                name: "Electron pulse",
+                description: "A single short pulse hitting one electron",
+                waveform: Waveform::Gaussian,
+                frequency: WAVE_FREQUENCY.initial,
+                particle_type: electron,
+                particle_count: 1,
+                particle_spacing: 3.0,
+                mass: electron.mass(),
+                spring_constant: electron.default_spring_constant(),
+                damping: electron.default_damping(),
+                solver: SolverType::default(),
+                probe: None,
+                attenuation: false,
+                scattering: false,
+                delay: false,
+            },
+            Preset::ElectronChain => PresetProperties {
+                name: "Electron chain",
+                description: "A plane wave passing through a row of electrons",
+                waveform: Waveform::PlaneWave,
+                frequency: WAVE_FREQUENCY.initial,
+                particle_type: electron,
+                particle_count: 6,
+                particle_spacing: 3.0,
+                mass: electron.mass(),
+                spring_constant: electron.default_spring_constant(),
+                damping: 0.2,
+                solver: SolverType::default(),
+                probe: None,
+                attenuation: false,
+                scattering: false,
+                delay: false,
+            },
+            // sheets of charge bound far more stiffly than their resonance with the wave, so each delays it only
+            // a little. damped so the ringing from the wave being switched on dies away, leaving the delay to measure
+            Preset::DiluteGas => PresetProperties {
+                name: "Dilute gas",
+                description: "Many weak oscillators slowing a plane wave slightly, \
+                    with the refractive index measured beyond them",
+                waveform: Waveform::PlaneWave,
+                frequency: WAVE_FREQUENCY.initial,
+                particle_type: electron,
+                particle_count: 10,
+                particle_spacing: 2.0,
+                mass: electron.mass(),
+                spring_constant: 10.0,
+                damping: 0.5,
+                solver: SolverType::Fdtd,
+                probe: Some(-19.5),
+                attenuation: false,
+                scattering: false,
+                delay: false,
+            },
+            // heavy sheets with a resonance inside the frequency slider's range, damped enough that the peak and
+            // dip of the index are broad, and that the wave still gets through the slab near resonance.
+            // starts below resonance, to be tuned up through it with the frequency slider
+            Preset::AnomalousDispersion => PresetProperties {
+                name: "Anomalous dispersion",
+                description: "Oscillators with a resonance the plane wave can be tuned through, \
+                    with the refractive index rising, peaking and dipping below 1 on the way",
+                waveform: Waveform::PlaneWave,
+                frequency: 1.0,
+                particle_type: electron,
+                particle_count: 10,
+                particle_spacing: 2.0,
+                mass: 5.0,
+                spring_constant: 10.0,
+                damping: 2.5,
+                solver: SolverType::Fdtd,
+                probe: Some(-19.5),
+                attenuation: false,
+                scattering: false,
+                delay: false,
+            },
+            // sheets driven at resonance, where they absorb most strongly, and damped heavily so that they absorb
+            // the wave gradually over the slab rather than reflecting it. the wave is quick enough for the intensity
+            // to be averaged over more than a period
+            Preset::AbsorbingMedium => PresetProperties {
+                name: "Absorbing medium",
+                description: "A dense slab of strongly damped oscillators, \
+                    with the intensity falling exponentially through it",
+                waveform: Waveform::PlaneWave,
+                frequency: 2.5,
+                particle_type: electron,
+                particle_count: 16,
+                particle_spacing: 0.5,
+                mass: electron.mass(),
+                spring_constant: 3.125,
+                damping: 6.0,
+                solver: SolverType::Fdtd,
+                probe: None,
+                attenuation: true,
+                scattering: false,
+                delay: false,
+            },
+            // a point charge resonating far above the wave's frequency, as the electrons in air do for visible light,
+            // so its displacement hardly depends on the frequency and the power it scatters goes as ω⁴
+            Preset::RayleighScattering => PresetProperties {
+                name: "Rayleigh scattering",
+                description: "A plane wave scattered by a stiffly bound electron, \
+                    comparing the power scattered at two frequencies",
+                waveform: Waveform::PlaneWave,
+                frequency: WAVE_FREQUENCY.initial,
+                particle_type: electron,
+                particle_count: 1,
+                particle_spacing: 3.0,
+                mass: electron.mass(),
+                spring_constant: 50.0,
+                damping: 1.0,
+                solver: SolverType::RetardedPotentials,
+                probe: None,
+                attenuation: false,
+                scattering: true,
+                delay: false,
+            },
+            // light sheets resonating a little above the packet's carrier, where the index rises steeply with
+            // frequency, so the packet's peak travels well below c. damped just enough to keep the many identical
+            // sheets from ringing, and detuned far enough that most of the packet gets through
+            Preset::SlowLight => PresetProperties {
+                name: "Slow light",
+                description: "A wave packet slowed by a dense slab with a narrow resonance, \
+                    with the delay of its peak measured",
+                waveform: Waveform::GaussianPacket,
+                frequency: WAVE_FREQUENCY.initial,
+                particle_type: electron,
+                particle_count: 40,
+                particle_spacing: 0.25,
+                mass: 0.1,
+                spring_constant: 6.4,
+                damping: 0.3,
+                solver: SolverType::Fdtd,
+                probe: None,
+                attenuation: false,
+                scattering: false,
+                delay: true,
+            },
+            // sheets resonating at half the packet's carrier, so it drives them well above resonance where the
+            // index is below 1, yet far enough from it that little of the packet is absorbed or reflected
+            Preset::SuperluminalPhase => PresetProperties {
+                name: "Superluminal phase",
+                description: "A wave packet driving a slab above its resonance, \
+                    where the phase fronts travel faster than light but the peak does not",
+                waveform: Waveform::GaussianPacket,
+                frequency: WAVE_FREQUENCY.initial,
+                particle_type: electron,
+                particle_count: 20,
+                particle_spacing: 0.5,
+                mass: 0.5,
+                spring_constant: 3.125,
+                damping: 0.5,
+                solver: SolverType::Fdtd,
+                probe: None,
+                attenuation: false,
+                scattering: false,
+                delay: true,
+            },
+        }
+    }
+}
+
+pub struct PresetProperties {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub waveform: Waveform,
+    pub frequency: f32, // angular frequency of the plane wave
+    pub particle_type: ChargedParticleType,
+    pub particle_count: usize,
+    pub particle_spacing: f32,
+    pub mass: f32,
+    pub spring_constant: f32,
+    pub damping: f32,
+    pub solver: SolverType,
+    pub probe: Option<f32>, // position the phasor probe is moved to, where the preset is for measuring there
+    pub attenuation: bool,  // shows the intensity, with an exponential fitted over the particles
+    pub scattering: bool, // shows the scattered field, and the panel comparing its power at two frequencies
+    pub delay: bool,      // opens the panel timing the pulse's peak through the particles
+}
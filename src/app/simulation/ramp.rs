@@ -0,0 +1,170 @@
+//! How changes to the particles' properties reach them while the simulation runs.
+//! Changing the mass or spring constant of an oscillating particle in a single step changes its energy at once,
+//! which can set it ringing. Changes can instead be ramped in over a number of updates, or held back until the
+//! simulation restarts.
+
+use serde::{Deserialize, Serialize};
+
+use super::group::GroupProperties;
+use super::{ChargedParticle, Simulation};
+
+// number of updates a change is ramped in over, unless chosen otherwise
+pub const DEFAULT_RAMP_STEPS: usize = 120;
+
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum ParameterChanges {
+    #[default]
+    Immediate, // the next update
+    Ramped {
+        steps: usize, // number of updates to change over, in a straight line
+    },
+    OnRestart, // the particles keep their properties until the simulation restarts
+}
+
+impl ParameterChanges {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParameterChanges::Immediate => "Immediately",
+            ParameterChanges::Ramped { .. } => "Ramped",
+            ParameterChanges::OnRestart => "On restart",
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ParameterChanges::Ramped { steps: 0 } => {
+                Err("changes must be ramped over at least one update".to_owned())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// change of a particle's properties part of the way through being ramped in
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
+pub struct Ramp {
+    from: GroupProperties,
+    to: GroupProperties,
+    step: usize, // updates since the change started
+}
+
+impl ChargedParticle {
+    fn properties(&self) -> GroupProperties {
+        GroupProperties {
+            mass: self.mass,
+            spring_constant: self.spring_constant,
+            damping: self.damping,
+        }
+    }
+
+    fn set_properties(&mut self, properties: GroupProperties) {
+        self.mass = properties.mass;
+        self.spring_constant = properties.spring_constant;
+        self.damping = properties.damping;
+    }
+}
+
+// properties a fraction of the way from one set to another
+fn interpolate(from: GroupProperties, to: GroupProperties, fraction: f32) -> GroupProperties {
+    let between = |a: f32, b: f32| a + (b - a) * fraction;
+    GroupProperties {
+        mass: between(from.mass, to.mass),
+        spring_constant: between(from.spring_constant, to.spring_constant),
+        damping: between(from.damping, to.damping),
+    }
+}
+
+impl Simulation {
+    // moves each particle's properties towards those set in the UI, or of its group, before an update.
+    // particles that have just been created take them straight away, whichever way changes are made
+    pub(super) fn update_properties(&mut self) {
+        for i in 0..self.particles.len() {
+            let target = self.properties_of(self.particles[i].id);
+            let changes = self.parameter_changes;
+            let p = &mut self.particles[i];
+            let current = p.properties();
+            let properties = match changes {
+                _ if !p.configured || current == target => {
+                    p.ramp = None;
+                    target
+                }
+                ParameterChanges::Immediate => {
+                    p.ramp = None;
+                    target
+                }
+                ParameterChanges::OnRestart => current,
+                ParameterChanges::Ramped { steps } => {
+                    // a change made part of the way through ramping starts again from where the particle is
+                    let mut ramp = match p.ramp {
+                        Some(ramp) if ramp.to == target => ramp,
+                        _ => Ramp {
+                            from: current,
+                            to: target,
+                            step: 0,
+                        },
+                    };
+                    ramp.step += 1;
+                    let fraction = (ramp.step as f32 / steps.max(1) as f32).min(1.0);
+                    p.ramp = (fraction < 1.0).then_some(ramp);
+                    interpolate(ramp.from, ramp.to, fraction)
+                }
+            };
+            p.set_properties(properties);
+            p.configured = true;
+        }
+    }
+
+    // whether any particle's properties are yet to reach those set, while ramping or waiting for a restart
+    pub fn pending_changes(&self) -> bool {
+        self.particles
+            .iter()
+            .any(|p| p.configured && p.properties() != self.properties_of(p.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParameterChanges;
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+
+    fn running(parameter_changes: ParameterChanges) -> Simulation {
+        let mut simulation = Simulation::from_config(&SimulationConfig::default().scene(Scene {
+            particle_count: 2,
+            mass: 1.0,
+            parameter_changes,
+            ..Scene::blank()
+        }))
+        .unwrap();
+        simulation.update();
+        simulation
+    }
+
+    #[test]
+    fn ramped_changes_arrive_in_a_straight_line() {
+        let mut simulation = running(ParameterChanges::Ramped { steps: 4 });
+        simulation.particle_mass = 3.0;
+        simulation.update();
+        assert_eq!(simulation.particles()[0].mass, 1.5);
+        assert!(simulation.pending_changes());
+        for _ in 0..3 {
+            simulation.update();
+        }
+        assert_eq!(simulation.particles()[0].mass, 3.0);
+        assert!(!simulation.pending_changes());
+    }
+
+    #[test]
+    fn changes_on_restart_wait_for_reset() {
+        let mut simulation = running(ParameterChanges::OnRestart);
+        simulation.particle_mass = 3.0;
+        simulation.update();
+        assert_eq!(simulation.particles()[0].mass, 1.0);
+        assert!(simulation.pending_changes());
+        simulation.reset();
+        simulation.update();
+        assert_eq!(simulation.particles()[0].mass, 3.0);
+    }
+}
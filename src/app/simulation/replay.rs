@@ -0,0 +1,179 @@
+//! Recording runs of the simulation to replay files, and playing them back.
+//! The simulation is deterministic, so rather than its state a replay holds the scene at the start of the run and
+//! every change made to it while running, with the update it was made before. Playing the replay reruns the scene,
+//! making the same changes at the same updates, so the run can be watched again with the view moved freely.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::Simulation;
+use super::scene::Scene;
+use super::variables::TIME_STEP;
+
+// extension of replay files saved by the user
+pub const REPLAY_EXTENSION: &str = "replay";
+
+// replays recorded before the time step could follow the display's refresh rate used this one
+fn default_time_step() -> f32 {
+    TIME_STEP
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    scene: Scene,                 // setup at the start of the run
+    changes: Vec<(usize, Scene)>, // setup after each change, with the number of the update it was made before
+    steps: usize,                 // length of the run in updates
+    #[serde(default = "default_time_step")]
+    time_step: f32, // time between the updates of the run, which is rerun with the same
+}
+
+impl Replay {
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    // reads a replay file, rejecting it if the scene it starts with, or any it changes to, isn't valid
+    pub fn open(path: &Path) -> Result<Replay, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let replay: Replay = ron::from_str(&text).map_err(|e| e.to_string())?;
+        if !(replay.time_step.is_finite() && replay.time_step > 0.0) {
+            return Err("the time step must be a positive number".to_owned());
+        }
+        replay.scene.validate()?;
+        for (step, scene) in &replay.changes {
+            scene
+                .validate()
+                .map_err(|e| format!("change at update {step}: {e}"))?;
+        }
+        Ok(replay)
+    }
+
+    // saved without pretty printing, as replays can hold many changes
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = ron::ser::to_string(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
+
+pub struct Recorder {
+    replay: Replay,
+    last: Scene, // setup as of the latest change
+}
+
+impl Recorder {
+    // starts recording a simulation that has just been reset
+    pub fn start(simulation: &Simulation) -> Self {
+        let scene = simulation.scene();
+        Recorder {
+            replay: Replay {
+                scene: scene.clone(),
+                changes: Vec::new(),
+                steps: 0,
+                time_step: simulation.time_step(),
+            },
+            last: scene,
+        }
+    }
+
+    // records any change to the setup, called before each update. returns false if the simulation has been
+    // reset since the last update, which ends the run
+    pub fn record(&mut self, simulation: &Simulation) -> bool {
+        let step = simulation.steps();
+        if step < self.replay.steps {
+            return false;
+        }
+        // the scene shares any loaded wave with the simulation, so taking it every update doesn't copy the samples
+        let scene = simulation.scene();
+        if scene != self.last {
+            self.replay.changes.push((step, scene.clone()));
+            self.last = scene;
+        }
+        self.replay.steps = step + 1;
+        true
+    }
+
+    pub fn steps(&self) -> usize {
+        self.replay.steps
+    }
+
+    // bytes taken by the changes recorded so far. loaded waves are shared with the simulation, so aren't counted
+    pub fn memory(&self) -> usize {
+        let changes = self.replay.changes.capacity() * std::mem::size_of::<(usize, Scene)>();
+        changes
+            + self
+                .replay
+                .changes
+                .iter()
+                .map(|(_, scene)| scene.heap_memory())
+                .sum::<usize>()
+    }
+
+    pub fn finish(self) -> Replay {
+        self.replay
+    }
+}
+
+pub struct Player {
+    replay: Replay,
+}
+
+impl Player {
+    pub fn new(replay: Replay) -> Self {
+        Player { replay }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn scene(&self) -> &Scene {
+        &self.replay.scene
+    }
+
+    // makes the change recorded before the simulation's next update, if any. returns false once the recorded run
+    // is over. a simulation that has been reset is put back to the setup the run started with, so it can be watched
+    // again, and one moved back to an earlier point in the run carries on from there
+    pub fn apply(&self, simulation: &mut Simulation) -> bool {
+        let step = simulation.steps();
+        if step == 0 {
+            simulation.set_time_step(self.replay.time_step);
+            simulation.set_up(&self.replay.scene);
+        }
+        // changes are in order of the update they were made before, with at most one for each
+        if let Ok(i) = self
+            .replay
+            .changes
+            .binary_search_by_key(&step, |(change_step, _)| *change_step)
+        {
+            simulation.apply_changes(&self.replay.changes[i].1);
+        }
+        step < self.replay.steps
+    }
+}
+
+impl Simulation {
+    // changes the setup while running, the way the controls do, without resetting
+    pub fn apply_changes(&mut self, scene: &Scene) {
+        if self.samples != scene.samples {
+            self.samples = scene.samples.clone();
+        }
+        self.waveform = scene.waveform;
+        self.frequency = scene.frequency;
+        self.beat = scene.beat;
+        self.parameter_changes = scene.parameter_changes;
+        self.particle_mass = scene.mass;
+        self.spring_constant = scene.spring_constant;
+        self.damping = scene.damping;
+        let spacing_changed = self.particle_spacing != scene.particle_spacing;
+        if spacing_changed || self.particle_count != scene.particle_count {
+            self.particle_count = scene.particle_count;
+            self.particle_spacing = scene.particle_spacing;
+            self.update_particles(spacing_changed);
+        }
+        for x in scene.placed.iter().skip(self.placed.len()) {
+            self.add_particle(*x);
+        }
+        self.set_group_setups(&scene.groups);
+    }
+}
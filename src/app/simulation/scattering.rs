@@ -0,0 +1,102 @@
+//! Comparison of the power the particles scatter at two frequencies of the plane wave.
+//! Driven well below resonance, an oscillator's displacement hardly depends on the frequency, so its acceleration,
+//! and the field it radiates, grow as ω². The scattered power then grows as ω⁴, the frequency dependence of
+//! Rayleigh scattering that makes the sky blue.
+
+use std::f32::consts::TAU;
+
+use num_complex::Complex32;
+
+use super::Simulation;
+use super::config::SimulationConfig;
+use super::scene::Scene;
+use super::solver::SolverType;
+use super::variables::TIME_STEP;
+use super::waveform::Waveform;
+
+// time the particles are left to settle into their steady motion before the power is measured
+pub const SETTLING_TIME: f32 = 30.0;
+// shortest time the scattered power is averaged over, rounded up to a whole number of periods
+const AVERAGING_TIME: f32 = 20.0;
+
+// power scattered at each of two frequencies, from runs of the same scene
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScatteringComparison {
+    pub frequencies: [f32; 2],
+    pub powers: [f32; 2], // scattered field squared, averaged over time and the world
+    pub oscillator_ratio: Option<f32>, // ratio of the powers expected of a single driven damped point charge
+}
+
+impl ScatteringComparison {
+    // runs a scene with a plane wave at each frequency, unless it can't be set up.
+    // progress is called with the number of runs finished
+    pub fn run(
+        scene: &Scene,
+        frequencies: [f32; 2],
+        progress: impl Fn(usize),
+    ) -> Result<Self, String> {
+        let mut powers = [0.0; 2];
+        for (i, (power, frequency)) in powers.iter_mut().zip(frequencies).enumerate() {
+            *power = scattered_power(scene, frequency)?;
+            progress(i + 1);
+        }
+        // field radiated per unit of applied field goes as the acceleration, ω² times the displacement.
+        // this is for point charges: the sheets of the FDTD solver radiate in proportion to their velocity instead
+        let response = |omega: f32| {
+            let restoring = Complex32::new(
+                scene.spring_constant - scene.mass * omega * omega,
+                scene.damping * omega,
+            );
+            (omega * omega / restoring).norm_sqr()
+        };
+        let oscillator_ratio = Some(response(frequencies[1]) / response(frequencies[0]))
+            .filter(|ratio| ratio.is_finite() && scene.solver != SolverType::Fdtd);
+        Ok(ScatteringComparison {
+            frequencies,
+            powers,
+            oscillator_ratio,
+        })
+    }
+
+    // power scattered at the second frequency relative to the first
+    pub fn ratio(&self) -> f32 {
+        self.powers[1] / self.powers[0]
+    }
+
+    // ratio of the powers expected from Rayleigh's ω⁴ law
+    pub fn rayleigh_ratio(&self) -> f32 {
+        (self.frequencies[1] / self.frequencies[0]).powi(4)
+    }
+
+    // number of runs made by a comparison
+    pub fn runs() -> usize {
+        2
+    }
+}
+
+// scattered field squared once the particles have settled, averaged over whole periods of the wave
+// and the points of the grid
+fn scattered_power(scene: &Scene, frequency: f32) -> Result<f32, String> {
+    let scene = Scene {
+        frequency,
+        ..scene.clone()
+    };
+    let config = SimulationConfig::default()
+        .scene(scene)
+        .waveform(Waveform::PlaneWave);
+    let mut simulation = Simulation::from_config(&config)?;
+    let period = TAU / frequency;
+    let averaging_time = (AVERAGING_TIME / period).ceil() * period;
+    let settling_steps = (SETTLING_TIME / TIME_STEP).round() as usize;
+    let averaging_steps = (averaging_time / TIME_STEP).round().max(1.0) as usize;
+    for _ in 0..settling_steps {
+        simulation.update();
+    }
+    let mut total = 0.0;
+    for _ in 0..averaging_steps {
+        simulation.update();
+        let field = simulation.scattered_field();
+        total += field.iter().map(|e| e * e).sum::<f32>() / field.len() as f32;
+    }
+    Ok(total / averaging_steps as f32)
+}
@@ -0,0 +1,200 @@
+//! The user-chosen setup of a simulation, which can be saved to and opened from scene files.
+//! This is everything needed to recreate a simulation from the beginning, but not its progress.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::group::GroupSetup;
+use super::limiter::Limiter;
+use super::particle::ChargedParticleType;
+use super::ramp::ParameterChanges;
+use super::solver::SolverType;
+use super::variables::{DIVISIONS, PARTICLE_SPACING, WAVE_FREQUENCY, WORLD_SIZE};
+use super::waveform::{Beat, SampledWave, Waveform};
+
+// extension of scene files saved by the user
+pub const SCENE_EXTENSION: &str = "scene";
+
+// scenes saved before the plane wave's frequency could be chosen used this one
+fn default_frequency() -> f32 {
+    WAVE_FREQUENCY.initial
+}
+
+// text shown over the canvas while the simulation time is in a range, so a demo explains itself as it plays
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Caption {
+    pub start: f32, // simulation time the caption appears at
+    pub end: f32,   // and disappears at
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub waveform: Waveform,
+    #[serde(default = "default_frequency")]
+    pub frequency: f32, // angular frequency of Waveform::PlaneWave, and the first wave of Waveform::Beat
+    #[serde(default)]
+    pub beat: Beat,
+    pub particle_type: ChargedParticleType,
+    pub particle_count: usize,
+    pub particle_spacing: f32,
+    pub mass: f32,
+    pub spring_constant: f32,
+    pub damping: f32,
+    #[serde(default)]
+    pub temperature: f32, // of the heat bath giving the particles thermal motion, 0 for none
+    #[serde(default)]
+    pub parameter_changes: ParameterChanges, // how changes to mass, k and damping reach the particles while running
+    #[serde(default)]
+    pub limiter: Limiter,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub samples: Option<Arc<SampledWave>>, // for Waveform::Sampled, shared rather than copied with the scene
+    #[serde(default)]
+    pub solver: SolverType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub placed: Vec<f32>, // x coordinates of particles placed individually, besides the evenly spaced row
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupSetup>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captions: Vec<Caption>,
+}
+
+impl Scene {
+    // bytes the scene has allocated besides itself, other than any loaded wave, which is shared
+    pub fn heap_memory(&self) -> usize {
+        self.placed.capacity() * std::mem::size_of::<f32>()
+            + self.groups.capacity() * std::mem::size_of::<GroupSetup>()
+            + self
+                .groups
+                .iter()
+                .map(|g| g.name.capacity() + g.members.capacity() * std::mem::size_of::<usize>())
+                .sum::<usize>()
+            + self.captions.capacity() * std::mem::size_of::<Caption>()
+            + self
+                .captions
+                .iter()
+                .map(|c| c.text.capacity())
+                .sum::<usize>()
+    }
+
+    // the default wave with no particles, for the user to build on
+    pub fn blank() -> Self {
+        let particle_type = ChargedParticleType::default();
+        Scene {
+            waveform: Waveform::GaussianPacket,
+            frequency: WAVE_FREQUENCY.initial,
+            beat: Beat::default(),
+            particle_type,
+            particle_count: 0,
+            particle_spacing: PARTICLE_SPACING.initial,
+            mass: particle_type.mass(),
+            spring_constant: particle_type.default_spring_constant(),
+            damping: particle_type.default_damping(),
+            temperature: 0.0,
+            parameter_changes: ParameterChanges::default(),
+            limiter: Limiter::default(),
+            samples: None,
+            solver: SolverType::default(),
+            placed: Vec::new(),
+            groups: Vec::new(),
+            captions: Vec::new(),
+        }
+    }
+
+    // checks the scene could have been set up with the controls, before it is applied. presets go beyond the ranges
+    // of the sliders for the particles' properties, which only keep values to them when edited, so those are only
+    // checked to be physical, while the frequency and particles are kept to the ranges the controls allow
+    pub fn validate(&self) -> Result<(), String> {
+        if !(WAVE_FREQUENCY.min..=WAVE_FREQUENCY.max).contains(&self.frequency) {
+            return Err(format!(
+                "the frequency must be between {} and {}",
+                WAVE_FREQUENCY.min, WAVE_FREQUENCY.max
+            ));
+        }
+        self.beat.validate()?;
+        self.parameter_changes.validate()?;
+        self.limiter.validate()?;
+        // no closer than the divisions of the grid, or further apart than the spacing slider allows
+        let min_spacing = WORLD_SIZE.span() / DIVISIONS as f32;
+        if !(min_spacing..=PARTICLE_SPACING.max).contains(&self.particle_spacing) {
+            return Err(format!(
+                "the particle spacing must be between {min_spacing} and {}",
+                PARTICLE_SPACING.max
+            ));
+        }
+        // as many particles as fit on screen at once, as for the particle count control
+        let max_particles = (WORLD_SIZE.min.abs() / self.particle_spacing).floor() as usize;
+        if self.particle_count > max_particles {
+            return Err(format!(
+                "at most {max_particles} particles fit at this spacing"
+            ));
+        }
+        if self.placed.len() > DIVISIONS {
+            return Err(format!("at most {DIVISIONS} particles can be placed"));
+        }
+        if let Some(x) = self.placed.iter().find(|x| !WORLD_SIZE.contains(**x)) {
+            return Err(format!("a particle placed at {x} is outside the world"));
+        }
+        validate_properties(self.mass, self.spring_constant, self.damping)?;
+        if !(self.temperature.is_finite() && self.temperature >= 0.0) {
+            return Err("the temperature must be at least 0".to_owned());
+        }
+        let particles = self.particle_count + self.placed.len();
+        for group in &self.groups {
+            validate_properties(
+                group.properties.mass,
+                group.properties.spring_constant,
+                group.properties.damping,
+            )
+            .map_err(|e| format!("group {}: {e}", group.name))?;
+            if group.members.iter().any(|i| *i >= particles) {
+                return Err(format!(
+                    "group {} has a particle that isn't in the scene",
+                    group.name
+                ));
+            }
+        }
+        if let Some(caption) = self.captions.iter().find(|c| {
+            !(c.start.is_finite() && c.end.is_finite() && 0.0 <= c.start && c.start < c.end)
+        }) {
+            return Err(format!(
+                "the caption \"{}\" must start at a time of at least 0, before it ends",
+                caption.text
+            ));
+        }
+        match &self.samples {
+            Some(samples) => samples.validate(),
+            None => Ok(()),
+        }
+    }
+
+    // reads a scene file, rejecting it if the scene isn't valid
+    pub fn open(path: &Path) -> Result<Scene, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let scene: Scene = ron::from_str(&text).map_err(|e| e.to_string())?;
+        scene.validate()?;
+        Ok(scene)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
+
+// particles need a mass to move, and can't be pushed along by their spring or damping
+fn validate_properties(mass: f32, spring_constant: f32, damping: f32) -> Result<(), String> {
+    if !(mass.is_finite() && mass > 0.0) {
+        return Err("the particle mass must be a positive number".to_owned());
+    }
+    if !(spring_constant.is_finite() && spring_constant >= 0.0) {
+        return Err("the spring constant must be a number of at least 0".to_owned());
+    }
+    if !(damping.is_finite() && damping >= 0.0) {
+        return Err("the damping must be a number of at least 0".to_owned());
+    }
+    Ok(())
+}
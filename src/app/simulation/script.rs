@@ -0,0 +1,276 @@
+//! Diagnostics scripted by the user, run after every update of a simulation, for quantities the app doesn't calculate
+//! itself without changing it. Built with the `scripting` feature. A script is written in Rhai and run as a whole
+//! after each update, with the constants `t` and `step`, reading the fields and particles through the functions below
+//! and appending values to named channels with `record`:
+//!
+//! ```text
+//! record("y₀", displacement(0));
+//! record("E at probe", resultant(-5.0));
+//! if particle_count() > 1 { record("y₁ - y₀", displacement(1) - displacement(0)); }
+//! ```
+//!
+//! - `applied(x)`, `resultant(x)`, `scattered(x)`: the fields at x, between the divisions of the grid
+//! - `particle_count()`, and for the particle numbered i: `particle_x(i)`, `displacement(i)`, `velocity(i)`,
+//!   `acceleration(i)`
+//! - `record(name, value)`: appends a value at the time `t` to the channel of that name
+//! - `last(name)`: the latest value recorded in a channel, or () if it has none, for values carried between updates
+//!
+//! Scripts can't change the simulation. Each run is limited to a number of operations, so a runaway loop stops the
+//! script rather than freezing the app.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+use std::rc::Rc;
+
+use egui::{Pos2, pos2};
+use rhai::{AST, Dynamic, Engine, EvalAltResult, FLOAT, INT, Scope};
+
+use super::Simulation;
+use super::field::Field;
+use super::observer::StepObserver;
+
+// most operations a script may take in one run
+const MAX_OPERATIONS: u64 = 1_000_000;
+// most values kept in each channel, the oldest being dropped beyond it
+const MAX_CHANNEL_SAMPLES: usize = 200_000;
+
+// what a script can read of the simulation after an update. the script's functions outlive the borrow of the
+// simulation, so the fields and particles are copied into it
+#[derive(Default)]
+struct Frame {
+    fields: Option<[Field; 3]>,  // applied, resultant and scattered fields
+    particles: Vec<[f32; 4]>,    // x, displacement, velocity and acceleration of each particle
+    records: Vec<(String, f32)>, // values recorded during the run
+    channels: BTreeMap<String, VecDeque<Pos2>>, // values recorded in each channel, against time
+}
+
+impl Frame {
+    fn update(&mut self, simulation: &Simulation) {
+        let grid = simulation.x_intervals();
+        let fields = [
+            simulation.applied_field(),
+            simulation.resultant_field(),
+            simulation.scattered_field(),
+        ];
+        if self
+            .fields
+            .as_ref()
+            .is_none_or(|fields| fields[0].intervals() != grid)
+        {
+            self.fields = Some([(); 3].map(|_| Field::from_points(grid)));
+        }
+        for (field, values) in self.fields.iter_mut().flatten().zip(fields) {
+            field.values_mut().copy_from_slice(values);
+        }
+        self.particles.clear();
+        self.particles
+            .extend(simulation.particles().iter().map(|p| {
+                let position = p.position();
+                [position.x, position.y, p.velocity(), p.acceleration()]
+            }));
+    }
+
+    fn field_at(&self, field: usize, x: FLOAT) -> FLOAT {
+        self.fields
+            .as_ref()
+            .map_or(0.0, |fields| fields[field].value_at(x as f32).into())
+    }
+
+    fn particle(&self, i: INT, property: usize) -> Result<FLOAT, Box<EvalAltResult>> {
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| self.particles.get(i))
+            .map(|particle| particle[property].into())
+            .ok_or_else(|| format!("there is no particle {i}").into())
+    }
+}
+
+// the functions scripts call, reading from and recording into the frame
+fn engine(frame: &Rc<RefCell<Frame>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    for (name, field) in [("applied", 0), ("resultant", 1), ("scattered", 2)] {
+        let frame = frame.clone();
+        engine.register_fn(name, move |x: FLOAT| frame.borrow().field_at(field, x));
+    }
+    let properties = [
+        ("particle_x", 0),
+        ("displacement", 1),
+        ("velocity", 2),
+        ("acceleration", 3),
+    ];
+    for (name, property) in properties {
+        let frame = frame.clone();
+        engine.register_fn(name, move |i: INT| frame.borrow().particle(i, property));
+    }
+    let count = frame.clone();
+    engine.register_fn("particle_count", move || {
+        count.borrow().particles.len() as INT
+    });
+    let record = frame.clone();
+    engine.register_fn("record", move |name: &str, value: FLOAT| {
+        record
+            .borrow_mut()
+            .records
+            .push((name.to_owned(), value as f32));
+    });
+    let record = frame.clone();
+    engine.register_fn("record", move |name: &str, value: INT| {
+        record
+            .borrow_mut()
+            .records
+            .push((name.to_owned(), value as f32));
+    });
+    let last = frame.clone();
+    engine.register_fn("last", move |name: &str| {
+        let frame = last.borrow();
+        match frame.channels.get(name).and_then(|values| values.back()) {
+            Some(point) => Dynamic::from_float(point.y.into()),
+            None => Dynamic::UNIT,
+        }
+    });
+    engine
+}
+
+pub struct DiagnosticScript {
+    pub name: String,
+    engine: Engine,
+    ast: AST,
+    frame: Rc<RefCell<Frame>>,
+    steps: usize, // number of updates of the simulation at the latest run, to tell when it is reset
+    error: Option<String>, // why the script stopped, if it has
+}
+
+impl DiagnosticScript {
+    pub fn compile(name: String, source: &str) -> Result<Self, String> {
+        let frame = Rc::new(RefCell::new(Frame::default()));
+        let engine = engine(&frame);
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+        Ok(DiagnosticScript {
+            name,
+            engine,
+            ast,
+            frame,
+            steps: 0,
+            error: None,
+        })
+    }
+
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let name = path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        DiagnosticScript::compile(name, &source)
+    }
+
+    // values recorded in each channel against time, in order of the channels' names
+    pub fn channels(&self) -> std::cell::Ref<'_, BTreeMap<String, VecDeque<Pos2>>> {
+        std::cell::Ref::map(self.frame.borrow(), |frame| &frame.channels)
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    // forgets the values recorded so far, and runs the script again if it stopped with an error
+    pub fn clear(&mut self) {
+        self.frame.borrow_mut().channels.clear();
+        self.steps = 0;
+        self.error = None;
+    }
+
+    // every value recorded, one per line with the channel it is in and the time it was recorded at
+    pub fn to_csv(&self) -> String {
+        let mut csv = "channel,t,value\n".to_owned();
+        for (name, values) in self.channels().iter() {
+            // names are quoted, as they may contain commas
+            let name = format!("\"{}\"", name.replace('"', "\"\""));
+            for point in values {
+                csv.push_str(&format!("{name},{},{}\n", point.x, point.y));
+            }
+        }
+        csv
+    }
+}
+
+impl StepObserver for DiagnosticScript {
+    fn observe(&mut self, simulation: &Simulation) {
+        if simulation.steps() <= self.steps {
+            self.clear();
+        }
+        self.steps = simulation.steps();
+        if self.error.is_some() {
+            return;
+        }
+        self.frame.borrow_mut().update(simulation);
+        // sample time of the fields just calculated
+        let t = simulation.time() - simulation.time_step();
+        let mut scope = Scope::new();
+        scope.push_constant("t", FLOAT::from(t));
+        scope.push_constant("step", simulation.steps() as INT);
+        let result = self.engine.run_ast_with_scope(&mut scope, &self.ast);
+        let mut frame = self.frame.borrow_mut();
+        let records = std::mem::take(&mut frame.records);
+        match result {
+            Ok(()) => {
+                for (name, value) in records {
+                    let values = frame.channels.entry(name).or_default();
+                    if values.len() == MAX_CHANNEL_SAMPLES {
+                        values.pop_front();
+                    }
+                    values.push_back(pos2(t, value));
+                }
+            }
+            Err(e) => self.error = Some(format!("t = {t:.3} s: {e}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiagnosticScript;
+    use crate::app::compare::RunData;
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::observer::StepObserver;
+
+    // a script reads the simulation after each update and records into its channels, stopping at an error
+    #[test]
+    fn script_records_channels() {
+        let source = r#"
+            record("E", resultant(0.0));
+            record("y", displacement(0));
+            let previous = last("count");
+            record("count", if previous == () { 1 } else { previous + 1.0 });
+        "#;
+        let mut script = DiagnosticScript::compile("test".to_owned(), source).unwrap();
+        let mut simulation = Simulation::from_config(&SimulationConfig::default()).unwrap();
+        for _ in 0..3 {
+            simulation.update_observed(&mut [&mut script]);
+        }
+        assert_eq!(script.error(), None);
+        {
+            let channels = script.channels();
+            assert_eq!(channels.keys().collect::<Vec<_>>(), ["E", "count", "y"]);
+            let counts: Vec<f32> = channels["count"].iter().map(|p| p.y).collect();
+            assert_eq!(counts, [1.0, 2.0, 3.0]);
+            let y = channels["y"].back().unwrap();
+            assert_eq!(y.y, simulation.particles()[0].position().y);
+        }
+        assert!(script.to_csv().starts_with("channel,t,value\n\"E\","));
+        // the values exported can be compared with other runs
+        let run = RunData::from_csv(script.name.clone(), &script.to_csv()).unwrap();
+        assert_eq!(run.curves.len(), 3);
+
+        let mut failing = DiagnosticScript::compile(String::new(), "velocity(99)").unwrap();
+        failing.observe(&simulation);
+        assert!(
+            failing
+                .error()
+                .is_some_and(|e| e.contains("no particle 99"))
+        );
+        assert!(DiagnosticScript::compile(String::new(), "record(").is_err());
+    }
+}
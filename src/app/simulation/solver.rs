@@ -0,0 +1,398 @@
+//! Methods of moving the particles forward in time and calculating the fields they induce.
+//! The retarded potential method finds each particle's field directly from its past motion, treating it as a point charge.
+//! The FDTD method instead steps Maxwell's equations forward on a grid, treating each particle as a sheet of charge
+//! spread across the y axis, which costs the same for every particle however far its field has travelled.
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use super::field::Field;
+use super::variables::{C, Float, PARALLEL_CHUNKS};
+use super::{ChargedParticle, ParticleId};
+
+// largest distance a wave travels in one FDTD step, in divisions. the method is unstable above 1
+const MAX_COURANT: f32 = 0.9;
+
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, Serialize, Deserialize)]
+pub enum SolverType {
+    #[default]
+    RetardedPotentials,
+    Fdtd,
+}
+
+impl SolverType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SolverType::RetardedPotentials => "Retarded potentials",
+            SolverType::Fdtd => "FDTD",
+        }
+    }
+
+    // a solver of this type with nothing calculated yet. grid is the evenly spaced x coordinates of the world,
+    // and time_step the time between updates
+    pub fn create(&self, grid: &[f32], time_step: f32) -> Box<dyn Solver> {
+        match self {
+            SolverType::RetardedPotentials => Box::new(RetardedPotentials),
+            SolverType::Fdtd => Box::new(Fdtd::new(grid, time_step)),
+        }
+    }
+}
+
+// an engine moving the particles and calculating their fields. the simulation owns the particles and the applied
+// and total fields, and hands them to whichever solver is chosen, so it doesn't depend on which one is running
+pub trait Solver {
+    // moves the particles forward by one time step from time t, driven by the applied field,
+    // and updates the field each of them induces, on several threads if parallel is set
+    fn step(
+        &mut self,
+        particles: &mut [ChargedParticle],
+        applied_field: &Field,
+        t: Float,
+        parallel: bool,
+    );
+
+    // forgets everything calculated so far, for a restart on a grid and with a time step that may have changed
+    fn reset(&mut self, grid: &[f32], time_step: f32);
+
+    // which kind of solver this is, to save with a scene and show in the UI
+    fn solver_type(&self) -> SolverType;
+
+    // copy of the solver and anything it has calculated, for a copy of the simulation
+    fn clone_box(&self) -> Box<dyn Solver>;
+
+    // copy of the solver and anything it has calculated, to be saved
+    #[cfg(feature = "serde-state")]
+    fn saved(&self) -> saved::Saved;
+}
+
+impl Clone for Box<dyn Solver> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/*
+== Retarded potentials =====================================================================
+*/
+
+#[derive(Clone)]
+pub struct RetardedPotentials;
+
+impl Solver for RetardedPotentials {
+    fn clone_box(&self) -> Box<dyn Solver> {
+        Box::new(self.clone())
+    }
+
+    // the fields are found from the particles' histories, which are kept by the particles themselves
+    fn reset(&mut self, _grid: &[f32], _time_step: f32) {}
+
+    fn solver_type(&self) -> SolverType {
+        SolverType::RetardedPotentials
+    }
+
+    #[cfg(feature = "serde-state")]
+    fn saved(&self) -> saved::Saved {
+        saved::Saved::RetardedPotentials
+    }
+
+    fn step(
+        &mut self,
+        particles: &mut [ChargedParticle],
+        applied_field: &Field,
+        t: Float,
+        parallel: bool,
+    ) {
+        for i in 0..particles.len() {
+            let (updated, remaining) = particles.split_at_mut(i);
+            let p = &mut remaining[0];
+            // each particle feels the applied wave and the particles before it, which have already moved.
+            // their fields are only needed at this point, so are found without calculating them everywhere
+            let x = p.position.x;
+            let e_y = applied_field.value_at(x)
+                + updated
+                    .iter()
+                    .map(|q| applied_field.value_of_function_at(x, |x| q.radiation(x, t).field))
+                    .sum::<f32>();
+            p.update_position(e_y, t);
+        }
+
+        // each particle's field only depends on its own motion, so they can be calculated at the same time
+        in_parallel(particles, parallel, |_, chunk| {
+            for p in chunk {
+                p.update_induced_field(t);
+            }
+        });
+    }
+}
+
+// calls work on consecutive chunks of items, with the index of the first item in each, on a pool of threads kept
+// for the whole run when parallel is set. every item is worked on by itself in the same way however the items are
+// split, so the results don't depend on the number of threads
+pub(super) fn in_parallel<T: Send>(
+    items: &mut [T],
+    parallel: bool,
+    work: impl Fn(usize, &mut [T]) + Sync,
+) {
+    if !parallel || items.len() < 2 {
+        work(0, items);
+        return;
+    }
+    let chunk_size = items.len().div_ceil(PARALLEL_CHUNKS);
+    items
+        .par_chunks_mut(chunk_size)
+        .enumerate()
+        .for_each(|(i, chunk)| work(i * chunk_size, chunk));
+}
+
+/*
+== FDTD ====================================================================================
+*/
+
+// electric and magnetic fields of one particle on a Yee grid.
+// h[i] lies halfway between e[i] and e[i + 1], and is half a time step ahead of e
+#[derive(Clone)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
+struct YeeGrid {
+    e: Field,
+    h: Vec<f32>,
+}
+
+impl YeeGrid {
+    fn new(grid: &[f32]) -> Self {
+        YeeGrid {
+            e: Field::from_points(grid),
+            h: vec![0.0; grid.len() - 1],
+        }
+    }
+
+    // steps the fields forward by a time step, with a current density flowing at e[source]
+    fn step(&mut self, source: usize, current: f32, time_step: f32) {
+        // waves mustn't cross more than one division per step for the method to be stable,
+        // so the time step is split up on fine grids
+        let step = self.e.step();
+        let substeps = (C * time_step / (MAX_COURANT * step)).ceil().max(1.0);
+        let time_step = time_step / substeps;
+        for _ in 0..substeps as usize {
+            self.substep(source, current, C * time_step / step, time_step);
+        }
+    }
+
+    // steps the fields forward by a time step short enough for a wave to cross less than one division
+    fn substep(&mut self, source: usize, current: f32, courant: f32, time_step: f32) {
+        let e = self.e.values_mut();
+        let n = e.len();
+        let (e_first, e_second, e_last, e_before_last) = (e[0], e[1], e[n - 1], e[n - 2]);
+
+        for (i, h) in self.h.iter_mut().enumerate() {
+            *h -= courant * (e[i + 1] - e[i]);
+        }
+        for (e, h) in e[1..n - 1].iter_mut().zip(self.h.windows(2)) {
+            *e -= courant * (h[1] - h[0]);
+        }
+        e[source] -= time_step * current;
+
+        // first order Mur boundaries, which absorb waves leaving the world instead of reflecting them
+        let mur = (courant - 1.0) / (courant + 1.0);
+        e[0] = e_second + mur * (e[1] - e_first);
+        e[n - 1] = e_before_last + mur * (e[n - 2] - e_last);
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde-state", derive(Serialize, Deserialize))]
+pub struct Fdtd {
+    grid: Vec<f32>, // evenly spaced x coordinates the fields are stepped on
+    time_step: f32, // time between updates
+    particles: HashMap<ParticleId, YeeGrid>, // fields of each particle
+}
+
+impl Fdtd {
+    fn new(grid: &[f32], time_step: f32) -> Self {
+        Fdtd {
+            grid: grid.to_vec(),
+            time_step,
+            particles: HashMap::new(),
+        }
+    }
+}
+
+impl Solver for Fdtd {
+    fn clone_box(&self) -> Box<dyn Solver> {
+        Box::new(self.clone())
+    }
+
+    fn reset(&mut self, grid: &[f32], time_step: f32) {
+        *self = Fdtd::new(grid, time_step);
+    }
+
+    fn solver_type(&self) -> SolverType {
+        SolverType::Fdtd
+    }
+
+    #[cfg(feature = "serde-state")]
+    fn saved(&self) -> saved::Saved {
+        saved::Saved::Fdtd(self.clone())
+    }
+
+    fn step(
+        &mut self,
+        particles: &mut [ChargedParticle],
+        applied_field: &Field,
+        t: Float,
+        parallel: bool,
+    ) {
+        // fields of particles that have been removed are dropped, and particles added since the last step
+        // start with no field, so each keeps its own field however the particles are reordered
+        self.particles
+            .retain(|id, _| particles.iter().any(|p| p.id == *id));
+        let grid = &self.grid;
+
+        // each particle feels the particles before it, whose grids have already been stepped, so the particles
+        // and their grids are stepped one after another. the grids are read where each particle is
+        for i in 0..particles.len() {
+            let x = particles[i].position.x;
+            let e_y = applied_field.value_at(x)
+                + particles[..i]
+                    .iter()
+                    .map(|q| self.particles[&q.id].e.value_at(x))
+                    .sum::<f32>();
+            let p = &mut particles[i];
+            p.update_position(e_y, t);
+
+            // the particle's polarization current, spread over the division it is in
+            let yee = self
+                .particles
+                .entry(p.id)
+                .or_insert_with(|| YeeGrid::new(grid));
+            let step = yee.e.step();
+            let source = (((x - grid[0]) / step).round().max(0.0) as usize).min(grid.len() - 1);
+            let current = p.particle_type.charge() * p.velocity() / step;
+            yee.step(source, current, self.time_step);
+        }
+
+        // copying the grids onto the particles' own divisions only depends on each particle
+        let yees = &self.particles;
+        in_parallel(particles, parallel, |_, chunk| {
+            for p in chunk {
+                let yee = &yees[&p.id];
+                p.field.set_from_function(|x, _| yee.e.value_at(x), 0.0);
+            }
+        });
+    }
+}
+
+// saving and restoring whichever solver a simulation has, with the serde-state feature
+#[cfg(feature = "serde-state")]
+pub(super) mod saved {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Fdtd, RetardedPotentials, Solver};
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Saved {
+        RetardedPotentials,
+        Fdtd(Fdtd),
+    }
+
+    impl Serialize for Box<dyn Solver> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.saved().serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Box<dyn Solver> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Ok(match Saved::deserialize(deserializer)? {
+                Saved::RetardedPotentials => Box::new(RetardedPotentials),
+                Saved::Fdtd(fdtd) => Box::new(fdtd),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use num_complex::Complex32;
+
+    use strum::IntoEnumIterator;
+
+    use super::SolverType;
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::waveform::Waveform;
+
+    // updating the particles on several threads finds the same fields, to the bit, as updating them on one
+    #[test]
+    fn parallel_updates_match_serial() {
+        for solver in SolverType::iter() {
+            let config = SimulationConfig::default().divisions(600).scene(Scene {
+                waveform: Waveform::PlaneWave,
+                particle_count: 12,
+                particle_spacing: 0.5,
+                damping: 0.3,
+                solver,
+                ..Scene::blank()
+            });
+            let mut parallel = Simulation::from_config(&config).unwrap();
+            let mut serial = Simulation::from_config(&config).unwrap();
+            serial.set_parallel(false);
+            for _ in 0..150 {
+                parallel.update();
+                serial.update();
+            }
+            assert_eq!(
+                parallel.resultant_field(),
+                serial.resultant_field(),
+                "{solver:?}"
+            );
+            for (p, q) in parallel.particles().iter().zip(serial.particles()) {
+                assert_eq!(p.position(), q.position(), "{solver:?}");
+            }
+        }
+    }
+
+    // a sheet of charge driven by a plane wave settles to oscillating with the amplitude of a driven damped
+    // oscillator, and radiates a wave of strength qv/2 to either side of it, as for a current sheet
+    #[test]
+    fn fdtd_sheet_settles_to_analytic_steady_state() {
+        let (omega, mass, spring_constant, damping) = (1.0, 0.5, 2.0, 0.5);
+        let mut simulation = Simulation::from_config(&SimulationConfig::default().scene(Scene {
+            waveform: Waveform::PlaneWave,
+            frequency: omega,
+            particle_count: 1,
+            mass,
+            spring_constant,
+            damping,
+            solver: SolverType::Fdtd,
+            ..Scene::blank()
+        }))
+        .unwrap();
+        let charge = simulation.particles()[0].particle_type.charge();
+        let displacement = (charge
+            / Complex32::new(spring_constant - mass * omega * omega, damping * omega))
+        .norm();
+        let radiated = charge.abs() * omega * displacement / 2.0;
+
+        // long enough for the wave to arrive and the transient motion to die away
+        while simulation.time() < 40.0 {
+            simulation.update();
+        }
+        // largest displacement and field over one period
+        let (mut largest_displacement, mut largest_field) = (0.0_f32, 0.0_f32);
+        let end = simulation.time() + TAU / omega;
+        while simulation.time() < end {
+            simulation.update();
+            let particle = &simulation.particles()[0];
+            largest_displacement = largest_displacement.max(particle.position.y.abs());
+            largest_field = largest_field.max(particle.field.value_at(-8.0).abs());
+        }
+        assert!((largest_displacement - displacement).abs() < 0.01 * displacement);
+        assert!((largest_field - radiated).abs() < 0.01 * radiated);
+    }
+}
@@ -0,0 +1,179 @@
+//! Steady-state measurements for parameter sweeps, run without the window to build up dispersion datasets.
+//! For each value of a parameter, a plane wave is run through the particles until their motion has settled, then
+//! the fields either side of them are demodulated at the wave's frequency over whole periods. The resultant beyond
+//! the particles gives the fraction of the power transmitted and the phase it lags by, from which the refractive
+//! index follows as in the phasor panel, and the scattered field before them the fraction reflected.
+
+use std::f32::consts::TAU;
+
+use num_complex::Complex32;
+use strum_macros::EnumIter;
+
+use super::Simulation;
+use super::config::SimulationConfig;
+use super::scene::Scene;
+use super::variables::{C, WORLD_SIZE};
+use super::waveform::Waveform;
+
+// time run after the wave has crossed the world, for the particles' motion to settle
+const SETTLING_TIME: f32 = 40.0;
+// shortest time the fields are demodulated over, rounded up to whole periods of the wave
+const AVERAGING_TIME: f32 = 10.0;
+
+// property of the scene a sweep steps through
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum SweepParameter {
+    Frequency,
+    Mass,
+    SpringConstant,
+    Damping,
+    Spacing,
+    Count,
+}
+
+impl SweepParameter {
+    // name given on the command line, which also heads the column of values
+    pub fn name(&self) -> &'static str {
+        match self {
+            SweepParameter::Frequency => "frequency",
+            SweepParameter::Mass => "mass",
+            SweepParameter::SpringConstant => "spring_constant",
+            SweepParameter::Damping => "damping",
+            SweepParameter::Spacing => "spacing",
+            SweepParameter::Count => "count",
+        }
+    }
+
+    // scene with the parameter set to a value, the number of particles rounded to a whole one
+    pub fn set(&self, scene: &Scene, value: f32) -> Scene {
+        let mut scene = scene.clone();
+        match self {
+            SweepParameter::Frequency => scene.frequency = value,
+            SweepParameter::Mass => scene.mass = value,
+            SweepParameter::SpringConstant => scene.spring_constant = value,
+            SweepParameter::Damping => scene.damping = value,
+            SweepParameter::Spacing => scene.particle_spacing = value,
+            SweepParameter::Count => scene.particle_count = value.round().max(0.0) as usize,
+        }
+        scene
+    }
+}
+
+// measurements of the particles as a slab of medium, once a plane wave through them has settled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SteadyState {
+    pub index: f32, // refractive index n, from the phase the transmitted wave lags by
+    pub transmittance: f32, // fraction of the wave's power passing through the particles, T
+    pub reflectance: f32, // fraction scattered back towards the source, R
+    pub absorptance: f32, // the rest, 1 − T − R
+}
+
+impl SteadyState {
+    // runs a scene with its wave made a plane wave until it has settled, then measures it.
+    // the particles must be an evenly spaced row of at least two
+    pub fn run(scene: &Scene, time_step: f32) -> Result<Self, String> {
+        let scene = Scene {
+            waveform: Waveform::PlaneWave,
+            ..scene.clone()
+        };
+        let config = SimulationConfig::default()
+            .time_step(time_step)
+            .scene(scene);
+        let mut simulation = Simulation::from_config(&config)?;
+        let omega = simulation.frequency();
+
+        let positions: Vec<f32> = simulation
+            .particles()
+            .iter()
+            .map(|p| p.position().x)
+            .collect();
+        if positions.len() < 2 {
+            return Err("the sweep needs at least two particles".to_owned());
+        }
+        let first = positions.iter().copied().fold(f32::INFINITY, f32::min);
+        let last = positions.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        // the wave travels towards -x, so it is transmitted to the left of the particles and reflected to the right
+        let transmitted_x = (WORLD_SIZE.min + first) / 2.0;
+        let reflected_x = (last + WORLD_SIZE.max) / 2.0;
+
+        while simulation.time() < WORLD_SIZE.span() / C + SETTLING_TIME {
+            simulation.update();
+        }
+        let period = TAU / omega;
+        let steps = ((AVERAGING_TIME / period).ceil() * period / time_step).round() as usize;
+        let [mut applied, mut transmitted, mut incident, mut reflected] = [Complex32::ZERO; 4];
+        for _ in 0..steps {
+            simulation.update();
+            // mixing with the wave shifts the oscillation at omega down to a constant, which the average extracts
+            let t = simulation.time() - time_step;
+            let mixer = Complex32::from_polar(2.0 / steps as f32, -omega * t);
+            let applied_at = |x| simulation.applied_field_at(x);
+            let resultant_at = |x| simulation.resultant_field_at(x);
+            applied += mixer * applied_at(transmitted_x);
+            transmitted += mixer * resultant_at(transmitted_x);
+            incident += mixer * applied_at(reflected_x);
+            reflected += mixer * (resultant_at(reflected_x) - applied_at(reflected_x));
+        }
+
+        // taking each particle as a slab of medium as thick as the spacing between them, as the phasor panel does
+        let lag = (applied.arg() - transmitted.arg() + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0;
+        let spacing = (last - first) / (positions.len() - 1) as f32;
+        let thickness = spacing * positions.len() as f32;
+        let transmittance = transmitted.norm_sqr() / applied.norm_sqr();
+        let reflectance = reflected.norm_sqr() / incident.norm_sqr();
+        let measured = SteadyState {
+            index: 1.0 + lag / (omega / C * thickness),
+            transmittance,
+            reflectance,
+            absorptance: 1.0 - transmittance - reflectance,
+        };
+        match [measured.index, transmittance, reflectance]
+            .iter()
+            .all(|v| v.is_finite())
+        {
+            true => Ok(measured),
+            false => Err("the fields diverged before settling".to_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SteadyState, SweepParameter};
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::solver::SolverType;
+    use crate::app::simulation::variables::TIME_STEP;
+    use crate::app::simulation::waveform::Waveform;
+
+    // the index measured in a few sheets of damped particles is close to the theory's, and some of the power is
+    // absorbed by the damping
+    #[test]
+    fn steady_state_of_damped_sheets() {
+        let scene = Scene {
+            waveform: Waveform::GaussianPacket,
+            particle_count: 3,
+            particle_spacing: 2.0,
+            spring_constant: 2.0,
+            damping: 0.3,
+            solver: SolverType::Fdtd,
+            ..Scene::blank()
+        };
+        let scene = SweepParameter::Frequency.set(&scene, 1.0);
+        let measured = SteadyState::run(&scene, TIME_STEP).unwrap();
+        let theory = Simulation::from_config(&SimulationConfig::default().scene(Scene {
+            waveform: Waveform::PlaneWave,
+            ..scene
+        }))
+        .unwrap()
+        .dilute_index()
+        .unwrap();
+        assert!(
+            (measured.index - theory).abs() < 0.1 * (theory - 1.0).abs(),
+            "{measured:?} against n = {theory}"
+        );
+        assert!(measured.transmittance < 1.0 && measured.reflectance > 0.0);
+        assert!(measured.absorptance > 0.0, "{measured:?}");
+    }
+}
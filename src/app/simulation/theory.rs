@@ -0,0 +1,273 @@
+//! Analytic steady state of the simulation, to check the numerical results against.
+//! Driven by a plane wave, every particle eventually oscillates at the wave's frequency with a fixed amplitude and phase,
+//! which the Lorentz model of a driven damped oscillator gives directly.
+
+use num_complex::Complex32;
+
+use crate::app::simulation::Simulation;
+use crate::app::simulation::solver::SolverType;
+use crate::app::simulation::variables::{C, INV_C_2, WORLD_SIZE, narrow};
+use crate::app::simulation::waveform::Waveform;
+
+// relative change in frequency over which the index is differentiated for the group index
+const GROUP_INDEX_STEP: f32 = 1e-3;
+
+impl Simulation {
+    // resultant field at each x interval, at the time of the latest update, once the particles' motion has settled.
+    // as in update(), each particle is driven by the applied wave plus the fields of the particles before it.
+    // the displacements of the particles are assumed small compared to their distance from each point.
+    // None if the wave isn't a plane wave, the particles are sheets of charge in the FDTD solver,
+    // or the particles are driven at resonance with no damping
+    pub fn steady_state_field(&self) -> Option<Vec<f32>> {
+        // the theory is of point charges, as modelled by the retarded potential solver
+        if self.waveform != Waveform::PlaneWave
+            || self.solver_type() != SolverType::RetardedPotentials
+        {
+            return None;
+        }
+        let omega = self.carrier()?;
+        let k = omega / C;
+        let charge = self.particle_type.charge();
+
+        // complex amplitudes of oscillations at omega, so that a value is the imaginary part of amplitude * e^(iωt)
+        let applied = |x: f32| Complex32::from_polar(1.0, k * (x - WORLD_SIZE.max));
+        // displacement of a particle per unit of driving field
+        let response = charge
+            / Complex32::new(
+                self.spring_constant - self.particle_mass * omega * omega,
+                self.damping * omega,
+            );
+        // field at x of a particle at x_p with displacement y, delayed by the time light takes to cross the distance.
+        // fields aren't calculated right next to particles, matching ChargedParticle::radiation
+        let radiated = |y: Complex32, x_p: f32, grid_step: f32, x: f32| {
+            let r = (x - x_p).abs();
+            match r < grid_step {
+                true => Complex32::ZERO,
+                false => {
+                    let acceleration = -omega * omega * y;
+                    INV_C_2 * charge * acceleration * Complex32::from_polar(1.0, -k * r) / r
+                }
+            }
+        };
+
+        let mut displacements: Vec<Complex32> = Vec::with_capacity(self.particles.len());
+        for p in &self.particles {
+            let drive = applied(p.position.x)
+                + displacements
+                    .iter()
+                    .zip(&self.particles)
+                    .map(|(y, q)| radiated(*y, q.position.x, q.grid_step, p.position.x))
+                    .sum::<Complex32>();
+            displacements.push(response * drive);
+        }
+
+        let t = (narrow(self.t) - self.time_step).max(0.0);
+        let oscillation = Complex32::from_polar(1.0, omega * t);
+        let field: Vec<f32> = self
+            .x_intervals()
+            .iter()
+            .map(|x| {
+                let total = applied(*x)
+                    + displacements
+                        .iter()
+                        .zip(&self.particles)
+                        .map(|(y, p)| radiated(*y, p.position.x, p.grid_step, *x))
+                        .sum::<Complex32>();
+                (total * oscillation).im
+            })
+            .collect();
+        field.iter().all(|e| e.is_finite()).then_some(field)
+    }
+
+    // refractive index of the particles as sheets of charge in the FDTD solver, taken as a dilute medium.
+    // each sheet radiates a field in proportion to its velocity, which delays the wave passing through it a little;
+    // over sheets a distance a apart, this is a medium with n = 1 + q² / (2ε₀ a (k − mω² + iγω)), as in the
+    // textbook derivation. None unless the particles are an evenly spaced row with the same properties
+    pub fn dilute_index(&self) -> Option<f32> {
+        self.dilute_index_at(self.carrier()?)
+    }
+
+    // refractive index as above, for a plane wave of angular frequency omega
+    pub fn dilute_index_at(&self, omega: f32) -> Option<f32> {
+        let index = 1.0 + self.susceptibility(omega)?.re / 2.0;
+        index.is_finite().then_some(index)
+    }
+
+    // refractive index of the Lorentz model, n² = 1 + χ, for the same particles at angular frequency omega.
+    // the dilute index is its first order in χ, so the two part where the particles are dense or near resonance
+    pub fn lorentz_index_at(&self, omega: f32) -> Option<f32> {
+        let index = (1.0 + self.susceptibility(omega)?).sqrt().re;
+        index.is_finite().then_some(index)
+    }
+
+    // refractive index of the Lorentz model at the applied wave's carrier frequency, the factor its phase fronts
+    // are slowed by. below 1 above resonance, where they travel faster than light
+    pub fn lorentz_index(&self) -> Option<f32> {
+        self.lorentz_index_at(self.carrier()?)
+    }
+
+    // attenuation coefficient of the intensity in the dilute medium above, from the imaginary part of its index:
+    // the amplitude falls by e^(−ω|Im n|/c) per unit distance, and the intensity twice as fast
+    pub fn dilute_attenuation(&self) -> Option<f32> {
+        let omega = self.carrier()?;
+        let attenuation = omega / C * self.susceptibility(omega)?.im.abs();
+        attenuation.is_finite().then_some(attenuation)
+    }
+
+    // electric susceptibility χ of the particles as a medium, with a density of one sheet per spacing:
+    // χ = N q² / (ε₀ (k − mω² + iγω)). complex where the particles are damped
+    pub(super) fn susceptibility(&self, omega: f32) -> Option<Complex32> {
+        if self.solver_type() != SolverType::Fdtd
+            || self.particle_count < 2
+            || !self.placed.is_empty()
+            || !self.groups.is_empty()
+        {
+            return None;
+        }
+        Some(sheet_susceptibility(
+            self.particle_type.charge(),
+            self.particle_spacing,
+            [self.particle_mass, self.spring_constant, self.damping],
+            omega,
+        ))
+    }
+
+    // angular frequencies around the particles' resonance at which the dilute index peaks, the particles resonate
+    // (where the index crosses 1), and the index dips lowest: where k − mω² is γω, 0 and −γω.
+    // None without damping, as the index is then unbounded at resonance
+    pub fn dispersion_frequencies(&self) -> Option<[f32; 3]> {
+        let (m, k, b) = (self.particle_mass, self.spring_constant, self.damping);
+        if b <= 0.0 || m <= 0.0 || k <= 0.0 {
+            return None;
+        }
+        let root = (b * b + 4.0 * m * k).sqrt();
+        Some([
+            (root - b) / (2.0 * m),
+            (k / m).sqrt(),
+            (root + b) / (2.0 * m),
+        ])
+    }
+
+    // group index n + ω dn/dω of the Lorentz model at the applied wave's carrier frequency, the factor a pulse's
+    // peak is slowed by, which is large where the index changes steeply with frequency
+    pub fn group_index(&self) -> Option<f32> {
+        let omega = self.carrier()?;
+        let step = GROUP_INDEX_STEP * omega;
+        let optical_length = |omega: f32| Some(omega * self.lorentz_index_at(omega)?);
+        let index = (optical_length(omega + step)? - optical_length(omega - step)?) / (2.0 * step);
+        index.is_finite().then_some(index)
+    }
+}
+
+// susceptibility as above of sheets of a charge a spacing apart, with a mass, spring constant and damping
+pub(super) fn sheet_susceptibility(
+    charge: f32,
+    spacing: f32,
+    [mass, spring_constant, damping]: [f32; 3],
+    omega: f32,
+) -> Complex32 {
+    let restoring = Complex32::new(spring_constant - mass * omega * omega, damping * omega);
+    // ε₀ is 1 in the simulation's units
+    charge * charge / (spacing * restoring)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_complex::Complex32;
+
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::solver::SolverType;
+    use crate::app::simulation::waveform::Waveform;
+
+    const MASS: f32 = 0.5;
+    const SPRING_CONSTANT: f32 = 2.0;
+    const SPACING: f32 = 2.0;
+
+    // an evenly spaced row of sheets in the FDTD solver, which the indices are calculated for
+    fn row(omega: f32, damping: f32) -> Simulation {
+        Simulation::from_config(&SimulationConfig::default().scene(Scene {
+            waveform: Waveform::PlaneWave,
+            frequency: omega,
+            particle_count: 4,
+            particle_spacing: SPACING,
+            mass: MASS,
+            spring_constant: SPRING_CONSTANT,
+            damping,
+            solver: SolverType::Fdtd,
+            ..Scene::blank()
+        }))
+        .unwrap()
+    }
+
+    // χ = q² / (ε₀ a (k − mω² + iγω)), worked out independently of the simulation
+    fn susceptibility(simulation: &Simulation, omega: f32, damping: f32) -> Complex32 {
+        let charge = simulation.particle_type.charge();
+        charge * charge
+            / (SPACING * Complex32::new(SPRING_CONSTANT - MASS * omega * omega, damping * omega))
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4 * expected.abs().max(1.0),
+            "{actual} is not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn lorentz_index_is_square_root_of_permittivity() {
+        for omega in [0.5, 1.0, 2.5] {
+            let simulation = row(omega, 0.3);
+            let expected = (1.0 + susceptibility(&simulation, omega, 0.3)).sqrt().re;
+            assert_close(simulation.lorentz_index().unwrap(), expected);
+        }
+        // without damping, below resonance the index is above 1 and above it below 1
+        assert!(row(1.0, 0.0).lorentz_index().unwrap() > 1.0);
+        assert!(row(2.5, 0.0).lorentz_index().unwrap() < 1.0);
+    }
+
+    #[test]
+    fn dilute_index_is_first_order_in_susceptibility() {
+        for omega in [0.5, 1.0, 2.5] {
+            let simulation = row(omega, 0.3);
+            let expected = 1.0 + susceptibility(&simulation, omega, 0.3).re / 2.0;
+            assert_close(simulation.dilute_index().unwrap(), expected);
+        }
+        // the two part by no more than the second order term of the square root, χ² / 8
+        let simulation = row(0.5, 0.0);
+        let chi = susceptibility(&simulation, 0.5, 0.0).re;
+        let (dilute, lorentz) = (
+            simulation.dilute_index().unwrap(),
+            simulation.lorentz_index().unwrap(),
+        );
+        assert!(dilute > lorentz && dilute - lorentz <= chi * chi / 8.0);
+    }
+
+    #[test]
+    fn group_index_matches_derivative_of_index() {
+        // without damping n² = 1 + χ with dχ/dω = 2mωχ / (k − mω²), so n + ω dn/dω = n + ω dχ/dω / 2n
+        let omega = 1.0;
+        let simulation = row(omega, 0.0);
+        let chi = susceptibility(&simulation, omega, 0.0).re;
+        let n = (1.0 + chi).sqrt();
+        let derivative = 2.0 * MASS * omega * chi / (SPRING_CONSTANT - MASS * omega * omega);
+        let expected = n + omega * derivative / (2.0 * n);
+        let group_index = simulation.group_index().unwrap();
+        assert!(
+            (group_index - expected).abs() < 1e-3 * expected,
+            "{group_index} is not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn indices_need_an_even_row_in_fdtd() {
+        let mut simulation = row(1.0, 0.3);
+        simulation.set_solver(SolverType::RetardedPotentials);
+        assert!(simulation.lorentz_index().is_none());
+        assert!(simulation.dilute_index().is_none());
+        let mut simulation = row(1.0, 0.3);
+        simulation.add_particle(2.0);
+        assert!(simulation.group_index().is_none());
+    }
+}
@@ -0,0 +1,101 @@
+//! Thermal motion of the particles, as if each sat in a heat bath: every update it is kicked by a random force, as in
+//! the Langevin equation. The collisions with the bath that kick a particle also slow it down, so the strength of the
+//! kicks is tied to its damping by the fluctuation–dissipation theorem. With a force of standard deviation √(2γT/Δt)
+//! the kicks put back the energy the damping takes out once the particle has settled at temperature T, where its
+//! kinetic and potential energies each average ½T (taking Boltzmann's constant as 1). Without damping there is no
+//! bath, and no thermal motion. The kicks are drawn from a seed set on reset, so each run of a scene is the same.
+
+use std::f32::consts::TAU;
+
+use fastrand::Rng;
+
+use super::Simulation;
+
+// seed the kicks are drawn from after a reset
+pub const THERMAL_SEED: u64 = 0x7e41;
+
+// number drawn from the standard normal distribution, from two uniformly distributed numbers by the Box–Muller
+// transform
+pub fn standard_normal(rng: &mut Rng) -> f32 {
+    (-2.0 * (1.0 - rng.f32()).ln()).sqrt() * (TAU * rng.f32()).cos()
+}
+
+// the state of the random number generator is saved with the rest of the simulation, so a restored one draws the
+// same kicks as the one it was saved from
+#[cfg(feature = "serde-state")]
+pub(super) mod rng_state {
+    use fastrand::Rng;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(rng: &Rng, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(rng.get_seed())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rng, D::Error> {
+        u64::deserialize(deserializer).map(Rng::with_seed)
+    }
+}
+
+impl Simulation {
+    // temperature of the heat bath the particles sit in, which is 0 without thermal motion
+    pub fn temperature(&self) -> f32 {
+        self.temperature
+    }
+
+    // draws the random force each particle feels during the coming update
+    pub(super) fn kick_particles(&mut self) {
+        let (temperature, time_step) = (self.temperature, self.time_step);
+        for particle in &mut self.particles {
+            particle.thermal_force = match temperature > 0.0 && particle.damping > 0.0 {
+                true => {
+                    (2.0 * particle.damping * temperature / time_step).sqrt()
+                        * standard_normal(&mut self.thermal_noise)
+                }
+                false => 0.0,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::waveform::Waveform;
+
+    // once the pulse has passed, the particles settle into thermal motion with the energy equipartition gives them,
+    // the same in every run
+    #[test]
+    fn particles_settle_at_temperature() {
+        let (temperature, spring_constant) = (0.01, 0.5);
+        let scene = Scene {
+            waveform: Waveform::Gaussian,
+            particle_count: 1,
+            spring_constant,
+            damping: 0.5,
+            temperature,
+            ..Scene::blank()
+        };
+        let run = |steps: usize| {
+            let mut simulation =
+                Simulation::from_config(&SimulationConfig::default().scene(scene.clone())).unwrap();
+            let mut squares = Vec::new();
+            for step in 0..steps {
+                simulation.update();
+                if step >= 3000 || steps < 3000 {
+                    squares.push(simulation.particles()[0].position().y.powi(2));
+                }
+            }
+            squares.iter().sum::<f32>() / squares.len() as f32
+        };
+        // ½k⟨y²⟩ = ½T
+        let mean_square = run(24_000);
+        let expected = temperature / spring_constant;
+        assert!(
+            (mean_square - expected).abs() < 0.25 * expected,
+            "⟨y²⟩ = {mean_square}, expected {expected}"
+        );
+        assert_eq!(run(100), run(100));
+    }
+}
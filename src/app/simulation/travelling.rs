@@ -0,0 +1,158 @@
+//! The applied wave, kept as its shape rather than evaluated afresh at every division every update.
+//! Every waveform travels to the left at the speed of light without changing shape, so its value at x and time t
+//! only depends on u = x + C t. The shape is sampled evenly in u across the world, in a ring buffer that moves along
+//! with the wave as time passes: each update the waveform is only evaluated for the few samples where the wave enters
+//! the world, and the field at each division is interpolated between the samples either side of it.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use egui::Rangef;
+
+use super::Simulation;
+use super::variables::{C, Float, narrow};
+use super::waveform::{Beat, SampledWave, Waveform, wave_function};
+
+// samples of the shape for every division of the grid, where it is finest
+const OVERSAMPLING: f32 = 4.0;
+
+// everything the shape depends on, so it is sampled afresh when any of it changes
+#[derive(Clone)]
+struct Shape {
+    waveform: Waveform,
+    frequency: f32,
+    beat: Beat,
+    samples: Option<Arc<SampledWave>>,
+    spacing: f32, // distance between samples in u
+}
+
+impl PartialEq for Shape {
+    fn eq(&self, other: &Self) -> bool {
+        let same_samples = match (&self.samples, &other.samples) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        self.waveform == other.waveform
+            && self.frequency == other.frequency
+            && self.beat == other.beat
+            && self.spacing == other.spacing
+            && same_samples
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct TravellingWave {
+    shape: Option<Shape>,
+    first: i64, // index of the first sample kept. sample i is the wave at u = i * spacing
+    values: VecDeque<f32>, // samples of the shape in order of u
+}
+
+impl TravellingWave {
+    // moves the samples along to cover the x range at time t
+    fn advance(&mut self, shape: Shape, range: Rangef, t: Float) {
+        if self.shape.as_ref() != Some(&shape) {
+            self.values.clear();
+        }
+        let shape = self.shape.insert(shape);
+        let spacing = Float::from(shape.spacing);
+        let index = |x: f32| (Float::from(x) + Float::from(C) * t) / spacing;
+        let (first, last) = (
+            index(range.min).floor() as i64 - 1,
+            index(range.max).ceil() as i64 + 1,
+        );
+
+        // samples that have left the world are dropped, and only those where the wave enters are found.
+        // after a jump back in time, e.g. a reset, none of them can be kept
+        if first < self.first || first >= self.first + self.values.len() as i64 {
+            self.values.clear();
+        }
+        if self.values.is_empty() {
+            self.first = first;
+        }
+        let leaving = (first - self.first) as usize;
+        self.values.drain(..leaving);
+        self.first = first;
+        let wave = wave_function(
+            shape.waveform,
+            shape.samples.as_deref(),
+            shape.frequency,
+            shape.beat,
+        );
+        let known = self.first + self.values.len() as i64;
+        self.values
+            .extend((known..=last).map(|i| wave(narrow(i as Float * spacing), 0.0)));
+    }
+
+    // field of the wave at x and time t, between the samples either side. t must be the time last advanced to
+    fn value_at(&self, x: f32, t: Float) -> f32 {
+        let Some(shape) = &self.shape else {
+            return 0.0;
+        };
+        let index = (Float::from(x) + Float::from(C) * t) / Float::from(shape.spacing)
+            - self.first as Float;
+        let lower = index.floor();
+        let fraction = narrow(index - lower);
+        let sample = |i: Float| self.values.get(i as usize).copied().unwrap_or(0.0);
+        sample(lower) * (1.0 - fraction) + sample(lower + 1.0) * fraction
+    }
+}
+
+impl Simulation {
+    // sets the applied and resultant fields to the applied wave at the current time, before the particles add theirs
+    pub(super) fn set_applied_fields(&mut self) {
+        let shape = Shape {
+            waveform: self.waveform,
+            frequency: self.frequency,
+            beat: self.beat,
+            samples: self.samples.clone(),
+            spacing: self.applied_field.step() / OVERSAMPLING,
+        };
+        self.applied_wave.advance(shape, self.size, self.t);
+        let (wave, t) = (&self.applied_wave, self.t);
+        self.applied_field
+            .set_from_function(|x, _| wave.value_at(x, t), 0.0);
+        self.resultant_field
+            .values_mut()
+            .copy_from_slice(self.applied_field.values());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::simulation::Simulation;
+    use crate::app::simulation::config::SimulationConfig;
+    use crate::app::simulation::scene::Scene;
+    use crate::app::simulation::waveform::{Waveform, wave_function};
+
+    // the shifted shape follows the waveform evaluated directly as it crosses the world
+    #[test]
+    fn shifted_shape_matches_waveform() {
+        for waveform in [
+            Waveform::GaussianPacket,
+            Waveform::PlaneWave,
+            Waveform::Beat,
+        ] {
+            let scene = Scene {
+                waveform,
+                frequency: 2.0,
+                particle_count: 0,
+                ..Scene::blank()
+            };
+            let mut simulation =
+                Simulation::from_config(&SimulationConfig::default().scene(scene.clone())).unwrap();
+            let wave = wave_function(waveform, None, scene.frequency, scene.beat);
+            for _ in 0..600 {
+                let t = simulation.time();
+                simulation.update();
+                for (x, e) in simulation
+                    .x_intervals()
+                    .iter()
+                    .zip(simulation.applied_field())
+                {
+                    let error = e - wave(*x, t);
+                    assert!(error.abs() < 1e-3, "{waveform:?} at x = {x}, t = {t}");
+                }
+            }
+        }
+    }
+}
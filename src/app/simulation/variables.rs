@@ -1,6 +1,19 @@
 use egui::Rangef;
 extern crate static_assertions as sa;
 
+// precision of the simulation's time and particle motion. f32 error builds up over long runs,
+// so the f64 feature can be enabled where measurements need to stay accurate
+#[cfg(not(feature = "f64"))]
+pub type Float = f32;
+#[cfg(feature = "f64")]
+pub type Float = f64;
+
+// narrows a simulation value to f32, for drawing and the UI
+#[allow(clippy::unnecessary_cast)] // the cast does nothing without the f64 feature
+pub fn narrow(value: Float) -> f32 {
+    value as f32
+}
+
 pub struct Variable {
     pub initial: f32,
     pub min: f32,
@@ -9,11 +22,47 @@ pub struct Variable {
 
 // number of x axis points to calculate the field for
 pub const DIVISIONS: usize = 1000;
+// half the width of the region around each particle where the grid is made finer, to resolve the near field
+pub const REFINEMENT_WINDOW: f32 = 0.5;
+// number of divisions each division of the grid is split into near particles
+pub const REFINEMENT_FACTOR: usize = 4;
+// age of the particles' motion history kept at full resolution. older samples are only seen by points
+// further away than this times C, where the field is weaker, so they can be thinned out to save memory
+pub const FULL_HISTORY_TIME: f32 = 2.0;
+// number of samples thinned out at once, so each sample isn't moved around the history too often
+pub const DECIMATION_BLOCK: usize = 64;
+// largest factor the older history can be thinned out by
+pub const MAX_HISTORY_DECIMATION: usize = 16;
+// number of particles from which they are updated, and their fields added up, on several threads
+pub const PARALLEL_PARTICLES: usize = 8;
+// number of chunks the particles and divisions are split into between threads, more than most machines have, so
+// the work is shared out evenly
+pub const PARALLEL_CHUNKS: usize = 64;
 // amount internal simulation time increments by each update
 pub const TIME_STEP: f32 = 1.0 / (crate::app::SIMULATION_FPS as f32);
 // speed of light
 pub const C: f32 = 1.0;
 pub const INV_C_2: f32 = 1.0 / (C * C);
+// field strength, relative to the applied wave's amplitude, above which the simulation is considered to have blown up
+pub const RUNAWAY_FIELD: f32 = 1e4;
+// displacement of a particle above which the simulation is considered to have blown up
+pub const RUNAWAY_DISPLACEMENT: f32 = 1e3;
+// largest displacement of a particle the limiter allows, by default within the canvas
+pub const LIMIT_DISPLACEMENT: Variable = Variable {
+    initial: 1.5,
+    min: 0.1,
+    max: 10.0,
+};
+// largest resultant field the limiter allows, relative to the applied wave's amplitude
+pub const LIMIT_FIELD: Variable = Variable {
+    initial: 2.0,
+    min: 0.5,
+    max: 100.0,
+};
+// damping added each update a bound is exceeded, when the limiter raises the damping
+pub const LIMITER_DAMPING_STEP: f32 = 0.002;
+// time over which the intensity is averaged, long enough to cover a few periods of the built-in waves
+pub const INTENSITY_WINDOW: f32 = 4.0;
 
 // size of simulation
 pub const WORLD_SIZE: Rangef = Rangef {
@@ -38,6 +87,30 @@ pub const ELECTRON_DAMPING: Variable = Variable {
     min: 0.0,
     max: 1.0,
 };
+// temperature of the heat bath giving the particles thermal motion, in units of energy
+pub const TEMPERATURE: Variable = Variable {
+    initial: 0.0,
+    min: 0.0,
+    max: 0.05,
+};
+// angular frequency of the plane wave
+pub const WAVE_FREQUENCY: Variable = Variable {
+    initial: 1.0,
+    min: 0.25,
+    max: 3.0,
+};
+// angular frequency of the second wave of a beat above the first
+pub const BEAT_SEPARATION: Variable = Variable {
+    initial: 0.2,
+    min: 0.02,
+    max: 1.0,
+};
+// strength of each wave of a beat
+pub const BEAT_AMPLITUDE: Variable = Variable {
+    initial: 0.5,
+    min: 0.0,
+    max: 1.0,
+};
 // distance between neighbouring electrons
 pub const PARTICLE_SPACING: Variable = Variable {
     initial: 3.0,
@@ -49,6 +122,13 @@ sa::const_assert!(WORLD_SIZE.min < WORLD_SIZE.max);
 sa::const_assert!(SPRING_CONSTANT.min < SPRING_CONSTANT.max);
 sa::const_assert!(ELECTRON_MASS.min < ELECTRON_MASS.max);
 sa::const_assert!(ELECTRON_DAMPING.min < ELECTRON_DAMPING.max);
+sa::const_assert!(TEMPERATURE.min < TEMPERATURE.max);
+sa::const_assert!(WAVE_FREQUENCY.min < WAVE_FREQUENCY.max);
+sa::const_assert!(BEAT_SEPARATION.min > 0.0);
+sa::const_assert!(BEAT_AMPLITUDE.min < BEAT_AMPLITUDE.max);
 sa::const_assert!(PARTICLE_SPACING.min < PARTICLE_SPACING.max);
+sa::const_assert!(LIMIT_DISPLACEMENT.min < LIMIT_DISPLACEMENT.max);
+sa::const_assert!(LIMIT_FIELD.min < LIMIT_FIELD.max);
 sa::const_assert!(TIME_STEP > 0.0);
+sa::const_assert!(REFINEMENT_FACTOR >= 1);
 sa::const_assert!(C > 0.0);
@@ -1,50 +1,224 @@
+use std::f32::consts::TAU;
+
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
-use crate::app::simulation::variables::{C, WORLD_SIZE};
+use crate::app::simulation::variables::{
+    BEAT_AMPLITUDE, BEAT_SEPARATION, C, WAVE_FREQUENCY, WORLD_SIZE,
+};
 
 /*
 == Possible forms for the applied wave ========================================
 */
 
 // Dropdown in the UI will be automatically populated with these options
-#[derive(Debug, PartialEq, Clone, Copy, EnumIter)]
+#[derive(Debug, PartialEq, Clone, Copy, EnumIter, Serialize, Deserialize)]
 pub enum Waveform {
     Gaussian,       // single gaussian pulse
     GaussianPacket, // sine wave modulated by gaussian
     PlaneWave,      // sine wave
+    Beat,           // two sine waves at nearby frequencies
+    Sampled,        // loaded from a file, the samples are held by the simulation
 }
 
 impl Waveform {
-    pub fn properties(&self) -> WaveformProperties {
-        // function separated out to allow for future flexibility
-        self.retrieve_properties()
+    // name, colour and shape of the waveform with the initial frequency and beat, e.g. for the dropdown
+    pub fn properties(&self) -> WaveformProperties<'static> {
+        self.properties_with(None, WAVE_FREQUENCY.initial, Beat::default())
     }
 
-    fn retrieve_properties(&self) -> WaveformProperties {
+    // properties of the waveform with the samples used if it is Waveform::Sampled,
+    // the angular frequency chosen if it is Waveform::PlaneWave or Waveform::Beat, and the second wave of a beat
+    pub fn properties_with<'a>(
+        &self,
+        samples: Option<&'a SampledWave>,
+        frequency: f32,
+        beat: Beat,
+    ) -> WaveformProperties<'a> {
         match self {
             Waveform::Gaussian => WaveformProperties {
                 name: "Gaussian",
-                function: wavefunctions::gaussian_wave,
+                function: Box::new(wavefunctions::gaussian_wave),
                 colour: (255, 50, 50),
+                carrier: None,
             },
             Waveform::GaussianPacket => WaveformProperties {
                 name: "Gaussian Packet",
-                function: wavefunctions::gaussian_packet_wave,
+                function: Box::new(wavefunctions::gaussian_packet_wave),
                 colour: (50, 255, 50),
+                carrier: Some(5.0 * C),
             },
             Waveform::PlaneWave => WaveformProperties {
                 name: "Plane Wave",
-                function: wavefunctions::plane_wave,
-                colour: (255, 50, 50),
+                function: Box::new(move |x, t| wavefunctions::plane_wave_at(x, t, frequency)),
+                colour: (255, 90, 170),
+                carrier: Some(frequency),
+            },
+            Waveform::Beat => WaveformProperties {
+                name: "Beat",
+                function: Box::new(move |x, t| wavefunctions::beat_at(x, t, frequency, beat)),
+                colour: (200, 120, 255),
+                // oscillates at the mean of its two frequencies, inside an envelope that swells and fades
+                carrier: Some(frequency + beat.separation / 2.0),
+            },
+            Waveform::Sampled => WaveformProperties {
+                name: "Sampled",
+                // no wave until one has been loaded
+                function: match samples {
+                    Some(samples) => Box::new(move |x, t| samples.value(x, t)),
+                    None => Box::new(|_, _| 0.0),
+                },
+                colour: (255, 170, 40),
+                carrier: None,
             },
         }
     }
 }
 
-pub struct WaveformProperties {
+// combines a waveform with the samples used if it is Waveform::Sampled,
+// the angular frequency chosen if it is Waveform::PlaneWave or Waveform::Beat, and the second wave of a beat
+pub fn wave_function(
+    waveform: Waveform,
+    samples: Option<&SampledWave>,
+    frequency: f32,
+    beat: Beat,
+) -> impl Fn(f32, f32) -> f32 + '_ {
+    waveform.properties_with(samples, frequency, beat).function
+}
+
+pub struct WaveformProperties<'a> {
     pub name: &'static str,
-    pub function: fn(f32, f32) -> f32,
-    pub colour: (u8, u8, u8), // RGB - default should be (255, 50, 50)
+    pub function: Box<dyn Fn(f32, f32) -> f32 + 'a>, // field at x and time t, with the parameters it was made with
+    pub colour: (u8, u8, u8),                        // RGB - default should be (255, 50, 50)
+    pub carrier: Option<f32>, // angular frequency of the oscillation inside the wave, if it has a single one
+}
+
+// two plane waves at nearby frequencies, drifting in and out of step so that their sum swells and fades.
+// the first is at the frequency chosen for the plane wave, and the second a little above it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Beat {
+    pub separation: f32,      // angular frequency of the second wave above the first
+    pub amplitudes: [f32; 2], // strength of the first and second waves
+}
+
+impl Default for Beat {
+    fn default() -> Self {
+        Beat {
+            separation: BEAT_SEPARATION.initial,
+            amplitudes: [BEAT_AMPLITUDE.initial; 2],
+        }
+    }
+}
+
+impl Beat {
+    // time between the swells, when the two waves are back in step
+    pub fn period(&self) -> f32 {
+        TAU / self.separation
+    }
+
+    // checks the beat could have been set up with the controls
+    pub fn validate(&self) -> Result<(), String> {
+        if !(BEAT_SEPARATION.min..=BEAT_SEPARATION.max).contains(&self.separation) {
+            return Err(format!(
+                "the beat's frequency separation must be between {} and {}",
+                BEAT_SEPARATION.min, BEAT_SEPARATION.max
+            ));
+        }
+        if !self
+            .amplitudes
+            .iter()
+            .all(|a| (BEAT_AMPLITUDE.min..=BEAT_AMPLITUDE.max).contains(a))
+        {
+            return Err(format!(
+                "the beat's amplitudes must be between {} and {}",
+                BEAT_AMPLITUDE.min, BEAT_AMPLITUDE.max
+            ));
+        }
+        Ok(())
+    }
+}
+
+/*
+== Waveforms loaded from files ================================================
+*/
+
+// field strength of the applied wave as it enters the right edge of the world, sampled over time.
+// like the built-in waveforms, it travels to the left at the speed of light
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampledWave {
+    pub name: String,
+    times: Vec<f32>, // strictly increasing
+    values: Vec<f32>,
+}
+
+impl SampledWave {
+    // reads two columns of numbers, time and field strength, one sample per line.
+    // columns may be separated by commas, semicolons or whitespace, and lines that aren't numbers (e.g. headers) are skipped.
+    // NaN or infinite samples are rejected, as they would spread through every field
+    pub fn from_csv(name: String, text: &str) -> Result<Self, String> {
+        let mut times = Vec::new();
+        let mut values = Vec::new();
+        for (number, line) in (1..).zip(text.lines()) {
+            let columns: Vec<f32> = line
+                .split([',', ';', ' ', '\t'])
+                .filter(|column| !column.is_empty())
+                .map_while(|column| column.trim().parse().ok())
+                .collect();
+            let [t, e] = columns[..] else {
+                continue;
+            };
+            if !(t.is_finite() && e.is_finite()) {
+                return Err(format!("line {number}: samples must be finite numbers"));
+            }
+            if let Some(last) = times.last().filter(|last| t <= **last) {
+                return Err(format!(
+                    "line {number}: times must increase, but {t} follows {last}"
+                ));
+            }
+            times.push(t);
+            values.push(e);
+        }
+        if times.len() < 2 {
+            return Err("at least two samples of time and field strength are needed".to_owned());
+        }
+        Ok(SampledWave {
+            name,
+            times,
+            values,
+        })
+    }
+
+    // checks samples read from somewhere other than a wave file, such as a scene, as from_csv does
+    pub fn validate(&self) -> Result<(), String> {
+        if self.times.len() != self.values.len() || self.times.len() < 2 {
+            return Err("at least two samples of time and field strength are needed".to_owned());
+        }
+        if !self.times.iter().chain(&self.values).all(|v| v.is_finite()) {
+            return Err("samples must be finite numbers".to_owned());
+        }
+        if !self.times.windows(2).all(|t| t[0] < t[1]) {
+            return Err("times must increase".to_owned());
+        }
+        Ok(())
+    }
+
+    // largest absolute field strength of any sample
+    pub fn peak(&self) -> f32 {
+        self.values.iter().map(|e| e.abs()).fold(0.0, f32::max)
+    }
+
+    // field strength at position x and time t, interpolated between samples and zero outside them
+    pub fn value(&self, x: f32, t: f32) -> f32 {
+        // time at which the part of the wave now at x entered the world
+        let entry_t = t - (WORLD_SIZE.max - x) / C;
+        let i = self.times.partition_point(|sample_t| *sample_t <= entry_t);
+        if i == 0 || i == self.times.len() {
+            return 0.0;
+        }
+        let (t1, t2) = (self.times[i - 1], self.times[i]);
+        let (e1, e2) = (self.values[i - 1], self.values[i]);
+        e1 + (e2 - e1) * (entry_t - t1) / (t2 - t1)
+    }
 }
 
 mod wavefunctions {
@@ -58,8 +232,13 @@ mod wavefunctions {
         let xp = x + C * t - WORLD_SIZE.max;
         (-xp * xp).exp() * (5.0 * xp).sin()
     }
-    pub fn plane_wave(x: f32, t: f32) -> f32 {
+    pub fn plane_wave_at(x: f32, t: f32, frequency: f32) -> f32 {
         let xp = x + C * t - WORLD_SIZE.max;
-        (1.0 * xp).sin()
+        (frequency / C * xp).sin()
+    }
+    pub fn beat_at(x: f32, t: f32, frequency: f32, beat: Beat) -> f32 {
+        let [first, second] = beat.amplitudes;
+        first * plane_wave_at(x, t, frequency)
+            + second * plane_wave_at(x, t, frequency + beat.separation)
     }
 }
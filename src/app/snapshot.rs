@@ -0,0 +1,38 @@
+//! A frozen copy of the resultant field, drawn as a ghost trace behind the live one.
+//! Taking a snapshot, changing a parameter and running again shows the effect of the change in a single picture.
+//! The simulation pauses when it reaches the time the snapshot was taken, so the two are compared at the same moment.
+
+use crate::app::simulation::Simulation;
+
+pub struct Snapshot {
+    x_points: Vec<f32>,
+    field: Vec<f32>,
+    time: f32,
+}
+
+impl Snapshot {
+    pub fn take(simulation: &Simulation) -> Self {
+        Snapshot {
+            x_points: simulation.x_intervals().to_vec(),
+            field: simulation.resultant_field().to_vec(),
+            time: simulation.time(),
+        }
+    }
+
+    pub fn x_points(&self) -> &[f32] {
+        &self.x_points
+    }
+
+    pub fn field(&self) -> &[f32] {
+        &self.field
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    // whether an update from one time to another has just reached the time of the snapshot
+    pub fn reached(&self, before: f32, after: f32) -> bool {
+        before < self.time && self.time <= after
+    }
+}
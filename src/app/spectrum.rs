@@ -0,0 +1,185 @@
+//! Frequency spectrum of the fields at the probe, from their latest samples in time, showing which frequencies the
+//! medium passes on and which it absorbs or adds. The spectrum is the amplitude of each frequency in the samples,
+//! which are windowed first so a wave that doesn't fit the samples a whole number of times doesn't spread out. The
+//! fields are sampled every few updates, as the waves' frequencies are far below the rate the simulation updates at,
+//! and a longer time sampled resolves them more finely.
+
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+
+use egui::{Pos2, Ui, pos2};
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+use crate::app::i18n::tr;
+use crate::app::plot::{Series, line_plot};
+use crate::app::simulation::Simulation;
+use crate::app::simulation::variables::{WAVE_FREQUENCY, WORLD_SIZE};
+use crate::app::{applied_field_colour, resultant_field_colour};
+
+// most samples transformed, a power of two for the fastest transform
+const SAMPLES: usize = 1024;
+// fewest samples a spectrum is shown for, coarser than that of all of them
+const MIN_SAMPLES: usize = 128;
+// number of updates between samples
+const DECIMATION: usize = 8;
+// highest angular frequency plotted, well above that of any wave applied
+const MAX_PLOTTED_FREQUENCY: f32 = 2.0 * WAVE_FREQUENCY.max;
+const PLOT_HEIGHT: f32 = 200.0;
+
+// amplitude of each frequency in evenly spaced samples, against angular frequency, up to half the sample rate
+pub fn amplitude_spectrum(
+    planner: &mut FftPlanner<f32>,
+    samples: &[f32],
+    time_step: f32,
+) -> Vec<Pos2> {
+    let n = samples.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    // Hann window, its mean taken out of the amplitudes so a sine of amplitude a peaks at a
+    let window = |i: usize| 0.5 - 0.5 * (TAU * i as f32 / n as f32).cos();
+    let mut buffer: Vec<Complex<f32>> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| Complex::new(sample * window(i), 0.0))
+        .collect();
+    planner.plan_fft_forward(n).process(&mut buffer);
+    let gain: f32 = (0..n).map(window).sum();
+    buffer[..=n / 2]
+        .iter()
+        .enumerate()
+        .map(|(k, value)| {
+            // each frequency but the mean and Nyquist ones is split between positive and negative frequencies
+            let scale = if k == 0 || 2 * k == n { 1.0 } else { 2.0 };
+            let omega = TAU * k as f32 / (n as f32 * time_step);
+            pos2(omega, scale * value.norm() / gain)
+        })
+        .collect()
+}
+
+pub struct SpectrumTracker {
+    applied: VecDeque<f32>,
+    resultant: VecDeque<f32>,
+    time_step: f32, // time between the samples, restarted when the simulation's changes
+    probe_x: f32,   // position the samples are of, restarted when the probe moves
+    steps: usize, // number of updates of the simulation at the latest sample, to tell when it is reset
+    planner: FftPlanner<f32>,
+}
+
+impl Default for SpectrumTracker {
+    fn default() -> Self {
+        SpectrumTracker {
+            applied: VecDeque::with_capacity(SAMPLES),
+            resultant: VecDeque::with_capacity(SAMPLES),
+            time_step: f32::NAN,
+            probe_x: f32::NAN,
+            steps: 0,
+            planner: FftPlanner::new(),
+        }
+    }
+}
+
+impl SpectrumTracker {
+    pub fn record(&mut self, simulation: &Simulation, probe_x: f32) {
+        let time_step = simulation.time_step() * DECIMATION as f32;
+        if probe_x != self.probe_x
+            || time_step != self.time_step
+            || simulation.steps() <= self.steps
+        {
+            self.clear();
+            self.probe_x = probe_x;
+            self.time_step = time_step;
+        }
+        self.steps = simulation.steps();
+        if !self.steps.is_multiple_of(DECIMATION) {
+            return;
+        }
+        if self.applied.len() == SAMPLES {
+            self.applied.pop_front();
+            self.resultant.pop_front();
+        }
+        self.applied.push_back(simulation.applied_field_at(probe_x));
+        self.resultant
+            .push_back(simulation.resultant_field_at(probe_x));
+    }
+
+    pub fn clear(&mut self) {
+        self.applied.clear();
+        self.resultant.clear();
+        self.time_step = f32::NAN;
+        self.probe_x = f32::NAN;
+        self.steps = 0;
+    }
+
+    pub fn draw(&mut self, ui: &mut Ui, simulation: &Simulation, probe_x: &mut f32) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Probe position"));
+            ui.add(egui::Slider::new(probe_x, WORLD_SIZE.min..=WORLD_SIZE.max));
+        });
+        if self.applied.len() < MIN_SAMPLES {
+            ui.label(format!(
+                "{} ({}/{MIN_SAMPLES})",
+                tr("Collecting samples at the probe"),
+                self.applied.len()
+            ));
+            return;
+        }
+        let spectra = [
+            (
+                tr("Applied"),
+                applied_field_colour(0.9, &simulation.waveform()),
+                &self.applied,
+            ),
+            (
+                tr("Resultant"),
+                resultant_field_colour(0.9),
+                &self.resultant,
+            ),
+        ]
+        .map(|(name, colour, samples)| {
+            let samples: Vec<f32> = samples.iter().copied().collect();
+            Series {
+                name,
+                colour,
+                points: amplitude_spectrum(&mut self.planner, &samples, self.time_step)
+                    .into_iter()
+                    .take_while(|point| point.x <= MAX_PLOTTED_FREQUENCY)
+                    .collect(),
+            }
+        });
+        line_plot(ui, PLOT_HEIGHT, &spectra, "ω");
+        ui.label(tr(
+            "Amplitude of each angular frequency in the fields at the probe, over the latest samples. \
+            The peaks sharpen as more samples are taken.",
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustfft::FftPlanner;
+
+    use super::amplitude_spectrum;
+
+    // a sine peaks at its frequency, with its amplitude, whether or not it fits the samples a whole number of times
+    #[test]
+    fn sine_peaks_at_its_frequency() {
+        let time_step = 0.05;
+        for omega in [4.0, 4.1] {
+            let samples: Vec<f32> = (0..1024)
+                .map(|i| 0.5 + 2.0 * (omega * i as f32 * time_step).sin())
+                .collect();
+            let spectrum = amplitude_spectrum(&mut FftPlanner::new(), &samples, time_step);
+            assert_eq!(spectrum.len(), 513);
+            assert!((spectrum[0].y - 0.5).abs() < 0.01);
+            let peak = spectrum[1..]
+                .iter()
+                .max_by(|a, b| a.y.total_cmp(&b.y))
+                .unwrap();
+            let resolution = spectrum[1].x;
+            assert!((peak.x - omega).abs() <= resolution / 2.0);
+            assert!((peak.y - 2.0).abs() < 0.3);
+        }
+    }
+}
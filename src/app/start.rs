@@ -0,0 +1,154 @@
+//! Start screen shown at launch, for choosing what to simulate.
+//! Lists the built-in presets with a preview of each, a blank simulation, and recently used session and scene files.
+
+use std::path::PathBuf;
+
+use egui::{Color32, Context, Sense, Stroke, Ui, pos2, vec2};
+use strum::IntoEnumIterator;
+
+use crate::app::i18n::tr;
+use crate::app::palette::colours;
+use crate::app::session::display_name;
+use crate::app::settings::Settings;
+use crate::app::simulation::preset::Preset;
+use crate::app::simulation::scene::Scene;
+use crate::app::simulation::variables::{C, WORLD_SIZE};
+
+const PREVIEW_SIZE: [f32; 2] = [180.0, 60.0];
+// time at which the applied wave is shown in previews, chosen so a pulse sits in view ahead of the particles
+const PREVIEW_TIME: f32 = (WORLD_SIZE.max - 2.0) / C;
+const PREVIEW_POINTS: usize = 120;
+// number of preset cards on each row
+const COLUMNS: usize = 3;
+
+pub enum StartChoice {
+    Preset(Preset),
+    Blank,
+    File(PathBuf),
+}
+
+// draws a sketch of a scene: the applied wave, and the particles on the x axis
+fn draw_preview(ui: &mut Ui, scene: &Scene) {
+    let (rect, _) = ui.allocate_exact_size(PREVIEW_SIZE.into(), Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, Color32::from_rgb(10, 10, 10));
+    painter.hline(
+        rect.x_range(),
+        rect.center().y,
+        (1.0, Color32::from_gray(40)),
+    );
+
+    let to_screen_x =
+        |x: f32| rect.left() + rect.width() * (x - WORLD_SIZE.min) / WORLD_SIZE.span();
+    let wave =
+        scene
+            .waveform
+            .properties_with(scene.samples.as_deref(), scene.frequency, scene.beat);
+    let points = (0..PREVIEW_POINTS)
+        .map(|i| {
+            let x = WORLD_SIZE.min + WORLD_SIZE.span() * i as f32 / (PREVIEW_POINTS - 1) as f32;
+            let y = (wave.function)(x, PREVIEW_TIME);
+            pos2(to_screen_x(x), rect.center().y - 0.4 * rect.height() * y)
+        })
+        .collect();
+    let (r, g, b) = colours().applied.unwrap_or(wave.colour);
+    painter.line(points, Stroke::new(1.5, Color32::from_rgb(r, g, b)));
+
+    let (r, g, b) = colours().particle(scene.particle_type);
+    for i in 0..scene.particle_count {
+        let x = -(i as f32) * scene.particle_spacing;
+        painter.circle_filled(
+            pos2(to_screen_x(x), rect.center().y),
+            3.0,
+            Color32::from_rgb(r, g, b),
+        );
+    }
+}
+
+// a clickable card with a preview of a scene, its name and description
+fn scene_card(ui: &mut Ui, scene: &Scene, name: &str, description: &str) -> bool {
+    let response = egui::Frame::group(ui.style())
+        .show(ui, |ui| {
+            ui.set_width(PREVIEW_SIZE[0]);
+            draw_preview(ui, scene);
+            ui.strong(name);
+            ui.add(egui::Label::new(description).wrap());
+        })
+        .response
+        .interact(Sense::click());
+    if response.hovered() {
+        ui.painter().rect_stroke(
+            response.rect,
+            4.0,
+            ui.visuals().selection.stroke,
+            egui::StrokeKind::Inside,
+        );
+    }
+    response.clicked()
+}
+
+// shows the start screen while open is true, returning what the user chose to start with
+pub fn show(ctx: &Context, open: &mut bool, settings: &mut Settings) -> Option<StartChoice> {
+    if !*open {
+        return None;
+    }
+    let mut choice = None;
+    let modal = egui::Modal::new(egui::Id::new("start screen")).show(ctx, |ui| {
+        ui.heading(tr("What would you like to simulate?"));
+        ui.add_space(8.0);
+
+        egui::Grid::new("start screen scenes")
+            .spacing(vec2(8.0, 8.0))
+            .show(ui, |ui| {
+                for (i, preset) in Preset::iter().enumerate() {
+                    let properties = preset.properties();
+                    if scene_card(
+                        ui,
+                        &preset.scene(),
+                        tr(properties.name),
+                        tr(properties.description),
+                    ) {
+                        choice = Some(StartChoice::Preset(preset));
+                    }
+                    if (i + 1) % COLUMNS == 0 {
+                        ui.end_row();
+                    }
+                }
+                if scene_card(
+                    ui,
+                    &Scene::blank(),
+                    tr("Blank simulation"),
+                    tr("The applied wave with no particles, to build your own setup"),
+                ) {
+                    choice = Some(StartChoice::Blank);
+                }
+            });
+
+        if !settings.recent_files.is_empty() {
+            ui.add_space(8.0);
+            ui.strong(tr("Recent files"));
+            for path in &settings.recent_files {
+                if ui
+                    .add_enabled(path.exists(), egui::Link::new(display_name(path)))
+                    .on_hover_text(path.display().to_string())
+                    .clicked()
+                {
+                    choice = Some(StartChoice::File(path.clone()));
+                }
+            }
+        }
+
+        ui.add_space(8.0);
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut settings.show_start_screen, tr("Show at startup"));
+            if ui.button(tr("Close")).clicked() {
+                *open = false;
+            }
+        });
+    });
+    if choice.is_some() || modal.should_close() {
+        *open = false;
+    }
+    choice
+}
@@ -0,0 +1,271 @@
+//! Status bar reporting the state of the simulation, and any problems detected with it
+
+use egui::{Color32, RichText, Ui};
+use strum::IntoEnumIterator;
+
+use crate::app::canvas::VISIBLE_Y_SPAN;
+use crate::app::i18n::tr;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::command::SimulationCommand;
+use crate::app::simulation::divergence::{Divergence, Quantity};
+use crate::app::simulation::limiter::{LimitAction, Limited};
+use crate::app::simulation::solver::SolverType;
+use crate::app::simulation::variables::{LIMIT_DISPLACEMENT, LIMIT_FIELD, MAX_HISTORY_DECIMATION};
+
+// oscillators are integrated inaccurately when they complete a large part of a cycle in one time step.
+// the integration becomes unstable at 2, so warn well before that
+const MAX_STABLE_PHASE_STEP: f32 = 1.0;
+
+const WARNING_COLOUR: Color32 = Color32::from_rgb(255, 170, 40);
+
+// range of the memory cap, in MiB
+const MIN_MEMORY_CAP: usize = 64;
+const MAX_MEMORY_CAP: usize = 1 << 16;
+
+// bytes taken by the parts of the app that grow as the simulation runs
+pub struct MemoryUse {
+    pub history: usize,   // motion histories of the particles
+    pub recording: usize, // replay being recorded, if any
+    pub surface: usize,   // field history of the 3D surface
+}
+
+impl MemoryUse {
+    pub fn total(&self) -> usize {
+        self.history + self.recording + self.surface
+    }
+}
+
+// problems that can be detected in a running simulation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Warning {
+    Clipping,
+    UnstableTimeStep,
+    MemoryCap,
+}
+
+impl Warning {
+    fn message(&self) -> &'static str {
+        match self {
+            Warning::Clipping => "Clipping",
+            Warning::UnstableTimeStep => "Unstable time step",
+            Warning::MemoryCap => "Memory cap reached",
+        }
+    }
+
+    fn explanation(&self) -> &'static str {
+        match self {
+            Warning::Clipping => "The resultant field is too large to fit on the canvas.",
+            Warning::UnstableTimeStep => {
+                "The particles oscillate too quickly for the simulation time step, so their motion is inaccurate. \
+                Try reducing the spring constant or increasing the mass."
+            }
+            Warning::MemoryCap => {
+                "The particles' histories have been thinned out as far as they can be, and motion too old to be \
+                seen anywhere forgotten, but more memory is used than the cap allows. Try fewer particles or a higher cap."
+            }
+        }
+    }
+}
+
+// check the simulation for problems. over_memory_cap is whether the memory used couldn't be kept within the cap
+pub fn check(simulation: &Simulation, over_memory_cap: bool) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    if simulation
+        .resultant_field()
+        .iter()
+        .any(|e| e.abs() > VISIBLE_Y_SPAN / 2.0)
+    {
+        warnings.push(Warning::Clipping);
+    }
+    if simulation.natural_frequency() * simulation.time_step() > MAX_STABLE_PHASE_STEP {
+        warnings.push(Warning::UnstableTimeStep);
+    }
+    if over_memory_cap {
+        warnings.push(Warning::MemoryCap);
+    }
+    warnings
+}
+
+// name of a value, and the particle it belongs to if any
+fn describe_quantity(quantity: &Quantity) -> String {
+    let name = tr(quantity.name());
+    match quantity.particle() {
+        Some(i) => format!("{name} {} {}", tr("of particle"), i + 1),
+        None => name.to_string(),
+    }
+}
+
+// which value blew up, and when
+fn describe_divergence(divergence: &Divergence) -> String {
+    let quantity = describe_quantity(&divergence.quantity);
+    format!(
+        "{quantity} {} {} {}, t = {:.2} s",
+        tr(divergence.kind.message()),
+        tr("at step"),
+        divergence.step,
+        divergence.time
+    )
+}
+
+// which value the limiter found beyond its bound, and what it did
+fn describe_limited(limited: &Limited, damping: f32) -> String {
+    let action = match limited.action {
+        LimitAction::Damp => format!("{} {damping:.3}", tr("damping raised to")),
+        LimitAction::Pause => tr("paused").to_owned(),
+        LimitAction::Off | LimitAction::Clamp => tr("held at its bound").to_owned(),
+    };
+    format!(
+        "{}: {} {}, {action}",
+        tr("Limiter"),
+        describe_quantity(&limited.quantity),
+        tr("out of bounds")
+    )
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1 << 30 => format!("{:.1} GiB", b as f32 / (1 << 30) as f32),
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f32 / (1 << 20) as f32),
+        b if b >= 1 << 10 => format!("{:.1} KiB", b as f32 / (1 << 10) as f32),
+        b => format!("{b} B"),
+    }
+}
+
+// draws a single line describing the simulation
+pub fn draw_status_bar(
+    ui: &mut Ui,
+    simulation: &mut Simulation,
+    warnings: &[Warning],
+    refresh_rate: f32,
+    memory: &MemoryUse,
+    memory_cap: &mut usize,
+) {
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "{} {}",
+            tr("Particles:"),
+            simulation.particles().len()
+        ));
+        ui.separator();
+        ui.label(format!("{} {}", tr("Memory:"), format_bytes(memory.total())))
+            .on_hover_text(format!(
+                "{}\n{} {}\n{} {}\n{} {}",
+                tr("Memory used by what grows as the simulation runs"),
+                tr("Particle histories:"),
+                format_bytes(memory.history),
+                tr("Recording:"),
+                format_bytes(memory.recording),
+                tr("3D field history:"),
+                format_bytes(memory.surface),
+            ));
+        ui.add(
+            egui::DragValue::new(memory_cap)
+                .range(MIN_MEMORY_CAP..=MAX_MEMORY_CAP)
+                .prefix("≤ ")
+                .suffix(" MiB"),
+        )
+        .on_hover_text(tr(
+            "Most memory to use. Beyond it the older motion history is thinned out further, \
+            and motion too old to be seen anywhere is forgotten, so long runs left unattended can't use up the memory.",
+        ));
+        let mut decimation = simulation.history_decimation();
+        ui.add(
+            egui::DragValue::new(&mut decimation)
+                .range(1..=MAX_HISTORY_DECIMATION)
+                .prefix("1/"),
+        )
+        .on_hover_text(tr(
+            "Fraction of the older motion history kept. Older motion is only seen far from each particle, \
+            where its field is weak, so keeping less of it saves memory on long runs with little loss of accuracy.",
+        ));
+        if decimation != simulation.history_decimation() {
+            simulation.send(SimulationCommand::HistoryDecimation(decimation));
+        }
+        ui.separator();
+        ui.label(tr("Limiter:")).on_hover_text(tr(
+            "Safeguard for demonstrations, e.g. at resonance. When a particle moves further than the displacement bound, \
+            or the resultant field grows beyond its bound times the amplitude of the applied wave, \
+            the limiter holds them at the bounds, raises the damping until the motion settles, or pauses.",
+        ));
+        let mut limiter = simulation.limiter();
+        egui::ComboBox::from_id_salt("Limiter")
+            .selected_text(tr(limiter.action.name()))
+            .show_ui(ui, |ui| {
+                for action in LimitAction::iter() {
+                    ui.selectable_value(&mut limiter.action, action, tr(action.name()));
+                }
+            });
+        if limiter.action != LimitAction::Off {
+            ui.add(
+                egui::DragValue::new(&mut limiter.displacement)
+                    .range(LIMIT_DISPLACEMENT.min..=LIMIT_DISPLACEMENT.max)
+                    .speed(0.01)
+                    .prefix("|y| ≤ "),
+            )
+            .on_hover_text(tr("Largest displacement of any particle"));
+            ui.add(
+                egui::DragValue::new(&mut limiter.field)
+                    .range(LIMIT_FIELD.min..=LIMIT_FIELD.max)
+                    .speed(0.05)
+                    .prefix("|E| ≤ ")
+                    .suffix(" E₀"),
+            )
+            .on_hover_text(tr(
+                "Largest resultant field, as a multiple of the amplitude of the applied wave",
+            ));
+        }
+        if limiter != simulation.limiter() {
+            simulation.send(SimulationCommand::Limiter(limiter));
+        }
+        ui.separator();
+        ui.label(format!(
+            "{} {} {}, Δx = {:.3}",
+            tr("Grid:"),
+            simulation.grid_points(),
+            tr("points"),
+            simulation.grid_step()
+        ));
+        ui.separator();
+        ui.label(tr("Solver:")).on_hover_text(tr(
+            "Method used to calculate the fields. Retarded potentials treats each particle as a point charge, \
+            while FDTD steps Maxwell's equations forward on a grid, treating each particle as a sheet of charge. \
+            Changing it restarts the simulation.",
+        ));
+        let mut solver = simulation.solver_type();
+        egui::ComboBox::from_id_salt("Solver")
+            .selected_text(tr(simulation.solver_name()))
+            .show_ui(ui, |ui| {
+                for solver_type in SolverType::iter() {
+                    ui.selectable_value(&mut solver, solver_type, tr(solver_type.name()));
+                }
+            });
+        if solver != simulation.solver_type() {
+            simulation.set_solver(solver);
+        }
+        ui.separator();
+        ui.label(format!("{} {refresh_rate:.0} Hz", tr("Display:")))
+            .on_hover_text(tr("Refresh rate of the display, measured at startup"));
+        for warning in warnings {
+            ui.separator();
+            ui.label(RichText::new(format!("⚠ {}", tr(warning.message()))).color(WARNING_COLOUR))
+                .on_hover_text(tr(warning.explanation()));
+        }
+        if let Some(divergence) = simulation.divergence() {
+            ui.separator();
+            ui.label(
+                RichText::new(format!("⚠ {}", describe_divergence(divergence)))
+                    .color(WARNING_COLOUR),
+            )
+            .on_hover_text(tr(
+                "The simulation was paused because it blew up. Try adding damping and restarting.",
+            ));
+        }
+        if let Some(limited) = simulation.limited() {
+            ui.separator();
+            ui.label(
+                RichText::new(format!("⚠ {}", describe_limited(limited, simulation.damping())))
+                    .color(WARNING_COLOUR),
+            );
+        }
+    });
+}
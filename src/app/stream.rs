@@ -0,0 +1,376 @@
+//! Streaming the fields live to web browsers on the local network, so students can follow along on their own
+//! devices while the simulation is driven from the app. Built with the `stream` feature.
+//! A small HTTP server serves a page that repeatedly fetches the fields in view as JSON and draws them itself,
+//! which is far cheaper than sending images of the canvas.
+
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use egui::{Color32, DragValue, Rangef, Ui};
+use serde::Serialize;
+
+use crate::app::canvas::VISIBLE_Y_SPAN;
+use crate::app::i18n::tr;
+use crate::app::settings::Settings;
+use crate::app::simulation::Simulation;
+use crate::app::{
+    applied_field_colour, intensity_colour, particle_colour, particle_field_colour,
+    resultant_field_colour, scattered_field_colour, zoom_to,
+};
+
+const DEFAULT_PORT: u16 = 8080;
+// how often the server checks whether it has been stopped, while waiting for connections
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+// most bytes read of a request's line and headers, beyond which it is refused
+const MAX_REQUEST_LENGTH: u64 = 8 * 1024;
+// most connections answered at once, others being closed straight away
+const MAX_CONNECTIONS: usize = 32;
+const PAGE: &str = include_str!("stream.html");
+
+// the fields in view, as drawn by the page
+#[derive(Serialize)]
+struct Frame {
+    time: f32,
+    x_axis: [f32; 2],
+    y_axis: [f32; 2],
+    x: Vec<f32>,
+    traces: Vec<Trace>,
+    particles: Vec<Particle>,
+}
+
+#[derive(Serialize)]
+struct Trace {
+    colour: String,
+    y: Vec<f32>,
+}
+
+#[derive(Serialize)]
+struct Particle {
+    x: f32,
+    y: f32,
+    colour: String,
+}
+
+// a CSS colour
+fn css(colour: Color32) -> String {
+    let [r, g, b, a] = colour.to_srgba_unmultiplied();
+    format!("rgba({r},{g},{b},{:.3})", a as f32 / 255.0)
+}
+
+impl Frame {
+    fn new(simulation: &Simulation, settings: &Settings) -> Self {
+        let x_axis = zoom_to(simulation.size(), settings.zoom, settings.world_centre);
+        let y_axis = zoom_to(
+            &Rangef::new(-VISIBLE_Y_SPAN / 2.0, VISIBLE_Y_SPAN / 2.0),
+            settings.y_zoom,
+            settings.y_centre,
+        );
+        let reference = simulation.applied_amplitude();
+        let scale = |y: &[f32]| -> Vec<f32> {
+            y.iter()
+                .map(|y| settings.y_scale.apply(*y, reference))
+                .collect()
+        };
+
+        let mut fields = vec![];
+        for particle in simulation.particles() {
+            fields.push((
+                particle.field(),
+                particle_field_colour(settings.particle_field_opacity),
+            ));
+        }
+        fields.extend([
+            (
+                simulation.applied_field(),
+                applied_field_colour(settings.applied_field_opacity, &simulation.waveform()),
+            ),
+            (
+                simulation.resultant_field(),
+                resultant_field_colour(settings.resultant_field_opacity),
+            ),
+            (
+                simulation.scattered_field(),
+                scattered_field_colour(settings.scattered_field_opacity),
+            ),
+            (
+                simulation.intensity(),
+                intensity_colour(settings.intensity_opacity),
+            ),
+        ]);
+        let traces = fields
+            .into_iter()
+            .filter(|(_, colour)| colour.a() > 0)
+            .map(|(field, colour)| Trace {
+                colour: css(colour),
+                y: scale(field),
+            })
+            .collect();
+        let particles = simulation
+            .particles()
+            .iter()
+            .map(|particle| Particle {
+                x: particle.position().x,
+                y: settings.y_scale.apply(particle.position().y, reference),
+                colour: css(particle_colour(1.0, particle)),
+            })
+            .collect();
+
+        Frame {
+            time: simulation.time(),
+            x_axis: [x_axis.min, x_axis.max],
+            y_axis: [y_axis.min, y_axis.max],
+            x: simulation.x_intervals().to_vec(),
+            traces,
+            particles,
+        }
+    }
+}
+
+// a server that is running
+struct Server {
+    address: SocketAddr,
+    latest: Arc<Mutex<String>>, // the latest frame, as JSON
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+pub struct Stream {
+    port: u16,
+    server: Option<Server>,
+    error: Option<String>,
+}
+
+impl Default for Stream {
+    fn default() -> Self {
+        Stream {
+            port: DEFAULT_PORT,
+            server: None,
+            error: None,
+        }
+    }
+}
+
+impl Stream {
+    pub fn draw(&mut self, ui: &mut Ui) {
+        ui.label(tr(
+            "Shows the fields live in a web browser, so others on the same network \
+            can follow along on their own devices.",
+        ));
+        match &self.server {
+            Some(server) => {
+                ui.horizontal(|ui| {
+                    ui.label(tr("Open in a browser:"));
+                    let url = format!("http://{}", server.address);
+                    ui.hyperlink(url);
+                });
+                if ui.button(tr("Stop streaming")).clicked() {
+                    self.server = None;
+                }
+            }
+            None => {
+                ui.horizontal(|ui| {
+                    ui.label(tr("Port"));
+                    ui.add(DragValue::new(&mut self.port).range(1024..=65535));
+                    if ui.button(tr("Start streaming")).clicked() {
+                        match start(self.port) {
+                            Ok(server) => {
+                                self.server = Some(server);
+                                self.error = None;
+                            }
+                            Err(e) => self.error = Some(e.to_string()),
+                        }
+                    }
+                });
+            }
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+
+    // makes the simulation's current state the one sent to browsers, if streaming
+    pub fn publish(&self, simulation: &Simulation, settings: &Settings) {
+        let Some(server) = &self.server else {
+            return;
+        };
+        match serde_json::to_string(&Frame::new(simulation, settings)) {
+            Ok(json) => *server.latest.lock().unwrap() = json,
+            Err(e) => log::warn!("could not stream frame: {e}"),
+        }
+    }
+}
+
+// a connection being answered, counted until it is closed
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    // takes a slot, unless as many connections as allowed are already being answered
+    fn take(connections: &Arc<AtomicUsize>) -> Option<Self> {
+        connections
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < MAX_CONNECTIONS).then_some(count + 1)
+            })
+            .ok()
+            .map(|_| ConnectionSlot(connections.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+// listens for browsers on all network interfaces, answering each on its own thread, up to a limit at once
+fn start(port: u16) -> std::io::Result<Server> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    listener.set_nonblocking(true)?;
+    let latest = Arc::new(Mutex::new(String::from("null")));
+    let stop = Arc::new(AtomicBool::new(false));
+    let connections = Arc::new(AtomicUsize::new(0));
+    let server = Server {
+        address: SocketAddr::new(local_address(), listener.local_addr()?.port()),
+        latest: latest.clone(),
+        stop: stop.clone(),
+    };
+    std::thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((connection, _)) => {
+                    let Some(slot) = ConnectionSlot::take(&connections) else {
+                        log::debug!("stream connection refused: too many connections");
+                        continue;
+                    };
+                    let latest = latest.clone();
+                    std::thread::spawn(move || {
+                        if let Err(e) = respond(connection, &latest) {
+                            log::debug!("stream connection failed: {e}");
+                        }
+                        drop(slot);
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+                Err(e) => log::warn!("stream connection failed: {e}"),
+            }
+        }
+    });
+    Ok(server)
+}
+
+// answers a single request, for the page or the latest frame
+fn respond(mut connection: TcpStream, latest: &Mutex<String>) -> std::io::Result<()> {
+    connection.set_nonblocking(false)?;
+    connection.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new((&connection).take(MAX_REQUEST_LENGTH));
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // the headers aren't needed, but are read so the browser isn't cut off while sending them
+    let mut header = String::new();
+    let mut complete = request.ends_with('\n');
+    while complete {
+        header.clear();
+        let length = reader.read_line(&mut header)?;
+        complete = header.ends_with('\n');
+        if length <= 2 {
+            break;
+        }
+    }
+
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+    let (status, content_type, body) = match path {
+        // the request ran past the most read, or was cut off
+        _ if !complete => (
+            "431 Request Header Fields Too Large",
+            "text/plain",
+            String::from("request too large"),
+        ),
+        "/" => ("200 OK", "text/html; charset=utf-8", PAGE.to_string()),
+        "/frame" => ("200 OK", "application/json", latest.lock().unwrap().clone()),
+        _ => ("404 Not Found", "text/plain", String::from("not found")),
+    };
+    write!(
+        connection,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+        Cache-Control: no-store\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    if !complete {
+        // closing with the rest of the request unread would reset the connection, losing the response, so a
+        // little more of it is read and thrown away first
+        connection.shutdown(Shutdown::Write)?;
+        let _ = std::io::copy(
+            &mut (&connection).take(MAX_REQUEST_LENGTH),
+            &mut std::io::sink(),
+        );
+    }
+    Ok(())
+}
+
+// the address of this computer on the local network, found from the route to a public address.
+// nothing is sent, as connecting a UDP socket only chooses the route
+fn local_address() -> std::net::IpAddr {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("8.8.8.8:80")?;
+            socket.local_addr()
+        })
+        .map_or(std::net::Ipv4Addr::LOCALHOST.into(), |address| address.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{Shutdown, TcpStream};
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+
+    use super::{ConnectionSlot, MAX_CONNECTIONS, MAX_REQUEST_LENGTH, start};
+
+    fn get(port: u16, request: &[u8]) -> String {
+        let mut connection = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        // the server may stop reading once the request is too long, so a failed write is expected then
+        let _ = connection.write_all(request);
+        let _ = connection.shutdown(Shutdown::Write);
+        let mut response = String::new();
+        connection.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    // requests are answered up to a length, and those longer are refused rather than read in full
+    #[test]
+    fn long_requests_are_refused() {
+        let server = start(0).unwrap();
+        let port = server.address.port();
+        let response = get(port, b"GET /frame HTTP/1.1\r\nHost: test\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("null"));
+
+        let path = "a".repeat(MAX_REQUEST_LENGTH as usize);
+        let response = get(port, format!("GET /{path} HTTP/1.1\r\n\r\n").as_bytes());
+        assert!(response.starts_with("HTTP/1.1 431"));
+        let header = format!("X-Padding: {path}\r\n");
+        let response = get(port, format!("GET / HTTP/1.1\r\n{header}\r\n").as_bytes());
+        assert!(response.starts_with("HTTP/1.1 431"));
+    }
+
+    // connections beyond the limit aren't answered until one of those being answered is closed
+    #[test]
+    fn connections_are_limited() {
+        let connections = Arc::new(AtomicUsize::new(0));
+        let mut slots: Vec<ConnectionSlot> = (0..MAX_CONNECTIONS)
+            .map(|_| ConnectionSlot::take(&connections).unwrap())
+            .collect();
+        assert!(ConnectionSlot::take(&connections).is_none());
+        slots.pop();
+        assert!(ConnectionSlot::take(&connections).is_some());
+    }
+}
@@ -0,0 +1,412 @@
+//! 3D view of the history of the resultant field, as a surface of field strength over position and time.
+//! Wavefronts appear as ridges across the surface, which bend where the wave slows down inside the medium.
+//! The surface is drawn with OpenGL through a paint callback, projected on the GPU with a depth buffer to hide the
+//! parts behind. Where OpenGL 3 isn't available, it is projected and depth sorted on the CPU and drawn as an egui mesh.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use eframe::egui_glow::{self, ShaderVersion};
+use eframe::glow::{self, HasContext};
+use egui::epaint::{Mesh, Vertex, WHITE_UV};
+use egui::{Color32, PaintCallback, Painter, Pos2, Rect, Sense, Shape, Ui, Vec2, vec2};
+
+use crate::app::canvas::VISIBLE_Y_SPAN;
+use crate::app::i18n::tr;
+use crate::app::simulation::Simulation;
+use crate::app::simulation::observer::StepObserver;
+
+// number of samples of the field kept along each axis of the surface
+const COLUMNS: usize = 160;
+const ROWS: usize = 120;
+// number of simulation updates between recorded rows
+const RECORD_EVERY: u32 = 2;
+
+const VIEW_HEIGHT: f32 = 320.0;
+const CAMERA_DISTANCE: f32 = 4.0;
+// radians of rotation per point dragged
+const DRAG_SENSITIVITY: f32 = 0.01;
+
+const POSITIVE_COLOUR: [f32; 3] = [230.0, 60.0, 60.0];
+const NEGATIVE_COLOUR: [f32; 3] = [60.0, 110.0, 230.0];
+const ZERO_COLOUR: [f32; 3] = [200.0, 200.0, 200.0];
+
+// numbers per vertex sent to the GPU: position in the unit cube, then colour
+const VERTEX_SIZE: usize = 6;
+
+const VERTEX_SHADER: &str = r#"
+in vec3 a_position;
+in vec3 a_colour;
+out vec3 v_colour;
+uniform vec2 u_angles; // yaw and pitch
+uniform vec2 u_scale;  // half the smaller side of the view over half the width and height
+uniform float u_distance;
+
+void main() {
+    // as FieldSurface::project, rotating about the vertical (field) axis, then tilting towards the viewer
+    float x = a_position.x;
+    float t = a_position.y;
+    float e = a_position.z;
+    float x1 = x * cos(u_angles.x) - t * sin(u_angles.x);
+    float z1 = x * sin(u_angles.x) + t * cos(u_angles.x);
+    float y2 = e * cos(u_angles.y) - z1 * sin(u_angles.y);
+    float z2 = e * sin(u_angles.y) + z1 * cos(u_angles.y);
+    // dividing by the depth gives the perspective, and the depth buffer keeps the nearest point
+    gl_Position = vec4(u_distance * u_scale * vec2(x1, y2), 0.5 * z2, u_distance + z2);
+    v_colour = a_colour;
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"
+precision mediump float;
+in vec3 v_colour;
+out vec4 f_colour;
+
+void main() {
+    f_colour = vec4(v_colour, 1.0);
+}
+"#;
+
+pub struct FieldSurface {
+    rows: VecDeque<Vec<f32>>, // downsampled resultant field, oldest first
+    updates: u32,             // updates since the last recorded row
+    yaw: f32,
+    pitch: f32,
+    renderer: Option<Arc<Mutex<SurfaceRenderer>>>, // None where the surface is drawn on the CPU
+}
+
+// colour of the surface for a field strength, scaled so that ±1 is the most saturated
+fn field_colour(e: f32) -> Color32 {
+    let strength = e.abs().min(1.0);
+    let target = if e > 0.0 {
+        POSITIVE_COLOUR
+    } else {
+        NEGATIVE_COLOUR
+    };
+    let [r, g, b] =
+        std::array::from_fn(|i| (ZERO_COLOUR[i] + (target[i] - ZERO_COLOUR[i]) * strength) as u8);
+    Color32::from_rgb(r, g, b)
+}
+
+impl StepObserver for FieldSurface {
+    // records the resultant field every few updates
+    fn observe(&mut self, simulation: &Simulation) {
+        self.updates += 1;
+        if self.updates < RECORD_EVERY {
+            return;
+        }
+        self.updates = 0;
+
+        let size = simulation.size();
+        let row = (0..COLUMNS)
+            .map(|i| {
+                let x = size.min + size.span() * i as f32 / (COLUMNS - 1) as f32;
+                simulation.resultant_field_at(x)
+            })
+            .collect();
+        if self.rows.len() == ROWS {
+            self.rows.pop_front();
+        }
+        self.rows.push_back(row);
+    }
+}
+
+impl FieldSurface {
+    // gl is the OpenGL context the window is drawn with, if any
+    pub fn new(gl: Option<&glow::Context>) -> Self {
+        let renderer = gl.and_then(|gl| {
+            SurfaceRenderer::new(gl)
+                .inspect_err(|e| log::warn!("Drawing the 3D field history on the CPU instead: {e}"))
+                .ok()
+        });
+        FieldSurface {
+            rows: VecDeque::with_capacity(ROWS),
+            updates: 0,
+            yaw: -0.6,
+            pitch: 0.5,
+            renderer: renderer.map(|renderer| Arc::new(Mutex::new(renderer))),
+        }
+    }
+
+    // frees the renderer's OpenGL objects, when the window closes
+    pub fn destroy(&self, gl: &glow::Context) {
+        if let Some(renderer) = &self.renderer {
+            renderer.lock().unwrap().destroy(gl);
+        }
+    }
+
+    // bytes taken by the recorded rows
+    pub fn memory(&self) -> usize {
+        self.rows.capacity() * std::mem::size_of::<Vec<f32>>()
+            + self.rows.iter().map(|row| row.capacity()).sum::<usize>() * std::mem::size_of::<f32>()
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.updates = 0;
+    }
+
+    // rotates a point in the unit cube by the view angles, then projects it onto the screen.
+    // returns the screen position and the distance from the camera
+    fn project(&self, rect: Rect, x: f32, t: f32, e: f32) -> (Pos2, f32) {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        // rotate about the vertical (field) axis, then tilt towards the viewer
+        let x1 = x * cos_yaw - t * sin_yaw;
+        let z1 = x * sin_yaw + t * cos_yaw;
+        let y2 = e * cos_pitch - z1 * sin_pitch;
+        let z2 = e * sin_pitch + z1 * cos_pitch;
+        let depth = CAMERA_DISTANCE + z2;
+        let scale = 0.5 * rect.width().min(rect.height()) * CAMERA_DISTANCE / depth;
+        (rect.center() + scale * vec2(x1, -y2), depth)
+    }
+
+    pub fn draw(&mut self, ui: &mut Ui) {
+        let size = vec2(ui.available_width(), VIEW_HEIGHT);
+        let (response, painter) = ui.allocate_painter(size, Sense::drag());
+        let rect = response.rect;
+        painter.rect_filled(rect, 2.0, Color32::from_rgb(10, 10, 10));
+
+        let drag: Vec2 = response.drag_delta();
+        self.yaw += drag.x * DRAG_SENSITIVITY;
+        self.pitch = (self.pitch + drag.y * DRAG_SENSITIVITY).clamp(-1.5, 1.5);
+
+        if self.rows.len() < 2 {
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                tr("Play the simulation to build up the field history"),
+                egui::FontId::proportional(14.0),
+                Color32::GRAY,
+            );
+            return;
+        }
+
+        match &self.renderer {
+            Some(renderer) => self.paint_gpu(&painter, rect, renderer.clone()),
+            None => self.paint_mesh(&painter, rect),
+        }
+
+        // label the axes at the edges of the base of the surface
+        let label = |x, t, text: &str| {
+            let (pos, _) = self.project(rect, x, t, -0.5);
+            painter.text(
+                pos,
+                egui::Align2::CENTER_CENTER,
+                text,
+                egui::FontId::proportional(12.0),
+                Color32::GRAY,
+            );
+        };
+        label(0.0, -1.1, "x");
+        label(1.1, 0.0, "t");
+
+        ui.label(tr(
+            "Resultant field over the last few seconds. Drag to rotate the view.",
+        ));
+    }
+
+    // position of a point of the surface in the unit cube, where x runs across the world and t from oldest to newest,
+    // with its colour
+    fn point(&self, row: usize, column: usize) -> ([f32; 3], Color32) {
+        let x = 2.0 * column as f32 / (COLUMNS - 1) as f32 - 1.0;
+        let t = 2.0 * row as f32 / (ROWS - 1) as f32 - 1.0;
+        let e = self.rows[row][column];
+        let height = (e / (0.5 * VISIBLE_Y_SPAN)).clamp(-1.0, 1.0);
+        ([x, t, 0.5 * height], field_colour(e))
+    }
+
+    // sends the heights and colours to the GPU, which projects them and hides the parts behind
+    fn paint_gpu(&self, painter: &Painter, rect: Rect, renderer: Arc<Mutex<SurfaceRenderer>>) {
+        let mut vertices = Vec::with_capacity(self.rows.len() * COLUMNS * VERTEX_SIZE);
+        for row in 0..self.rows.len() {
+            for column in 0..COLUMNS {
+                let (position, colour) = self.point(row, column);
+                vertices.extend(position);
+                vertices.extend(colour.to_array()[..3].iter().map(|c| *c as f32 / 255.0));
+            }
+        }
+        let view = View {
+            angles: [self.yaw, self.pitch],
+            scale: [rect.width(), rect.height()].map(|side| rect.width().min(rect.height()) / side),
+        };
+        let rows = self.rows.len();
+        let callback = egui_glow::CallbackFn::new(move |_, painter| {
+            renderer
+                .lock()
+                .unwrap()
+                .paint(painter.gl(), &vertices, rows, view);
+        });
+        painter.add(PaintCallback {
+            rect,
+            callback: Arc::new(callback),
+        });
+    }
+
+    // projects and depth sorts the cells of the surface, for when it can't be drawn on the GPU
+    fn paint_mesh(&self, painter: &Painter, rect: Rect) {
+        let point = |row: usize, column: usize| {
+            let ([x, t, e], colour) = self.point(row, column);
+            let (pos, depth) = self.project(rect, x, t, e);
+            (pos, depth, colour)
+        };
+        let mut cells = Vec::with_capacity(self.rows.len() * COLUMNS);
+        for row in 0..self.rows.len() - 1 {
+            for column in 0..COLUMNS - 1 {
+                let corners = [
+                    point(row, column),
+                    point(row, column + 1),
+                    point(row + 1, column + 1),
+                    point(row + 1, column),
+                ];
+                let depth = corners.iter().map(|c| c.1).sum::<f32>() / 4.0;
+                cells.push((depth, corners));
+            }
+        }
+        // painter's algorithm: draw the furthest cells first so nearer ones cover them
+        cells.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut mesh = Mesh::default();
+        for (_, corners) in cells {
+            let first = mesh.vertices.len() as u32;
+            for (pos, _, colour) in corners {
+                mesh.vertices.push(Vertex {
+                    pos,
+                    uv: WHITE_UV,
+                    color: colour,
+                });
+            }
+            mesh.add_triangle(first, first + 1, first + 2);
+            mesh.add_triangle(first, first + 2, first + 3);
+        }
+        painter.add(Shape::mesh(mesh));
+    }
+}
+
+// angles the surface is viewed from, and the shape of the view, for the vertex shader
+#[derive(Clone, Copy)]
+struct View {
+    angles: [f32; 2],
+    scale: [f32; 2],
+}
+
+// shader and buffers for drawing the surface with OpenGL. the triangles join the same points of the grid
+// every frame, so only the heights and colours are sent each time
+struct SurfaceRenderer {
+    program: glow::Program,
+    vertex_array: glow::VertexArray,
+    vertices: glow::Buffer,
+    indices: glow::Buffer,
+}
+
+impl SurfaceRenderer {
+    fn new(gl: &glow::Context) -> Result<Self, String> {
+        let version = ShaderVersion::get(gl);
+        if !version.is_new_shader_interface() {
+            return Err(format!("{version:?} shaders are too old"));
+        }
+        // two triangles for each cell, with the rows of the grid one after another
+        let mut indices: Vec<u32> = Vec::with_capacity((ROWS - 1) * (COLUMNS - 1) * 6);
+        for row in 0..ROWS - 1 {
+            for column in 0..COLUMNS - 1 {
+                let corner = (row * COLUMNS + column) as u32;
+                let (next_column, next_row) = (corner + 1, corner + COLUMNS as u32);
+                indices.extend([corner, next_column, next_row + 1]);
+                indices.extend([corner, next_row + 1, next_row]);
+            }
+        }
+        let index_bytes: Vec<u8> = indices.iter().flat_map(|i| i.to_ne_bytes()).collect();
+
+        unsafe {
+            let program = gl.create_program()?;
+            let mut shaders = Vec::new();
+            for (kind, source) in [
+                (glow::VERTEX_SHADER, VERTEX_SHADER),
+                (glow::FRAGMENT_SHADER, FRAGMENT_SHADER),
+            ] {
+                let shader = gl.create_shader(kind)?;
+                gl.shader_source(
+                    shader,
+                    &format!("{}{source}", version.version_declaration()),
+                );
+                gl.compile_shader(shader);
+                if !gl.get_shader_compile_status(shader) {
+                    return Err(gl.get_shader_info_log(shader));
+                }
+                gl.attach_shader(program, shader);
+                shaders.push(shader);
+            }
+            gl.bind_attrib_location(program, 0, "a_position");
+            gl.bind_attrib_location(program, 1, "a_colour");
+            gl.link_program(program);
+            for shader in shaders {
+                gl.detach_shader(program, shader);
+                gl.delete_shader(shader);
+            }
+            if !gl.get_program_link_status(program) {
+                return Err(gl.get_program_info_log(program));
+            }
+
+            let vertex_array = gl.create_vertex_array()?;
+            let vertices = gl.create_buffer()?;
+            let indices = gl.create_buffer()?;
+            gl.bind_vertex_array(Some(vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(vertices));
+            let stride = (VERTEX_SIZE * size_of::<f32>()) as i32;
+            gl.vertex_attrib_pointer_f32(0, 3, glow::FLOAT, false, stride, 0);
+            gl.vertex_attrib_pointer_f32(
+                1,
+                3,
+                glow::FLOAT,
+                false,
+                stride,
+                3 * size_of::<f32>() as i32,
+            );
+            gl.enable_vertex_attrib_array(0);
+            gl.enable_vertex_attrib_array(1);
+            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(indices));
+            gl.buffer_data_u8_slice(glow::ELEMENT_ARRAY_BUFFER, &index_bytes, glow::STATIC_DRAW);
+            gl.bind_vertex_array(None);
+
+            Ok(SurfaceRenderer {
+                program,
+                vertex_array,
+                vertices,
+                indices,
+            })
+        }
+    }
+
+    // draws the first rows of the surface, given VERTEX_SIZE numbers for each point
+    fn paint(&self, gl: &glow::Context, vertices: &[f32], rows: usize, view: View) {
+        let vertex_bytes: Vec<u8> = vertices.iter().flat_map(|v| v.to_ne_bytes()).collect();
+        let count = ((rows - 1) * (COLUMNS - 1) * 6) as i32;
+        unsafe {
+            gl.use_program(Some(self.program));
+            let uniform = |name| gl.get_uniform_location(self.program, name);
+            gl.uniform_2_f32(uniform("u_angles").as_ref(), view.angles[0], view.angles[1]);
+            gl.uniform_2_f32(uniform("u_scale").as_ref(), view.scale[0], view.scale[1]);
+            gl.uniform_1_f32(uniform("u_distance").as_ref(), CAMERA_DISTANCE);
+
+            gl.bind_vertex_array(Some(self.vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vertices));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, &vertex_bytes, glow::STREAM_DRAW);
+            // the depth buffer is only used here, so is cleared within the view, which the scissor test limits it to
+            gl.enable(glow::DEPTH_TEST);
+            gl.depth_func(glow::LESS);
+            gl.clear(glow::DEPTH_BUFFER_BIT);
+            gl.draw_elements(glow::TRIANGLES, count, glow::UNSIGNED_INT, 0);
+            gl.disable(glow::DEPTH_TEST);
+            gl.bind_vertex_array(None);
+        }
+    }
+
+    fn destroy(&self, gl: &glow::Context) {
+        unsafe {
+            gl.delete_program(self.program);
+            gl.delete_vertex_array(self.vertex_array);
+            gl.delete_buffer(self.vertices);
+            gl.delete_buffer(self.indices);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Command line sweep, run without opening the window:
+//!
+//! ```text
+//! refraction sweep <parameter> <start> <end> <step> [--scene <file>] [--output <file>]
+//! ```
+//!
+//! steps a parameter of the scene from start to end, running each value to steady state, and appends a row of the
+//! measured refractive index, transmittance, reflectance and absorptance to a CSV file after each one. Rows are
+//! written as they are measured, so a sweep left running overnight keeps everything measured if it is stopped.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use strum::IntoEnumIterator;
+
+use crate::app::simulation::preset::Preset;
+use crate::app::simulation::scene::Scene;
+use crate::app::simulation::sweep::{SteadyState, SweepParameter};
+use crate::app::simulation::variables::TIME_STEP;
+
+// file the rows are appended to unless another is given
+const DEFAULT_OUTPUT: &str = "sweep.csv";
+const HEADER: &str = "parameter,value,n,T,R,A";
+
+// runs a sweep from the arguments after "sweep"
+pub fn run(args: &[String]) -> Result<(), String> {
+    let usage = || {
+        let names: Vec<&str> = SweepParameter::iter().map(|p| p.name()).collect();
+        format!(
+            "usage: refraction sweep <parameter> <start> <end> <step> [--scene <file>] [--output <file>]\n\
+            where the parameter is one of {}",
+            names.join(", ")
+        )
+    };
+    let [name, start, end, step, options @ ..] = args else {
+        return Err(usage());
+    };
+    let parameter = SweepParameter::iter()
+        .find(|p| p.name() == name)
+        .ok_or_else(usage)?;
+    let number = |text: &String| {
+        text.parse::<f32>()
+            .map_err(|_| format!("{text} isn't a number"))
+    };
+    let (start, end, step) = (number(start)?, number(end)?, number(step)?);
+    if !(step > 0.0 && start <= end) {
+        return Err("the step must be positive, and the end no less than the start".to_owned());
+    }
+
+    // the dilute gas preset is a plane wave through a row of particles, ready to be measured
+    let mut scene = Preset::DiluteGas.scene();
+    let mut output = PathBuf::from(DEFAULT_OUTPUT);
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let value = options.next().ok_or_else(usage)?;
+        match option.as_str() {
+            "--scene" => scene = Scene::open(value.as_ref())?,
+            "--output" => output = PathBuf::from(value),
+            _ => return Err(usage()),
+        }
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&output)
+        .map_err(|e| format!("couldn't open {}: {e}", output.display()))?;
+    let empty = file.metadata().map_err(|e| e.to_string())?.len() == 0;
+    if empty {
+        writeln!(file, "{HEADER}").map_err(|e| e.to_string())?;
+    }
+    // values are counted from the start rather than added up, so rounding doesn't build up along the sweep
+    let count = ((end - start) / step + 1e-3).floor() as usize + 1;
+    for i in 0..count {
+        let value = start + step * i as f32;
+        let measured = SteadyState::run(&parameter.set(&scene, value), TIME_STEP)
+            .map_err(|e| format!("{} = {value}: {e}", parameter.name()))?;
+        let row = format!(
+            "{},{value},{},{},{},{}",
+            parameter.name(),
+            measured.index,
+            measured.transmittance,
+            measured.reflectance,
+            measured.absorptance
+        );
+        writeln!(file, "{row}").map_err(|e| e.to_string())?;
+        println!("{}/{count} {row}", i + 1);
+    }
+    Ok(())
+}
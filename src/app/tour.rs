@@ -0,0 +1,203 @@
+//! Interactive walkthrough that introduces the controls and the physics of the simulation one step at a time
+
+use std::collections::HashMap;
+
+use egui::{Align2, Color32, Context, Id, LayerId, Order, Rect, Stroke, StrokeKind, vec2};
+
+use crate::app::i18n::tr;
+use crate::app::simulation::preset::Preset;
+
+// parts of the UI that a tour step can point at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TourTarget {
+    Canvas,
+    Particle,
+    Waveform,
+    ParticleProperties,
+    FieldOpacities,
+    Playback,
+}
+
+struct TourStep {
+    title: &'static str,
+    text: &'static str,
+    target: TourTarget,
+    preset: Option<Preset>, // loaded when the step is entered
+    play: bool,             // whether the simulation runs during the step
+}
+
+const STEPS: [TourStep; 7] = [
+    TourStep {
+        title: "Welcome",
+        text: "This tour walks through how light slows down in a material. \
+            The canvas shows the electric field along the x axis, with charged particles sitting on the axis.",
+        target: TourTarget::Canvas,
+        preset: Some(Preset::ElectronPulse),
+        play: false,
+    },
+    TourStep {
+        title: "Apply a pulse",
+        text: "The applied wave is chosen here. A single Gaussian pulse has been selected, \
+            which travels from right to left at the speed of light.",
+        target: TourTarget::Waveform,
+        preset: None,
+        play: false,
+    },
+    TourStep {
+        title: "Play the simulation",
+        text: "These controls run, pause, step and restart the simulation. \
+            The simulation is now playing - watch the pulse approach the electron.",
+        target: TourTarget::Playback,
+        preset: None,
+        play: true,
+    },
+    TourStep {
+        title: "The electron responds",
+        text: "The electron is bound in place like a mass on a spring. \
+            When the pulse arrives it is pushed up and down, and keeps oscillating after the pulse has passed.",
+        target: TourTarget::Particle,
+        preset: None,
+        play: true,
+    },
+    TourStep {
+        title: "The electron re-radiates",
+        text: "An accelerating charge creates its own electric field. \
+            Its induced field (blue) spreads out from the electron at the speed of light. \
+            Use these sliders to make each field easier to see.",
+        target: TourTarget::FieldOpacities,
+        preset: None,
+        play: true,
+    },
+    TourStep {
+        title: "Observe the phase shift",
+        text: "A plane wave now passes through a row of electrons. Each one re-radiates, \
+            and the resultant field (purple) falls behind the applied field: it is phase shifted. \
+            This delay is what we see as light travelling slower in a material.",
+        target: TourTarget::Canvas,
+        preset: Some(Preset::ElectronChain),
+        play: true,
+    },
+    TourStep {
+        title: "Experiment",
+        text: "The size of the phase shift depends on the particles' mass, spring constant and damping. \
+            Try changing them and see how the resultant field changes.",
+        target: TourTarget::ParticleProperties,
+        preset: None,
+        play: true,
+    },
+];
+
+// what the app needs to do when the tour enters a new step
+pub struct TourAction {
+    pub preset: Option<Preset>,
+    pub play: bool,
+}
+
+#[derive(Default)]
+pub struct Tour {
+    step: Option<usize>, // current step, None when the tour isn't running
+    targets: HashMap<TourTarget, Rect>, // screen area of each target, refreshed every frame
+}
+
+impl Tour {
+    pub fn start(&mut self) -> TourAction {
+        self.step = Some(0);
+        self.action(0)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.step.is_some()
+    }
+
+    // record where a target was drawn this frame
+    pub fn register(&mut self, target: TourTarget, rect: Rect) {
+        if self.is_running() {
+            self.targets.insert(target, rect);
+        }
+    }
+
+    fn action(&self, step: usize) -> TourAction {
+        TourAction {
+            preset: STEPS[step].preset,
+            play: STEPS[step].play,
+        }
+    }
+
+    // going back reloads the preset the step was entered with, so the simulation matches its text again
+    fn back_action(&self, step: usize) -> TourAction {
+        TourAction {
+            preset: STEPS[..=step].iter().rev().find_map(|s| s.preset),
+            play: STEPS[step].play,
+        }
+    }
+
+    // draws the current step's highlight and explanation, returning an action if the step changed
+    pub fn show(&mut self, ctx: &Context) -> Option<TourAction> {
+        let step = self.step?;
+        let current = &STEPS[step];
+        let screen = ctx.screen_rect();
+        let target = self.targets.get(&current.target).copied().unwrap_or(screen);
+
+        // pulse the highlight so it catches the eye
+        let pulse = 0.5 + 0.5 * (3.0 * ctx.input(|i| i.time) as f32).sin();
+        let highlight = Color32::from_rgba_unmultiplied(255, 200, 0, (120.0 + 135.0 * pulse) as u8);
+        ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("tour-highlight")))
+            .rect_stroke(
+                target.expand(4.0),
+                4.0,
+                Stroke::new(3.0, highlight),
+                StrokeKind::Outside,
+            );
+
+        // place the explanation next to the target without covering it
+        let (pivot, position) = if target.height() > screen.height() / 2.0 {
+            (Align2::LEFT_TOP, target.left_top() + vec2(24.0, 24.0))
+        } else if target.center().y < screen.center().y {
+            (Align2::LEFT_TOP, target.left_bottom() + vec2(0.0, 12.0))
+        } else {
+            (Align2::LEFT_BOTTOM, target.left_top() - vec2(0.0, 12.0))
+        };
+
+        let mut open = true;
+        let mut next_step = Some(step);
+        egui::Window::new(tr(current.title))
+            .id(Id::new("tour"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .pivot(pivot)
+            .fixed_pos(position.min(screen.right_bottom() - vec2(360.0, 0.0)))
+            .default_width(340.0)
+            .show(ctx, |ui| {
+                ui.label(tr(current.text));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(step > 0, egui::Button::new(tr("Back")))
+                        .clicked()
+                    {
+                        next_step = Some(step - 1);
+                    }
+                    ui.label(format!("{} / {}", step + 1, STEPS.len()));
+                    let last = step + 1 == STEPS.len();
+                    if ui
+                        .button(tr(if last { "Finish" } else { "Next" }))
+                        .clicked()
+                    {
+                        next_step = if last { None } else { Some(step + 1) };
+                    }
+                });
+            });
+        if !open {
+            next_step = None;
+        }
+
+        self.step = next_step;
+        self.targets.clear();
+        match next_step {
+            Some(new_step) if new_step < step => Some(self.back_action(new_step)),
+            Some(new_step) if new_step > step => Some(self.action(new_step)),
+            _ => None,
+        }
+    }
+}
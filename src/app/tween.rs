@@ -0,0 +1,123 @@
+//! Drawing between simulation updates in slow motion. Below normal speed several frames are drawn for each update,
+//! so the canvas would stand still for a few frames and then jump. The state from before the last update is kept,
+//! and the particles and fields are drawn the part of the way from it to the current state that the time carried
+//! over towards the next update amounts to.
+
+use std::borrow::Cow;
+
+use egui::Pos2;
+
+use crate::app::simulation::{ParticleId, Simulation};
+
+// state of the simulation drawn on the canvas, before the last update
+struct Frame {
+    steps: usize, // number of updates the simulation had made
+    x_points: Vec<f32>,
+    particles: Vec<(ParticleId, Pos2, Vec<f32>)>, // with each particle's induced field
+    applied: Vec<f32>,
+    resultant: Vec<f32>,
+    scattered: Vec<f32>,
+}
+
+// particles and fields to draw, borrowed from the simulation unless they are between two updates
+pub struct Drawn<'a> {
+    pub particles: Vec<(Pos2, Cow<'a, [f32]>)>, // in the order of the simulation's particles
+    pub applied: Cow<'a, [f32]>,
+    pub resultant: Cow<'a, [f32]>,
+    pub scattered: Cow<'a, [f32]>,
+}
+
+#[derive(Default)]
+pub struct Tween {
+    previous: Option<Frame>,
+    fraction: f32, // how far from the previous state towards the current one is drawn, from 0 to 1
+}
+
+fn between(from: &[f32], to: &[f32], fraction: f32) -> Vec<f32> {
+    from.iter()
+        .zip(to)
+        .map(|(a, b)| a + (b - a) * fraction)
+        .collect()
+}
+
+impl Tween {
+    // keeps the state of the simulation just before it updates
+    pub fn record(&mut self, simulation: &Simulation) {
+        self.previous = Some(Frame {
+            steps: simulation.steps(),
+            x_points: simulation.x_intervals().to_vec(),
+            particles: simulation
+                .particles()
+                .iter()
+                .map(|p| (p.id(), *p.position(), p.field().to_vec()))
+                .collect(),
+            applied: simulation.applied_field().to_vec(),
+            resultant: simulation.resultant_field().to_vec(),
+            scattered: simulation.scattered_field().to_vec(),
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.previous = None;
+    }
+
+    pub fn set_fraction(&mut self, fraction: f32) {
+        self.fraction = fraction.clamp(0.0, 1.0);
+    }
+
+    // the particles and fields to draw: between the previous and current states if the simulation has made exactly
+    // one update since the previous state was kept, on the same grid and with the same particles, otherwise as they
+    // are now
+    pub fn drawn<'a>(&self, simulation: &'a Simulation) -> Drawn<'a> {
+        let previous = self.previous.as_ref().filter(|previous| {
+            previous.steps + 1 == simulation.steps()
+                && previous.x_points == simulation.x_intervals()
+                && previous.particles.len() == simulation.particles().len()
+                && previous
+                    .particles
+                    .iter()
+                    .zip(simulation.particles())
+                    .all(|((id, _, _), p)| *id == p.id())
+        });
+        match previous {
+            Some(previous) if self.fraction < 1.0 => Drawn {
+                particles: previous
+                    .particles
+                    .iter()
+                    .zip(simulation.particles())
+                    .map(|((_, position, field), p)| {
+                        (
+                            position.lerp(*p.position(), self.fraction),
+                            Cow::Owned(between(field, p.field(), self.fraction)),
+                        )
+                    })
+                    .collect(),
+                applied: Cow::Owned(between(
+                    &previous.applied,
+                    simulation.applied_field(),
+                    self.fraction,
+                )),
+                resultant: Cow::Owned(between(
+                    &previous.resultant,
+                    simulation.resultant_field(),
+                    self.fraction,
+                )),
+                scattered: Cow::Owned(between(
+                    &previous.scattered,
+                    simulation.scattered_field(),
+                    self.fraction,
+                )),
+            },
+            _ => Drawn {
+                particles: simulation
+                    .particles()
+                    .iter()
+                    .map(|p| (*p.position(), Cow::Borrowed(p.field())))
+                    .collect(),
+                applied: Cow::Borrowed(simulation.applied_field()),
+                resultant: Cow::Borrowed(simulation.resultant_field()),
+                scattered: Cow::Borrowed(simulation.scattered_field()),
+            },
+        }
+    }
+}
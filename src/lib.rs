@@ -0,0 +1,3 @@
+//! The app and its simulation as a library, for the binary to start and the benchmarks in benches/ to measure
+
+pub mod app;
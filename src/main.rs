@@ -1,35 +1,63 @@
 //! Initialises the application
 
-mod app;
-use crate::app::RefractionApp;
+use refraction::app::{self, APP_NAME, RefractionApp};
 
-use egui::{Pos2, Style, Vec2, Visuals, pos2, vec2};
+use egui::{Pos2, Vec2, pos2, vec2};
+
+// commands run without opening the window, with the arguments each takes
+const USAGE: &str = "usage: refraction [command]\n\
+    with no command, opens the window. commands:\n  \
+    sweep <parameter> <start> <end> <step> [--scene <file>] [--output <file>]\n  \
+    run <scene> --until <time> [--checkpoint <file>] [--every <minutes>] [--divisions <count>]\n  \
+    resume <checkpoint> --until <time> [--every <minutes>]";
 
 fn main() -> eframe::Result {
     env_logger::init();
 
+    // sweeps and long runs are made without opening the window, e.g. overnight
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match args
+        .split_first()
+        .map(|(first, rest)| (first.as_str(), rest))
+    {
+        Some(("sweep", sweep_args)) => Some(app::sweep::run(sweep_args)),
+        #[cfg(feature = "serde-state")]
+        Some(("run", run_args)) => Some(app::checkpoint::run(run_args, false)),
+        #[cfg(feature = "serde-state")]
+        Some(("resume", run_args)) => Some(app::checkpoint::run(run_args, true)),
+        #[cfg(not(feature = "serde-state"))]
+        Some(("run" | "resume", _)) => Some(Err(
+            "runs and resumes need refraction to be built with the serde-state feature".to_owned(),
+        )),
+        Some((unknown, _)) => Some(Err(format!("unknown command {unknown}\n{USAGE}"))),
+        None => None,
+    };
+    if let Some(result) = command {
+        if let Err(e) = result {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     const WINDOW_POSITION: Pos2 = pos2(50.0, 50.0);
     const WINDOW_SIZE: Vec2 = vec2(1500.0, 900.0);
     const MIN_WINDOW_SIZE: Vec2 = vec2(100.0, 100.0);
 
+    // the window's size and position are remembered between launches, these are used on the first launch
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size(WINDOW_SIZE)
             .with_min_inner_size(MIN_WINDOW_SIZE)
             .with_position(WINDOW_POSITION),
+        // for the 3D field history, which hides the parts of the surface behind others
+        depth_buffer: 24,
         ..Default::default()
     };
 
     eframe::run_native(
-        "Refraction",
+        APP_NAME,
         native_options,
-        Box::new(|cc| {
-            let style = Style {
-                visuals: Visuals::dark(),
-                ..Style::default()
-            };
-            cc.egui_ctx.set_style(style);
-            Ok(Box::new(RefractionApp::new(cc)))
-        }),
+        Box::new(|cc| Ok(Box::new(RefractionApp::new(cc)))),
     )
 }